@@ -1,7 +1,7 @@
 #![feature(absolute_path)]
 use anyhow::{Context, Result};
 use clap::Parser;
-use custom_rpc::RpcAsset;
+use custom_rpc::{RpcAsset, RpcKeyActivation};
 use futures::FutureExt;
 use serde::Serialize;
 use std::{io::Write, path::PathBuf, sync::Arc};
@@ -11,8 +11,10 @@ use crate::settings::{
 	LiquidityProviderSubcommands,
 };
 use api::{
-	lp::LpApi, primitives::RedemptionAmount, queries::QueryApi, AccountId32, BrokerApi,
-	GovernanceApi, KeyPair, OperatorApi, StateChainApi, SwapDepositAddress,
+	lp::LpApi,
+	primitives::{Hash, RedemptionAmount, FLIPPERINOS_PER_FLIP},
+	queries::QueryApi,
+	AccountId32, BrokerApi, GovernanceApi, KeyPair, OperatorApi, StateChainApi, SwapDepositAddress,
 };
 use cf_chains::eth::Address as EthereumAddress;
 use chainflip_api as api;
@@ -132,6 +134,8 @@ async fn run_cli() -> Result<()> {
 					api.operator_api().set_vanity_name(name).await?;
 				},
 				PreUpdateCheck {} => pre_update_check(api.query_api()).await?,
+				RotationDryRun { offline, block_hash, json } =>
+					rotation_dry_run(api.query_api(), offline, block_hash, json).await?,
 				ForceRotation {} => {
 					api.governance_api().force_rotation().await?;
 				},
@@ -269,6 +273,62 @@ async fn pre_update_check(api: QueryApi) -> Result<()> {
 	Ok(())
 }
 
+async fn rotation_dry_run(
+	api: QueryApi,
+	offline: Vec<AccountId32>,
+	block_hash: Option<Hash>,
+	json: bool,
+) -> Result<()> {
+	let dry_run = api.rotation_dry_run(block_hash, offline).await?;
+
+	if json {
+		println!("{}", serde_json::to_string_pretty(&dry_run)?);
+		return Ok(())
+	}
+
+	println!("Simulated rotation to epoch {}.", dry_run.new_epoch_index);
+	match (dry_run.failed_stage, dry_run.failure) {
+		(Some(stage), Some(failure)) =>
+			println!("❌ The rotation would fail at the {stage:?} stage: {failure:?}"),
+		// Without a simulated failure, the failed stage comes from the key activation estimates.
+		(Some(stage), None) =>
+			println!("❌ The rotation would fail at the {stage:?} stage (estimated)."),
+		_ => println!("✅ The rotation would complete."),
+	}
+	if let Some(bond) = dry_run.bond {
+		let bond = u128::try_from(bond).map_err(anyhow::Error::msg)?;
+		println!("Bond: {} FLIP", bond / FLIPPERINOS_PER_FLIP);
+	}
+	println!(
+		"Ceremonies required: {} keygen, {} key handover.",
+		dry_run.keygen_attempts, dry_run.key_handover_attempts
+	);
+	println!("New authorities ({}):", dry_run.new_authorities.len());
+	for authority in &dry_run.new_authorities {
+		println!("  {authority}");
+	}
+	println!("Key handover sharing participants ({}):", dry_run.sharing_participants.len());
+	for participant in &dry_run.sharing_participants {
+		println!("  {participant}");
+	}
+	println!("Excluded ({}):", dry_run.excluded.len());
+	for (account_id, reason) in &dry_run.excluded {
+		println!("  {account_id}: {reason:?}");
+	}
+	if !dry_run.estimated_key_activations.is_empty() {
+		println!("Key activations (estimated using the current key in place of the new one):");
+		for (chain, activation) in &dry_run.estimated_key_activations {
+			match activation {
+				RpcKeyActivation::Broadcast { call } =>
+					println!("  {chain}: Broadcast 0x{}", hex::encode(&call.0)),
+				activation => println!("  {chain}: {activation:?}"),
+			}
+		}
+	}
+
+	Ok(())
+}
+
 fn confirm_submit() -> bool {
 	use std::{io, io::*};
 
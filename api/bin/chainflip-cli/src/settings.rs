@@ -1,4 +1,7 @@
-use chainflip_api::primitives::{AccountRole, Asset, ForeignChain};
+use chainflip_api::{
+	primitives::{AccountRole, Asset, ForeignChain, Hash},
+	AccountId32,
+};
 pub use chainflip_engine::settings::StateChain;
 use chainflip_engine::{
 	constants::{CONFIG_ROOT, DEFAULT_CONFIG_ROOT},
@@ -162,6 +165,23 @@ pub enum CliCommand {
 	},
 	#[clap(about = "Check if it is safe to update your node/engine")]
 	PreUpdateCheck {},
+	#[clap(
+		about = "Simulate the next authority rotation against the state at a given block. Nothing is submitted to the network."
+	)]
+	RotationDryRun {
+		#[clap(
+			help = "Accounts to treat as offline. Any ceremony these accounts take part in will fail.",
+			long = "offline"
+		)]
+		offline: Vec<AccountId32>,
+		#[clap(
+			help = "Hash of the block whose state should be used. Defaults to the latest finalized block.",
+			long = "at"
+		)]
+		block_hash: Option<Hash>,
+		#[clap(help = "Output the full simulation result as JSON.", short, long, action)]
+		json: bool,
+	},
 	#[clap(
         // This is only useful for testing. No need to show to the end user.
         hide = true,
@@ -1,10 +1,14 @@
+use cf_primitives::ForeignChain;
 use chainflip_engine::settings::{HttpBasicAuthEndpoint, WsHttpEndpoints};
-use futures::FutureExt;
-use jsonrpsee::{core::Error, server::ServerBuilder, RpcModule};
+use futures::{future::BoxFuture, stream::FuturesUnordered, FutureExt, StreamExt, TryStreamExt};
+use jsonrpsee::{core::Error, server::ServerBuilder, types::error::CallError, RpcModule};
 use std::{env, io::Write, net::SocketAddr, path::PathBuf};
 use tracing::log;
 use utilities::task_scope;
+use witness_events::{Encoding, EventFilter, SubscriptionOptions, WitnessEvent};
 
+mod witness_events;
+mod witness_history;
 mod witnessing;
 
 #[derive(Clone)]
@@ -15,6 +19,9 @@ pub struct DepositTrackerSettings {
 	dot_node: WsHttpEndpoints,
 	state_chain_ws_endpoint: String,
 	btc: HttpBasicAuthEndpoint,
+	witness_history_db_path: PathBuf,
+	/// The oldest witnessed calls are pruned beyond this many. All calls are kept if it is None.
+	witness_history_max_records: Option<u64>,
 }
 
 async fn start(
@@ -29,38 +36,190 @@ async fn start(
 
 	let btc_tracker = witnessing::btc_mempool::start(scope, settings.btc.clone()).await;
 
+	// Broadcast channel will drop old messages when the buffer is full to
+	// avoid "memory leaks" due to slow receivers.
+	const EVENT_BUFFER_SIZE: usize = 1024;
+	let (witness_sender, _) =
+		tokio::sync::broadcast::channel::<state_chain_runtime::RuntimeCall>(EVENT_BUFFER_SIZE);
+
+	let (network_environment, mempool_trackers, witness_history) =
+		witnessing::start(scope, settings, btc_tracker, witness_sender.clone()).await?;
+
 	module.register_async_method("status", move |arguments, _context| {
-		let btc_tracker = btc_tracker.clone();
+		let mempool_trackers = mempool_trackers.clone();
 		async move {
 			arguments.parse::<Vec<String>>().map_err(Error::Call).and_then(|addresses| {
-				btc_tracker
+				mempool_trackers
 					.lookup_transactions(&addresses)
 					.map_err(|err| jsonrpsee::core::Error::Custom(err.to_string()))
 			})
 		}
 	})?;
 
-	// Broadcast channel will drop old messages when the buffer is full to
-	// avoid "memory leaks" due to slow receivers.
-	const EVENT_BUFFER_SIZE: usize = 1024;
-	let (witness_sender, _) =
-		tokio::sync::broadcast::channel::<state_chain_runtime::RuntimeCall>(EVENT_BUFFER_SIZE);
+	module.register_method("witness_history_by_deposit_address", {
+		let witness_history = witness_history.clone();
+		move |params, _context| {
+			let mut params = params.sequence();
+			let address: String = params.next()?;
+			let page: Option<String> = params.optional_next()?;
+			let page_size: Option<u32> = params.optional_next()?;
+			witness_history
+				.by_deposit_address(&address, page.as_deref(), page_size)
+				.map_err(|err| Error::Custom(err.to_string()))
+		}
+	})?;
 
-	witnessing::start(scope, settings, witness_sender.clone()).await?;
+	module.register_method("witness_history_by_tx_hash", {
+		let witness_history = witness_history.clone();
+		move |params, _context| {
+			let mut params = params.sequence();
+			let tx_hash: String = params.next()?;
+			let page: Option<String> = params.optional_next()?;
+			let page_size: Option<u32> = params.optional_next()?;
+			witness_history
+				.by_tx_hash(&tx_hash, page.as_deref(), page_size)
+				.map_err(|err| Error::Custom(err.to_string()))
+		}
+	})?;
+
+	module.register_method("witness_history_by_block_range", {
+		let witness_history = witness_history.clone();
+		move |params, _context| {
+			let mut params = params.sequence();
+			let chain: ForeignChain = params.next()?;
+			let from_block: u64 = params.next()?;
+			let to_block: u64 = params.next()?;
+			let page: Option<String> = params.optional_next()?;
+			let page_size: Option<u32> = params.optional_next()?;
+			witness_history
+				.by_block_range(chain, from_block..=to_block, page.as_deref(), page_size)
+				.map_err(|err| Error::Custom(err.to_string()))
+		}
+	})?;
+
+	// The subscription callbacks must be 'static, so the subscriptions are sent to this task to
+	// run them within the task scope.
+	let (subscription_sender, mut subscription_receiver) =
+		tokio::sync::mpsc::unbounded_channel::<BoxFuture<'static, ()>>();
+	scope.spawn(async move {
+		let mut subscriptions = FuturesUnordered::new();
+		loop {
+			tokio::select! {
+				Some(subscription) = subscription_receiver.recv() => subscriptions.push(subscription),
+				Some(()) = subscriptions.next() => {},
+				else => break Ok(()),
+			}
+		}
+	});
+
+	// Unlike `subscribe_witnessing`, this doesn't drop calls for slow subscribers, as the calls
+	// are read from the witness history. Subscribing with the cursor after the last received call
+	// resumes the subscription where it left off.
+	module.register_subscription(
+		"subscribe_witness_history",
+		"s_witness_history",
+		"unsubscribe_witness_history",
+		move |params, mut sink, _context| {
+			let cursor = match params.sequence().optional_next::<u64>() {
+				Ok(cursor) => cursor.unwrap_or_else(|| witness_history.next_cursor()),
+				Err(err) => {
+					let _ = sink.reject(err);
+					return Ok(())
+				},
+			};
+			let witness_history = witness_history.clone();
+			let next_cursor_receiver = witness_history.subscribe();
+
+			let records = futures::stream::try_unfold(
+				(cursor, next_cursor_receiver),
+				move |(cursor, mut next_cursor_receiver)| {
+					let witness_history = witness_history.clone();
+					async move {
+						loop {
+							let records = witness_history.records_from(
+								cursor,
+								witness_history::DEFAULT_PAGE_SIZE as usize,
+							)?;
+							match records.last() {
+								Some(last_record) => {
+									let next_cursor = last_record.cursor + 1;
+									return anyhow::Ok(Some((
+										records,
+										(next_cursor, next_cursor_receiver),
+									)))
+								},
+								None =>
+									if next_cursor_receiver.changed().await.is_err() {
+										return anyhow::Ok(None)
+									},
+							}
+						}
+					}
+				},
+			)
+			.map_ok(|records| futures::stream::iter(records.into_iter().map(anyhow::Ok)))
+			.try_flatten()
+			.inspect_err(|err| log::error!("Failed to read the witness history: {}", err));
+
+			// Piping the records notices when the subscription is closed, even while waiting for
+			// new records.
+			let _ = subscription_sender.send(
+				async move {
+					let closed = sink.pipe_from_try_stream(Box::pin(records)).await;
+					log::debug!("Subscription is closed: {:?}", closed);
+				}
+				.boxed(),
+			);
+			Ok(())
+		},
+	)?;
 
 	module.register_subscription(
 		"subscribe_witnessing",
 		"s_witnessing",
 		"unsubscribe_witnessing",
-		move |_params, mut sink, _context| {
+		move |params, mut sink, _context| {
+			let options = match params.sequence().optional_next::<SubscriptionOptions>() {
+				Ok(options) => options.unwrap_or_default(),
+				Err(err) => {
+					let _ = sink.reject(err);
+					return Ok(())
+				},
+			};
+			let filter = match EventFilter::new(&options, network_environment) {
+				Ok(filter) => filter,
+				Err(err) => {
+					let _ = sink.reject(CallError::InvalidParams(err));
+					return Ok(())
+				},
+			};
 			let mut witness_receiver = witness_sender.subscribe();
 
 			tokio::spawn(async move {
-				while let Ok(event) = witness_receiver.recv().await {
-					use codec::Encode;
-					if let Ok(false) = sink.send(&event.encode()) {
-						log::debug!("Subscription is closed");
-						break
+				while let Ok(call) = witness_receiver.recv().await {
+					let events = WitnessEvent::from_call(&call, network_environment)
+						.into_iter()
+						.filter(|event| filter.matches(event))
+						.collect::<Vec<_>>();
+					let messages = match options.encoding {
+						// The SCALE encoded calls are sent if any of their events match the
+						// filter, or if there is no filter, in which case all calls are sent.
+						Encoding::Scale if filter.is_unfiltered() || !events.is_empty() => {
+							use codec::Encode;
+							vec![serde_json::to_value(call.encode())]
+						},
+						Encoding::Scale => vec![],
+						Encoding::Json => events.iter().map(serde_json::to_value).collect(),
+					};
+					for message in messages {
+						match message {
+							Ok(message) =>
+								if let Ok(false) = sink.send(&message) {
+									log::debug!("Subscription is closed");
+									return
+								},
+							Err(err) => log::error!("Failed to serialize witnessed call: {}", err),
+						}
 					}
 				}
 			});
@@ -117,6 +276,13 @@ async fn main() -> anyhow::Result<()> {
 			basic_auth_user: env::var("BTC_USERNAME").unwrap_or("flip".to_string()),
 			basic_auth_password: env::var("BTC_PASSWORD").unwrap_or("flip".to_string()),
 		},
+		witness_history_db_path: env::var("WITNESS_HISTORY_DB_PATH")
+			.unwrap_or("witness_history".to_string())
+			.into(),
+		witness_history_max_records: env::var("WITNESS_HISTORY_MAX_RECORDS")
+			.ok()
+			.map(|max_records| max_records.parse())
+			.transpose()?,
 	};
 
 	task_scope::task_scope(|scope| async move { start(scope, settings).await }.boxed()).await
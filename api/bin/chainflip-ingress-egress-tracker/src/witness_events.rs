@@ -0,0 +1,398 @@
+use std::{collections::BTreeSet, str::FromStr};
+
+use anyhow::anyhow;
+use cf_chains::address::{
+	to_encoded_address, try_from_encoded_address, EncodedAddress, ForeignChainAddress,
+};
+use cf_primitives::{Asset, AssetAmount, ForeignChain, NetworkEnvironment};
+use codec::Encode;
+use serde::{Deserialize, Serialize};
+use sp_core::crypto::AccountId32;
+use state_chain_runtime::RuntimeCall;
+use utilities::rpc::NumberOrHex;
+
+use crate::witnessing::chain_of_address;
+
+/// The events of the witnessed calls, with human-readable addresses. Ethereum and Polkadot
+/// addresses are hex encoded, and Bitcoin addresses are encoded for the network. Amounts are hex
+/// encoded, as they may be too large for JSON numbers.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WitnessEvent {
+	/// A deposit into a deposit channel.
+	DepositWitnessed {
+		chain: ForeignChain,
+		deposit_address: String,
+		asset: Asset,
+		amount: NumberOrHex,
+		block_height: u64,
+		/// Only known for Bitcoin deposits.
+		tx_hash: Option<String>,
+	},
+	/// A transaction broadcast by the vault, e.g. of an egress, succeeded. The id of the
+	/// transaction is the transaction hash for Bitcoin, and the signature of the transaction for
+	/// Polkadot. For Ethereum it isn't the transaction hash, which isn't witnessed, but the
+	/// signature of the call to the Vault contract: its `s` followed by its `k_times_g_address`,
+	/// as passed to the contract in the call's `sigData`.
+	EgressBroadcast { chain: ForeignChain, tx_out_id: String, signer: String },
+	/// A transaction swapping through the Vault contract was confirmed.
+	TransactionConfirmed {
+		chain: ForeignChain,
+		tx_hash: String,
+		source_asset: Asset,
+		deposit_amount: NumberOrHex,
+		destination_asset: Asset,
+		destination_address: String,
+	},
+}
+
+fn human_readable(address: ForeignChainAddress, network: NetworkEnvironment) -> String {
+	to_encoded_address(address, || network).to_string()
+}
+
+fn hex_encoded(bytes: impl AsRef<[u8]>) -> String {
+	format!("0x{}", hex::encode(bytes))
+}
+
+/// Bitcoin hashes are displayed in the reverse of their internal byte order.
+fn btc_tx_hash(hash: &[u8; 32]) -> String {
+	hex_encoded(hash.iter().rev().copied().collect::<Vec<_>>())
+}
+
+impl WitnessEvent {
+	/// The events of the call, with addresses encoded for the network. Calls that aren't deposits,
+	/// broadcasts or swaps through the Vault contract have none.
+	pub fn from_call(call: &RuntimeCall, network: NetworkEnvironment) -> Vec<Self> {
+		match call {
+			RuntimeCall::EthereumIngressEgress(
+				pallet_cf_ingress_egress::Call::process_deposits {
+					deposit_witnesses,
+					block_height,
+				},
+			) => deposit_witnesses
+				.iter()
+				.map(|witness| WitnessEvent::DepositWitnessed {
+					chain: ForeignChain::Ethereum,
+					deposit_address: human_readable(
+						ForeignChainAddress::Eth(witness.deposit_address),
+						network,
+					),
+					asset: witness.asset.into(),
+					amount: witness.amount.into(),
+					block_height: *block_height,
+					tx_hash: None,
+				})
+				.collect(),
+			RuntimeCall::PolkadotIngressEgress(
+				pallet_cf_ingress_egress::Call::process_deposits {
+					deposit_witnesses,
+					block_height,
+				},
+			) => deposit_witnesses
+				.iter()
+				.map(|witness| WitnessEvent::DepositWitnessed {
+					chain: ForeignChain::Polkadot,
+					deposit_address: human_readable(
+						ForeignChainAddress::Dot(witness.deposit_address),
+						network,
+					),
+					asset: witness.asset.into(),
+					amount: witness.amount.into(),
+					block_height: (*block_height).into(),
+					tx_hash: None,
+				})
+				.collect(),
+			RuntimeCall::BitcoinIngressEgress(
+				pallet_cf_ingress_egress::Call::process_deposits {
+					deposit_witnesses,
+					block_height,
+				},
+			) => deposit_witnesses
+				.iter()
+				.map(|witness| WitnessEvent::DepositWitnessed {
+					chain: ForeignChain::Bitcoin,
+					deposit_address: human_readable(
+						ForeignChainAddress::Btc(witness.deposit_address.clone()),
+						network,
+					),
+					asset: witness.asset.into(),
+					amount: AssetAmount::from(witness.amount).into(),
+					block_height: *block_height,
+					tx_hash: Some(btc_tx_hash(&witness.deposit_details.tx_id)),
+				})
+				.collect(),
+			RuntimeCall::EthereumBroadcaster(
+				pallet_cf_broadcast::Call::transaction_succeeded { tx_out_id, signer_id, .. },
+			) => vec![WitnessEvent::EgressBroadcast {
+				chain: ForeignChain::Ethereum,
+				tx_out_id: hex_encoded(tx_out_id.encode()),
+				signer: human_readable(ForeignChainAddress::Eth(*signer_id), network),
+			}],
+			RuntimeCall::PolkadotBroadcaster(
+				pallet_cf_broadcast::Call::transaction_succeeded { tx_out_id, signer_id, .. },
+			) => vec![WitnessEvent::EgressBroadcast {
+				chain: ForeignChain::Polkadot,
+				tx_out_id: hex_encoded(tx_out_id.encode()),
+				signer: human_readable(ForeignChainAddress::Dot(*signer_id), network),
+			}],
+			RuntimeCall::BitcoinBroadcaster(pallet_cf_broadcast::Call::transaction_succeeded {
+				tx_out_id,
+				signer_id,
+				..
+			}) => vec![WitnessEvent::EgressBroadcast {
+				chain: ForeignChain::Bitcoin,
+				tx_out_id: btc_tx_hash(tx_out_id),
+				signer: human_readable(ForeignChainAddress::Btc(signer_id.clone()), network),
+			}],
+			RuntimeCall::Swapping(pallet_cf_swapping::Call::schedule_swap_from_contract {
+				from,
+				to,
+				deposit_amount,
+				destination_address,
+				tx_hash,
+			}) => vec![WitnessEvent::TransactionConfirmed {
+				chain: (*from).into(),
+				tx_hash: hex_encoded(tx_hash),
+				source_asset: *from,
+				deposit_amount: (*deposit_amount).into(),
+				destination_asset: *to,
+				destination_address: destination_address.to_string(),
+			}],
+			RuntimeCall::Swapping(pallet_cf_swapping::Call::ccm_deposit {
+				source_asset,
+				deposit_amount,
+				destination_asset,
+				destination_address,
+				tx_hash,
+				..
+			}) => vec![WitnessEvent::TransactionConfirmed {
+				chain: (*source_asset).into(),
+				tx_hash: hex_encoded(tx_hash),
+				source_asset: *source_asset,
+				deposit_amount: (*deposit_amount).into(),
+				destination_asset: *destination_asset,
+				destination_address: destination_address.to_string(),
+			}],
+			_ => vec![],
+		}
+	}
+
+	fn chain(&self) -> ForeignChain {
+		match self {
+			WitnessEvent::DepositWitnessed { chain, .. } |
+			WitnessEvent::EgressBroadcast { chain, .. } |
+			WitnessEvent::TransactionConfirmed { chain, .. } => *chain,
+		}
+	}
+
+	fn assets(&self) -> Vec<Asset> {
+		match self {
+			WitnessEvent::DepositWitnessed { asset, .. } => vec![*asset],
+			WitnessEvent::EgressBroadcast { .. } => vec![],
+			WitnessEvent::TransactionConfirmed { source_asset, destination_asset, .. } =>
+				vec![*source_asset, *destination_asset],
+		}
+	}
+
+	fn addresses(&self) -> Vec<&str> {
+		match self {
+			WitnessEvent::DepositWitnessed { deposit_address, .. } => vec![deposit_address],
+			WitnessEvent::EgressBroadcast { signer, .. } => vec![signer],
+			WitnessEvent::TransactionConfirmed { destination_address, .. } =>
+				vec![destination_address],
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Encoding {
+	/// The SCALE encoded calls.
+	#[default]
+	Scale,
+	/// The events of the calls, as JSON.
+	Json,
+}
+
+/// The parameters of `subscribe_witnessing`. Each filter matches the events of any of its values,
+/// and is ignored if it isn't given.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SubscriptionOptions {
+	pub encoding: Encoding,
+	pub chains: Option<Vec<ForeignChain>>,
+	pub assets: Option<Vec<Asset>>,
+	/// The addresses are matched against the deposit address of deposits, the signer of
+	/// broadcasts, and the destination address of swaps.
+	pub addresses: Option<Vec<String>>,
+}
+
+/// Addresses are compared in the form they are displayed in by the events, so the filter's
+/// addresses are validated for the network, and converted into that form.
+fn normalise_address(address: &str, network: NetworkEnvironment) -> anyhow::Result<String> {
+	let invalid_address = || anyhow!("Invalid address: {address}");
+	let encoded_address = match chain_of_address(address, &network.into())? {
+		ForeignChain::Ethereum => EncodedAddress::Eth(
+			hex::decode(&address[2..])
+				.ok()
+				.and_then(|bytes| bytes.try_into().ok())
+				.ok_or_else(invalid_address)?,
+		),
+		ForeignChain::Polkadot => EncodedAddress::Dot(
+			AccountId32::from_str(address).map_err(|_| invalid_address())?.into(),
+		),
+		ForeignChain::Bitcoin => EncodedAddress::Btc(address.as_bytes().to_vec()),
+	};
+	let address =
+		try_from_encoded_address(encoded_address, || network).map_err(|()| invalid_address())?;
+	Ok(human_readable(address, network))
+}
+
+/// Decides which events are sent to a subscriber. The addresses are matched against the events'
+/// addresses, which are encoded for the network.
+#[derive(Clone, Debug, Default)]
+pub struct EventFilter {
+	chains: Option<BTreeSet<ForeignChain>>,
+	assets: Option<BTreeSet<Asset>>,
+	addresses: Option<BTreeSet<String>>,
+}
+
+impl EventFilter {
+	pub fn new(options: &SubscriptionOptions, network: NetworkEnvironment) -> anyhow::Result<Self> {
+		Ok(Self {
+			chains: options.chains.as_ref().map(|chains| chains.iter().copied().collect()),
+			assets: options.assets.as_ref().map(|assets| assets.iter().copied().collect()),
+			addresses: options
+				.addresses
+				.as_ref()
+				.map(|addresses| {
+					addresses.iter().map(|address| normalise_address(address, network)).collect()
+				})
+				.transpose()?,
+		})
+	}
+
+	pub fn is_unfiltered(&self) -> bool {
+		self.chains.is_none() && self.assets.is_none() && self.addresses.is_none()
+	}
+
+	pub fn matches(&self, event: &WitnessEvent) -> bool {
+		self.chains.as_ref().map_or(true, |chains| chains.contains(&event.chain())) &&
+			self.assets
+				.as_ref()
+				.map_or(true, |assets| event.assets().iter().any(|asset| assets.contains(asset))) &&
+			self.addresses.as_ref().map_or(true, |addresses| {
+				event.addresses().into_iter().any(|address| addresses.contains(address))
+			})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use cf_chains::btc::{BitcoinNetwork, ScriptPubkey, UtxoId};
+	use cf_primitives::chains::assets::{btc, eth};
+	use pallet_cf_ingress_egress::DepositWitness;
+	use sp_core::{crypto::Ss58Codec, H160};
+
+	use super::*;
+
+	fn events(call: RuntimeCall) -> Vec<WitnessEvent> {
+		WitnessEvent::from_call(&call, NetworkEnvironment::Mainnet)
+	}
+
+	fn filter(
+		chains: Option<Vec<ForeignChain>>,
+		assets: Option<Vec<Asset>>,
+		addresses: Option<Vec<String>>,
+	) -> EventFilter {
+		EventFilter::new(
+			&SubscriptionOptions { encoding: Encoding::Json, chains, assets, addresses },
+			NetworkEnvironment::Mainnet,
+		)
+		.unwrap()
+	}
+
+	#[test]
+	fn deposits_are_decoded_with_human_readable_addresses() {
+		let btc_address = ScriptPubkey::Taproot([1; 32]);
+		let mut tx_id = [0; 32];
+		tx_id[0] = 2;
+
+		let event = events(RuntimeCall::BitcoinIngressEgress(
+			pallet_cf_ingress_egress::Call::process_deposits {
+				deposit_witnesses: vec![DepositWitness {
+					deposit_address: btc_address.clone(),
+					asset: btc::Asset::Btc,
+					amount: 1000,
+					deposit_details: UtxoId { tx_id, vout: 0 },
+				}],
+				block_height: 10,
+			},
+		));
+
+		let mut displayed_tx_id = [0; 32];
+		displayed_tx_id[31] = 2;
+		assert_eq!(
+			event,
+			vec![WitnessEvent::DepositWitnessed {
+				chain: ForeignChain::Bitcoin,
+				deposit_address: btc_address.to_address(&BitcoinNetwork::Mainnet),
+				asset: Asset::Btc,
+				amount: 1000u128.into(),
+				block_height: 10,
+				tx_hash: Some(hex_encoded(displayed_tx_id)),
+			}]
+		);
+		let json = serde_json::to_value(&event[0]).unwrap();
+		assert_eq!(json["type"], serde_json::json!("deposit_witnessed"));
+		assert_eq!(json["amount"], serde_json::json!("0x3e8"));
+	}
+
+	#[test]
+	fn events_are_filtered_by_chain_asset_and_address() {
+		let deposit_address = H160::repeat_byte(0xab);
+		let event = events(RuntimeCall::EthereumIngressEgress(
+			pallet_cf_ingress_egress::Call::process_deposits {
+				deposit_witnesses: vec![DepositWitness {
+					deposit_address,
+					asset: eth::Asset::Flip,
+					amount: 1000,
+					deposit_details: (),
+				}],
+				block_height: 10,
+			},
+		))
+		.pop()
+		.unwrap();
+
+		assert!(filter(None, None, None).is_unfiltered());
+		assert!(filter(None, None, None).matches(&event));
+		assert!(filter(Some(vec![ForeignChain::Ethereum]), None, None).matches(&event));
+		assert!(!filter(Some(vec![ForeignChain::Bitcoin]), None, None).matches(&event));
+		assert!(filter(None, Some(vec![Asset::Usdc, Asset::Flip]), None).matches(&event));
+		assert!(!filter(None, Some(vec![Asset::Eth]), None).matches(&event));
+		// Hex addresses are matched regardless of case.
+		let uppercase_address = format!("0x{}", hex::encode_upper(deposit_address));
+		assert!(filter(None, None, Some(vec![uppercase_address])).matches(&event));
+		assert!(!filter(
+			Some(vec![ForeignChain::Ethereum]),
+			None,
+			Some(vec![AccountId32::from([1; 32]).to_ss58check()])
+		)
+		.matches(&event));
+		// Addresses that aren't valid for any chain, or for the network, are rejected.
+		for invalid_address in [
+			"0xab".to_string(),
+			ScriptPubkey::Taproot([1; 32]).to_address(&BitcoinNetwork::Testnet),
+		] {
+			assert!(EventFilter::new(
+				&SubscriptionOptions {
+					addresses: Some(vec![invalid_address]),
+					..Default::default()
+				},
+				NetworkEnvironment::Mainnet
+			)
+			.is_err());
+		}
+	}
+}
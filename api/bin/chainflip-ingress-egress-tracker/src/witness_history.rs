@@ -0,0 +1,564 @@
+use std::{
+	ops::{Range, RangeInclusive},
+	path::Path,
+	str::FromStr,
+	sync::Mutex,
+};
+
+use anyhow::{anyhow, bail, Context};
+use cf_chains::btc::BitcoinNetwork;
+use cf_primitives::{EpochIndex, ForeignChain};
+use chainflip_engine::db::persistent::rocksdb_kv::{RocksDBKeyValueStore, PREFIX_SIZE};
+use codec::Encode;
+use scale_info::PortableRegistry;
+use serde::{Deserialize, Serialize};
+use sp_core::crypto::AccountId32;
+use state_chain_runtime::RuntimeCall;
+use tokio::sync::watch;
+
+use crate::witnessing::chain_of_address;
+
+/// The witnessed calls, keyed by their cursor.
+const RECORDS_PREFIX: &[u8; PREFIX_SIZE] = b"record____";
+/// Index entries are keyed by the indexed value followed by the cursor of the call, and their
+/// value is the cursor.
+const ADDRESS_INDEX_PREFIX: &[u8; PREFIX_SIZE] = b"address___";
+const TX_HASH_INDEX_PREFIX: &[u8; PREFIX_SIZE] = b"tx_hash___";
+const BLOCK_INDEX_PREFIX: &[u8; PREFIX_SIZE] = b"block_____";
+/// The keys of the index entries of each call, keyed by its cursor, so they can be pruned.
+const INDEX_KEYS_PREFIX: &[u8; PREFIX_SIZE] = b"index_keys";
+
+/// Keys used to store the cursors of the oldest call that hasn't been pruned, and of the next
+/// call, in the metadata column.
+const FIRST_CURSOR_KEY: &[u8; 12] = b"first_cursor";
+const NEXT_CURSOR_KEY: &[u8; 11] = b"next_cursor";
+
+pub const DEFAULT_PAGE_SIZE: u32 = 100;
+const MAX_PAGE_SIZE: u32 = 1000;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WitnessRecord {
+	/// The position of the call in the history. The cursors of consecutive calls are consecutive.
+	pub cursor: u64,
+	pub epoch_index: EpochIndex,
+	/// The call, decoded using the type information of the runtime.
+	pub call: serde_json::Value,
+	/// The hex encoded SCALE encoding of the call.
+	pub encoded_call: String,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Page {
+	pub records: Vec<WitnessRecord>,
+	/// Passed to the same query to get the next page. None if there are no more records.
+	pub next_page: Option<String>,
+}
+
+/// The values of a call that it can be looked up by.
+#[derive(Default, Debug, PartialEq)]
+struct IndexEntries {
+	deposit_addresses: Vec<Vec<u8>>,
+	tx_hashes: Vec<Vec<u8>>,
+	block: Option<(ForeignChain, u64)>,
+}
+
+/// Bitcoin hashes are stored in the internal byte order, but displayed reversed, so they are
+/// indexed in the order in which they are displayed.
+fn btc_display_order(hash: &[u8; 32]) -> Vec<u8> {
+	hash.iter().rev().copied().collect()
+}
+
+fn index_entries(call: &RuntimeCall, btc_network: &BitcoinNetwork) -> IndexEntries {
+	match call {
+		RuntimeCall::EthereumIngressEgress(pallet_cf_ingress_egress::Call::process_deposits {
+			deposit_witnesses,
+			block_height,
+		}) => IndexEntries {
+			deposit_addresses: deposit_witnesses
+				.iter()
+				.map(|witness| witness.deposit_address.0.to_vec())
+				.collect(),
+			tx_hashes: vec![],
+			block: Some((ForeignChain::Ethereum, *block_height)),
+		},
+		RuntimeCall::PolkadotIngressEgress(pallet_cf_ingress_egress::Call::process_deposits {
+			deposit_witnesses,
+			block_height,
+		}) => IndexEntries {
+			deposit_addresses: deposit_witnesses
+				.iter()
+				.map(|witness| witness.deposit_address.aliased_ref().to_vec())
+				.collect(),
+			tx_hashes: vec![],
+			block: Some((ForeignChain::Polkadot, (*block_height).into())),
+		},
+		RuntimeCall::BitcoinIngressEgress(pallet_cf_ingress_egress::Call::process_deposits {
+			deposit_witnesses,
+			block_height,
+		}) => IndexEntries {
+			deposit_addresses: deposit_witnesses
+				.iter()
+				.map(|witness| witness.deposit_address.to_address(btc_network).into_bytes())
+				.collect(),
+			tx_hashes: deposit_witnesses
+				.iter()
+				.map(|witness| btc_display_order(&witness.deposit_details.tx_id))
+				.collect(),
+			block: Some((ForeignChain::Bitcoin, *block_height)),
+		},
+		RuntimeCall::BitcoinBroadcaster(pallet_cf_broadcast::Call::transaction_succeeded {
+			tx_out_id,
+			..
+		}) => IndexEntries { tx_hashes: vec![btc_display_order(tx_out_id)], ..Default::default() },
+		RuntimeCall::Swapping(
+			pallet_cf_swapping::Call::schedule_swap_from_contract { tx_hash, .. } |
+			pallet_cf_swapping::Call::ccm_deposit { tx_hash, .. },
+		) => IndexEntries { tx_hashes: vec![tx_hash.to_vec()], ..Default::default() },
+		_ => Default::default(),
+	}
+}
+
+/// The keys of the index entries of the call with the given cursor.
+fn index_keys(
+	call: &RuntimeCall,
+	btc_network: &BitcoinNetwork,
+	cursor_bytes: &[u8],
+) -> Vec<Vec<u8>> {
+	let index_entries = index_entries(call, btc_network);
+	index_entries
+		.deposit_addresses
+		.iter()
+		.map(|address| {
+			[&ADDRESS_INDEX_PREFIX[..], &length_prefixed(address), cursor_bytes].concat()
+		})
+		.chain(index_entries.tx_hashes.iter().map(|tx_hash| {
+			[&TX_HASH_INDEX_PREFIX[..], &length_prefixed(tx_hash), cursor_bytes].concat()
+		}))
+		.chain(index_entries.block.map(|(chain, block_height)| {
+			[&BLOCK_INDEX_PREFIX[..], &chain.encode(), &block_height.to_be_bytes(), cursor_bytes]
+				.concat()
+		}))
+		.collect()
+}
+
+fn metadata_cursor(db: &RocksDBKeyValueStore, key: &[u8]) -> anyhow::Result<Option<u64>> {
+	db.get_metadata(key)
+		.map(|bytes| {
+			bytes
+				.try_into()
+				.map(u64::from_be_bytes)
+				.map_err(|_| anyhow!("Invalid cursor in the witness history"))
+		})
+		.transpose()
+}
+
+/// Prefixes variable length values with their length, so that no value is a prefix of another.
+fn length_prefixed(value: &[u8]) -> Vec<u8> {
+	[&[value.len() as u8][..], value].concat()
+}
+
+fn parse_hex(hex_string: &str) -> anyhow::Result<Vec<u8>> {
+	hex::decode(hex_string.strip_prefix("0x").unwrap_or(hex_string))
+		.map_err(|e| anyhow!("Invalid hex: {e}"))
+}
+
+fn parse_deposit_address(address: &str, btc_network: &BitcoinNetwork) -> anyhow::Result<Vec<u8>> {
+	Ok(match chain_of_address(address, btc_network)? {
+		ForeignChain::Ethereum => parse_hex(address)?,
+		ForeignChain::Polkadot => AccountId32::from_str(address)
+			.map(<[u8; 32]>::from)
+			.map_err(|e| anyhow!("Invalid address: {e}"))?
+			.to_vec(),
+		ForeignChain::Bitcoin => address.as_bytes().to_vec(),
+	})
+}
+
+fn parse_page_size(page_size: Option<u32>) -> anyhow::Result<usize> {
+	match page_size.unwrap_or(DEFAULT_PAGE_SIZE) {
+		0 => bail!("Page size must be greater than 0."),
+		page_size if page_size > MAX_PAGE_SIZE =>
+			bail!("Page size must be at most {MAX_PAGE_SIZE}."),
+		page_size => Ok(page_size as usize),
+	}
+}
+
+/// Stores every witnessed call, so they can be looked up, and so subscribers can catch up on the
+/// calls they missed. If there is a maximum number of calls, the oldest calls are pruned.
+pub struct WitnessHistory {
+	db: RocksDBKeyValueStore,
+	types: PortableRegistry,
+	call_type_id: u32,
+	btc_network: BitcoinNetwork,
+	max_records: Option<u64>,
+	/// The cursors of the calls that are stored. Held while a call is stored, so calls are written
+	/// in the order of their cursors.
+	cursors: Mutex<Range<u64>>,
+	/// Sends the next cursor whenever a call is stored.
+	next_cursor_sender: watch::Sender<u64>,
+}
+
+impl WitnessHistory {
+	pub fn open(
+		db_path: &Path,
+		max_records: Option<u64>,
+		btc_network: BitcoinNetwork,
+	) -> anyhow::Result<Self> {
+		if max_records == Some(0) {
+			bail!("The maximum number of witness records must be greater than 0.");
+		}
+		let db = RocksDBKeyValueStore::open(db_path)?;
+		let first_cursor = metadata_cursor(&db, FIRST_CURSOR_KEY)?.unwrap_or_default();
+		let next_cursor = metadata_cursor(&db, NEXT_CURSOR_KEY)?.unwrap_or_default();
+
+		let mut registry = scale_info::Registry::new();
+		let call_type_id = registry.register_type(&scale_info::meta_type::<RuntimeCall>()).id;
+
+		Ok(Self {
+			db,
+			types: registry.into(),
+			call_type_id,
+			btc_network,
+			max_records,
+			cursors: Mutex::new(first_cursor..next_cursor),
+			next_cursor_sender: watch::channel(next_cursor).0,
+		})
+	}
+
+	pub fn store(&self, call: &RuntimeCall, epoch_index: EpochIndex) -> anyhow::Result<()> {
+		let encoded_call = call.encode();
+		let decoded_call = scale_value::scale::decode_as_type(
+			&mut &encoded_call[..],
+			self.call_type_id,
+			&self.types,
+		)
+		.map_err(|e| anyhow!("Failed to decode call: {e:?}"))?;
+
+		let mut cursors = self.cursors.lock().unwrap();
+		let cursor = cursors.end;
+		let cursor_bytes = cursor.to_be_bytes();
+		let index_keys = index_keys(call, &self.btc_network, &cursor_bytes);
+
+		let record = WitnessRecord {
+			cursor,
+			epoch_index,
+			call: serde_json::to_value(decoded_call)?,
+			encoded_call: format!("0x{}", hex::encode(encoded_call)),
+		};
+
+		let mut batch = self.db.create_batch();
+		batch.put_value(
+			&[&RECORDS_PREFIX[..], &cursor_bytes].concat(),
+			&serde_json::to_vec(&record)?,
+		);
+		for key in &index_keys {
+			batch.put_value(key, &cursor_bytes);
+		}
+		batch.put_value(
+			&[&INDEX_KEYS_PREFIX[..], &cursor_bytes].concat(),
+			&serde_json::to_vec(&index_keys)?,
+		);
+
+		// Prunes the oldest calls, along with their index entries, so at most `max_records` are
+		// kept.
+		let mut first_cursor = cursors.start;
+		while self
+			.max_records
+			.is_some_and(|max_records| cursor + 1 - first_cursor > max_records)
+		{
+			let pruned_cursor_bytes = first_cursor.to_be_bytes();
+			let index_keys_key = [&INDEX_KEYS_PREFIX[..], &pruned_cursor_bytes].concat();
+			let pruned_index_keys: Vec<Vec<u8>> =
+				serde_json::from_slice(&self.db.get_value(&index_keys_key)?.ok_or_else(|| {
+					anyhow!("Index keys of witness record {first_cursor} are missing")
+				})?)
+				.context("Invalid index keys")?;
+			for key in pruned_index_keys {
+				batch.delete_value(&key);
+			}
+			batch.delete_value(&index_keys_key);
+			batch.delete_value(&[&RECORDS_PREFIX[..], &pruned_cursor_bytes].concat());
+			first_cursor += 1;
+		}
+
+		batch.put_metadata(FIRST_CURSOR_KEY, first_cursor.to_be_bytes());
+		batch.put_metadata(NEXT_CURSOR_KEY, (cursor + 1).to_be_bytes());
+		batch.write()?;
+
+		*cursors = first_cursor..cursor + 1;
+		self.next_cursor_sender.send_replace(cursors.end);
+
+		Ok(())
+	}
+
+	/// The cursor the next call will be stored with.
+	pub fn next_cursor(&self) -> u64 {
+		*self.next_cursor_sender.borrow()
+	}
+
+	/// Notifies of each change to the next cursor, i.e. whenever calls are stored.
+	pub fn subscribe(&self) -> watch::Receiver<u64> {
+		self.next_cursor_sender.subscribe()
+	}
+
+	/// Up to `limit` calls, starting with the call at `cursor`, or with the oldest call if the
+	/// call at `cursor` has been pruned.
+	pub fn records_from(&self, cursor: u64, limit: usize) -> anyhow::Result<Vec<WitnessRecord>> {
+		self.db
+			.get_values_from(RECORDS_PREFIX, &cursor.to_be_bytes())
+			.take(limit)
+			.map(|entry| serde_json::from_slice(&entry?.1).context("Invalid witness record"))
+			.collect()
+	}
+
+	fn record(&self, cursor_bytes: &[u8]) -> anyhow::Result<WitnessRecord> {
+		serde_json::from_slice(
+			&self
+				.db
+				.get_value(&[&RECORDS_PREFIX[..], cursor_bytes].concat())?
+				.ok_or_else(|| anyhow!("Indexed witness record is missing"))?,
+		)
+		.context("Invalid witness record")
+	}
+
+	/// Pages through the calls of the index entries that start with `prefix`, starting at the
+	/// entry following `prefix` with `start`, or at the one the `page` token points to, until
+	/// `in_range` returns false for the rest of an entry's key.
+	fn index_page(
+		&self,
+		prefix: &[u8],
+		start: &[u8],
+		in_range: impl Fn(&[u8]) -> bool,
+		page: Option<&str>,
+		page_size: Option<u32>,
+	) -> anyhow::Result<Page> {
+		let page_size = parse_page_size(page_size)?;
+		let start = page.map(parse_hex).transpose()?.unwrap_or_else(|| start.to_vec());
+
+		let mut entries = self
+			.db
+			.get_values_from(prefix, &start)
+			.take_while(|entry| entry.as_ref().map_or(true, |(key, _)| in_range(key)));
+
+		Ok(Page {
+			records: entries
+				.by_ref()
+				.take(page_size)
+				.map(|entry| self.record(&entry?.1))
+				.collect::<anyhow::Result<_>>()?,
+			next_page: entries
+				.next()
+				.transpose()?
+				.map(|(key, _)| format!("0x{}", hex::encode(key))),
+		})
+	}
+
+	/// The deposits to the address, in the order in which they were witnessed. Ethereum and
+	/// Polkadot addresses can be hex encoded, and Polkadot addresses can also be SS58 encoded.
+	pub fn by_deposit_address(
+		&self,
+		address: &str,
+		page: Option<&str>,
+		page_size: Option<u32>,
+	) -> anyhow::Result<Page> {
+		let prefix = [
+			&ADDRESS_INDEX_PREFIX[..],
+			&length_prefixed(&parse_deposit_address(address, &self.btc_network)?),
+		]
+		.concat();
+		self.index_page(&prefix, &[], |_| true, page, page_size)
+	}
+
+	/// The calls witnessing the transaction, in the order in which they were witnessed. These are
+	/// the Bitcoin deposits and broadcasts, and the Ethereum swaps made through the Vault
+	/// contract. Bitcoin transaction ids are in the order displayed by block explorers.
+	pub fn by_tx_hash(
+		&self,
+		tx_hash: &str,
+		page: Option<&str>,
+		page_size: Option<u32>,
+	) -> anyhow::Result<Page> {
+		let prefix = [&TX_HASH_INDEX_PREFIX[..], &length_prefixed(&parse_hex(tx_hash)?)].concat();
+		self.index_page(&prefix, &[], |_| true, page, page_size)
+	}
+
+	/// The deposits witnessed in the blocks of the chain, ordered by block.
+	pub fn by_block_range(
+		&self,
+		chain: ForeignChain,
+		blocks: RangeInclusive<u64>,
+		page: Option<&str>,
+		page_size: Option<u32>,
+	) -> anyhow::Result<Page> {
+		let prefix = [&BLOCK_INDEX_PREFIX[..], &chain.encode()].concat();
+		let last_block = *blocks.end();
+		self.index_page(
+			&prefix,
+			&blocks.start().to_be_bytes(),
+			|key| u64::from_be_bytes(key[..8].try_into().unwrap()) <= last_block,
+			page,
+			page_size,
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use cf_chains::{
+		btc::{ScriptPubkey, UtxoId},
+		dot::PolkadotAccountId,
+	};
+	use cf_primitives::chains::assets::{btc, dot};
+	use pallet_cf_ingress_egress::DepositWitness;
+	use sp_core::crypto::Ss58Codec;
+
+	use super::*;
+
+	fn btc_deposit(address: &ScriptPubkey, tx_id: [u8; 32], block_height: u64) -> RuntimeCall {
+		RuntimeCall::BitcoinIngressEgress(pallet_cf_ingress_egress::Call::process_deposits {
+			deposit_witnesses: vec![DepositWitness {
+				deposit_address: address.clone(),
+				asset: btc::Asset::Btc,
+				amount: 1000,
+				deposit_details: UtxoId { tx_id, vout: 0 },
+			}],
+			block_height,
+		})
+	}
+
+	fn dot_deposit(address: PolkadotAccountId, block_height: u32) -> RuntimeCall {
+		RuntimeCall::PolkadotIngressEgress(pallet_cf_ingress_egress::Call::process_deposits {
+			deposit_witnesses: vec![DepositWitness {
+				deposit_address: address,
+				asset: dot::Asset::Dot,
+				amount: 1000,
+				deposit_details: (),
+			}],
+			block_height,
+		})
+	}
+
+	fn cursors(page: &Page) -> Vec<u64> {
+		page.records.iter().map(|record| record.cursor).collect()
+	}
+
+	#[test]
+	fn calls_can_be_looked_up_by_address_tx_hash_and_block() {
+		let dir = tempfile::tempdir().unwrap();
+		let db_path = dir.path().join("witness_history");
+		let history = WitnessHistory::open(&db_path, None, BitcoinNetwork::Regtest).unwrap();
+
+		let btc_address = ScriptPubkey::Taproot([1; 32]);
+		let dot_address = PolkadotAccountId::from_aliased([2; 32]);
+		let mut tx_id = [0; 32];
+		tx_id[0] = 3;
+
+		for call in [
+			btc_deposit(&btc_address, tx_id, 10),
+			dot_deposit(dot_address, 10),
+			btc_deposit(&btc_address, [4; 32], 12),
+			btc_deposit(&ScriptPubkey::Taproot([5; 32]), [5; 32], 11),
+		] {
+			history.store(&call, 1).unwrap();
+		}
+		assert_eq!(history.next_cursor(), 4);
+
+		let page = history
+			.by_deposit_address(&btc_address.to_address(&BitcoinNetwork::Regtest), None, None)
+			.unwrap();
+		assert_eq!(cursors(&page), vec![0, 2]);
+		assert_eq!(page.next_page, None);
+		assert_eq!(page.records[0].epoch_index, 1);
+		assert_eq!(
+			page.records[0].encoded_call,
+			format!("0x{}", hex::encode(btc_deposit(&btc_address, tx_id, 10).encode()))
+		);
+
+		let dot_ss58 = AccountId32::from([2; 32]).to_ss58check();
+		assert_eq!(cursors(&history.by_deposit_address(&dot_ss58, None, None).unwrap()), vec![1]);
+
+		// Bitcoin transaction ids are looked up in display order.
+		let mut displayed_tx_id = [0; 32];
+		displayed_tx_id[31] = 3;
+		assert_eq!(
+			cursors(&history.by_tx_hash(&hex::encode(displayed_tx_id), None, None).unwrap()),
+			vec![0]
+		);
+
+		let page = history.by_block_range(ForeignChain::Bitcoin, 10..=11, None, Some(1)).unwrap();
+		assert_eq!(cursors(&page), vec![0]);
+		let page = history
+			.by_block_range(ForeignChain::Bitcoin, 10..=11, page.next_page.as_deref(), Some(1))
+			.unwrap();
+		assert_eq!(cursors(&page), vec![3]);
+		assert_eq!(page.next_page, None);
+	}
+
+	#[test]
+	fn history_is_resumed_after_reopening() {
+		let dir = tempfile::tempdir().unwrap();
+		let db_path = dir.path().join("witness_history");
+		let call = dot_deposit(PolkadotAccountId::from_aliased([2; 32]), 10);
+
+		{
+			let history = WitnessHistory::open(&db_path, None, BitcoinNetwork::Regtest).unwrap();
+			history.store(&call, 1).unwrap();
+			history.store(&call, 1).unwrap();
+		}
+
+		let history = WitnessHistory::open(&db_path, None, BitcoinNetwork::Regtest).unwrap();
+		let mut next_cursor = history.subscribe();
+		history.store(&call, 2).unwrap();
+		assert!(next_cursor.has_changed().unwrap());
+		assert_eq!(*next_cursor.borrow_and_update(), 3);
+
+		let records = history.records_from(1, 10).unwrap();
+		assert_eq!(
+			records
+				.iter()
+				.map(|record| (record.cursor, record.epoch_index))
+				.collect::<Vec<_>>(),
+			vec![(1, 1), (2, 2)]
+		);
+	}
+
+	#[test]
+	fn oldest_calls_are_pruned() {
+		let dir = tempfile::tempdir().unwrap();
+		let db_path = dir.path().join("witness_history");
+		let dot_address = PolkadotAccountId::from_aliased([2; 32]);
+		let dot_ss58 = AccountId32::from([2; 32]).to_ss58check();
+
+		{
+			let history = WitnessHistory::open(&db_path, Some(2), BitcoinNetwork::Regtest).unwrap();
+			for block_height in 10..13 {
+				history.store(&dot_deposit(dot_address, block_height), 1).unwrap();
+			}
+			assert_eq!(
+				cursors(&history.by_deposit_address(&dot_ss58, None, None).unwrap()),
+				vec![1, 2]
+			);
+		}
+
+		// The pruned calls are tracked when the history is reopened, with a lower maximum.
+		let history = WitnessHistory::open(&db_path, Some(1), BitcoinNetwork::Regtest).unwrap();
+		history.store(&dot_deposit(dot_address, 13), 1).unwrap();
+		assert_eq!(cursors(&history.by_deposit_address(&dot_ss58, None, None).unwrap()), vec![3]);
+		assert_eq!(
+			cursors(
+				&history
+					.by_block_range(ForeignChain::Polkadot, 0..=u64::MAX, None, None)
+					.unwrap()
+			),
+			vec![3]
+		);
+		assert_eq!(
+			history
+				.records_from(0, 10)
+				.unwrap()
+				.iter()
+				.map(|record| record.cursor)
+				.collect::<Vec<_>>(),
+			vec![3]
+		);
+	}
+}
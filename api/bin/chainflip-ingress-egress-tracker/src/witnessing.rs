@@ -1,12 +1,18 @@
 mod btc;
 pub mod btc_mempool;
 mod dot;
+pub mod dot_mempool;
 mod eth;
+pub mod eth_mempool;
 
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
-use cf_chains::dot::PolkadotHash;
-use cf_primitives::chains::assets::eth::Asset;
+use anyhow::anyhow;
+use cf_chains::{
+	btc::{BitcoinNetwork, ScriptPubkey},
+	dot::PolkadotHash,
+};
+use cf_primitives::{chains::assets::eth::Asset, ForeignChain, NetworkEnvironment};
 use chainflip_engine::{
 	state_chain_observer::{
 		self,
@@ -14,10 +20,102 @@ use chainflip_engine::{
 	},
 	witness::common::{epoch_source::EpochSource, STATE_CHAIN_CONNECTION},
 };
-use sp_core::H160;
+use serde::Serialize;
+use sp_core::{
+	crypto::{AccountId32, Ss58Codec},
+	H160,
+};
 use utilities::task_scope;
 
-use crate::DepositTrackerSettings;
+use crate::{witness_history::WitnessHistory, DepositTrackerSettings};
+
+/// The status of the latest transaction to an address, tagged with the address's chain.
+#[derive(Clone, Serialize)]
+#[serde(tag = "chain")]
+pub enum QueryResult {
+	Bitcoin(btc_mempool::QueryResult),
+	Ethereum(eth_mempool::QueryResult),
+	Polkadot(dot_mempool::QueryResult),
+}
+
+#[derive(Clone)]
+pub struct MempoolTrackers {
+	pub btc: btc_mempool::BtcTracker,
+	pub eth: eth_mempool::EthTracker,
+	pub dot: dot_mempool::DotTracker,
+	pub btc_network: BitcoinNetwork,
+}
+
+impl MempoolTrackers {
+	/// Looks up each address in the tracker of its chain. Fails if any of the addresses is invalid.
+	pub fn lookup_transactions(
+		&self,
+		addresses: &[String],
+	) -> anyhow::Result<Vec<Option<QueryResult>>> {
+		let mut results = vec![None; addresses.len()];
+		let chains = addresses
+			.iter()
+			.map(|address| chain_of_address(address, &self.btc_network))
+			.collect::<anyhow::Result<Vec<_>>>()?;
+
+		let addresses_of_chain = |chain: ForeignChain| {
+			addresses
+				.iter()
+				.enumerate()
+				.filter(|(index, _)| chains[*index] == chain)
+				.map(|(index, address)| (index, address.clone()))
+				.unzip::<_, _, Vec<_>, Vec<_>>()
+		};
+
+		let (indices, btc_addresses) = addresses_of_chain(ForeignChain::Bitcoin);
+		if !indices.is_empty() {
+			for (index, result) in
+				indices.into_iter().zip(self.btc.lookup_transactions(&btc_addresses)?)
+			{
+				results[index] = result.map(QueryResult::Bitcoin);
+			}
+		}
+
+		let (indices, eth_addresses) = addresses_of_chain(ForeignChain::Ethereum);
+		if !indices.is_empty() {
+			for (index, result) in
+				indices.into_iter().zip(self.eth.lookup_transactions(&eth_addresses)?)
+			{
+				results[index] = result.map(QueryResult::Ethereum);
+			}
+		}
+
+		let (indices, dot_addresses) = addresses_of_chain(ForeignChain::Polkadot);
+		if !indices.is_empty() {
+			for (index, result) in
+				indices.into_iter().zip(self.dot.lookup_transactions(&dot_addresses)?)
+			{
+				results[index] = result.map(QueryResult::Polkadot);
+			}
+		}
+
+		Ok(results)
+	}
+}
+
+/// The chain of an address. Ethereum addresses are 20 bytes of hex, Polkadot addresses are either
+/// SS58 or 32 bytes of hex, and Bitcoin addresses must be valid for the Bitcoin network.
+pub(crate) fn chain_of_address(
+	address: &str,
+	btc_network: &BitcoinNetwork,
+) -> anyhow::Result<ForeignChain> {
+	let hex_digits = address
+		.strip_prefix("0x")
+		.filter(|digits| digits.chars().all(|c| c.is_ascii_hexdigit()));
+	match hex_digits.map(str::len) {
+		Some(40) => Ok(ForeignChain::Ethereum),
+		Some(64) => Ok(ForeignChain::Polkadot),
+		_ if AccountId32::from_ss58check(address).is_ok() => Ok(ForeignChain::Polkadot),
+		_ if ScriptPubkey::try_from_address(address, btc_network).is_ok() =>
+			Ok(ForeignChain::Bitcoin),
+		_ => Err(anyhow!("Invalid address: {address}")),
+	}
+}
 
 #[derive(Clone)]
 struct EnvironmentParameters {
@@ -28,7 +126,8 @@ struct EnvironmentParameters {
 	usdc_contract_address: H160,
 	supported_erc20_tokens: HashMap<H160, cf_primitives::Asset>,
 	dot_genesis_hash: PolkadotHash,
-	btc_network: cf_chains::btc::BitcoinNetwork,
+	chainflip_network: NetworkEnvironment,
+	btc_network: BitcoinNetwork,
 }
 
 async fn get_env_parameters(state_chain_client: &StateChainClient<()>) -> EnvironmentParameters {
@@ -80,13 +179,12 @@ async fn get_env_parameters(state_chain_client: &StateChainClient<()>) -> Enviro
 		.await
 		.expect(STATE_CHAIN_CONNECTION);
 
-	let btc_network = state_chain_client
+	let chainflip_network = state_chain_client
 		.storage_value::<pallet_cf_environment::ChainflipNetworkEnvironment<state_chain_runtime::Runtime>>(
 			state_chain_client.latest_finalized_block().hash,
 		)
 		.await
-		.expect(STATE_CHAIN_CONNECTION)
-		.into();
+		.expect(STATE_CHAIN_CONNECTION);
 
 	EnvironmentParameters {
 		eth_chain_id,
@@ -96,15 +194,19 @@ async fn get_env_parameters(state_chain_client: &StateChainClient<()>) -> Enviro
 		eth_address_checker_address,
 		supported_erc20_tokens,
 		dot_genesis_hash,
-		btc_network,
+		chainflip_network,
+		btc_network: chainflip_network.into(),
 	}
 }
 
+const STORE_RETRY_DELAY: Duration = Duration::from_secs(5);
+
 pub(super) async fn start(
 	scope: &task_scope::Scope<'_, anyhow::Error>,
 	settings: DepositTrackerSettings,
+	btc_tracker: btc_mempool::BtcTracker,
 	witness_sender: tokio::sync::broadcast::Sender<state_chain_runtime::RuntimeCall>,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<(NetworkEnvironment, MempoolTrackers, Arc<WitnessHistory>)> {
 	let (state_chain_stream, unfinalized_chain_stream, state_chain_client) = {
 		state_chain_observer::client::StateChainClient::connect_without_account(
 			scope,
@@ -115,15 +217,45 @@ pub(super) async fn start(
 	};
 
 	let env_params = get_env_parameters(&state_chain_client).await;
+	let network_environment = env_params.chainflip_network;
+
+	let witness_history = Arc::new(WitnessHistory::open(
+		&settings.witness_history_db_path,
+		settings.witness_history_max_records,
+		env_params.btc_network,
+	)?);
+
+	let mempool_trackers = MempoolTrackers {
+		btc: btc_tracker,
+		eth: eth_mempool::start(
+			scope,
+			settings.eth_node.ws_endpoint.clone(),
+			env_params.supported_erc20_tokens.clone(),
+		),
+		dot: dot_mempool::start(
+			scope,
+			settings.dot_node.http_endpoint.clone(),
+			env_params.dot_genesis_hash,
+		)?,
+		btc_network: env_params.btc_network,
+	};
 
 	let epoch_source =
 		EpochSource::builder(scope, state_chain_stream.clone(), state_chain_client.clone()).await;
 
 	let witness_call = {
 		let witness_sender = witness_sender.clone();
-		move |call: state_chain_runtime::RuntimeCall, _epoch_index| {
+		let witness_history = witness_history.clone();
+		move |call: state_chain_runtime::RuntimeCall, epoch_index| {
 			let witness_sender = witness_sender.clone();
+			let witness_history = witness_history.clone();
 			async move {
+				// Calls are only broadcast once they are stored, so subscribers resuming from the
+				// witness history don't miss any.
+				while let Err(err) = witness_history.store(&call, epoch_index) {
+					tracing::error!("Failed to store witnesser call {:?}, retrying: {}", call, err);
+					tokio::time::sleep(STORE_RETRY_DELAY).await;
+				}
 				// Send may fail if there aren't any subscribers,
 				// but it is safe to ignore the error.
 				if let Ok(n) = witness_sender.send(call.clone()) {
@@ -166,5 +298,5 @@ pub(super) async fn start(
 	)
 	.await?;
 
-	Ok(())
+	Ok((network_environment, mempool_trackers, witness_history))
 }
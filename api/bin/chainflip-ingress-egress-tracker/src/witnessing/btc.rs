@@ -35,7 +35,7 @@ where
 {
 	let btc_client = BtcRetryRpcClient::new(
 		scope,
-		NodeContainer { primary: settings.btc, backup: None },
+		NodeContainer { primary: settings.btc, backup: None, additional: vec![], quorum: None },
 		env_params.btc_network,
 	)
 	.await?;
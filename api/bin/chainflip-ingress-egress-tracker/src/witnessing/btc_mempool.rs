@@ -226,6 +226,7 @@ mod tests {
 	use bitcoin::{
 		absolute::LockTime,
 		address::{self},
+		bip158::BlockFilter,
 		block::{Header, Version},
 		hash_types::TxMerkleNode,
 		hashes::Hash,
@@ -276,6 +277,10 @@ mod tests {
 			unimplemented!()
 		}
 
+		async fn block_filter(&self, _block_hash: BlockHash) -> anyhow::Result<BlockFilter> {
+			unimplemented!()
+		}
+
 		async fn send_raw_transaction(&self, _transaction_bytes: Vec<u8>) -> anyhow::Result<Txid> {
 			unimplemented!()
 		}
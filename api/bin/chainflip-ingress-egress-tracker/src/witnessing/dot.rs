@@ -41,7 +41,12 @@ where
 {
 	let dot_client = DotRetryRpcClient::new(
 		scope,
-		NodeContainer { primary: settings.dot_node, backup: None },
+		NodeContainer {
+			primary: settings.dot_node,
+			backup: None,
+			additional: vec![],
+			quorum: None,
+		},
 		env_params.dot_genesis_hash,
 	)?;
 
@@ -0,0 +1,376 @@
+use std::{
+	collections::BTreeMap,
+	str::FromStr,
+	sync::{Arc, Mutex},
+	time::Duration,
+};
+
+use anyhow::{anyhow, bail};
+use cf_chains::dot::{PolkadotAccountId, PolkadotAccountIdLookup, PolkadotBalance, PolkadotHash};
+use chainflip_engine::dot::{http_rpc::DotHttpRpcClient, rpc::DotRpcApi};
+use codec::{Compact, Decode};
+use scale_info::PortableRegistry;
+use serde::Serialize;
+use sp_core::crypto::AccountId32;
+use sp_runtime::{MultiAddress, MultiSignature};
+use tracing::{error, info, warn};
+use utilities::{redact_endpoint_secret::SecretUrl, task_scope};
+
+#[derive(Clone, Serialize, Debug, PartialEq)]
+pub struct QueryResult {
+	confirmations: u32,
+	destination: PolkadotAccountId,
+	value: f64,
+	tx_hash: PolkadotHash,
+}
+
+#[derive(Default, Clone)]
+enum CacheStatus {
+	#[default]
+	Init,
+	Ready,
+	Down,
+}
+
+#[derive(Clone, Default)]
+struct Cache {
+	status: CacheStatus,
+	best_block_hash: PolkadotHash,
+	transactions: BTreeMap<PolkadotAccountId, QueryResult>,
+}
+
+const SAFETY_MARGIN: u32 = 10;
+const REFRESH_INTERVAL: u64 = 6;
+const PLANCKS_PER_DOT: PolkadotBalance = 10_000_000_000;
+
+/// Decodes the DOT transfers in extrinsics, using the runtime's metadata for the index of the
+/// Balances pallet and its transfer calls, and for the signed extensions of the extrinsic format.
+#[derive(Clone)]
+struct TransferDecoder {
+	spec_version: u32,
+	types: PortableRegistry,
+	signed_extension_types: Vec<u32>,
+	balances_pallet_index: u8,
+	transfer_call_indices: Vec<u8>,
+}
+
+impl TransferDecoder {
+	fn from_metadata(metadata: &subxt::Metadata, spec_version: u32) -> anyhow::Result<Self> {
+		let balances = metadata
+			.pallet_by_name("Balances")
+			.ok_or_else(|| anyhow!("The runtime has no Balances pallet"))?;
+		Ok(Self {
+			spec_version,
+			types: metadata.types().clone(),
+			signed_extension_types: metadata
+				.extrinsic()
+				.signed_extensions()
+				.iter()
+				.map(|signed_extension| signed_extension.extra_ty())
+				.collect(),
+			balances_pallet_index: balances.index(),
+			transfer_call_indices: ["transfer", "transfer_allow_death", "transfer_keep_alive"]
+				.into_iter()
+				.filter_map(|call| balances.call_variant_by_name(call))
+				.map(|variant| variant.index)
+				.collect(),
+		})
+	}
+
+	/// The destination and value of a transfer, or `None` if the extrinsic makes any other call.
+	fn decode(
+		&self,
+		extrinsic: &[u8],
+	) -> anyhow::Result<Option<(PolkadotAccountId, PolkadotBalance)>> {
+		const SIGNED_EXTRINSIC_V4: u8 = 0b1000_0100;
+
+		let input = &mut &extrinsic[..];
+		let _length = Compact::<u32>::decode(input)?;
+		// Unsigned extrinsics, i.e. inherents, aren't transfers.
+		match u8::decode(input)? {
+			SIGNED_EXTRINSIC_V4 => {},
+			version if version & 0b1000_0000 == 0 => return Ok(None),
+			version => bail!("Unsupported extrinsic version {version:#x}"),
+		}
+		let _signer = MultiAddress::<PolkadotAccountId, ()>::decode(input)?;
+		let _signature = MultiSignature::decode(input)?;
+		for signed_extension_type in &self.signed_extension_types {
+			scale_value::scale::decode_as_type(input, *signed_extension_type, &self.types)
+				.map_err(|e| anyhow!("Failed to decode signed extension: {e:?}"))?;
+		}
+
+		let [pallet_index, call_index] = <[u8; 2]>::decode(input)?;
+		if pallet_index != self.balances_pallet_index ||
+			!self.transfer_call_indices.contains(&call_index)
+		{
+			return Ok(None)
+		}
+		match (PolkadotAccountIdLookup::decode(input)?, Compact::<PolkadotBalance>::decode(input)?)
+		{
+			(MultiAddress::Id(destination), Compact(value)) => Ok(Some((destination, value))),
+			_ => Ok(None),
+		}
+	}
+
+	fn transfer(&self, extrinsic: &[u8], confirmations: u32) -> Option<QueryResult> {
+		let tx_hash = sp_core::blake2_256(extrinsic).into();
+		match self.decode(extrinsic) {
+			Ok(transfer) => transfer.map(|(destination, value)| QueryResult {
+				confirmations,
+				destination,
+				value: value as f64 / PLANCKS_PER_DOT as f64,
+				tx_hash,
+			}),
+			Err(err) => {
+				warn!("Failed to decode Polkadot extrinsic {tx_hash:?}: {err}");
+				None
+			},
+		}
+	}
+}
+
+/// Returns the decoder for the runtime of the given block, reusing the previous decoder unless
+/// there has been a runtime upgrade.
+async fn updated_transfer_decoder(
+	dot: &DotHttpRpcClient,
+	block_hash: PolkadotHash,
+	previous_decoder: Option<TransferDecoder>,
+) -> anyhow::Result<TransferDecoder> {
+	let spec_version = dot.runtime_version(Some(block_hash)).await?.spec_version;
+	match previous_decoder {
+		Some(decoder) if decoder.spec_version == spec_version => Ok(decoder),
+		_ => TransferDecoder::from_metadata(&dot.metadata(block_hash).await?, spec_version),
+	}
+}
+
+async fn get_updated_cache<T: DotRpcApi>(
+	dot: &T,
+	decoder: &TransferDecoder,
+	block_hash: PolkadotHash,
+	previous_cache: Cache,
+) -> anyhow::Result<Cache> {
+	let mut transactions: BTreeMap<PolkadotAccountId, QueryResult> = dot
+		.pending_extrinsics()
+		.await?
+		.iter()
+		.filter_map(|extrinsic| decoder.transfer(&extrinsic.0, 0))
+		.map(|transfer| (transfer.destination, transfer))
+		.collect();
+
+	if previous_cache.best_block_hash == block_hash {
+		for entry in previous_cache.transactions {
+			if entry.1.confirmations > 0 {
+				transactions.insert(entry.0, entry.1);
+			}
+		}
+	} else {
+		info!("New Polkadot block found: {}", block_hash);
+		let mut block_hash_to_query = block_hash;
+		for confirmations in 1..SAFETY_MARGIN {
+			let block = dot
+				.block(block_hash_to_query)
+				.await?
+				.ok_or_else(|| anyhow!("Block {block_hash_to_query} missing"))?
+				.block;
+			for extrinsic in block.extrinsics {
+				if let Some(transfer) = decoder.transfer(&extrinsic.0, confirmations) {
+					transactions.insert(transfer.destination, transfer);
+				}
+			}
+			block_hash_to_query = block.header.parent_hash;
+		}
+	}
+	Ok(Cache { status: CacheStatus::Ready, best_block_hash: block_hash, transactions })
+}
+
+fn lookup_transactions(
+	cache: &Cache,
+	addresses: &[String],
+) -> anyhow::Result<Vec<Option<QueryResult>>> {
+	let addresses = addresses
+		.iter()
+		.map(|address| {
+			AccountId32::from_str(address)
+				.map(|account_id| PolkadotAccountId::from_aliased(account_id.into()))
+				.map_err(|e| anyhow!("Invalid address: {e}"))
+		})
+		.collect::<anyhow::Result<Vec<_>>>()?;
+
+	match cache.status {
+		CacheStatus::Ready => Ok(addresses
+			.iter()
+			.map(|address| cache.transactions.get(address).cloned())
+			.collect()),
+		CacheStatus::Init => Err(anyhow!("Address cache is not initialised.")),
+		CacheStatus::Down => Err(anyhow!("Address cache is down - check dot connection.")),
+	}
+}
+
+#[derive(Clone)]
+pub struct DotTracker {
+	cache: Arc<Mutex<Cache>>,
+}
+
+impl DotTracker {
+	pub fn lookup_transactions(
+		&self,
+		addresses: &[String],
+	) -> anyhow::Result<Vec<Option<QueryResult>>> {
+		lookup_transactions(&self.cache.lock().unwrap(), addresses)
+	}
+}
+
+pub fn start(
+	scope: &task_scope::Scope<'_, anyhow::Error>,
+	http_endpoint: SecretUrl,
+	genesis_hash: PolkadotHash,
+) -> anyhow::Result<DotTracker> {
+	let cache: Arc<Mutex<Cache>> = Default::default();
+	let client = DotHttpRpcClient::new(http_endpoint, Some(genesis_hash))?;
+	scope.spawn({
+		let cache = cache.clone();
+		async move {
+			let client = client.await;
+			let mut interval = tokio::time::interval(Duration::from_secs(REFRESH_INTERVAL));
+			interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+			let mut decoder = None;
+			loop {
+				interval.tick().await;
+				let cache_copy = cache.lock().unwrap().clone();
+				let updated_cache = async {
+					let block_hash = client.best_block_hash().await?;
+					let updated_decoder =
+						updated_transfer_decoder(&client, block_hash, decoder.take()).await?;
+					let updated_cache =
+						get_updated_cache(&client, &updated_decoder, block_hash, cache_copy).await;
+					decoder = Some(updated_decoder);
+					updated_cache
+				}
+				.await;
+				match updated_cache {
+					Ok(updated_cache) => {
+						let mut cache = cache.lock().unwrap();
+						*cache = updated_cache;
+					},
+					Err(err) => {
+						error!("Error when querying Polkadot chain: {}", err);
+						let mut cache = cache.lock().unwrap();
+						cache.status = CacheStatus::Down;
+					},
+				}
+			}
+		}
+	});
+
+	Ok(DotTracker { cache })
+}
+
+#[cfg(test)]
+mod tests {
+	use cf_chains::dot::{BalancesCall, PolkadotRuntimeCall, PolkadotSignedExtra};
+	use codec::Encode;
+	use sp_core::sr25519;
+	use sp_runtime::generic::UncheckedExtrinsic;
+
+	use super::*;
+
+	/// A decoder for the extrinsics that Chainflip builds for Polkadot.
+	fn decoder() -> TransferDecoder {
+		let mut registry = scale_info::Registry::new();
+		let signed_extra_type =
+			registry.register_type(&scale_info::meta_type::<PolkadotSignedExtra>()).id;
+		TransferDecoder {
+			spec_version: 0,
+			types: registry.into(),
+			signed_extension_types: vec![signed_extra_type],
+			balances_pallet_index: 5,
+			transfer_call_indices: vec![0, 3],
+		}
+	}
+
+	fn encoded_extrinsic(call: PolkadotRuntimeCall) -> Vec<u8> {
+		UncheckedExtrinsic::new_signed(
+			call,
+			MultiAddress::<PolkadotAccountId, ()>::Id(PolkadotAccountId::from_aliased([1; 32])),
+			MultiSignature::Sr25519(sr25519::Signature::from_raw([2; 64])),
+			// immortal, with a nonce and tip of zero
+			PolkadotSignedExtra::decode(&mut &[0u8, 0, 0][..]).unwrap(),
+		)
+		.encode()
+	}
+
+	#[test]
+	fn transfers_are_decoded() {
+		let destination = PolkadotAccountId::from_aliased([3; 32]);
+		let extrinsic = encoded_extrinsic(PolkadotRuntimeCall::Balances(BalancesCall::transfer {
+			dest: MultiAddress::Id(destination),
+			value: 25_000_000_000,
+		}));
+
+		assert_eq!(
+			decoder().transfer(&extrinsic, 2),
+			Some(QueryResult {
+				confirmations: 2,
+				destination,
+				value: 2.5,
+				tx_hash: sp_core::blake2_256(&extrinsic).into(),
+			})
+		);
+	}
+
+	#[test]
+	fn other_calls_are_ignored() {
+		assert_eq!(
+			decoder().transfer(
+				&encoded_extrinsic(PolkadotRuntimeCall::Balances(BalancesCall::transfer_all {
+					dest: MultiAddress::Id(PolkadotAccountId::from_aliased([3; 32])),
+					keep_alive: false,
+				})),
+				0
+			),
+			None
+		);
+	}
+
+	#[test]
+	fn truncated_extrinsics_fail_to_decode() {
+		let extrinsic = encoded_extrinsic(PolkadotRuntimeCall::Balances(BalancesCall::transfer {
+			dest: MultiAddress::Id(PolkadotAccountId::from_aliased([3; 32])),
+			value: 25_000_000_000,
+		}));
+
+		assert!(decoder().decode(&extrinsic[..extrinsic.len() - 1]).is_err());
+		assert_eq!(decoder().transfer(&extrinsic[..extrinsic.len() - 1], 0), None);
+	}
+
+	#[test]
+	fn lookup_accepts_ss58_and_hex_addresses() {
+		let destination = PolkadotAccountId::from_aliased([3; 32]);
+		let cache = Cache {
+			status: CacheStatus::Ready,
+			best_block_hash: Default::default(),
+			transactions: BTreeMap::from([(
+				destination,
+				QueryResult {
+					confirmations: 0,
+					destination,
+					value: 1.0,
+					tx_hash: Default::default(),
+				},
+			)]),
+		};
+
+		let result = lookup_transactions(
+			&cache,
+			&[
+				AccountId32::from([3; 32]).to_string(),
+				format!("{:?}", sp_core::H256::repeat_byte(3)),
+				AccountId32::from([4; 32]).to_string(),
+			],
+		)
+		.unwrap();
+		assert_eq!(result[0].as_ref().unwrap().destination, destination);
+		assert_eq!(result[1].as_ref().unwrap().destination, destination);
+		assert!(result[2].is_none());
+	}
+}
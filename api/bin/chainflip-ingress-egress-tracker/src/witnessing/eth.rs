@@ -38,7 +38,12 @@ where
 	ProcessingFut: futures::Future<Output = ()> + Send + 'static,
 {
 	let eth_client = {
-		let nodes = NodeContainer { primary: settings.eth_node.clone(), backup: None };
+		let nodes = NodeContainer {
+			primary: settings.eth_node.clone(),
+			backup: None,
+			additional: vec![],
+			quorum: None,
+		};
 
 		EthersRetryRpcClient::new(
 			scope,
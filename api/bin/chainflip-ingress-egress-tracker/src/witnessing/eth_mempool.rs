@@ -0,0 +1,400 @@
+use std::{
+	collections::{HashMap, HashSet},
+	str::FromStr,
+	sync::{Arc, Mutex},
+	time::Duration,
+};
+
+use anyhow::anyhow;
+use cf_primitives::Asset;
+use ethers::{
+	abi::{ParamType, Token},
+	providers::{Middleware, Provider, Ws},
+	types::{BlockId, BlockNumber, Transaction, H160, H256, U256},
+};
+use futures::StreamExt;
+use serde::Serialize;
+use tracing::{error, info};
+use utilities::{redact_endpoint_secret::SecretUrl, task_scope};
+
+#[derive(Clone, Serialize, Debug, PartialEq)]
+pub struct QueryResult {
+	confirmations: u32,
+	destination: H160,
+	asset: Asset,
+	// in the smallest unit of the asset, i.e. wei for ETH.
+	amount: U256,
+	tx_hash: H256,
+}
+
+#[derive(Default, Clone)]
+enum CacheStatus {
+	#[default]
+	Init,
+	Ready,
+	Down,
+}
+
+#[derive(Clone)]
+struct BlockTransfers {
+	hash: H256,
+	number: u64,
+	parent_hash: H256,
+	transfers: Vec<QueryResult>,
+}
+
+#[derive(Default)]
+struct Cache {
+	status: CacheStatus,
+	best_block_number: u64,
+	// Transfers of the transactions seen in the mempool, and the best block number when they were
+	// first seen, so they can be forgotten if they are never mined.
+	pending: HashMap<H256, (u64, Vec<QueryResult>)>,
+	transactions: HashMap<H160, QueryResult>,
+}
+
+const SAFETY_MARGIN: u32 = 10;
+const REFRESH_INTERVAL: u64 = 6;
+const PENDING_TRANSACTION_EXPIRY_BLOCKS: u64 = 50;
+
+const ERC20_TRANSFER_SELECTOR: [u8; 4] = [0xa9, 0x05, 0x9c, 0xbb];
+const ERC20_TRANSFER_FROM_SELECTOR: [u8; 4] = [0x23, 0xb8, 0x72, 0xdd];
+
+/// The deposits a transaction makes, either of ETH, or of an ERC-20 token by calling the token
+/// contract's `transfer` or `transferFrom` directly. Tokens transferred by other contracts can't
+/// be seen without executing the transaction, so are only reported once witnessed.
+fn transfers(
+	tx: &Transaction,
+	erc20_tokens: &HashMap<H160, Asset>,
+	confirmations: u32,
+) -> Vec<QueryResult> {
+	let Some(to) = tx.to else { return vec![] };
+
+	let mut transfers = vec![];
+	if !tx.value.is_zero() {
+		transfers.push((to, Asset::Eth, tx.value));
+	}
+	if let Some(asset) = erc20_tokens.get(&to) {
+		if let Some((recipient, amount)) = decode_erc20_transfer(&tx.input) {
+			transfers.push((recipient, *asset, amount));
+		}
+	}
+
+	transfers
+		.into_iter()
+		.map(|(destination, asset, amount)| QueryResult {
+			confirmations,
+			destination,
+			asset,
+			amount,
+			tx_hash: tx.hash,
+		})
+		.collect()
+}
+
+fn decode_erc20_transfer(input: &[u8]) -> Option<(H160, U256)> {
+	let (recipient_index, parameters) = match <[u8; 4]>::try_from(input.get(..4)?).ok()? {
+		ERC20_TRANSFER_SELECTOR => (0, vec![ParamType::Address, ParamType::Uint(256)]),
+		ERC20_TRANSFER_FROM_SELECTOR =>
+			(1, vec![ParamType::Address, ParamType::Address, ParamType::Uint(256)]),
+		_ => return None,
+	};
+	let tokens = ethers::abi::decode(&parameters, &input[4..]).ok()?;
+	match (tokens.get(recipient_index), tokens.last()) {
+		(Some(Token::Address(recipient)), Some(Token::Uint(amount))) => Some((*recipient, *amount)),
+		_ => None,
+	}
+}
+
+impl Cache {
+	fn add_pending_transaction(&mut self, tx: &Transaction, erc20_tokens: &HashMap<H160, Asset>) {
+		// The transaction may have been mined by the time we fetched it, and until the first block
+		// is fetched we can't tell when a transaction was first seen.
+		if tx.block_hash.is_some() || matches!(self.status, CacheStatus::Init) {
+			return
+		}
+		let transfers = transfers(tx, erc20_tokens, 0);
+		if !transfers.is_empty() {
+			for transfer in &transfers {
+				self.transactions
+					.entry(transfer.destination)
+					.or_insert_with(|| transfer.clone());
+			}
+			self.pending.insert(tx.hash, (self.best_block_number, transfers));
+		}
+	}
+
+	/// `blocks` are the latest blocks, starting with the best block.
+	fn update_blocks(&mut self, blocks: &[BlockTransfers]) {
+		if let Some(best_block) = blocks.first() {
+			self.best_block_number = best_block.number;
+		}
+
+		let mined_tx_hashes: HashSet<H256> = blocks
+			.iter()
+			.flat_map(|block| block.transfers.iter().map(|transfer| transfer.tx_hash))
+			.collect();
+		let best_block_number = self.best_block_number;
+		self.pending.retain(|tx_hash, (first_seen, _)| {
+			!mined_tx_hashes.contains(tx_hash) &&
+				best_block_number.saturating_sub(*first_seen) < PENDING_TRANSACTION_EXPIRY_BLOCKS
+		});
+
+		// Later inserts replace earlier ones, so we report the oldest transfer to each address.
+		self.transactions = self
+			.pending
+			.values()
+			.flat_map(|(_, transfers)| transfers.iter().cloned())
+			.chain(blocks.iter().zip(1..).flat_map(|(block, confirmations)| {
+				block
+					.transfers
+					.iter()
+					.map(move |transfer| QueryResult { confirmations, ..transfer.clone() })
+			}))
+			.map(|transfer| (transfer.destination, transfer))
+			.collect();
+		self.status = CacheStatus::Ready;
+	}
+}
+
+/// Fetches the latest `SAFETY_MARGIN` blocks, following the parent hashes from the best block so
+/// reorgs are handled, and only fetching the blocks that aren't in `known_blocks` already.
+async fn latest_blocks(
+	eth: &Provider<Ws>,
+	known_blocks: &HashMap<H256, BlockTransfers>,
+	erc20_tokens: &HashMap<H160, Asset>,
+) -> anyhow::Result<Vec<BlockTransfers>> {
+	let mut blocks = Vec::new();
+	let mut next_block_hash = None;
+	for _ in 0..SAFETY_MARGIN {
+		let block = match next_block_hash.and_then(|hash| known_blocks.get(&hash)) {
+			Some(block) => block.clone(),
+			None => {
+				let block = eth
+					.get_block_with_txs(
+						next_block_hash.map_or(BlockId::Number(BlockNumber::Latest), BlockId::Hash),
+					)
+					.await?
+					.ok_or_else(|| anyhow!("Block {next_block_hash:?} not found"))?;
+				BlockTransfers {
+					hash: block.hash.ok_or_else(|| anyhow!("Block is pending"))?,
+					number: block.number.ok_or_else(|| anyhow!("Block is pending"))?.as_u64(),
+					parent_hash: block.parent_hash,
+					transfers: block
+						.transactions
+						.iter()
+						.flat_map(|tx| transfers(tx, erc20_tokens, 0))
+						.collect(),
+				}
+			},
+		};
+		next_block_hash = Some(block.parent_hash);
+		blocks.push(block);
+	}
+	Ok(blocks)
+}
+
+fn lookup_transactions(
+	cache: &Cache,
+	addresses: &[String],
+) -> anyhow::Result<Vec<Option<QueryResult>>> {
+	let addresses = addresses
+		.iter()
+		.map(|address| H160::from_str(address).map_err(|e| anyhow!("Invalid address: {e}")))
+		.collect::<anyhow::Result<Vec<_>>>()?;
+
+	match cache.status {
+		CacheStatus::Ready => Ok(addresses
+			.iter()
+			.map(|address| cache.transactions.get(address).cloned())
+			.collect()),
+		CacheStatus::Init => Err(anyhow!("Address cache is not initialised.")),
+		CacheStatus::Down => Err(anyhow!("Address cache is down - check eth connection.")),
+	}
+}
+
+async fn track_mempool(
+	ws_endpoint: &SecretUrl,
+	erc20_tokens: &HashMap<H160, Asset>,
+	cache: &Mutex<Cache>,
+) -> anyhow::Result<()> {
+	let provider = Provider::<Ws>::connect(ws_endpoint.as_ref()).await?;
+	// Subscribing to the full transactions, rather than to their hashes, saves requesting each
+	// transaction in the mempool. The node must support this, as Geth does.
+	let mut pending_transactions =
+		provider.subscribe::<_, Transaction>(("newPendingTransactions", true)).await?;
+
+	let mut interval = tokio::time::interval(Duration::from_secs(REFRESH_INTERVAL));
+	interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+	let mut known_blocks = HashMap::new();
+	loop {
+		tokio::select! {
+			tx = pending_transactions.next() => match tx {
+				Some(tx) => cache.lock().unwrap().add_pending_transaction(&tx, erc20_tokens),
+				None => return Err(anyhow!("Pending transaction subscription ended")),
+			},
+			_ = interval.tick() => {
+				let blocks = latest_blocks(&provider, &known_blocks, erc20_tokens).await?;
+				if blocks.first().map(|block| block.number) !=
+					Some(cache.lock().unwrap().best_block_number)
+				{
+					info!("New Ethereum block found: {}", blocks[0].hash);
+				}
+				cache.lock().unwrap().update_blocks(&blocks);
+				known_blocks = blocks.into_iter().map(|block| (block.hash, block)).collect();
+			},
+		}
+	}
+}
+
+#[derive(Clone)]
+pub struct EthTracker {
+	cache: Arc<Mutex<Cache>>,
+}
+
+impl EthTracker {
+	pub fn lookup_transactions(
+		&self,
+		addresses: &[String],
+	) -> anyhow::Result<Vec<Option<QueryResult>>> {
+		lookup_transactions(&self.cache.lock().unwrap(), addresses)
+	}
+}
+
+pub fn start(
+	scope: &task_scope::Scope<'_, anyhow::Error>,
+	ws_endpoint: SecretUrl,
+	erc20_tokens: HashMap<H160, Asset>,
+) -> EthTracker {
+	let cache: Arc<Mutex<Cache>> = Default::default();
+	scope.spawn({
+		let cache = cache.clone();
+		async move {
+			loop {
+				if let Err(err) = track_mempool(&ws_endpoint, &erc20_tokens, &cache).await {
+					error!("Error when tracking the Ethereum mempool: {}", err);
+					cache.lock().unwrap().status = CacheStatus::Down;
+				}
+				tokio::time::sleep(Duration::from_secs(REFRESH_INTERVAL)).await;
+			}
+		}
+	});
+
+	EthTracker { cache }
+}
+
+#[cfg(test)]
+mod tests {
+	use ethers::abi::encode;
+
+	use super::*;
+
+	const FLIP: H160 = H160([1; 20]);
+
+	fn erc20_tokens() -> HashMap<H160, Asset> {
+		HashMap::from([(FLIP, Asset::Flip)])
+	}
+
+	fn tx(hash: u8, to: H160, value: u64, input: Vec<u8>) -> Transaction {
+		Transaction {
+			hash: H256::repeat_byte(hash),
+			to: Some(to),
+			value: value.into(),
+			input: input.into(),
+			..Default::default()
+		}
+	}
+
+	fn erc20_transfer(recipient: H160, amount: u64) -> Vec<u8> {
+		[
+			ERC20_TRANSFER_SELECTOR.to_vec(),
+			encode(&[Token::Address(recipient), Token::Uint(amount.into())]),
+		]
+		.concat()
+	}
+
+	fn block(number: u64, transactions: Vec<Transaction>) -> BlockTransfers {
+		BlockTransfers {
+			hash: H256::from_low_u64_be(number),
+			number,
+			parent_hash: H256::from_low_u64_be(number - 1),
+			transfers: transactions
+				.iter()
+				.flat_map(|tx| transfers(tx, &erc20_tokens(), 0))
+				.collect(),
+		}
+	}
+
+	#[test]
+	fn eth_and_erc20_transfers_are_found() {
+		let (address1, address2) = (H160::repeat_byte(2), H160::repeat_byte(3));
+		let transfer_from = [
+			ERC20_TRANSFER_FROM_SELECTOR.to_vec(),
+			encode(&[Token::Address(address1), Token::Address(address2), Token::Uint(7.into())]),
+		]
+		.concat();
+
+		assert_eq!(
+			transfers(&tx(1, address1, 5, vec![]), &erc20_tokens(), 0)
+				.into_iter()
+				.map(|transfer| (transfer.destination, transfer.asset, transfer.amount))
+				.collect::<Vec<_>>(),
+			vec![(address1, Asset::Eth, 5.into())]
+		);
+		assert_eq!(
+			transfers(&tx(2, FLIP, 0, erc20_transfer(address2, 6)), &erc20_tokens(), 0)
+				.into_iter()
+				.map(|transfer| (transfer.destination, transfer.asset, transfer.amount))
+				.collect::<Vec<_>>(),
+			vec![(address2, Asset::Flip, 6.into())]
+		);
+		assert_eq!(
+			transfers(&tx(3, FLIP, 0, transfer_from), &erc20_tokens(), 0)
+				.into_iter()
+				.map(|transfer| (transfer.destination, transfer.asset, transfer.amount))
+				.collect::<Vec<_>>(),
+			vec![(address2, Asset::Flip, 7.into())]
+		);
+		// Calls to other contracts, and other calls to the token contract, aren't transfers.
+		assert!(transfers(&tx(4, address1, 0, erc20_transfer(address2, 6)), &erc20_tokens(), 0)
+			.is_empty());
+		assert!(transfers(&tx(5, FLIP, 0, vec![1, 2, 3, 4]), &erc20_tokens(), 0).is_empty());
+	}
+
+	#[test]
+	fn pending_transactions_are_confirmed_by_blocks() {
+		let (address1, address2) = (H160::repeat_byte(2), H160::repeat_byte(3));
+		let mut cache = Cache::default();
+		let pending_tx = tx(1, address1, 5, vec![]);
+
+		cache.update_blocks(&[block(100, vec![])]);
+		cache.add_pending_transaction(&pending_tx, &erc20_tokens());
+		cache
+			.add_pending_transaction(&tx(2, FLIP, 0, erc20_transfer(address2, 6)), &erc20_tokens());
+
+		let result = lookup_transactions(
+			&cache,
+			&[format!("{address1:?}"), format!("{address2:?}"), format!("{FLIP:?}")],
+		)
+		.unwrap();
+		assert_eq!(result[0].as_ref().unwrap().confirmations, 0);
+		assert_eq!(result[1].as_ref().unwrap().asset, Asset::Flip);
+		assert!(result[2].is_none());
+
+		cache.update_blocks(&[block(102, vec![]), block(101, vec![pending_tx])]);
+		let result = lookup_transactions(&cache, &[format!("{address1:?}")]).unwrap();
+		assert_eq!(result[0].as_ref().unwrap().confirmations, 2);
+		assert!(!cache.pending.contains_key(&H256::repeat_byte(1)));
+
+		// Transactions that are never mined are eventually forgotten.
+		cache.update_blocks(&[block(100 + PENDING_TRANSACTION_EXPIRY_BLOCKS, vec![])]);
+		let result = lookup_transactions(&cache, &[format!("{address2:?}")]).unwrap();
+		assert!(result[0].is_none());
+	}
+
+	#[test]
+	fn lookup_fails_until_ready() {
+		let cache = Cache::default();
+		assert!(lookup_transactions(&cache, &[format!("{FLIP:?}")]).is_err());
+	}
+}
@@ -5,6 +5,7 @@ use chainflip_engine::state_chain_observer::client::{
 	chain_api::ChainApi, storage_api::StorageApi,
 };
 use codec::Decode;
+use custom_rpc::{CustomApiClient, RpcRotationDryRun};
 use frame_support::sp_runtime::DigestItem;
 use pallet_cf_ingress_egress::DepositChannelDetails;
 use pallet_cf_validator::RotationPhase;
@@ -173,6 +174,24 @@ impl QueryApi {
 			.await?)
 	}
 
+	/// Simulates the next authority rotation against the state at the given block. Ceremonies are
+	/// mocked and succeed unless one of the `offline` accounts is required to participate.
+	pub async fn rotation_dry_run(
+		&self,
+		block_hash: Option<state_chain_runtime::Hash>,
+		offline: Vec<state_chain_runtime::AccountId>,
+	) -> Result<RpcRotationDryRun> {
+		let block_hash =
+			block_hash.unwrap_or_else(|| self.state_chain_client.latest_finalized_block().hash);
+
+		Ok(self
+			.state_chain_client
+			.base_rpc_client
+			.raw_rpc_client
+			.cf_rotation_dry_run(Some(offline), Some(block_hash))
+			.await?)
+	}
+
 	pub async fn pre_update_check(
 		&self,
 		block_hash: Option<state_chain_runtime::Hash>,
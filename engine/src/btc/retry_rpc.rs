@@ -1,10 +1,13 @@
-use bitcoin::{Block, BlockHash, Txid};
+pub mod fixture;
+
+use bitcoin::{bip158::BlockFilter, Block, BlockHash, Txid};
+use serde::Serialize;
 use utilities::task_scope::Scope;
 
 use crate::{
-	retrier::{Attempt, RequestLog, RetrierClient},
+	retrier::{Attempt, QuorumRetrierClient, RequestLog, RetrierClient, TypedFutureGenerator},
 	settings::{HttpBasicAuthEndpoint, NodeContainer},
-	witness::common::chain_source::{ChainClient, Header},
+	witness::common::chain_source::{fixture::FixtureRecorder, ChainClient, Header},
 };
 use cf_chains::{btc::BitcoinNetwork, Bitcoin};
 use core::time::Duration;
@@ -16,6 +19,9 @@ use super::rpc::{BlockHeader, BtcRpcApi, BtcRpcClient};
 #[derive(Clone)]
 pub struct BtcRetryRpcClient {
 	retry_client: RetrierClient<BtcRpcClient>,
+	// Used for witnessing-critical requests if a quorum of providers is configured.
+	quorum_retry_client: Option<QuorumRetrierClient<BtcRpcClient>>,
+	recorder: Option<FixtureRecorder>,
 }
 
 const BITCOIN_RPC_TIMEOUT: Duration = Duration::from_millis(4 * 1000);
@@ -29,6 +35,21 @@ impl BtcRetryRpcClient {
 		nodes: NodeContainer<HttpBasicAuthEndpoint>,
 		expected_btc_network: BitcoinNetwork,
 	) -> Result<Self> {
+		let quorum_rpc_clients = nodes
+			.quorum
+			.map(|quorum| {
+				Result::<_, anyhow::Error>::Ok((
+					nodes
+						.providers()
+						.map(|endpoint| {
+							BtcRpcClient::new(endpoint.clone(), Some(expected_btc_network))
+						})
+						.collect::<Result<Vec<_>>>()?,
+					quorum,
+				))
+			})
+			.transpose()?;
+
 		let rpc_client = BtcRpcClient::new(nodes.primary, Some(expected_btc_network))?;
 
 		let backup_rpc_client = nodes
@@ -45,8 +66,45 @@ impl BtcRetryRpcClient {
 				BITCOIN_RPC_TIMEOUT,
 				MAX_CONCURRENT_SUBMISSIONS,
 			),
+			quorum_retry_client: quorum_rpc_clients.map(|(rpc_clients, quorum)| {
+				QuorumRetrierClient::new(
+					scope,
+					"btc_rpc_quorum",
+					rpc_clients,
+					quorum,
+					BITCOIN_RPC_TIMEOUT,
+					MAX_CONCURRENT_SUBMISSIONS,
+				)
+			}),
+			recorder: None,
 		})
 	}
+
+	/// Records the responses to the requests made through this client, so they can be returned
+	/// by a [fixture::FixtureBtcRpcClient].
+	pub fn with_recorder(self, recorder: Option<FixtureRecorder>) -> Self {
+		Self { recorder, ..self }
+	}
+
+	fn record<Response: Serialize>(&self, method: &str, request: String, response: &Response) {
+		if let Some(recorder) = &self.recorder {
+			recorder.record_response(method, request, response);
+		}
+	}
+
+	/// Requests that witnessing depends on are checked against a quorum of providers, if one is
+	/// configured.
+	async fn witnessing_request<T: PartialEq + Clone + Send + 'static>(
+		&self,
+		specific_closure: TypedFutureGenerator<T, BtcRpcClient>,
+		request_log: RequestLog,
+	) -> T {
+		match &self.quorum_retry_client {
+			Some(quorum_retry_client) =>
+				quorum_retry_client.request(specific_closure, request_log).await,
+			None => self.retry_client.request(specific_closure, request_log).await,
+		}
+	}
 }
 
 #[async_trait::async_trait]
@@ -55,6 +113,8 @@ pub trait BtcRetryRpcApi: Clone {
 
 	async fn block_hash(&self, block_number: cf_chains::btc::BlockNumber) -> BlockHash;
 
+	async fn block_filter(&self, block_hash: BlockHash) -> BlockFilter;
+
 	async fn send_raw_transaction(&self, transaction_bytes: Vec<u8>) -> anyhow::Result<Txid>;
 
 	async fn next_block_fee_rate(&self) -> Option<cf_chains::btc::BtcAmount>;
@@ -67,19 +127,22 @@ pub trait BtcRetryRpcApi: Clone {
 #[async_trait::async_trait]
 impl BtcRetryRpcApi for BtcRetryRpcClient {
 	async fn block(&self, block_hash: BlockHash) -> Block {
-		self.retry_client
-			.request(
+		let block = self
+			.witnessing_request(
 				Box::pin(move |client| {
 					#[allow(clippy::redundant_async_block)]
 					Box::pin(async move { client.block(block_hash).await })
 				}),
 				RequestLog::new("block".to_string(), Some(format!("{block_hash}"))),
 			)
-			.await
+			.await;
+		self.record("block", format!("{block_hash}"), &block);
+		block
 	}
 
 	async fn block_hash(&self, block_number: cf_chains::btc::BlockNumber) -> BlockHash {
-		self.retry_client
+		let block_hash = self
+			.retry_client
 			.request(
 				Box::pin(move |client| {
 					#[allow(clippy::redundant_async_block)]
@@ -87,7 +150,25 @@ impl BtcRetryRpcApi for BtcRetryRpcClient {
 				}),
 				RequestLog::new("block_hash".to_string(), Some(format!("{block_number}"))),
 			)
-			.await
+			.await;
+		self.record("block_hash", format!("{block_number}"), &block_hash);
+		block_hash
+	}
+
+	async fn block_filter(&self, block_hash: BlockHash) -> BlockFilter {
+		// A false negative would mean missing a deposit, so this is checked like the block itself.
+		let block_filter = self
+			.witnessing_request(
+				Box::pin(move |client| {
+					#[allow(clippy::redundant_async_block)]
+					Box::pin(async move { client.block_filter(block_hash).await })
+				}),
+				RequestLog::new("block_filter".to_string(), Some(format!("{block_hash}"))),
+			)
+			.await;
+		// Filters don't support serde, so the content is recorded hex encoded.
+		self.record("block_filter", format!("{block_hash}"), &hex::encode(&block_filter.content));
+		block_filter
 	}
 
 	async fn send_raw_transaction(&self, transaction_bytes: Vec<u8>) -> anyhow::Result<Txid> {
@@ -109,7 +190,8 @@ impl BtcRetryRpcApi for BtcRetryRpcClient {
 	}
 
 	async fn next_block_fee_rate(&self) -> Option<cf_chains::btc::BtcAmount> {
-		self.retry_client
+		let fee_rate = self
+			.retry_client
 			.request(
 				Box::pin(move |client| {
 					#[allow(clippy::redundant_async_block)]
@@ -117,11 +199,14 @@ impl BtcRetryRpcApi for BtcRetryRpcClient {
 				}),
 				RequestLog::new("next_block_fee_rate".to_string(), None),
 			)
-			.await
+			.await;
+		self.record("next_block_fee_rate", String::new(), &fee_rate);
+		fee_rate
 	}
 
 	async fn average_block_fee_rate(&self, block_hash: BlockHash) -> cf_chains::btc::BtcAmount {
-		self.retry_client
+		let fee_rate = self
+			.retry_client
 			.request(
 				Box::pin(move |client| {
 					#[allow(clippy::redundant_async_block)]
@@ -132,11 +217,14 @@ impl BtcRetryRpcApi for BtcRetryRpcClient {
 					Some(format!("{block_hash}")),
 				),
 			)
-			.await
+			.await;
+		self.record("average_block_fee_rate", format!("{block_hash}"), &fee_rate);
+		fee_rate
 	}
 
 	async fn best_block_header(&self) -> BlockHeader {
-		self.retry_client
+		let header = self
+			.retry_client
 			.request(
 				Box::pin(move |client| {
 					#[allow(clippy::redundant_async_block)]
@@ -149,7 +237,9 @@ impl BtcRetryRpcApi for BtcRetryRpcClient {
 				}),
 				RequestLog::new("best_block_header".to_string(), None),
 			)
-			.await
+			.await;
+		self.record("best_block_header", String::new(), &header);
+		header
 	}
 }
 
@@ -205,6 +295,8 @@ pub mod mocks {
 
 			async fn block_hash(&self, block_number: cf_chains::btc::BlockNumber) -> BlockHash;
 
+			async fn block_filter(&self, block_hash: BlockHash) -> BlockFilter;
+
 			async fn send_raw_transaction(&self, transaction_bytes: Vec<u8>) -> anyhow::Result<Txid>;
 
 			async fn next_block_fee_rate(&self) -> Option<cf_chains::btc::BtcAmount>;
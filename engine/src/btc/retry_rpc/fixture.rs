@@ -0,0 +1,79 @@
+use anyhow::anyhow;
+use bitcoin::{bip158::BlockFilter, Block, BlockHash, Txid};
+use cf_chains::Bitcoin;
+
+use crate::{
+	btc::rpc::BlockHeader,
+	witness::common::chain_source::{
+		fixture::{FixtureClient, FixtureResponses, FixtureSource},
+		ChainClient, Header,
+	},
+};
+
+use super::BtcRetryRpcApi;
+
+/// Returns the responses recorded in a fixture by a `BtcRetryRpcClient`, so the Bitcoin witnessers
+/// can be run offline. Transactions can't be broadcast.
+#[derive(Clone)]
+pub struct FixtureBtcRpcClient {
+	headers: FixtureClient<u64, BlockHash, ()>,
+	responses: FixtureResponses,
+}
+
+impl FixtureBtcRpcClient {
+	pub fn new(source: &FixtureSource<Bitcoin, BlockHash, ()>) -> Self {
+		Self { headers: source.client(), responses: source.responses() }
+	}
+}
+
+#[async_trait::async_trait]
+impl BtcRetryRpcApi for FixtureBtcRpcClient {
+	async fn block(&self, block_hash: BlockHash) -> Block {
+		self.responses.get("block", format!("{block_hash}")).await
+	}
+
+	async fn block_hash(&self, block_number: cf_chains::btc::BlockNumber) -> BlockHash {
+		self.responses.get("block_hash", format!("{block_number}")).await
+	}
+
+	async fn block_filter(&self, block_hash: BlockHash) -> BlockFilter {
+		let content = self.responses.get::<String>("block_filter", format!("{block_hash}")).await;
+		match hex::decode(&content) {
+			Ok(content) => BlockFilter::new(&content),
+			Err(error) => {
+				tracing::error!("Invalid filter of block {block_hash} in the fixture: {error}");
+				futures::future::pending().await
+			},
+		}
+	}
+
+	async fn send_raw_transaction(&self, _transaction_bytes: Vec<u8>) -> anyhow::Result<Txid> {
+		Err(anyhow!("Transactions can't be broadcast while replaying a fixture"))
+	}
+
+	async fn next_block_fee_rate(&self) -> Option<cf_chains::btc::BtcAmount> {
+		self.responses.get("next_block_fee_rate", String::new()).await
+	}
+
+	async fn average_block_fee_rate(&self, block_hash: BlockHash) -> cf_chains::btc::BtcAmount {
+		self.responses.get("average_block_fee_rate", format!("{block_hash}")).await
+	}
+
+	async fn best_block_header(&self) -> BlockHeader {
+		self.responses.get("best_block_header", String::new()).await
+	}
+}
+
+#[async_trait::async_trait]
+impl ChainClient for FixtureBtcRpcClient {
+	type Index = <Bitcoin as cf_chains::Chain>::ChainBlockNumber;
+	type Hash = BlockHash;
+	type Data = ();
+
+	async fn header_at_index(
+		&self,
+		index: Self::Index,
+	) -> Header<Self::Index, Self::Hash, Self::Data> {
+		self.headers.header_at_index(index).await
+	}
+}
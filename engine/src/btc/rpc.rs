@@ -8,7 +8,7 @@ use serde::{Deserialize, Serialize};
 use serde;
 use serde_json::json;
 
-use bitcoin::{block::Version, Amount, Block, BlockHash, Transaction, Txid};
+use bitcoin::{bip158::BlockFilter, block::Version, Amount, Block, BlockHash, Transaction, Txid};
 use tracing::error;
 use utilities::make_periodic_tick;
 
@@ -242,6 +242,8 @@ pub trait BtcRpcApi {
 		block_number: cf_chains::btc::BlockNumber,
 	) -> anyhow::Result<BlockHash>;
 
+	async fn block_filter(&self, block_hash: BlockHash) -> anyhow::Result<BlockFilter>;
+
 	async fn send_raw_transaction(&self, transaction_bytes: Vec<u8>) -> anyhow::Result<Txid>;
 
 	async fn next_block_fee_rate(&self) -> anyhow::Result<Option<cf_chains::btc::BtcAmount>>;
@@ -288,6 +290,26 @@ impl BtcRpcApi for BtcRpcClient {
 			.ok_or_else(|| anyhow!("Response missing block hash"))?)
 	}
 
+	async fn block_filter(&self, block_hash: BlockHash) -> anyhow::Result<BlockFilter> {
+		// https://developer.bitcoin.org/reference/rpc/getblockfilter.html
+		#[derive(Deserialize)]
+		struct BlockFilterResponse {
+			filter: String,
+		}
+
+		let response: BlockFilterResponse = self
+			.call_rpc(
+				"getblockfilter",
+				ReqParams::Batch(vec![json!([json!(block_hash), json!("basic")])]),
+			)
+			.await?
+			.into_iter()
+			.next()
+			.ok_or_else(|| anyhow!("Response missing block filter"))?;
+
+		Ok(BlockFilter::new(&hex::decode(response.filter).context("Response not valid hex")?))
+	}
+
 	async fn send_raw_transaction(&self, transaction_bytes: Vec<u8>) -> anyhow::Result<Txid> {
 		Ok(self
 			.call_rpc(
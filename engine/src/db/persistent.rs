@@ -1,4 +1,4 @@
-mod rocksdb_kv;
+pub mod rocksdb_kv;
 #[cfg(test)]
 mod tests;
 
@@ -1,6 +1,9 @@
 use std::path::Path;
 
-use rocksdb::{ColumnFamily, ColumnFamilyDescriptor, Options, WriteBatch, DB};
+use rocksdb::{
+	ColumnFamily, ColumnFamilyDescriptor, Direction, IteratorMode, Options, ReadOptions,
+	WriteBatch, DB,
+};
 use serde::{de::DeserializeOwned, Serialize};
 
 use anyhow::{Context, Result};
@@ -97,6 +100,43 @@ impl RocksDBKeyValueStore {
 			})
 	}
 
+	/// Gets a value from the data column by its raw key, which includes the prefix.
+	pub fn get_value(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+		self.db
+			.get_cf(get_data_column_handle(&self.db), key)
+			.context("Failed to read data from database.")
+	}
+
+	/// Iterates, in key order, over the raw keys and values that start with `prefix`, beginning
+	/// at the key `[prefix, start].concat()`. The returned keys have `prefix` removed. The prefix
+	/// must be at least `PREFIX_SIZE` long, and may be longer, so keys can be grouped within a
+	/// key space, e.g. by the bytes of some id that follow the fixed size prefix. Iteration
+	/// continues past any errors reading the database, which are returned as items.
+	pub fn get_values_from<'a>(
+		&'a self,
+		prefix: &'a [u8],
+		start: &[u8],
+	) -> impl Iterator<Item = Result<(Vec<u8>, Vec<u8>)>> + 'a {
+		assert!(prefix.len() >= PREFIX_SIZE);
+
+		let mut read_options = ReadOptions::default();
+		read_options.set_prefix_same_as_start(true);
+
+		self.db
+			.iterator_cf_opt(
+				get_data_column_handle(&self.db),
+				read_options,
+				IteratorMode::From(&[prefix, start].concat(), Direction::Forward),
+			)
+			.map(|result| result.context("Failed to read data from database."))
+			.take_while(move |result| {
+				result.as_ref().map_or(true, |(key, _)| key.starts_with(prefix))
+			})
+			.map(move |result| {
+				result.map(|(key, value)| (Vec::from(&key[prefix.len()..]), Vec::from(value)))
+			})
+	}
+
 	pub fn put_metadata<V>(&self, key: &[u8], value: V) -> Result<()>
 	where
 		V: AsRef<[u8]>,
@@ -123,12 +163,10 @@ pub struct KVWriteBatch<'a> {
 }
 
 impl<'a> KVWriteBatch<'a> {
-	#[allow(dead_code)]
 	pub fn put_value(&mut self, key: &[u8], value: &[u8]) {
 		self.batch.put_cf(get_data_column_handle(self.db), key, value);
 	}
 
-	#[allow(dead_code)]
 	pub fn delete_value(&mut self, key: &[u8]) {
 		self.batch.delete_cf(get_data_column_handle(self.db), key);
 	}
@@ -311,3 +311,37 @@ fn test_migration_to_latest_from_0() {
 
 	assert_eq!(db.get_schema_version().unwrap(), LATEST_SCHEMA_VERSION);
 }
+
+#[test]
+fn can_iterate_over_values_from_a_key() {
+	let (_dir, db_path) = new_temp_directory_with_nonexistent_file();
+	let kv_db = RocksDBKeyValueStore::open(&db_path).unwrap();
+
+	const PREFIX: &[u8; PREFIX_SIZE] = b"test______";
+	const OTHER_PREFIX: &[u8; PREFIX_SIZE] = b"other_____";
+
+	let mut batch = kv_db.create_batch();
+	for (prefix, key) in
+		[(PREFIX, [1u8, 1]), (PREFIX, [1, 2]), (PREFIX, [2, 1]), (OTHER_PREFIX, [1, 3])]
+	{
+		batch.put_value(&[&prefix[..], &key].concat(), &key);
+	}
+	batch.write().unwrap();
+
+	assert_eq!(kv_db.get_value(&[&PREFIX[..], &[1, 2]].concat()).unwrap(), Some(vec![1, 2]));
+	assert_eq!(kv_db.get_value(&[&PREFIX[..], &[1, 3]].concat()).unwrap(), None);
+
+	assert_eq!(
+		kv_db.get_values_from(PREFIX, &[1, 2]).collect::<Result<Vec<_>>>().unwrap(),
+		vec![(vec![1, 2], vec![1, 2]), (vec![2, 1], vec![2, 1])]
+	);
+	// Keys can be grouped by extending the prefix.
+	let extended_prefix = [&PREFIX[..], &[1]].concat();
+	assert_eq!(
+		kv_db
+			.get_values_from(&extended_prefix, &[])
+			.collect::<Result<Vec<_>>>()
+			.unwrap(),
+		vec![(vec![1], vec![1, 1]), (vec![2], vec![1, 2])]
+	);
+}
@@ -137,6 +137,14 @@ impl DotRpcApi for DotHttpRpcClient {
 		Ok(self.online_client.rpc().block_hash(Some(block_number.into())).await?)
 	}
 
+	async fn best_block_hash(&self) -> Result<PolkadotHash> {
+		self.online_client
+			.rpc()
+			.block_hash(None)
+			.await?
+			.ok_or_else(|| anyhow::anyhow!("No best block hash"))
+	}
+
 	async fn block(
 		&self,
 		block_hash: PolkadotHash,
@@ -209,6 +217,14 @@ impl DotRpcApi for DotHttpRpcClient {
 			.request("author_submitExtrinsic", rpc_params![encoded_bytes.clone()])
 			.await?)
 	}
+
+	async fn pending_extrinsics(&self) -> Result<Vec<Bytes>> {
+		Ok(self
+			.online_client
+			.rpc()
+			.request("author_pendingExtrinsics", rpc_params![])
+			.await?)
+	}
 }
 
 #[cfg(test)]
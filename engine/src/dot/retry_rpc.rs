@@ -1,8 +1,10 @@
+pub mod fixture;
+
 use crate::{
 	common::option_inner,
 	retrier::Attempt,
 	settings::{NodeContainer, WsHttpEndpoints},
-	witness::common::chain_source::{ChainClient, Header},
+	witness::common::chain_source::{fixture::FixtureRecorder, ChainClient, Header},
 };
 use cf_chains::{
 	dot::{PolkadotHash, RuntimeVersion},
@@ -11,7 +13,8 @@ use cf_chains::{
 use cf_primitives::PolkadotBlockNumber;
 use core::time::Duration;
 use futures_core::Stream;
-use sp_core::H256;
+use serde::Serialize;
+use sp_core::{Bytes, H256};
 use std::pin::Pin;
 use subxt::{
 	config::Header as SubxtHeader, events::Events, rpc::types::ChainBlockExtrinsic, PolkadotConfig,
@@ -33,6 +36,7 @@ use crate::dot::rpc::DotRpcApi;
 pub struct DotRetryRpcClient {
 	rpc_retry_client: RetrierClient<DotHttpRpcClient>,
 	sub_retry_client: RetrierClient<DotSubClient>,
+	recorder: Option<FixtureRecorder>,
 }
 
 const POLKADOT_RPC_TIMEOUT: Duration = Duration::from_millis(4 * 1000);
@@ -84,8 +88,22 @@ impl DotRetryRpcClient {
 				POLKADOT_RPC_TIMEOUT,
 				MAX_CONCURRENT_SUBMISSIONS,
 			),
+			recorder: None,
 		})
 	}
+
+	/// Records the responses to the requests made through this client, so they can be returned
+	/// by a [fixture::FixtureDotRpcClient]. Events can't be recorded here, as they can only be
+	/// decoded using the metadata, so the decoded events are recorded by the chain source instead.
+	pub fn with_recorder(self, recorder: Option<FixtureRecorder>) -> Self {
+		Self { recorder, ..self }
+	}
+
+	fn record<Response: Serialize>(&self, method: &str, request: String, response: &Response) {
+		if let Some(recorder) = &self.recorder {
+			recorder.record_response(method, request, response);
+		}
+	}
 }
 
 #[async_trait::async_trait]
@@ -107,7 +125,8 @@ pub trait DotRetryRpcApi: Clone {
 #[async_trait::async_trait]
 impl DotRetryRpcApi for DotRetryRpcClient {
 	async fn block_hash(&self, block_number: PolkadotBlockNumber) -> Option<PolkadotHash> {
-		self.rpc_retry_client
+		let block_hash = self
+			.rpc_retry_client
 			.request(
 				Box::pin(move |client| {
 					#[allow(clippy::redundant_async_block)]
@@ -115,11 +134,14 @@ impl DotRetryRpcApi for DotRetryRpcClient {
 				}),
 				RequestLog::new("block_hash".to_string(), Some(format!("{block_number}"))),
 			)
-			.await
+			.await;
+		self.record("block_hash", format!("{block_number}"), &block_hash);
+		block_hash
 	}
 
 	async fn extrinsics(&self, block_hash: PolkadotHash) -> Vec<ChainBlockExtrinsic> {
-		self.rpc_retry_client
+		let extrinsics = self
+			.rpc_retry_client
 			.request(
 				Box::pin(move |client| {
 					#[allow(clippy::redundant_async_block)]
@@ -131,7 +153,17 @@ impl DotRetryRpcApi for DotRetryRpcClient {
 				}),
 				RequestLog::new("extrinsics".to_string(), Some(format!("{block_hash:?}"))),
 			)
-			.await
+			.await;
+		// Extrinsics don't support serialization, so they are recorded as bytes.
+		self.record(
+			"extrinsics",
+			format!("{block_hash:?}"),
+			&extrinsics
+				.iter()
+				.map(|extrinsic| Bytes(extrinsic.0.clone()))
+				.collect::<Vec<_>>(),
+		);
+		extrinsics
 	}
 
 	async fn events(&self, block_hash: PolkadotHash) -> Option<Events<PolkadotConfig>> {
@@ -147,7 +179,8 @@ impl DotRetryRpcApi for DotRetryRpcClient {
 	}
 
 	async fn runtime_version(&self, block_hash: Option<H256>) -> RuntimeVersion {
-		self.rpc_retry_client
+		let runtime_version = self
+			.rpc_retry_client
 			.request(
 				Box::pin(move |client| {
 					#[allow(clippy::redundant_async_block)]
@@ -155,7 +188,9 @@ impl DotRetryRpcApi for DotRetryRpcClient {
 				}),
 				RequestLog::new("runtime_version".to_string(), None),
 			)
-			.await
+			.await;
+		self.record("runtime_version", format!("{block_hash:?}"), &runtime_version);
+		runtime_version
 	}
 
 	async fn submit_raw_encoded_extrinsic(
@@ -324,6 +359,8 @@ mod tests {
 							ws_endpoint: "ws://127.0.0.1:9945".into(),
 						},
 						backup: None,
+						additional: vec![],
+						quorum: None,
 					},
 					None,
 				)
@@ -0,0 +1,57 @@
+use anyhow::anyhow;
+use cf_chains::dot::{PolkadotHash, RuntimeVersion};
+use cf_primitives::PolkadotBlockNumber;
+use sp_core::{Bytes, H256};
+use subxt::{events::Events, rpc::types::ChainBlockExtrinsic, PolkadotConfig};
+
+use crate::witness::common::chain_source::fixture::FixtureResponses;
+
+use super::DotRetryRpcApi;
+
+/// Returns the responses recorded in a fixture by a `DotRetryRpcClient`, so the Polkadot witnessers
+/// can be run offline. Transactions can't be submitted, and as events aren't recorded by the
+/// client, the fixture must be replayed from a source that produces the decoded events.
+#[derive(Clone)]
+pub struct FixtureDotRpcClient {
+	responses: FixtureResponses,
+}
+
+impl FixtureDotRpcClient {
+	pub fn new(responses: FixtureResponses) -> Self {
+		Self { responses }
+	}
+}
+
+#[async_trait::async_trait]
+impl DotRetryRpcApi for FixtureDotRpcClient {
+	async fn block_hash(&self, block_number: PolkadotBlockNumber) -> Option<PolkadotHash> {
+		self.responses.get("block_hash", format!("{block_number}")).await
+	}
+
+	async fn extrinsics(&self, block_hash: PolkadotHash) -> Vec<ChainBlockExtrinsic> {
+		self.responses
+			.get::<Vec<Bytes>>("extrinsics", format!("{block_hash:?}"))
+			.await
+			.into_iter()
+			.map(|extrinsic| ChainBlockExtrinsic(extrinsic.0))
+			.collect()
+	}
+
+	async fn events(&self, block_hash: PolkadotHash) -> Option<Events<PolkadotConfig>> {
+		tracing::error!(
+			"The events of block {block_hash:?} can't be replayed, only the decoded events recorded by the chain source"
+		);
+		futures::future::pending().await
+	}
+
+	async fn runtime_version(&self, block_hash: Option<H256>) -> RuntimeVersion {
+		self.responses.get("runtime_version", format!("{block_hash:?}")).await
+	}
+
+	async fn submit_raw_encoded_extrinsic(
+		&self,
+		_encoded_bytes: Vec<u8>,
+	) -> anyhow::Result<PolkadotHash> {
+		Err(anyhow!("Transactions can't be submitted while replaying a fixture"))
+	}
+}
@@ -8,7 +8,7 @@ use sp_core::H256;
 use std::sync::Arc;
 use subxt::{
 	events::Events,
-	rpc::types::{ChainBlockExtrinsic, ChainBlockResponse},
+	rpc::types::{Bytes, ChainBlockExtrinsic, ChainBlockResponse},
 	Config, OnlineClient, PolkadotConfig,
 };
 use tokio::sync::RwLock;
@@ -81,6 +81,9 @@ pub trait DotSubscribeApi: Send + Sync {
 pub trait DotRpcApi: Send + Sync {
 	async fn block_hash(&self, block_number: PolkadotBlockNumber) -> Result<Option<PolkadotHash>>;
 
+	/// The hash of the best, not necessarily finalised, block.
+	async fn best_block_hash(&self) -> Result<PolkadotHash>;
+
 	async fn block(
 		&self,
 		block_hash: PolkadotHash,
@@ -96,6 +99,9 @@ pub trait DotRpcApi: Send + Sync {
 	async fn runtime_version(&self, at: Option<H256>) -> Result<RuntimeVersion>;
 
 	async fn submit_raw_encoded_extrinsic(&self, encoded_bytes: Vec<u8>) -> Result<PolkadotHash>;
+
+	/// The encoded extrinsics in the node's transaction pool.
+	async fn pending_extrinsics(&self) -> Result<Vec<Bytes>>;
 }
 
 // Just pass through to the underlying http client
@@ -105,6 +111,10 @@ impl DotRpcApi for DotRpcClient {
 		self.http_client.block_hash(block_number).await
 	}
 
+	async fn best_block_hash(&self) -> Result<PolkadotHash> {
+		self.http_client.best_block_hash().await
+	}
+
 	async fn block(
 		&self,
 		block_hash: PolkadotHash,
@@ -132,6 +142,10 @@ impl DotRpcApi for DotRpcClient {
 	async fn submit_raw_encoded_extrinsic(&self, encoded_bytes: Vec<u8>) -> Result<PolkadotHash> {
 		self.http_client.submit_raw_encoded_extrinsic(encoded_bytes).await
 	}
+
+	async fn pending_extrinsics(&self) -> Result<Vec<Bytes>> {
+		self.http_client.pending_extrinsics().await
+	}
 }
 
 #[derive(Clone)]
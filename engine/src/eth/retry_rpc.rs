@@ -1,4 +1,6 @@
 pub mod address_checker;
+pub mod fixture;
+mod prefetched_logs;
 
 use ethers::{
 	prelude::*,
@@ -8,14 +10,16 @@ use ethers::{
 use utilities::task_scope::Scope;
 
 use crate::{
-	common::option_inner,
+	common::{option_inner, Mutex},
 	eth::rpc::EthRpcApi,
-	retrier::{Attempt, RequestLog, RetrierClient},
+	retrier::{Attempt, QuorumRetrierClient, RequestLog, RetrierClient, TypedFutureGenerator},
 	settings::{NodeContainer, WsHttpEndpoints},
-	witness::common::chain_source::{ChainClient, Header},
+	witness::common::chain_source::{fixture::FixtureRecorder, ChainClient, Header},
 };
-use std::{path::PathBuf, time::Duration};
+use serde::Serialize;
+use std::{ops::RangeInclusive, path::PathBuf, sync::Arc, time::Duration};
 
+use self::prefetched_logs::PrefetchedLogs;
 use super::{
 	rpc::{EthRpcClient, ReconnectSubscriptionClient},
 	ConscientiousEthWebsocketBlockHeaderStream,
@@ -29,12 +33,17 @@ use anyhow::{Context, Result};
 pub struct EthersRetryRpcClient {
 	rpc_retry_client: RetrierClient<EthRpcClient>,
 	sub_retry_client: RetrierClient<ReconnectSubscriptionClient>,
+	// Used for witnessing-critical requests if a quorum of providers is configured.
+	quorum_retry_client: Option<QuorumRetrierClient<EthRpcClient>>,
+	prefetched_logs: Arc<Mutex<PrefetchedLogs>>,
+	recorder: Option<FixtureRecorder>,
 }
 
 const ETHERS_RPC_TIMEOUT: Duration = Duration::from_millis(4 * 1000);
 const MAX_CONCURRENT_SUBMISSIONS: u32 = 100;
 
 const MAX_BROADCAST_RETRIES: Attempt = 2;
+const MAX_PREFETCH_LOGS_RETRIES: Attempt = 2;
 
 impl EthersRetryRpcClient {
 	pub fn new(
@@ -54,6 +63,25 @@ impl EthersRetryRpcClient {
 			))
 		};
 
+		let quorum_rpc_clients = nodes
+			.quorum
+			.map(|quorum| {
+				Result::<_, anyhow::Error>::Ok((
+					nodes
+						.providers()
+						.map(|endpoints| {
+							EthRpcClient::new(
+								private_key_file.clone(),
+								endpoints.http_endpoint.clone(),
+								expected_chain_id.as_u64(),
+							)
+						})
+						.collect::<Result<Vec<_>>>()?,
+					quorum,
+				))
+			})
+			.transpose()?;
+
 		let (rpc_client, sub_client) = f_create_clients(nodes.primary)?;
 		let (backup_rpc_client, backup_sub_client) =
 			option_inner(nodes.backup.map(f_create_clients).transpose()?);
@@ -75,8 +103,46 @@ impl EthersRetryRpcClient {
 				ETHERS_RPC_TIMEOUT,
 				MAX_CONCURRENT_SUBMISSIONS,
 			),
+			quorum_retry_client: quorum_rpc_clients.map(|(rpc_clients, quorum)| {
+				QuorumRetrierClient::new(
+					scope,
+					"eth_rpc_quorum",
+					rpc_clients,
+					quorum,
+					ETHERS_RPC_TIMEOUT,
+					MAX_CONCURRENT_SUBMISSIONS,
+				)
+			}),
+			prefetched_logs: Arc::new(Mutex::new(Default::default())),
+			recorder: None,
 		})
 	}
+
+	/// Records the responses to the requests made through this client, so they can be returned
+	/// by a [fixture::FixtureEthRpcClient].
+	pub fn with_recorder(self, recorder: Option<FixtureRecorder>) -> Self {
+		Self { recorder, ..self }
+	}
+
+	fn record<Response: Serialize>(&self, method: &str, request: String, response: &Response) {
+		if let Some(recorder) = &self.recorder {
+			recorder.record_response(method, request, response);
+		}
+	}
+
+	/// Requests that witnessing depends on are checked against a quorum of providers, if one is
+	/// configured.
+	async fn witnessing_request<T: PartialEq + Clone + Send + 'static>(
+		&self,
+		specific_closure: TypedFutureGenerator<T, EthRpcClient>,
+		request_log: RequestLog,
+	) -> T {
+		match &self.quorum_retry_client {
+			Some(quorum_retry_client) =>
+				quorum_retry_client.request(specific_closure, request_log).await,
+			None => self.rpc_retry_client.request(specific_closure, request_log).await,
+		}
+	}
 }
 
 #[async_trait::async_trait]
@@ -88,6 +154,10 @@ pub trait EthersRetryRpcApi: Clone {
 
 	async fn get_logs(&self, block_hash: H256, contract_address: H160) -> Vec<Log>;
 
+	/// Fetches the logs of the contracts for a range of blocks in a single request, so later
+	/// calls to `get_logs` for those blocks and contracts don't need a request each.
+	async fn prefetch_logs(&self, blocks: RangeInclusive<u64>, contract_addresses: Vec<H160>);
+
 	async fn chain_id(&self) -> U256;
 
 	async fn transaction_receipt(&self, tx_hash: H256) -> TransactionReceipt;
@@ -170,28 +240,88 @@ impl EthersRetryRpcApi for EthersRetryRpcClient {
 	}
 
 	async fn get_logs(&self, block_hash: H256, contract_address: H160) -> Vec<Log> {
-		self.rpc_retry_client
-			.request(
-				Box::pin(move |client| {
-					#[allow(clippy::redundant_async_block)]
-					Box::pin(async move {
-						client
-							.get_logs(
-								Filter::new().address(contract_address).at_block_hash(block_hash),
-							)
-							.await
-					})
+		let prefetched_logs = self.prefetched_logs.lock().await.get(block_hash, contract_address);
+		let logs = match prefetched_logs {
+			Some(logs) => logs,
+			None =>
+				self.witnessing_request(
+					Box::pin(move |client| {
+						#[allow(clippy::redundant_async_block)]
+						Box::pin(async move {
+							client
+								.get_logs(
+									Filter::new()
+										.address(contract_address)
+										.at_block_hash(block_hash),
+								)
+								.await
+						})
+					}),
+					RequestLog::new(
+						"get_logs".to_string(),
+						Some(format!("{block_hash:?}, {contract_address:?}")),
+					),
+				)
+				.await,
+		};
+		self.record("get_logs", format!("{block_hash:?}, {contract_address:?}"), &logs);
+		logs
+	}
+
+	/// Does nothing if a quorum of providers is configured, as the logs are then checked against
+	/// the quorum block by block.
+	async fn prefetch_logs(&self, blocks: RangeInclusive<u64>, contract_addresses: Vec<H160>) {
+		if self.quorum_retry_client.is_some() ||
+			!self.prefetched_logs.lock().await.start_prefetching(&blocks)
+		{
+			return
+		}
+
+		let log = RequestLog::new(
+			"prefetch_logs".to_string(),
+			Some(format!("{blocks:?}, {contract_addresses:?}")),
+		);
+		let logs = self
+			.rpc_retry_client
+			.request_with_limit(
+				Box::pin({
+					let (blocks, contract_addresses) = (blocks.clone(), contract_addresses.clone());
+					move |client| {
+						let (blocks, contract_addresses) =
+							(blocks.clone(), contract_addresses.clone());
+						#[allow(clippy::redundant_async_block)]
+						Box::pin(async move {
+							client
+								.get_logs(
+									Filter::new()
+										.address(contract_addresses)
+										.from_block(*blocks.start())
+										.to_block(*blocks.end()),
+								)
+								.await
+						})
+					}
 				}),
-				RequestLog::new(
-					"get_logs".to_string(),
-					Some(format!("{block_hash:?}, {contract_address:?}")),
-				),
+				log,
+				MAX_PREFETCH_LOGS_RETRIES,
 			)
 			.await
+			.map_err(|error| {
+				tracing::warn!(
+					"Failed to prefetch the logs of blocks {blocks:?}, they will be requested block by block: {error}"
+				)
+			})
+			.ok();
+
+		self.prefetched_logs
+			.lock()
+			.await
+			.finish_prefetching(&blocks, contract_addresses, logs);
 	}
 
 	async fn chain_id(&self) -> U256 {
-		self.rpc_retry_client
+		let chain_id = self
+			.rpc_retry_client
 			.request(
 				Box::pin(move |client| {
 					#[allow(clippy::redundant_async_block)]
@@ -199,35 +329,42 @@ impl EthersRetryRpcApi for EthersRetryRpcClient {
 				}),
 				RequestLog::new("chain_id".to_string(), None),
 			)
-			.await
+			.await;
+		self.record("chain_id", String::new(), &chain_id);
+		chain_id
 	}
 
 	async fn transaction_receipt(&self, tx_hash: H256) -> TransactionReceipt {
-		self.rpc_retry_client
-			.request(
+		let receipt = self
+			.witnessing_request(
 				Box::pin(move |client| {
 					#[allow(clippy::redundant_async_block)]
 					Box::pin(async move { client.transaction_receipt(tx_hash).await })
 				}),
 				RequestLog::new("transaction_receipt".to_string(), Some(format!("{tx_hash:?}"))),
 			)
-			.await
+			.await;
+		self.record("transaction_receipt", format!("{tx_hash:?}"), &receipt);
+		receipt
 	}
 
 	async fn block(&self, block_number: U64) -> Block<H256> {
-		self.rpc_retry_client
-			.request(
+		let block = self
+			.witnessing_request(
 				Box::pin(move |client| {
 					#[allow(clippy::redundant_async_block)]
 					Box::pin(async move { client.block(block_number).await })
 				}),
 				RequestLog::new("block".to_string(), Some(format!("{block_number}"))),
 			)
-			.await
+			.await;
+		self.record("block", format!("{block_number}"), &block);
+		block
 	}
 
 	async fn block_with_txs(&self, block_number: U64) -> Block<Transaction> {
-		self.rpc_retry_client
+		let block = self
+			.rpc_retry_client
 			.request(
 				Box::pin(move |client| {
 					#[allow(clippy::redundant_async_block)]
@@ -235,7 +372,9 @@ impl EthersRetryRpcApi for EthersRetryRpcClient {
 				}),
 				RequestLog::new("block_with_txs".to_string(), Some(format!("{block_number}"))),
 			)
-			.await
+			.await;
+		self.record("block_with_txs", format!("{block_number}"), &block);
+		block
 	}
 
 	async fn fee_history(
@@ -244,11 +383,10 @@ impl EthersRetryRpcApi for EthersRetryRpcClient {
 		newest_block: BlockNumber,
 		reward_percentiles: Vec<f64>,
 	) -> FeeHistory {
-		let log = RequestLog::new(
-			"fee_history".to_string(),
-			Some(format!("{block_count}, {newest_block}, {reward_percentiles:?}")),
-		);
-		self.rpc_retry_client
+		let request = format!("{block_count}, {newest_block}, {reward_percentiles:?}");
+		let log = RequestLog::new("fee_history".to_string(), Some(request.clone()));
+		let fee_history = self
+			.rpc_retry_client
 			.request(
 				Box::pin(move |client| {
 					let reward_percentiles = reward_percentiles.clone();
@@ -259,11 +397,14 @@ impl EthersRetryRpcApi for EthersRetryRpcClient {
 				}),
 				log,
 			)
-			.await
+			.await;
+		self.record("fee_history", request, &fee_history);
+		fee_history
 	}
 
 	async fn get_transaction(&self, tx_hash: H256) -> Transaction {
-		self.rpc_retry_client
+		let transaction = self
+			.rpc_retry_client
 			.request(
 				Box::pin(move |client| {
 					#[allow(clippy::redundant_async_block)]
@@ -271,7 +412,9 @@ impl EthersRetryRpcApi for EthersRetryRpcClient {
 				}),
 				RequestLog::new("get_transaction".to_string(), Some(format!("{tx_hash:?}"))),
 			)
-			.await
+			.await;
+		self.record("get_transaction", format!("{tx_hash:?}"), &transaction);
+		transaction
 	}
 }
 
@@ -357,6 +500,8 @@ pub mod mocks {
 
 			async fn get_logs(&self, block_hash: H256, contract_address: H160) -> Vec<Log>;
 
+			async fn prefetch_logs(&self, blocks: RangeInclusive<u64>, contract_addresses: Vec<H160>);
+
 			async fn chain_id(&self) -> U256;
 
 			async fn transaction_receipt(&self, tx_hash: H256) -> TransactionReceipt;
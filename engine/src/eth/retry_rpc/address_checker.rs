@@ -31,7 +31,9 @@ impl AddressCheckerRetryRpcApi for EthersRetryRpcClient {
 		contract_address: H160,
 		addresses: Vec<H160>,
 	) -> Vec<AddressState> {
-		self.rpc_retry_client
+		let request = format!("{block_hash:?}, {contract_address:?}, {addresses:?}");
+		let address_states = self
+			.rpc_retry_client
 			.request(
 				Box::pin(move |client| {
 					let addresses = addresses.clone();
@@ -45,7 +47,17 @@ impl AddressCheckerRetryRpcApi for EthersRetryRpcClient {
 					Some(format!("{block_hash:?}, {contract_address:?}")),
 				),
 			)
-			.await
+			.await;
+		// The contract's bindings don't support serde, so the states are recorded as tuples.
+		self.record(
+			"address_states",
+			request,
+			&address_states
+				.iter()
+				.map(|state| (state.has_contract, state.balance))
+				.collect::<Vec<_>>(),
+		);
+		address_states
 	}
 
 	async fn balances(
@@ -54,7 +66,9 @@ impl AddressCheckerRetryRpcApi for EthersRetryRpcClient {
 		contract_address: H160,
 		addresses: Vec<H160>,
 	) -> Vec<U256> {
-		self.rpc_retry_client
+		let request = format!("{block_hash:?}, {contract_address:?}, {addresses:?}");
+		let balances = self
+			.rpc_retry_client
 			.request(
 				Box::pin(move |client| {
 					let addresses = addresses.clone();
@@ -68,6 +82,8 @@ impl AddressCheckerRetryRpcApi for EthersRetryRpcClient {
 					Some(format!("{block_hash:?}, {contract_address:?}")),
 				),
 			)
-			.await
+			.await;
+		self.record("balances", request, &balances);
+		balances
 	}
 }
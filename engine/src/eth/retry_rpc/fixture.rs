@@ -0,0 +1,124 @@
+use ethers::prelude::*;
+
+use anyhow::anyhow;
+use cf_chains::Ethereum;
+use std::ops::RangeInclusive;
+
+use crate::{
+	eth::rpc::address_checker::AddressState,
+	witness::common::chain_source::{
+		fixture::{FixtureClient, FixtureResponses, FixtureSource},
+		ChainClient, Header,
+	},
+};
+
+use super::{address_checker::AddressCheckerRetryRpcApi, EthersRetryRpcApi};
+
+/// Returns the responses recorded in a fixture by an `EthersRetryRpcClient`, so the Ethereum
+/// witnessers can be run offline. Transactions can't be broadcast.
+#[derive(Clone)]
+pub struct FixtureEthRpcClient {
+	headers: FixtureClient<u64, H256, Bloom>,
+	responses: FixtureResponses,
+}
+
+impl FixtureEthRpcClient {
+	pub fn new(source: &FixtureSource<Ethereum, H256, Bloom>) -> Self {
+		Self { headers: source.client(), responses: source.responses() }
+	}
+}
+
+#[async_trait::async_trait]
+impl EthersRetryRpcApi for FixtureEthRpcClient {
+	async fn broadcast_transaction(
+		&self,
+		_tx: cf_chains::evm::Transaction,
+	) -> anyhow::Result<TxHash> {
+		Err(anyhow!("Transactions can't be broadcast while replaying a fixture"))
+	}
+
+	async fn get_logs(&self, block_hash: H256, contract_address: H160) -> Vec<Log> {
+		self.responses
+			.get("get_logs", format!("{block_hash:?}, {contract_address:?}"))
+			.await
+	}
+
+	/// The logs are recorded block by block, so there is nothing to prefetch.
+	async fn prefetch_logs(&self, _blocks: RangeInclusive<u64>, _contract_addresses: Vec<H160>) {}
+
+	async fn chain_id(&self) -> U256 {
+		self.responses.get("chain_id", String::new()).await
+	}
+
+	async fn transaction_receipt(&self, tx_hash: H256) -> TransactionReceipt {
+		self.responses.get("transaction_receipt", format!("{tx_hash:?}")).await
+	}
+
+	async fn block(&self, block_number: U64) -> Block<H256> {
+		self.responses.get("block", format!("{block_number}")).await
+	}
+
+	async fn block_with_txs(&self, block_number: U64) -> Block<Transaction> {
+		self.responses.get("block_with_txs", format!("{block_number}")).await
+	}
+
+	async fn fee_history(
+		&self,
+		block_count: U256,
+		newest_block: BlockNumber,
+		reward_percentiles: Vec<f64>,
+	) -> FeeHistory {
+		self.responses
+			.get("fee_history", format!("{block_count}, {newest_block}, {reward_percentiles:?}"))
+			.await
+	}
+
+	async fn get_transaction(&self, tx_hash: H256) -> Transaction {
+		self.responses.get("get_transaction", format!("{tx_hash:?}")).await
+	}
+}
+
+#[async_trait::async_trait]
+impl AddressCheckerRetryRpcApi for FixtureEthRpcClient {
+	async fn address_states(
+		&self,
+		block_hash: H256,
+		contract_address: H160,
+		addresses: Vec<H160>,
+	) -> Vec<AddressState> {
+		self.responses
+			.get::<Vec<(bool, U256)>>(
+				"address_states",
+				format!("{block_hash:?}, {contract_address:?}, {addresses:?}"),
+			)
+			.await
+			.into_iter()
+			.map(|(has_contract, balance)| AddressState { has_contract, balance })
+			.collect()
+	}
+
+	async fn balances(
+		&self,
+		block_hash: H256,
+		contract_address: H160,
+		addresses: Vec<H160>,
+	) -> Vec<U256> {
+		self.responses
+			.get("balances", format!("{block_hash:?}, {contract_address:?}, {addresses:?}"))
+			.await
+	}
+}
+
+#[async_trait::async_trait]
+impl ChainClient for FixtureEthRpcClient {
+	type Index = <Ethereum as cf_chains::Chain>::ChainBlockNumber;
+	type Hash = H256;
+	type Data = Bloom;
+
+	async fn header_at_index(
+		&self,
+		index: Self::Index,
+	) -> Header<Self::Index, Self::Hash, Self::Data> {
+		self.headers.header_at_index(index).await
+	}
+}
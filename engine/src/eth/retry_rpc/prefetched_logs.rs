@@ -0,0 +1,139 @@
+use std::{
+	collections::{BTreeSet, HashMap, VecDeque},
+	ops::RangeInclusive,
+};
+
+use ethers::prelude::*;
+
+/// The number of block ranges whose logs are kept. Witnessers catching up progress at different
+/// rates, so this needs to cover the spread between the slowest and the fastest of them.
+const MAX_PREFETCHED_RANGES: usize = 16;
+
+struct PrefetchedRange {
+	blocks: RangeInclusive<u64>,
+	contract_addresses: BTreeSet<H160>,
+	/// None while the request is in progress, or if it failed.
+	logs_by_block_hash: Option<HashMap<H256, Vec<Log>>>,
+}
+
+/// Logs of ranges of blocks, fetched with a single `eth_getLogs` request each, so requests for
+/// the logs of a single block can be answered without going to the node.
+#[derive(Default)]
+pub struct PrefetchedLogs {
+	ranges: VecDeque<PrefetchedRange>,
+}
+
+impl PrefetchedLogs {
+	/// Returns false if the range has already been, or is being, prefetched.
+	pub fn start_prefetching(&mut self, blocks: &RangeInclusive<u64>) -> bool {
+		if self.ranges.iter().any(|range| range.blocks == *blocks) {
+			false
+		} else {
+			self.ranges.push_back(PrefetchedRange {
+				blocks: blocks.clone(),
+				contract_addresses: Default::default(),
+				logs_by_block_hash: None,
+			});
+			while self.ranges.len() > MAX_PREFETCHED_RANGES {
+				self.ranges.pop_front();
+			}
+			true
+		}
+	}
+
+	/// Stores the result of prefetching. If the request failed the range is not retried, and
+	/// the logs of its blocks are requested individually instead.
+	pub fn finish_prefetching(
+		&mut self,
+		blocks: &RangeInclusive<u64>,
+		contract_addresses: Vec<H160>,
+		logs: Option<Vec<Log>>,
+	) {
+		if let (Some(range), Some(logs)) =
+			(self.ranges.iter_mut().find(|range| range.blocks == *blocks), logs)
+		{
+			let mut logs_by_block_hash = HashMap::<_, Vec<_>>::new();
+			for log in logs.into_iter().filter(|log| log.removed != Some(true)) {
+				if let Some(block_hash) = log.block_hash {
+					logs_by_block_hash.entry(block_hash).or_default().push(log);
+				}
+			}
+			range.contract_addresses = contract_addresses.into_iter().collect();
+			range.logs_by_block_hash = Some(logs_by_block_hash);
+		}
+	}
+
+	/// The logs of the contract in the block, if they have been prefetched. Only blocks that
+	/// contain at least one log of a prefetched contract can be found, as the blocks are only
+	/// identified by the logs' block hashes.
+	pub fn get(&self, block_hash: H256, contract_address: H160) -> Option<Vec<Log>> {
+		self.ranges
+			.iter()
+			.filter(|range| range.contract_addresses.contains(&contract_address))
+			.filter_map(|range| range.logs_by_block_hash.as_ref()?.get(&block_hash))
+			.next()
+			.map(|logs| {
+				logs.iter().filter(|log| log.address == contract_address).cloned().collect()
+			})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn log(block_hash: H256, contract_address: H160, log_index: u64) -> Log {
+		Log {
+			address: contract_address,
+			block_hash: Some(block_hash),
+			log_index: Some(log_index.into()),
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn prefetched_logs_are_served_per_block_and_contract() {
+		let (block_a, block_b, block_c) =
+			(H256::repeat_byte(1), H256::repeat_byte(2), H256::repeat_byte(3));
+		let (contract_x, contract_y, contract_z) =
+			(H160::repeat_byte(1), H160::repeat_byte(2), H160::repeat_byte(3));
+
+		let mut prefetched_logs = PrefetchedLogs::default();
+		assert!(prefetched_logs.start_prefetching(&(0..=9)));
+		assert!(!prefetched_logs.start_prefetching(&(0..=9)));
+		assert_eq!(prefetched_logs.get(block_a, contract_x), None);
+
+		prefetched_logs.finish_prefetching(
+			&(0..=9),
+			vec![contract_x, contract_y],
+			Some(vec![
+				log(block_a, contract_x, 0),
+				log(block_a, contract_y, 1),
+				log(block_b, contract_x, 0),
+			]),
+		);
+
+		assert_eq!(
+			prefetched_logs.get(block_a, contract_x),
+			Some(vec![log(block_a, contract_x, 0)])
+		);
+		assert_eq!(prefetched_logs.get(block_b, contract_y), Some(vec![]));
+		// Blocks without logs, and contracts that weren't prefetched, must be requested.
+		assert_eq!(prefetched_logs.get(block_c, contract_x), None);
+		assert_eq!(prefetched_logs.get(block_a, contract_z), None);
+	}
+
+	#[test]
+	fn failed_ranges_are_not_retried_and_old_ranges_are_evicted() {
+		let mut prefetched_logs = PrefetchedLogs::default();
+		assert!(prefetched_logs.start_prefetching(&(0..=9)));
+		prefetched_logs.finish_prefetching(&(0..=9), vec![H160::repeat_byte(1)], None);
+		assert!(!prefetched_logs.start_prefetching(&(0..=9)));
+		assert_eq!(prefetched_logs.get(H256::repeat_byte(1), H160::repeat_byte(1)), None);
+
+		for i in 1..=MAX_PREFETCHED_RANGES as u64 {
+			assert!(prefetched_logs.start_prefetching(&(i * 10..=i * 10 + 9)));
+		}
+		assert!(prefetched_logs.start_prefetching(&(0..=9)));
+	}
+}
@@ -7,7 +7,7 @@ use chainflip_engine::{
 	dot::retry_rpc::DotRetryRpcClient,
 	eth::retry_rpc::EthersRetryRpcClient,
 	health, p2p,
-	settings::{CommandLineOptions, Settings, DEFAULT_SETTINGS_DIR},
+	settings::{CommandLineOptions, EngineCommand, ReplayOptions, Settings, DEFAULT_SETTINGS_DIR},
 	state_chain_observer::{
 		self,
 		client::{
@@ -39,11 +39,16 @@ async fn main() -> anyhow::Result<()> {
 	use_chainflip_account_id_encoding();
 
 	let opts = CommandLineOptions::parse();
+	let command = opts.command.clone();
 
 	// the settings directory from opts.config_root that we'll use to read the settings file
 	let settings = Settings::new_with_settings_dir(DEFAULT_SETTINGS_DIR, opts)
 		.context("Error reading settings")?;
 
+	if let Some(EngineCommand::Replay(options)) = command {
+		return run_replay(settings, options).await
+	}
+
 	// Note: the greeting should only be printed in normal mode (i.e. not for short-lived commands
 	// like `--version`), so we execute it only after the settings have been parsed.
 	utilities::print_start_and_end!(async run_main(settings));
@@ -51,6 +56,90 @@ async fn main() -> anyhow::Result<()> {
 	Ok(())
 }
 
+async fn run_replay(settings: Settings, options: ReplayOptions) -> anyhow::Result<()> {
+	task_scope(|scope| {
+		async move {
+			utilities::logging::init_json_logger(settings.logging.clone()).await(scope);
+
+			let (state_chain_stream, _unfinalised_state_chain_stream, state_chain_client) =
+				state_chain_observer::client::StateChainClient::connect_with_account(
+					scope,
+					&settings.state_chain.ws_endpoint,
+					&settings.state_chain.signing_key_file,
+					AccountRole::Validator,
+					false,
+					None,
+				)
+				.await?;
+
+			// Only the client of the replayed chain is constructed
+			let eth_client = {
+				let state_chain_client = state_chain_client.clone();
+				let eth_settings = settings.eth;
+				async move {
+					let expected_eth_chain_id = web3::types::U256::from(
+						state_chain_client
+							.storage_value::<pallet_cf_environment::EthereumChainId<state_chain_runtime::Runtime>>(
+								state_chain_client.latest_finalized_block().hash,
+							)
+							.await
+							.expect(STATE_CHAIN_CONNECTION),
+					);
+					EthersRetryRpcClient::new(
+						scope,
+						eth_settings.private_key_file,
+						eth_settings.nodes,
+						expected_eth_chain_id,
+					)
+				}
+			};
+			let btc_client = {
+				let state_chain_client = state_chain_client.clone();
+				let btc_settings = settings.btc;
+				async move {
+					let expected_btc_network = cf_chains::btc::BitcoinNetwork::from(
+						state_chain_client
+							.storage_value::<pallet_cf_environment::ChainflipNetworkEnvironment<
+								state_chain_runtime::Runtime,
+							>>(state_chain_client.latest_finalized_block().hash)
+							.await
+							.expect(STATE_CHAIN_CONNECTION),
+					);
+					BtcRetryRpcClient::new(scope, btc_settings.nodes, expected_btc_network).await
+				}
+			};
+			let dot_client = {
+				let state_chain_client = state_chain_client.clone();
+				let dot_settings = settings.dot;
+				async move {
+					let expected_dot_genesis_hash = PolkadotHash::from(
+						state_chain_client
+							.storage_value::<pallet_cf_environment::PolkadotGenesisHash<state_chain_runtime::Runtime>>(
+								state_chain_client.latest_finalized_block().hash,
+							)
+							.await
+							.expect(STATE_CHAIN_CONNECTION),
+					);
+					DotRetryRpcClient::new(scope, dot_settings.nodes, expected_dot_genesis_hash)
+				}
+			};
+
+			witness::replay::replay(
+				scope,
+				options,
+				eth_client,
+				btc_client,
+				dot_client,
+				state_chain_client,
+				state_chain_stream,
+			)
+			.await
+		}
+		.boxed()
+	})
+	.await
+}
+
 async fn run_main(settings: Settings) -> anyhow::Result<()> {
 	task_scope(|scope| {
 		async move {
@@ -209,7 +298,9 @@ async fn run_main(settings: Settings) -> anyhow::Result<()> {
 				scope,
 				eth_client.clone(),
 				btc_client.clone(),
+				settings.btc.use_block_filters,
 				dot_client.clone(),
+				settings.witnessing.fixture_dir.clone(),
 				state_chain_client.clone(),
 				state_chain_stream.clone(),
 				unfinalised_state_chain_stream.clone(),
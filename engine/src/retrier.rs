@@ -10,19 +10,23 @@ use std::{
 	any::Any,
 	collections::{BTreeMap, VecDeque},
 	pin::Pin,
-	time::Duration,
+	sync::{Arc, Mutex},
+	time::{Duration, Instant},
 };
 
 use crate::common::Signal;
 use anyhow::Result;
 use core::cmp::min;
 use futures::Future;
-use futures_util::stream::FuturesUnordered;
+use futures_util::{stream::FuturesUnordered, StreamExt};
 use rand::Rng;
 use std::fmt;
 use tokio::sync::{mpsc, oneshot};
 use utilities::{
-	metrics::{RPC_RETRIER_REQUESTS, RPC_RETRIER_TOTAL_REQUESTS},
+	metrics::{
+		RPC_ENDPOINT_AVERAGE_LATENCY, RPC_ENDPOINT_ERROR_RATE, RPC_ENDPOINT_SELECTED,
+		RPC_QUORUM_DISAGREEMENTS, RPC_RETRIER_REQUESTS, RPC_RETRIER_TOTAL_REQUESTS,
+	},
 	task_scope::Scope,
 	UnendingStream,
 };
@@ -38,7 +42,7 @@ enum RetryLimit {
 	Limit(Attempt),
 }
 
-type TypedFutureGenerator<T, Client> = Pin<
+pub type TypedFutureGenerator<T, Client> = Pin<
 	Box<
 		dyn Fn(Client) -> Pin<Box<dyn Future<Output = Result<T, anyhow::Error>> + Send>>
 			+ Send
@@ -92,11 +96,136 @@ impl std::ops::Not for PrimaryOrSecondary {
 	}
 }
 
+impl PrimaryOrSecondary {
+	fn as_str(&self) -> &'static str {
+		match self {
+			PrimaryOrSecondary::Primary => "primary",
+			PrimaryOrSecondary::Secondary => "secondary",
+		}
+	}
+}
+
+// The weight given to the latest request when updating the moving averages of an endpoint.
+const ENDPOINT_STATS_SMOOTHING: f64 = 0.2;
+
+// The secondary must be expected to be at least this much faster than the primary before we
+// prefer it, so we don't flip between similarly healthy endpoints.
+const SECONDARY_PREFERENCE_MARGIN: f64 = 0.8;
+
+// Every this many requests, we send a request to the endpoint we don't prefer, so its statistics
+// stay up to date and it can win back preference once it has recovered.
+const ENDPOINT_EXPLORATION_INTERVAL: u64 = 20;
+
+/// Latency and error statistics of a single endpoint, as exponentially weighted moving averages.
+#[derive(Debug, Default, Clone, PartialEq)]
+struct EndpointStats {
+	average_latency: Option<Duration>,
+	error_rate: f64,
+}
+
+impl EndpointStats {
+	fn record(&mut self, latency: Duration, success: bool) {
+		self.average_latency = Some(match self.average_latency {
+			Some(average_latency) =>
+				average_latency.mul_f64(1.0 - ENDPOINT_STATS_SMOOTHING) +
+					latency.mul_f64(ENDPOINT_STATS_SMOOTHING),
+			None => latency,
+		});
+		self.error_rate = self.error_rate * (1.0 - ENDPOINT_STATS_SMOOTHING) +
+			if success { 0.0 } else { ENDPOINT_STATS_SMOOTHING };
+	}
+
+	/// The expected time until a successful response, taking into account that failed requests
+	/// have to be retried. `None` if no requests have been made to the endpoint yet.
+	fn expected_latency(&self) -> Option<Duration> {
+		self.average_latency
+			.map(|average_latency| average_latency.div_f64((1.0 - self.error_rate).max(0.01)))
+	}
+}
+
+/// Keeps track of the health of the primary and secondary endpoints, and selects the one new
+/// requests should be sent to.
+struct EndpointSelector {
+	name: &'static str,
+	has_secondary: bool,
+	primary: EndpointStats,
+	secondary: EndpointStats,
+	selections: u64,
+}
+
+impl EndpointSelector {
+	fn new(name: &'static str, has_secondary: bool) -> Self {
+		Self {
+			name,
+			has_secondary,
+			primary: Default::default(),
+			secondary: Default::default(),
+			selections: 0,
+		}
+	}
+
+	fn stats_mut(&mut self, endpoint: &PrimaryOrSecondary) -> &mut EndpointStats {
+		match endpoint {
+			PrimaryOrSecondary::Primary => &mut self.primary,
+			PrimaryOrSecondary::Secondary => &mut self.secondary,
+		}
+	}
+
+	fn record(&mut self, endpoint: &PrimaryOrSecondary, latency: Duration, success: bool) {
+		let name = self.name;
+		let stats = self.stats_mut(endpoint);
+		stats.record(latency, success);
+
+		let labels = [name, endpoint.as_str()];
+		RPC_ENDPOINT_AVERAGE_LATENCY
+			.set(&labels, stats.average_latency.unwrap_or_default().as_millis());
+		RPC_ENDPOINT_ERROR_RATE.set(&labels, (stats.error_rate * 1000.0).round() as i64);
+	}
+
+	// The endpoint with the lowest expected latency, biased towards the primary.
+	fn healthiest(&self) -> PrimaryOrSecondary {
+		if !self.has_secondary {
+			return PrimaryOrSecondary::Primary
+		}
+		match (self.primary.expected_latency(), self.secondary.expected_latency()) {
+			// We have to have tried the secondary to know if it's any better.
+			(Some(_), None) => PrimaryOrSecondary::Secondary,
+			(Some(primary), Some(secondary))
+				if secondary < primary.mul_f64(SECONDARY_PREFERENCE_MARGIN) =>
+				PrimaryOrSecondary::Secondary,
+			_ => PrimaryOrSecondary::Primary,
+		}
+	}
+
+	/// Selects the endpoint a new request should be sent to.
+	fn select(&mut self) -> PrimaryOrSecondary {
+		let healthiest = self.healthiest();
+		RPC_ENDPOINT_SELECTED.set(
+			&[self.name, PrimaryOrSecondary::Primary.as_str()],
+			i64::from(healthiest == PrimaryOrSecondary::Primary),
+		);
+		if self.has_secondary {
+			RPC_ENDPOINT_SELECTED.set(
+				&[self.name, PrimaryOrSecondary::Secondary.as_str()],
+				i64::from(healthiest == PrimaryOrSecondary::Secondary),
+			);
+		}
+
+		self.selections = self.selections.wrapping_add(1);
+		if self.has_secondary && self.selections % ENDPOINT_EXPLORATION_INTERVAL == 0 {
+			!healthiest
+		} else {
+			healthiest
+		}
+	}
+}
+
 type SubmissionFutureOutput = (
 	RequestId,
 	RequestLog,
 	RetryLimit,
 	PrimaryOrSecondary,
+	Duration,
 	Result<BoxAny, (anyhow::Error, Attempt)>,
 );
 type SubmissionFuture = Pin<Box<dyn Future<Output = SubmissionFutureOutput> + Send + 'static>>;
@@ -211,22 +340,25 @@ fn submission_future<Client: Clone + Send + Sync + 'static>(
 	let submission_fut = submission_fn(client);
 	// Apply exponential backoff to the request.
 	Box::pin(async move {
+		let start = Instant::now();
+		let result = match tokio::time::timeout(
+			max_sleep_duration(initial_request_timeout, attempt),
+			submission_fut,
+		)
+		.await
+		{
+			Ok(Ok(t)) => Ok(t),
+			Ok(Err(e)) => Err(e),
+			Err(_) => Err(anyhow::anyhow!("Request timed out")),
+		}
+		.map_err(|e| (e, attempt));
 		(
 			request_id,
 			request_log.clone(),
 			retry_limit,
 			primary_or_secondary,
-			match tokio::time::timeout(
-				max_sleep_duration(initial_request_timeout, attempt),
-				submission_fut,
-			)
-			.await
-			{
-				Ok(Ok(t)) => Ok(t),
-				Ok(Err(e)) => Err(e),
-				Err(_) => Err(anyhow::anyhow!("Request timed out")),
-			}
-			.map_err(|e| (e, attempt)),
+			start.elapsed(),
+			result,
 		)
 	})
 }
@@ -330,6 +462,8 @@ where
 		// This holds any submissions that are waiting for a slot to open up.
 		let mut submission_holder = SubmissionHolder::new(maximum_concurrent_submissions);
 
+		let mut endpoint_selector = EndpointSelector::new(name, secondary_client_fut.is_some());
+
 		let client_selector: ClientSelector<Client> =
 			ClientSelector::new(scope, primary_client_fut, secondary_client_fut);
 
@@ -337,14 +471,15 @@ where
 			utilities::loop_select! {
 				if let Some((response_sender, request_log, closure, retry_limit)) = request_receiver.recv() => {
 					let request_id = request_holder.next_request_id();
-					let (client, primary_or_secondary) = client_selector.select_client(PrimaryOrSecondary::Primary).await;
+					let (client, primary_or_secondary) = client_selector.select_client(endpoint_selector.select()).await;
 
 					tracing::debug!("Retrier {name}: Received request `{request_log}` assigning request_id `{request_id}` and requesting with `{primary_or_secondary:?}`");
 					submission_holder.push(submission_future(client, request_log, retry_limit, &closure, request_id, initial_request_timeout, 0, primary_or_secondary));
 					request_holder.insert(request_id, (response_sender, closure));
 				},
-				let (request_id, request_log, retry_limit, primary_or_secondary, result) = submission_holder.next_or_pending() => {
+				let (request_id, request_log, retry_limit, primary_or_secondary, latency, result) = submission_holder.next_or_pending() => {
 					RPC_RETRIER_TOTAL_REQUESTS.inc(&[name, request_log.rpc_method.as_str()]);
+					endpoint_selector.record(&primary_or_secondary, latency, result.is_ok());
 					match result {
 						Ok(value) => {
 							RPC_RETRIER_REQUESTS.inc(&[name, request_log.rpc_method.as_str()]);
@@ -453,6 +588,184 @@ where
 	}
 }
 
+// The number of attempts made against a single provider as part of a quorum request, before we
+// give up on it and query the next provider instead.
+const MAX_QUORUM_PROVIDER_ATTEMPTS: Attempt = 3;
+
+/// Issues requests to several providers and only accepts a response once `quorum` of them have
+/// returned an identical one. Each provider has its own [RetrierClient], so individual failures
+/// are retried as usual.
+///
+/// Providers are queried in order of how often they have disagreed with the quorum in the past,
+/// such that a dissenting provider is only consulted once the more reliable ones have failed to
+/// agree.
+#[derive(Clone)]
+pub struct QuorumRetrierClient<Client> {
+	name: &'static str,
+	providers: Vec<RetrierClient<Client>>,
+	quorum: usize,
+	initial_request_timeout: Duration,
+	// The number of times each provider has disagreed with the quorum.
+	dissents: Arc<Mutex<Vec<u32>>>,
+}
+
+impl<Client> QuorumRetrierClient<Client>
+where
+	Client: Clone + Send + Sync + 'static,
+{
+	pub fn new<ClientFut: Future<Output = Client> + Send + 'static>(
+		scope: &Scope<'_, anyhow::Error>,
+		// The name of the retrier that appears in the logs.
+		name: &'static str,
+		provider_futs: Vec<ClientFut>,
+		quorum: usize,
+		initial_request_timeout: Duration,
+		maximum_concurrent_submissions: u32,
+	) -> Self {
+		assert!(
+			quorum > 0 && quorum <= provider_futs.len(),
+			"Quorum must be between 1 and the number of providers."
+		);
+
+		let dissents = Arc::new(Mutex::new(vec![0; provider_futs.len()]));
+
+		Self {
+			name,
+			providers: provider_futs
+				.into_iter()
+				.enumerate()
+				.map(|(index, provider_fut)| {
+					RetrierClient::new(
+						scope,
+						// Each provider gets its own name so its logs and metrics can be told
+						// apart. The clients live for the lifetime of the engine, so leaking the
+						// names is fine.
+						Box::leak(format!("{name}_{index}").into_boxed_str()),
+						provider_fut,
+						None,
+						initial_request_timeout,
+						maximum_concurrent_submissions,
+					)
+				})
+				.collect(),
+			quorum,
+			initial_request_timeout,
+			dissents,
+		}
+	}
+
+	// Indices of the providers, the ones that have disagreed least often first.
+	fn providers_by_priority(&self) -> Vec<usize> {
+		let dissents = self.dissents.lock().unwrap();
+		let mut providers = (0..self.providers.len()).collect::<Vec<_>>();
+		providers.sort_by_key(|provider| dissents[*provider]);
+		providers
+	}
+
+	fn record_dissents<T: PartialEq>(
+		&self,
+		request_log: &RequestLog,
+		responses: &[(usize, T)],
+		agreed: &T,
+	) {
+		let mut dissents = self.dissents.lock().unwrap();
+		for (provider, _) in responses.iter().filter(|(_, response)| response != agreed) {
+			dissents[*provider] = dissents[*provider].saturating_add(1);
+			RPC_QUORUM_DISAGREEMENTS.inc(&[
+				self.name,
+				request_log.rpc_method.as_str(),
+				provider.to_string().as_str(),
+			]);
+			tracing::warn!(
+				"Retrier {}: Provider `{provider}` disagreed with the quorum for request `{request_log}`. It has now disagreed {} times.",
+				self.name,
+				dissents[*provider],
+			);
+		}
+	}
+
+	/// Requests something from the providers until `quorum` of them agree on the response.
+	/// Like [RetrierClient::request], this never gives up.
+	pub async fn request<T: PartialEq + Clone + Send + 'static>(
+		&self,
+		specific_closure: TypedFutureGenerator<T, Client>,
+		request_log: RequestLog,
+	) -> T {
+		let specific_closure = Arc::new(specific_closure);
+
+		let submit = |provider: usize| {
+			let specific_closure = specific_closure.clone();
+			let request_log = request_log.clone();
+			let retrier_client = &self.providers[provider];
+			async move {
+				(
+					provider,
+					retrier_client
+						.request_with_limit(
+							Box::pin(move |client| specific_closure(client)),
+							request_log,
+							MAX_QUORUM_PROVIDER_ATTEMPTS,
+						)
+						.await,
+				)
+			}
+		};
+
+		loop {
+			let mut remaining_providers = self.providers_by_priority().into_iter();
+			let mut pending = remaining_providers
+				.by_ref()
+				.take(self.quorum)
+				.map(submit)
+				.collect::<FuturesUnordered<_>>();
+			let mut responses: Vec<(usize, T)> = Vec::new();
+
+			while let Some((provider, result)) = pending.next().await {
+				match result {
+					Ok(response) => responses.push((provider, response)),
+					Err(e) => tracing::warn!(
+						"Retrier {}: Provider `{provider}` failed to respond to quorum request `{request_log}`: {e}",
+						self.name
+					),
+				}
+
+				let largest_agreement = responses
+					.iter()
+					.map(|(_, response)| {
+						(response, responses.iter().filter(|(_, other)| other == response).count())
+					})
+					.max_by_key(|(_, count)| *count);
+
+				if let Some((agreed, count)) = largest_agreement {
+					if count >= self.quorum {
+						let agreed = agreed.clone();
+						self.record_dissents(&request_log, &responses, &agreed);
+						return agreed
+					}
+				}
+
+				// Bring in further providers until the quorum can still be reached with the
+				// requests in flight.
+				let largest_agreement = largest_agreement.map(|(_, count)| count).unwrap_or(0);
+				while largest_agreement + pending.len() < self.quorum {
+					match remaining_providers.next() {
+						Some(next_provider) => pending.push(submit(next_provider)),
+						None => break,
+					}
+				}
+			}
+
+			tracing::error!(
+				"Retrier {}: Failed to reach a quorum of `{}` for request `{request_log}` with {} responses. Retrying.",
+				self.name,
+				self.quorum,
+				responses.len(),
+			);
+			tokio::time::sleep(self.initial_request_timeout).await;
+		}
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use std::any::Any;
@@ -816,6 +1129,162 @@ mod tests {
 		.unwrap();
 	}
 
+	#[test]
+	fn endpoint_selector_prefers_the_healthiest_endpoint() {
+		const FAST: Duration = Duration::from_millis(100);
+		const SLOW: Duration = Duration::from_millis(1000);
+
+		let mut selector = EndpointSelector::new("test", true);
+		assert_eq!(selector.healthiest(), PrimaryOrSecondary::Primary);
+
+		// The secondary is tried once the primary has some statistics.
+		selector.record(&PrimaryOrSecondary::Primary, SLOW, true);
+		assert_eq!(selector.healthiest(), PrimaryOrSecondary::Secondary);
+
+		// A consistently faster secondary is preferred.
+		for _ in 0..5 {
+			selector.record(&PrimaryOrSecondary::Secondary, FAST, true);
+		}
+		assert_eq!(selector.healthiest(), PrimaryOrSecondary::Secondary);
+
+		// Until it starts failing.
+		for _ in 0..10 {
+			selector.record(&PrimaryOrSecondary::Secondary, FAST, false);
+		}
+		assert_eq!(selector.healthiest(), PrimaryOrSecondary::Primary);
+
+		// Similarly healthy endpoints don't flip the preference away from the primary.
+		let mut selector = EndpointSelector::new("test", true);
+		selector.record(&PrimaryOrSecondary::Primary, FAST, true);
+		selector.record(&PrimaryOrSecondary::Secondary, FAST.mul_f64(0.9), true);
+		assert_eq!(selector.healthiest(), PrimaryOrSecondary::Primary);
+	}
+
+	#[test]
+	fn endpoint_selector_periodically_explores_the_other_endpoint() {
+		let mut selector = EndpointSelector::new("test", true);
+		selector.record(&PrimaryOrSecondary::Primary, Duration::from_millis(100), true);
+		selector.record(&PrimaryOrSecondary::Secondary, Duration::from_millis(1000), true);
+
+		let selections = (0..ENDPOINT_EXPLORATION_INTERVAL)
+			.map(|_| selector.select())
+			.collect::<Vec<_>>();
+		assert_eq!(
+			selections
+				.iter()
+				.filter(|endpoint| **endpoint == PrimaryOrSecondary::Secondary)
+				.count(),
+			1
+		);
+
+		// Without a secondary, we always use the primary.
+		let mut selector = EndpointSelector::new("test", false);
+		selector.record(&PrimaryOrSecondary::Primary, Duration::from_millis(100), false);
+		assert!((0..ENDPOINT_EXPLORATION_INTERVAL)
+			.all(|_| selector.select() == PrimaryOrSecondary::Primary));
+	}
+
+	// Each provider responds with a value derived from its id, so we can simulate disagreements.
+	fn provider_response_closure(
+		response: fn(u32) -> Result<u32>,
+	) -> TypedFutureGenerator<u32, u32> {
+		Box::pin(move |provider| Box::pin(async move { response(provider) }))
+	}
+
+	#[tokio::test]
+	async fn quorum_request_returns_agreed_response_and_deprioritises_dissenter() {
+		task_scope(|scope| {
+			async move {
+				const INITIAL_TIMEOUT: Duration = Duration::from_millis(100);
+
+				let quorum_client = QuorumRetrierClient::new(
+					scope,
+					"test",
+					(0..3u32).map(futures::future::ready).collect(),
+					2,
+					INITIAL_TIMEOUT,
+					100,
+				);
+
+				// Provider 0 disagrees with the others.
+				fn response(provider: u32) -> Result<u32> {
+					Ok(if provider == 0 { 1 } else { 2 })
+				}
+
+				assert_eq!(
+					quorum_client
+						.request(
+							provider_response_closure(response),
+							RequestLog::new("request 1".to_string(), None),
+						)
+						.await,
+					2
+				);
+				assert_eq!(*quorum_client.dissents.lock().unwrap(), vec![1, 0, 0]);
+				assert_eq!(quorum_client.providers_by_priority(), vec![1, 2, 0]);
+
+				// The dissenting provider is no longer consulted first, so the quorum is reached
+				// without it.
+				assert_eq!(
+					quorum_client
+						.request(
+							provider_response_closure(response),
+							RequestLog::new("request 2".to_string(), None),
+						)
+						.await,
+					2
+				);
+				assert_eq!(*quorum_client.dissents.lock().unwrap(), vec![1, 0, 0]);
+
+				Ok(())
+			}
+			.boxed()
+		})
+		.await
+		.unwrap();
+	}
+
+	#[tokio::test]
+	async fn quorum_request_succeeds_if_a_provider_keeps_failing() {
+		task_scope(|scope| {
+			async move {
+				const INITIAL_TIMEOUT: Duration = Duration::from_millis(20);
+
+				let quorum_client = QuorumRetrierClient::new(
+					scope,
+					"test",
+					(0..3u32).map(futures::future::ready).collect(),
+					2,
+					INITIAL_TIMEOUT,
+					100,
+				);
+
+				assert_eq!(
+					quorum_client
+						.request(
+							provider_response_closure(|provider| {
+								if provider == 0 {
+									Err(anyhow::anyhow!("Sorry, this just doesn't work."))
+								} else {
+									Ok(2)
+								}
+							}),
+							RequestLog::new("request".to_string(), None),
+						)
+						.await,
+					2
+				);
+				// Failing to respond is not a disagreement.
+				assert_eq!(*quorum_client.dissents.lock().unwrap(), vec![0, 0, 0]);
+
+				Ok(())
+			}
+			.boxed()
+		})
+		.await
+		.unwrap();
+	}
+
 	#[tokio::test]
 	#[ignore = "Test runs forever. Useful for manually testing the failing requests will never return (because they are retried until success)."]
 	async fn request_always_fails() {
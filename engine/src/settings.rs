@@ -21,6 +21,7 @@ use utilities::{
 };
 
 use crate::constants::{CONFIG_ROOT, DEFAULT_CONFIG_ROOT};
+use cf_primitives::{EpochIndex, ForeignChain};
 
 pub const DEFAULT_SETTINGS_DIR: &str = "config";
 
@@ -75,13 +76,46 @@ pub struct NodeContainer<NodeConfig> {
 	pub primary: NodeConfig,
 	#[serde(rename = "backup_rpc")]
 	pub backup: Option<NodeConfig>,
+	/// Further providers that are only queried, together with the primary and backup, for
+	/// witnessing-critical requests when a quorum is configured.
+	#[serde(rename = "additional_rpcs", default)]
+	pub additional: Vec<NodeConfig>,
+	/// The number of providers that must return identical responses before a
+	/// witnessing-critical response is accepted. If `None`, the first response is trusted.
+	#[serde(default)]
+	pub quorum: Option<usize>,
+}
+
+impl<NodeConfig> NodeContainer<NodeConfig> {
+	/// All configured providers, in order of preference.
+	pub fn providers(&self) -> impl Iterator<Item = &NodeConfig> {
+		std::iter::once(&self.primary)
+			.chain(self.backup.iter())
+			.chain(self.additional.iter())
+	}
 }
 
 impl<NodeConfig: ValidateSettings> NodeContainer<NodeConfig> {
 	pub fn validate(&self) -> Result<(), ConfigError> {
-		self.primary.validate()?;
-		if let Some(backup) = &self.backup {
-			backup.validate()?;
+		for provider in self.providers() {
+			provider.validate()?;
+		}
+		match self.quorum {
+			Some(quorum) => {
+				let provider_count = self.providers().count();
+				if quorum == 0 || quorum > provider_count {
+					return Err(ConfigError::Message(format!(
+						"Quorum must be between 1 and the number of configured providers ({provider_count}), got {quorum}"
+					)))
+				}
+			},
+			None =>
+				if !self.additional.is_empty() {
+					return Err(ConfigError::Message(
+						"Additional rpc providers are only used when a quorum is configured"
+							.to_string(),
+					))
+				},
 		}
 		Ok(())
 	}
@@ -121,9 +155,8 @@ impl Dot {
 					))
 				})
 		};
-		validate_dot_endpoints(&self.nodes.primary)?;
-		if let Some(backup) = &self.nodes.backup {
-			validate_dot_endpoints(backup)?;
+		for provider in self.nodes.providers() {
+			validate_dot_endpoints(provider)?;
 		}
 		Ok(())
 	}
@@ -160,6 +193,11 @@ impl ValidateSettings for HttpBasicAuthEndpoint {
 pub struct Btc {
 	#[serde(flatten)]
 	pub nodes: NodeContainer<HttpBasicAuthEndpoint>,
+	/// If set, the BIP-158 filter of each block is checked before downloading it, and only blocks
+	/// that may contain a deposit or vault transaction are downloaded. This requires the nodes to
+	/// be run with `-blockfilterindex=1`.
+	#[serde(default)]
+	pub use_block_filters: bool,
 }
 
 impl Btc {
@@ -168,6 +206,14 @@ impl Btc {
 	}
 }
 
+#[derive(Debug, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct Witnessing {
+	/// If set, the chain data and RPC responses used by each chain's witnessers are recorded to a
+	/// fixture file per chain in this directory, so the witnessing can be replayed offline.
+	#[serde(default)]
+	pub fixture_dir: Option<PathBuf>,
+}
+
 #[derive(Debug, Deserialize, Clone, Default, PartialEq, Eq)]
 pub struct HealthCheck {
 	pub hostname: String,
@@ -189,6 +235,9 @@ pub struct Settings {
 	pub dot: Dot,
 	pub btc: Btc,
 
+	#[serde(default)]
+	pub witnessing: Witnessing,
+
 	pub health_check: Option<HealthCheck>,
 	pub prometheus: Option<Prometheus>,
 	pub signing: Signing,
@@ -247,6 +296,9 @@ pub struct BtcOptions {
 	pub btc_backup_basic_auth_user: Option<String>,
 	#[clap(long = "btc.backup_rpc.basic_auth_password")]
 	pub btc_backup_basic_auth_password: Option<String>,
+
+	#[clap(long = "btc.use_block_filters")]
+	pub btc_use_block_filters: Option<bool>,
 }
 
 #[derive(Parser, Debug, Clone, Default)]
@@ -283,6 +335,10 @@ pub struct CommandLineOptions {
 	#[clap(flatten)]
 	pub btc_opts: BtcOptions,
 
+	// Witnessing Settings
+	#[clap(long = "witnessing.fixture_dir", parse(from_os_str))]
+	pub witnessing_fixture_dir: Option<PathBuf>,
+
 	// Health Check Settings
 	#[clap(long = "health_check.hostname")]
 	pub health_check_hostname: Option<String>,
@@ -305,6 +361,52 @@ pub struct CommandLineOptions {
 
 	#[clap(long = "logging.command_server_port")]
 	pub logging_command_server_port: Option<Port>,
+
+	#[clap(subcommand)]
+	pub command: Option<EngineCommand>,
+}
+
+#[derive(clap::Subcommand, Debug, Clone)]
+pub enum EngineCommand {
+	/// Re-witness a historical range of external chain blocks, instead of running the engine.
+	///
+	/// Deposits to deposit channels that have expired on the State Chain are not witnessed.
+	Replay(ReplayOptions),
+}
+
+#[derive(Parser, Debug, Clone, PartialEq, Eq)]
+pub struct ReplayOptions {
+	/// The chain to re-witness ("Ethereum"|"Bitcoin"|"Polkadot")
+	#[clap(long = "chain")]
+	pub chain: ForeignChain,
+	/// The epoch whose vault the blocks are witnessed for
+	#[clap(long = "epoch")]
+	pub epoch: EpochIndex,
+	/// The first block to re-witness
+	#[clap(long = "from-block")]
+	pub from_block: u64,
+	/// The last block to re-witness (inclusive)
+	#[clap(long = "to-block")]
+	pub to_block: u64,
+	/// Submit the witnessed calls to the State Chain. By default they are only logged.
+	#[clap(long = "submit")]
+	pub submit: bool,
+}
+
+impl ReplayOptions {
+	pub fn validate(&self) -> Result<()> {
+		if self.from_block > self.to_block {
+			bail!(
+				"The block range is empty: from block {} is after to block {}",
+				self.from_block,
+				self.to_block
+			);
+		}
+		if self.chain == ForeignChain::Polkadot && u32::try_from(self.to_block).is_err() {
+			bail!("Polkadot block {} doesn't exist", self.to_block);
+		}
+		Ok(())
+	}
 }
 
 impl Default for CommandLineOptions {
@@ -319,6 +421,7 @@ impl Default for CommandLineOptions {
 			eth_opts: EthOptions::default(),
 			dot_opts: DotOptions::default(),
 			btc_opts: BtcOptions::default(),
+			witnessing_fixture_dir: None,
 			health_check_hostname: None,
 			health_check_port: None,
 			prometheus_hostname: None,
@@ -326,6 +429,7 @@ impl Default for CommandLineOptions {
 			signing_db_file: None,
 			logging_span_lifecycle: false,
 			logging_command_server_port: None,
+			command: None,
 		}
 	}
 }
@@ -339,6 +443,8 @@ const STATE_CHAIN_SIGNING_KEY_FILE: &str = "state_chain.signing_key_file";
 
 const ETH_PRIVATE_KEY_FILE: &str = "eth.private_key_file";
 
+const WITNESSING_FIXTURE_DIR: &str = "witnessing.fixture_dir";
+
 const SIGNING_DB_FILE: &str = "signing.db_file";
 
 const LOGGING_SPAN_LIFECYCLE: &str = "logging.span_lifecycle";
@@ -583,6 +689,12 @@ impl Source for CommandLineOptions {
 
 		self.btc_opts.insert_all(&mut map);
 
+		insert_command_line_option_path(
+			&mut map,
+			WITNESSING_FIXTURE_DIR,
+			&self.witnessing_fixture_dir,
+		);
+
 		insert_command_line_option(&mut map, "health_check.hostname", &self.health_check_hostname);
 		insert_command_line_option(&mut map, "health_check.port", &self.health_check_port);
 
@@ -699,6 +811,8 @@ impl BtcOptions {
 			"btc.backup_rpc.basic_auth_password",
 			&self.btc_backup_basic_auth_password,
 		);
+
+		insert_command_line_option(map, "btc.use_block_filters", &self.btc_use_block_filters);
 	}
 }
 
@@ -951,7 +1065,10 @@ pub mod tests {
 				btc_backup_http_endpoint: Some("http://second.btc-endpoint:4321".to_owned()),
 				btc_backup_basic_auth_user: Some("second.my_username".to_owned()),
 				btc_backup_basic_auth_password: Some("second.my_password".to_owned()),
+
+				btc_use_block_filters: Some(true),
 			},
+			witnessing_fixture_dir: Some(PathBuf::from_str("not/a/real/fixtures").unwrap()),
 			health_check_hostname: Some("health_check_hostname".to_owned()),
 			health_check_port: Some(1337),
 			prometheus_hostname: Some(("prometheus_hostname").to_owned()),
@@ -959,6 +1076,7 @@ pub mod tests {
 			signing_db_file: Some(PathBuf::from_str("also/not/real.db").unwrap()),
 			logging_span_lifecycle: true,
 			logging_command_server_port: Some(6969),
+			command: None,
 		};
 
 		// Load the test opts into the settings
@@ -1040,6 +1158,9 @@ pub mod tests {
 			opts.btc_opts.btc_backup_basic_auth_password.unwrap(),
 			btc_backup_node.basic_auth_password
 		);
+		assert_eq!(opts.btc_opts.btc_use_block_filters.unwrap(), settings.btc.use_block_filters);
+
+		assert_eq!(opts.witnessing_fixture_dir, settings.witnessing.fixture_dir);
 
 		assert_eq!(
 			opts.health_check_hostname.unwrap(),
@@ -1122,6 +1243,8 @@ pub mod tests {
 					ws_endpoint: "ws://valid.endpoint_with_port:1234".into(),
 					http_endpoint: "http://valid.endpoint_with_port:6969".into(),
 				}),
+				additional: vec![],
+				quorum: None,
 			},
 		};
 		assert_ok!(valid_settings.validate_settings());
@@ -1137,6 +1260,90 @@ pub mod tests {
 			http_endpoint: "http://invalid.no_port_in_url/secret_key".into(),
 		});
 		assert!(invalid_backup_settings.validate_settings().is_err());
+
+		let mut invalid_additional_settings = valid_settings.clone();
+		invalid_additional_settings.nodes.additional = vec![WsHttpEndpoints {
+			ws_endpoint: "ws://invalid.no_port_in_url/secret_key".into(),
+			http_endpoint: "http://valid.endpoint_with_port:443".into(),
+		}];
+		invalid_additional_settings.nodes.quorum = Some(2);
+		assert!(invalid_additional_settings.validate_settings().is_err());
+	}
+
+	#[test]
+	fn test_replay_command_parsing() {
+		let opts = CommandLineOptions::try_parse_from([
+			"chainflip-engine",
+			"replay",
+			"--chain",
+			"Ethereum",
+			"--epoch",
+			"3",
+			"--from-block",
+			"100",
+			"--to-block",
+			"200",
+		])
+		.unwrap();
+
+		let Some(EngineCommand::Replay(replay_options)) = opts.command else {
+			panic!("Expected the replay command")
+		};
+		assert_eq!(
+			replay_options,
+			ReplayOptions {
+				chain: ForeignChain::Ethereum,
+				epoch: 3,
+				from_block: 100,
+				to_block: 200,
+				submit: false,
+			}
+		);
+		assert_ok!(replay_options.validate());
+
+		assert!(ReplayOptions { from_block: 201, ..replay_options.clone() }.validate().is_err());
+		assert_ok!(
+			ReplayOptions { chain: ForeignChain::Polkadot, ..replay_options.clone() }.validate()
+		);
+		assert!(ReplayOptions {
+			chain: ForeignChain::Polkadot,
+			to_block: u64::from(u32::MAX) + 1,
+			..replay_options
+		}
+		.validate()
+		.is_err());
+	}
+
+	#[test]
+	fn test_quorum_validation() {
+		let endpoint = |port: u16| WsHttpEndpoints {
+			ws_endpoint: format!("ws://valid.endpoint:{port}").into(),
+			http_endpoint: format!("http://valid.endpoint:{port}").into(),
+		};
+		let mut nodes = NodeContainer {
+			primary: endpoint(1),
+			backup: Some(endpoint(2)),
+			additional: vec![endpoint(3)],
+			quorum: Some(2),
+		};
+		assert_ok!(nodes.validate());
+		assert_eq!(nodes.providers().count(), 3);
+
+		nodes.quorum = Some(3);
+		assert_ok!(nodes.validate());
+
+		// Quorum can't be larger than the number of providers.
+		nodes.quorum = Some(4);
+		assert!(nodes.validate().is_err());
+
+		nodes.quorum = Some(0);
+		assert!(nodes.validate().is_err());
+
+		// Additional providers are only used for quorum requests.
+		nodes.quorum = None;
+		assert!(nodes.validate().is_err());
+		nodes.additional.clear();
+		assert_ok!(nodes.validate());
 	}
 
 	#[test]
@@ -2,4 +2,5 @@ pub mod btc;
 pub mod common;
 pub mod dot;
 pub mod eth;
+pub mod replay;
 pub mod start;
@@ -1,11 +1,15 @@
+mod btc_block_filters;
 mod btc_chain_tracking;
 mod btc_deposits;
 pub mod btc_source;
 
-use std::sync::Arc;
+use std::{ops::RangeInclusive, sync::Arc};
 
 use bitcoin::{BlockHash, Transaction};
-use cf_chains::btc::{self, deposit_address::DepositAddress, BlockNumber, CHANGE_ADDRESS_SALT};
+use cf_chains::{
+	btc::{self, deposit_address::DepositAddress, BlockNumber, CHANGE_ADDRESS_SALT},
+	Bitcoin,
+};
 use cf_primitives::EpochIndex;
 use futures_core::Future;
 use secp256k1::hashes::Hash;
@@ -21,7 +25,10 @@ use crate::{
 use btc_source::BtcSource;
 
 use super::common::{
-	chain_source::{extension::ChainSourceExt, Header},
+	chain_source::{
+		extension::ChainSourceExt, fixture::FixtureRecorder, replay::ReplaySource, ChainClient,
+		Header,
+	},
 	epoch_source::{EpochSourceBuilder, Vault},
 };
 
@@ -29,6 +36,9 @@ use anyhow::Result;
 
 // safety margin of 5 implies 6 block confirmations
 const SAFETY_MARGIN: usize = 5;
+/// The number of safe blocks whose hashes are remembered to detect reorgs deeper than the
+/// safety margin.
+const REORG_DETECTION_HISTORY: usize = 32;
 
 pub async fn process_egress<ProcessCall, ProcessingFut, ExtraInfo, ExtraHistoricInfo>(
 	epoch: Vault<cf_chains::Bitcoin, ExtraInfo, ExtraHistoricInfo>,
@@ -76,11 +86,13 @@ pub async fn start<
 >(
 	scope: &Scope<'_, anyhow::Error>,
 	btc_client: BtcRetryRpcClient,
+	use_block_filters: bool,
+	fixture_recorder: Option<FixtureRecorder>,
 	process_call: ProcessCall,
 	prewitness_call: PrewitnessCall,
 	state_chain_client: Arc<StateChainClient>,
 	state_chain_stream: StateChainStream,
-	unfinalised_state_chain_stream: impl StateChainStreamApi<false>,
+	unfinalised_state_chain_stream: impl StateChainStreamApi<false> + Clone,
 	epoch_source: EpochSourceBuilder<'_, '_, StateChainClient, (), ()>,
 	db: Arc<PersistentKeyDB>,
 ) -> Result<()>
@@ -100,7 +112,9 @@ where
 		+ 'static,
 	PrewitnessFut: Future<Output = ()> + Send + 'static,
 {
-	let btc_source = BtcSource::new(btc_client.clone()).shared(scope);
+	let btc_client = btc_client.with_recorder(fixture_recorder.clone());
+
+	let btc_source = BtcSource::new(btc_client.clone()).recording(fixture_recorder).shared(scope);
 
 	btc_source
 		.clone()
@@ -118,8 +132,14 @@ where
 			move |header| {
 				let btc_client = btc_client.clone();
 				async move {
-					let block = btc_client.block(header.hash).await;
-					(header.data, block.txdata)
+					// With block filters, we can only decide whether to download the block once we
+					// know the deposit addresses and the pending egresses, see `btc_block_filters`.
+					let txs = if use_block_filters {
+						None
+					} else {
+						Some(btc_client.block(header.hash).await.txdata)
+					};
+					(header.data, txs)
 				}
 			}
 		})
@@ -129,8 +149,15 @@ where
 	strictly_monotonic_source
 		.clone()
 		.chunk_by_vault(vaults.clone(), scope)
-		.deposit_addresses(scope, unfinalised_state_chain_stream, state_chain_client.clone())
+		.deposit_addresses(
+			scope,
+			unfinalised_state_chain_stream.clone(),
+			state_chain_client.clone(),
+		)
+		.await
+		.egress_items(scope, unfinalised_state_chain_stream, state_chain_client.clone())
 		.await
+		.btc_block_filters(btc_client.clone())
 		.btc_deposits(prewitness_call)
 		.logging("pre-witnessing")
 		.spawn(scope);
@@ -138,13 +165,15 @@ where
 	// Full witnessing stream.
 	strictly_monotonic_source
 		.lag_safety(SAFETY_MARGIN)
+		.reorg_detection(REORG_DETECTION_HISTORY)
 		.logging("safe block produced")
 		.chunk_by_vault(vaults, scope)
 		.deposit_addresses(scope, state_chain_stream.clone(), state_chain_client.clone())
 		.await
-		.btc_deposits(process_call.clone())
 		.egress_items(scope, state_chain_stream, state_chain_client.clone())
 		.await
+		.btc_block_filters(btc_client.clone())
+		.btc_deposits(process_call.clone())
 		.then({
 			let process_call = process_call.clone();
 			move |epoch, header| process_egress(epoch, header, process_call.clone())
@@ -156,6 +185,60 @@ where
 	Ok(())
 }
 
+/// Runs the full witnessing pipeline over a fixed range of already finalised blocks, for the
+/// epochs in `epoch_source`. Nothing is persisted, so this doesn't affect the progress of the
+/// regular witnessers. Completes once all the blocks have been witnessed.
+pub async fn replay<BtcClient, StateChainClient, StateChainStream, ProcessCall, ProcessingFut>(
+	scope: &Scope<'_, anyhow::Error>,
+	btc_client: BtcClient,
+	process_call: ProcessCall,
+	state_chain_client: Arc<StateChainClient>,
+	state_chain_stream: StateChainStream,
+	epoch_source: EpochSourceBuilder<'_, '_, StateChainClient, (), ()>,
+	blocks: RangeInclusive<BlockNumber>,
+) -> Result<()>
+where
+	BtcClient: BtcRetryRpcApi
+		+ ChainClient<Index = BlockNumber, Hash = BlockHash, Data = ()>
+		+ Send
+		+ Sync
+		+ 'static,
+	StateChainClient: StorageApi + SignedExtrinsicApi + 'static + Send + Sync,
+	StateChainStream: StateChainStreamApi + Clone + 'static + Send + Sync,
+	ProcessCall: Fn(state_chain_runtime::RuntimeCall, EpochIndex) -> ProcessingFut
+		+ Send
+		+ Sync
+		+ Clone
+		+ 'static,
+	ProcessingFut: Future<Output = ()> + Send + 'static,
+{
+	let vaults = epoch_source.vaults().await;
+
+	ReplaySource::<_, Bitcoin>::new(btc_client.clone(), blocks)
+		.logging("replayed block")
+		.then({
+			let btc_client = btc_client.clone();
+			move |header| {
+				let btc_client = btc_client.clone();
+				async move {
+					let block = btc_client.block(header.hash).await;
+					(header.data, block.txdata)
+				}
+			}
+		})
+		.chunk_by_vault(vaults, scope)
+		.deposit_addresses(scope, state_chain_stream.clone(), state_chain_client.clone())
+		.await
+		.egress_items(scope, state_chain_stream, state_chain_client)
+		.await
+		.btc_deposits(process_call.clone())
+		.then(move |epoch, header| process_egress(epoch, header, process_call.clone()))
+		.run_until_exhausted()
+		.await;
+
+	Ok(())
+}
+
 fn success_witnesses<'a>(
 	monitored_tx_hashes: impl Iterator<Item = &'a btc::Hash> + Clone,
 	txs: &Vec<Transaction>,
@@ -176,10 +259,26 @@ fn success_witnesses<'a>(
 mod tests {
 
 	use super::*;
+	use crate::{
+		btc::retry_rpc::fixture::FixtureBtcRpcClient,
+		witness::common::{
+			chain_source::{extension::ChainSourceExt, fixture::FixtureSource},
+			test_helpers::{witnessed_calls, MockStateChain},
+		},
+	};
 	use bitcoin::{
 		absolute::{Height, LockTime},
 		ScriptBuf, Transaction, TxOut,
 	};
+	use cf_chains::{
+		assets,
+		btc::{ScriptPubkey, UtxoId},
+		DepositChannel,
+	};
+	use futures::FutureExt;
+	use pallet_cf_ingress_egress::{ChannelAction, DepositChannelDetails, DepositWitness};
+	use sp_runtime::AccountId32;
+	use state_chain_runtime::BitcoinInstance;
 
 	fn fake_transaction(tx_outs: Vec<TxOut>) -> Transaction {
 		Transaction {
@@ -220,4 +319,158 @@ mod tests {
 
 		assert_eq!(success_witnesses, monitored_hashes);
 	}
+
+	fn deposit_channel(
+		address: ScriptPubkey,
+	) -> DepositChannelDetails<state_chain_runtime::Runtime, BitcoinInstance> {
+		DepositChannelDetails {
+			deposit_channel: DepositChannel {
+				channel_id: 1,
+				address,
+				asset: assets::btc::Asset::Btc,
+				state: DepositAddress::new([0; 32], 1),
+			},
+			opened_at: 0,
+			expires_at: 1000,
+			action: ChannelAction::LiquidityProvision { lp_account: AccountId32::new([0xab; 32]) },
+		}
+	}
+
+	fn tx_hash(hex: &str) -> btc::Hash {
+		hex::decode(hex).unwrap().try_into().unwrap()
+	}
+
+	fn load_fixture() -> FixtureSource<Bitcoin, BlockHash, ()> {
+		FixtureSource::load(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/bitcoin.jsonl")).unwrap()
+	}
+
+	fn egress_success(
+		tx_out_id: btc::Hash,
+		vault_key: &btc::AggKey,
+	) -> state_chain_runtime::RuntimeCall {
+		pallet_cf_broadcast::Call::<_, BitcoinInstance>::transaction_succeeded {
+			tx_out_id,
+			signer_id: DepositAddress::new(vault_key.current, CHANGE_ADDRESS_SALT).script_pubkey(),
+			tx_fee: Default::default(),
+			tx_metadata: (),
+		}
+		.into()
+	}
+
+	#[tokio::test]
+	async fn deposits_and_egress_success_are_witnessed_from_fixture() {
+		let source = load_fixture();
+		let vault_key = btc::AggKey { previous: None, current: [0xcc; 32] };
+		let deposit_address = ScriptPubkey::P2WPKH([0x33; 20]);
+		let egress_tx_hash =
+			tx_hash("d1427361252312381b6a142252e164a95dfaa7ebc96c73819155f95c7d4ee37b");
+
+		let witnessed_calls = witnessed_calls(
+			MockStateChain::default()
+				.with_vault::<Bitcoin>(
+					pallet_cf_vaults::Vault { public_key: vault_key, active_from_block: 0 },
+					202,
+				)
+				.with_deposit_channels::<Bitcoin>(vec![deposit_channel(deposit_address.clone())])
+				.with_egress_items::<Bitcoin>(vec![(egress_tx_hash, 200)]),
+			move |context| {
+				async move {
+					replay(
+						context.scope,
+						FixtureBtcRpcClient::new(&source),
+						context.process_call(),
+						context.state_chain_client,
+						context.state_chain_stream,
+						context.epoch_source,
+						200..=201,
+					)
+					.await
+				}
+				.boxed()
+			},
+		)
+		.await;
+
+		let deposit = |tx_hash, amount, block_height| -> state_chain_runtime::RuntimeCall {
+			pallet_cf_ingress_egress::Call::<_, BitcoinInstance>::process_deposits {
+				deposit_witnesses: vec![DepositWitness {
+					deposit_address: deposit_address.clone(),
+					asset: assets::btc::Asset::Btc,
+					amount,
+					deposit_details: UtxoId { tx_id: tx_hash, vout: 0 },
+				}],
+				block_height,
+			}
+			.into()
+		};
+		assert_eq!(
+			witnessed_calls,
+			vec![
+				deposit(
+					tx_hash("17ef88ed48bf98bf42a170209dafa459a08c578c6e94d2fe0ed77d785f730430"),
+					50_000,
+					200
+				),
+				deposit(
+					tx_hash("033e84693107d16731d469824efd1346560e171edd0dc3ce9f30d34d57ba7a72"),
+					75_000,
+					201
+				),
+				egress_success(egress_tx_hash, &vault_key),
+			]
+		);
+	}
+
+	#[tokio::test]
+	async fn block_filters_dont_hide_egress_success() {
+		let source = load_fixture();
+		let vault_key = btc::AggKey { previous: None, current: [0xcc; 32] };
+		// Spends no vault UTXOs and has no change output, so the filter of its block doesn't match
+		// any of the scripts we monitor.
+		let egress_tx_hash =
+			tx_hash("d1427361252312381b6a142252e164a95dfaa7ebc96c73819155f95c7d4ee37b");
+
+		let witnessed_calls = witnessed_calls(
+			MockStateChain::default()
+				.with_vault::<Bitcoin>(
+					pallet_cf_vaults::Vault { public_key: vault_key, active_from_block: 0 },
+					202,
+				)
+				.with_deposit_channels::<Bitcoin>(vec![])
+				.with_egress_items::<Bitcoin>(vec![(egress_tx_hash, 201)]),
+			move |context| {
+				async move {
+					let btc_client = FixtureBtcRpcClient::new(&source);
+					let process_call = context.process_call();
+					source
+						.then(|header| async move { (header.data, None::<Vec<Transaction>>) })
+						.chunk_by_vault(context.epoch_source.vaults().await, context.scope)
+						.deposit_addresses(
+							context.scope,
+							context.state_chain_stream.clone(),
+							context.state_chain_client.clone(),
+						)
+						.await
+						.egress_items(
+							context.scope,
+							context.state_chain_stream,
+							context.state_chain_client,
+						)
+						.await
+						.btc_block_filters(btc_client)
+						.btc_deposits(process_call.clone())
+						.then(move |epoch, header| {
+							process_egress(epoch, header, process_call.clone())
+						})
+						.run_until_exhausted()
+						.await;
+					Ok(())
+				}
+				.boxed()
+			},
+		)
+		.await;
+
+		assert_eq!(witnessed_calls, vec![egress_success(egress_tx_hash, &vault_key)]);
+	}
 }
@@ -0,0 +1,157 @@
+use bitcoin::{bip158::BlockFilter, BlockHash, Transaction};
+use cf_chains::{
+	btc::{deposit_address::DepositAddress, CHANGE_ADDRESS_SALT},
+	Bitcoin,
+};
+
+use super::super::common::chunked_chain_source::chunked_by_vault::{
+	builder::ChunkedByVaultBuilder, ChunkedByVault,
+};
+use crate::{
+	btc::retry_rpc::BtcRetryRpcApi,
+	witness::common::chunked_chain_source::chunked_by_vault::{
+		deposit_addresses::Addresses, egress_items::TxOutIdsInitiatedAt,
+	},
+};
+
+impl<Inner: ChunkedByVault> ChunkedByVaultBuilder<Inner> {
+	/// Downloads the transactions of the blocks that weren't downloaded upfront, but only if
+	/// egress transactions are pending, or if the block's BIP-158 filter matches the script of one
+	/// of the deposit channels or of the vault. The filters include both the output scripts and
+	/// the scripts of the spent outputs, so any deposit, or any transaction spending from or
+	/// returning change to the vault, will match. The other blocks are treated as having no
+	/// transactions, as they can't contain anything we witness.
+	pub fn btc_block_filters<BtcRetryClient>(
+		self,
+		btc_client: BtcRetryClient,
+	) -> ChunkedByVaultBuilder<
+		impl ChunkedByVault<
+			Index = u64,
+			Hash = BlockHash,
+			Data = ((((), Vec<Transaction>), Addresses<Inner>), TxOutIdsInitiatedAt<Inner>),
+			Chain = Bitcoin,
+		>,
+	>
+	where
+		Inner: ChunkedByVault<
+			Index = u64,
+			Hash = BlockHash,
+			Data = ((((), Option<Vec<Transaction>>), Addresses<Inner>), TxOutIdsInitiatedAt<Inner>),
+			Chain = Bitcoin,
+		>,
+		BtcRetryClient: BtcRetryRpcApi + Send + Sync + 'static,
+	{
+		self.then(move |epoch, header| {
+			let btc_client = btc_client.clone();
+			async move {
+				let ((((), txs), addresses), egress_items) = header.data;
+
+				let txs = match txs {
+					Some(txs) => txs,
+					// An egress can spend only UTXOs of expired deposit channels and have no change
+					// output, in which case it doesn't match any of the scripts we monitor.
+					None if !egress_items.is_empty() => btc_client.block(header.hash).await.txdata,
+					None => {
+						let vault_key = &epoch.info.0.public_key;
+						let monitored_scripts = addresses
+							.iter()
+							.map(|channel| channel.deposit_channel.address.bytes())
+							.chain(
+								[Some(vault_key.current), vault_key.previous]
+									.into_iter()
+									.flatten()
+									.map(|key| {
+										DepositAddress::new(key, CHANGE_ADDRESS_SALT)
+											.script_pubkey()
+											.bytes()
+									}),
+							)
+							.collect::<Vec<_>>();
+
+						if block_may_contain(
+							&btc_client.block_filter(header.hash).await,
+							&header.hash,
+							&monitored_scripts,
+						) {
+							btc_client.block(header.hash).await.txdata
+						} else {
+							Vec::new()
+						}
+					},
+				};
+
+				((((), txs), addresses), egress_items)
+			}
+		})
+	}
+}
+
+/// Filters have false positives but no false negatives, so if this returns false, the block
+/// definitely doesn't contain any of the scripts.
+fn block_may_contain(filter: &BlockFilter, block_hash: &BlockHash, scripts: &[Vec<u8>]) -> bool {
+	filter
+		.match_any(block_hash, &mut scripts.iter().map(|script| script.as_slice()))
+		.unwrap_or_else(|error| {
+			tracing::warn!(
+				"Failed to match the filter of block {block_hash}, downloading it anyway: {error}"
+			);
+			true
+		})
+}
+
+#[cfg(test)]
+mod tests {
+	use bitcoin::{
+		absolute::{Height, LockTime},
+		bip158,
+		block::{Header, Version},
+		hash_types::TxMerkleNode,
+		hashes::Hash,
+		Block, CompactTarget, OutPoint, ScriptBuf, TxOut,
+	};
+
+	use super::*;
+
+	fn block_paying_to(scripts: Vec<Vec<u8>>) -> Block {
+		Block {
+			header: Header {
+				version: Version::ONE,
+				prev_blockhash: BlockHash::all_zeros(),
+				merkle_root: TxMerkleNode::all_zeros(),
+				time: 0,
+				bits: CompactTarget::from_consensus(0),
+				nonce: 0,
+			},
+			txdata: vec![Transaction {
+				version: 2,
+				lock_time: LockTime::Blocks(Height::from_consensus(0).unwrap()),
+				input: vec![],
+				output: scripts
+					.into_iter()
+					.map(|script| TxOut { value: 1000, script_pubkey: ScriptBuf::from(script) })
+					.collect(),
+			}],
+		}
+	}
+
+	fn script(pubkey_x: u8) -> Vec<u8> {
+		DepositAddress::new([pubkey_x; 32], CHANGE_ADDRESS_SALT).script_pubkey().bytes()
+	}
+
+	#[test]
+	fn filter_matches_blocks_containing_a_monitored_script() {
+		let block = block_paying_to(vec![script(1), script(2)]);
+		let filter = BlockFilter::new_script_filter(
+			&block,
+			|_outpoint: &OutPoint| -> Result<ScriptBuf, bip158::Error> {
+				unreachable!("The block has no inputs")
+			},
+		)
+		.unwrap();
+		let block_hash = block.block_hash();
+
+		assert!(block_may_contain(&filter, &block_hash, &[script(2)]));
+		assert!(block_may_contain(&filter, &block_hash, &[script(3), script(1)]));
+		assert!(!block_may_contain(&filter, &block_hash, &[script(3), script(4)]));
+	}
+}
@@ -11,8 +11,10 @@ use super::super::common::chunked_chain_source::chunked_by_vault::{
 	builder::ChunkedByVaultBuilder, ChunkedByVault,
 };
 use crate::witness::common::{
-	chunked_chain_source::chunked_by_vault::deposit_addresses::Addresses, RuntimeCallHasChain,
-	RuntimeHasChain,
+	chunked_chain_source::chunked_by_vault::{
+		deposit_addresses::Addresses, egress_items::TxOutIdsInitiatedAt,
+	},
+	RuntimeCallHasChain, RuntimeHasChain,
 };
 use bitcoin::BlockHash;
 use cf_chains::{
@@ -26,13 +28,18 @@ impl<Inner: ChunkedByVault> ChunkedByVaultBuilder<Inner> {
 		self,
 		process_call: ProcessCall,
 	) -> ChunkedByVaultBuilder<
-		impl ChunkedByVault<Index = u64, Hash = BlockHash, Data = Vec<Transaction>, Chain = Bitcoin>,
+		impl ChunkedByVault<
+			Index = u64,
+			Hash = BlockHash,
+			Data = (Vec<Transaction>, TxOutIdsInitiatedAt<Inner>),
+			Chain = Bitcoin,
+		>,
 	>
 	where
 		Inner: ChunkedByVault<
 			Index = u64,
 			Hash = BlockHash,
-			Data = (((), Vec<Transaction>), Addresses<Inner>),
+			Data = ((((), Vec<Transaction>), Addresses<Inner>), TxOutIdsInitiatedAt<Inner>),
 			Chain = Bitcoin,
 		>,
 		ProcessCall: Fn(state_chain_runtime::RuntimeCall, EpochIndex) -> ProcessingFut
@@ -49,7 +56,7 @@ impl<Inner: ChunkedByVault> ChunkedByVaultBuilder<Inner> {
 			let process_call = process_call.clone();
 			async move {
 				// TODO: Make addresses a Map of some kind?
-				let (((), txs), addresses) = header.data;
+				let ((((), txs), addresses), egress_items) = header.data;
 
 				let script_addresses = script_addresses(addresses);
 
@@ -67,7 +74,7 @@ impl<Inner: ChunkedByVault> ChunkedByVaultBuilder<Inner> {
 					)
 					.await;
 				}
-				txs
+				(txs, egress_items)
 			}
 		})
 	}
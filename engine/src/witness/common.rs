@@ -1,6 +1,8 @@
 pub mod chain_source;
 pub mod chunked_chain_source;
 pub mod epoch_source;
+#[cfg(test)]
+pub mod test_helpers;
 
 use cf_chains::Chain;
 use futures_core::{stream::BoxStream, Future, Stream};
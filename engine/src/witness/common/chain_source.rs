@@ -1,7 +1,10 @@
 pub mod and_then;
 pub mod extension;
+pub mod fixture;
 pub mod lag_safety;
 pub mod logging;
+pub mod reorg_detection;
+pub mod replay;
 pub mod shared;
 pub mod strictly_monotonic;
 pub mod then;
@@ -11,8 +11,16 @@ use crate::witness::common::{
 };
 
 use super::{
-	aliases, and_then::AndThen, lag_safety::LagSafety, logging::Logging, shared::SharedSource,
-	strictly_monotonic::StrictlyMonotonic, then::Then, ChainSource, Header,
+	aliases,
+	and_then::AndThen,
+	fixture::{FixtureData, FixtureRecorder, Recording},
+	lag_safety::LagSafety,
+	logging::Logging,
+	reorg_detection::ReorgDetection,
+	shared::SharedSource,
+	strictly_monotonic::StrictlyMonotonic,
+	then::Then,
+	ChainSource, Header,
 };
 
 #[async_trait::async_trait]
@@ -51,6 +59,16 @@ pub trait ChainSourceExt: ChainSource {
 		LagSafety::new(self, margin)
 	}
 
+	/// Detects reorgs of the blocks this chain source has produced, by tracking the hashes of the
+	/// last `history` headers. When a reorg is detected it is logged and counted, and the canonical
+	/// replacements of the reorged blocks are produced again, so downstream adapters witness them.
+	fn reorg_detection(self, history: usize) -> ReorgDetection<Self>
+	where
+		Self: Sized,
+	{
+		ReorgDetection::new(self, history)
+	}
+
 	/// Allows sharing an underlying chain source between multiple consumers. This ensures that work
 	/// done in previous chain source adapters is not duplicated by downstream consumers.
 	fn shared<'env>(self, scope: &Scope<'env, anyhow::Error>) -> SharedSource<Self>
@@ -71,6 +89,17 @@ pub trait ChainSourceExt: ChainSource {
 		Logging::new(self, log_prefix)
 	}
 
+	/// Records the headers produced by the chain source, and those its client is queried for, so
+	/// they can be replayed offline using a `FixtureSource`. Does nothing without a recorder.
+	fn recording(self, recorder: Option<FixtureRecorder>) -> Recording<Self>
+	where
+		Self: Sized,
+		Self::Hash: serde::Serialize,
+		Self::Data: FixtureData,
+	{
+		Recording::new(self, recorder)
+	}
+
 	/// Ensures the stream is always increasing with respect to the header index (normally the block
 	/// number). We don't assume the root chain source is strictly increasing, since we could
 	/// encounter reorgs.
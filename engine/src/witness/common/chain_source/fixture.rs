@@ -0,0 +1,521 @@
+//! Recording of the chain data that a witnessing pipeline uses, so that the pipeline can be run
+//! again offline against exactly the same data.
+//!
+//! A fixture is a file with one JSON entry per line. To record one, insert
+//! `.recording(Some(recorder))` after the chain source of a pipeline that is running against a real
+//! node, and give the same recorder to the chain's RPC client using `with_recorder`. Every header
+//! the source produces, every header its client is queried for (for example by `lag_safety` or
+//! `continuous`), and every response the RPC client returns, is appended to the file. The engine
+//! does this for each chain if the `witnessing.fixture_dir` setting is set.
+//!
+//! The fixture can then be fed back into the same pipeline using a [FixtureSource], with the
+//! chain's fixture RPC client, which returns the recorded responses. If the pipeline makes a
+//! request that wasn't recorded, it has diverged from the recording, and the request never
+//! completes, the same as a request to a node that never succeeds.
+
+use std::{
+	collections::{BTreeMap, HashMap},
+	fs::File,
+	io::{BufRead, BufReader, Write},
+	marker::PhantomData,
+	path::Path,
+	sync::{Arc, Mutex},
+};
+
+use anyhow::{anyhow, Context, Result};
+use futures::stream;
+use futures_util::StreamExt;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::witness::common::{ExternalChain, ExternalChainSource};
+
+use super::{aliases, BoxChainStream, ChainClient, ChainSource, Header};
+
+/// Converts the data of a header to and from the form it is stored in within a fixture.
+pub trait FixtureData: Sized {
+	fn to_fixture(&self) -> Result<serde_json::Value>;
+	fn from_fixture(value: serde_json::Value) -> Result<Self>;
+}
+
+macro_rules! impl_fixture_data_using_serde {
+	($($data:ty),+ $(,)?) => {
+		$(
+			impl FixtureData for $data {
+				fn to_fixture(&self) -> Result<serde_json::Value> {
+					Ok(serde_json::to_value(self)?)
+				}
+
+				fn from_fixture(value: serde_json::Value) -> Result<Self> {
+					Ok(serde_json::from_value(value)?)
+				}
+			}
+		)+
+	};
+}
+
+impl_fixture_data_using_serde!((), ethers::types::Bloom, ((), Vec<bitcoin::Transaction>));
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FixtureHeader<Index, Hash> {
+	index: Index,
+	hash: Hash,
+	parent_hash: Option<Hash>,
+	data: serde_json::Value,
+}
+impl<Index: aliases::Index, Hash: aliases::Hash> FixtureHeader<Index, Hash> {
+	fn record<Data: FixtureData>(header: &Header<Index, Hash, Data>) -> Result<Self> {
+		Ok(Self {
+			index: header.index,
+			hash: header.hash,
+			parent_hash: header.parent_hash,
+			data: header.data.to_fixture()?,
+		})
+	}
+
+	fn replay<Data: FixtureData>(&self) -> Result<Header<Index, Hash, Data>> {
+		Ok(Header {
+			index: self.index,
+			hash: self.hash,
+			parent_hash: self.parent_hash,
+			data: Data::from_fixture(self.data.clone())?,
+		})
+	}
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FixtureResponse {
+	method: String,
+	/// Identifies the request among the requests made using the same method, normally its
+	/// arguments.
+	request: String,
+	response: serde_json::Value,
+}
+
+/// Each line of a fixture file is one of these, serialized as JSON.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum FixtureEntry<Index, Hash> {
+	/// A header produced by the stream of the recorded chain source.
+	Streamed(FixtureHeader<Index, Hash>),
+	/// A header returned by the client of the recorded chain source.
+	Queried(FixtureHeader<Index, Hash>),
+	/// A response returned by the RPC client of the recorded chain.
+	Response(FixtureResponse),
+}
+
+/// Appends the recorded headers and responses to a fixture file. Clones write to the same file.
+#[derive(Clone)]
+pub struct FixtureRecorder {
+	file: Arc<Mutex<File>>,
+}
+impl FixtureRecorder {
+	pub fn create<P: AsRef<Path>>(path: P) -> Result<Self> {
+		let path = path.as_ref();
+		Ok(Self {
+			file: Arc::new(Mutex::new(
+				File::create(path)
+					.with_context(|| format!("Failed to create fixture file {}", path.display()))?,
+			)),
+		})
+	}
+
+	fn record<Index, Hash, Data>(
+		&self,
+		entry: impl FnOnce(FixtureHeader<Index, Hash>) -> FixtureEntry<Index, Hash>,
+		header: &Header<Index, Hash, Data>,
+	) where
+		Index: aliases::Index,
+		Hash: aliases::Hash + Serialize,
+		Data: FixtureData,
+	{
+		// Failing to record shouldn't stop the pipeline, so we only log the error.
+		if let Err(error) =
+			FixtureHeader::record(header).and_then(|header| self.write(&entry(header)))
+		{
+			tracing::error!("Failed to record header {:?} to fixture: {error:?}", header.index);
+		}
+	}
+
+	/// Records a response returned by an RPC client, so a fixture RPC client can return it when it
+	/// is given the same `method` and `request`.
+	pub fn record_response<Response: Serialize>(
+		&self,
+		method: &str,
+		request: String,
+		response: &Response,
+	) {
+		// Failing to record shouldn't stop the pipeline, so we only log the error.
+		if let Err(error) =
+			serde_json::to_value(response).map_err(Into::into).and_then(|response| {
+				self.write(&FixtureEntry::<(), ()>::Response(FixtureResponse {
+					method: method.to_string(),
+					request: request.clone(),
+					response,
+				}))
+			}) {
+			tracing::error!(
+				"Failed to record the response to {method}({request}) to fixture: {error:?}"
+			);
+		}
+	}
+
+	fn write<Index: Serialize, Hash: Serialize>(
+		&self,
+		entry: &FixtureEntry<Index, Hash>,
+	) -> Result<()> {
+		let line = serde_json::to_string(entry)?;
+		let mut file = self.file.lock().unwrap();
+		writeln!(file, "{line}")?;
+		Ok(())
+	}
+}
+
+/// Records the headers produced by the inner source, and those its client is queried for. Without a
+/// recorder, the headers are passed on without being recorded, so recording can be turned on and
+/// off by a setting.
+#[derive(Clone)]
+pub struct Recording<InnerSource> {
+	inner_source: InnerSource,
+	recorder: Option<FixtureRecorder>,
+}
+impl<InnerSource> Recording<InnerSource> {
+	pub fn new(inner_source: InnerSource, recorder: Option<FixtureRecorder>) -> Self {
+		Self { inner_source, recorder }
+	}
+}
+
+#[async_trait::async_trait]
+impl<InnerSource: ChainSource> ChainSource for Recording<InnerSource>
+where
+	InnerSource::Hash: Serialize,
+	InnerSource::Data: FixtureData,
+{
+	type Index = InnerSource::Index;
+	type Hash = InnerSource::Hash;
+	type Data = InnerSource::Data;
+
+	type Client = RecordingClient<InnerSource::Client>;
+
+	async fn stream_and_client(
+		&self,
+	) -> (BoxChainStream<'_, Self::Index, Self::Hash, Self::Data>, Self::Client) {
+		let (chain_stream, chain_client) = self.inner_source.stream_and_client().await;
+		(
+			Box::pin(chain_stream.map(move |header| {
+				if let Some(recorder) = &self.recorder {
+					recorder.record(FixtureEntry::Streamed, &header);
+				}
+				header
+			})),
+			RecordingClient { inner_client: chain_client, recorder: self.recorder.clone() },
+		)
+	}
+}
+
+impl<InnerSource: ExternalChainSource> ExternalChainSource for Recording<InnerSource>
+where
+	InnerSource::Hash: Serialize,
+	InnerSource::Data: FixtureData,
+{
+	type Chain = InnerSource::Chain;
+}
+
+#[derive(Clone)]
+pub struct RecordingClient<InnerClient> {
+	inner_client: InnerClient,
+	recorder: Option<FixtureRecorder>,
+}
+
+#[async_trait::async_trait]
+impl<InnerClient: ChainClient> ChainClient for RecordingClient<InnerClient>
+where
+	InnerClient::Hash: Serialize,
+	InnerClient::Data: FixtureData,
+{
+	type Index = InnerClient::Index;
+	type Hash = InnerClient::Hash;
+	type Data = InnerClient::Data;
+
+	async fn header_at_index(
+		&self,
+		index: Self::Index,
+	) -> Header<Self::Index, Self::Hash, Self::Data> {
+		let header = self.inner_client.header_at_index(index).await;
+		if let Some(recorder) = &self.recorder {
+			recorder.record(FixtureEntry::Queried, &header);
+		}
+		header
+	}
+}
+
+/// Produces the headers recorded in a fixture, in the order they were originally produced, and
+/// then ends. Its client returns the recorded headers, and the recorded responses are available to
+/// the chain's fixture RPC client using [FixtureSource::responses].
+pub struct FixtureSource<TChain: ExternalChain, Hash, Data> {
+	streamed: Arc<Vec<FixtureHeader<TChain::ChainBlockNumber, Hash>>>,
+	client: FixtureClient<TChain::ChainBlockNumber, Hash, Data>,
+	responses: FixtureResponses,
+}
+impl<TChain: ExternalChain, Hash, Data> Clone for FixtureSource<TChain, Hash, Data> {
+	fn clone(&self) -> Self {
+		Self {
+			streamed: self.streamed.clone(),
+			client: self.client.clone(),
+			responses: self.responses.clone(),
+		}
+	}
+}
+impl<TChain, Hash, Data> FixtureSource<TChain, Hash, Data>
+where
+	TChain: ExternalChain,
+	Hash: aliases::Hash + DeserializeOwned,
+	Data: aliases::Data + FixtureData,
+{
+	pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+		let path = path.as_ref();
+		let file = File::open(path)
+			.with_context(|| format!("Failed to open fixture file {}", path.display()))?;
+
+		let mut streamed = Vec::new();
+		let mut headers = BTreeMap::new();
+		let mut responses = HashMap::new();
+		for (line_number, line) in BufReader::new(file).lines().enumerate() {
+			let entry =
+				serde_json::from_str::<FixtureEntry<TChain::ChainBlockNumber, Hash>>(&line?)
+					.with_context(|| {
+						format!("Invalid entry on line {} of fixture", line_number + 1)
+					})?;
+			if let FixtureEntry::Streamed(header) | FixtureEntry::Queried(header) = &entry {
+				// Check the data can be replayed now, so it can't fail later
+				header.replay::<Data>().with_context(|| {
+					format!("Invalid header data on line {} of fixture", line_number + 1)
+				})?;
+			}
+			match entry {
+				FixtureEntry::Streamed(header) => {
+					headers.insert(header.index, header.clone());
+					streamed.push(header);
+				},
+				FixtureEntry::Queried(header) => {
+					headers.insert(header.index, header);
+				},
+				FixtureEntry::Response(FixtureResponse { method, request, response }) => {
+					responses.insert((method, request), response);
+				},
+			}
+		}
+
+		Ok(Self {
+			streamed: Arc::new(streamed),
+			client: FixtureClient { headers: Arc::new(headers), _phantom: PhantomData },
+			responses: FixtureResponses { responses: Arc::new(responses) },
+		})
+	}
+
+	/// The client of the source, for fixture RPC clients that also need to act as a chain client.
+	pub fn client(&self) -> FixtureClient<TChain::ChainBlockNumber, Hash, Data> {
+		self.client.clone()
+	}
+
+	pub fn responses(&self) -> FixtureResponses {
+		self.responses.clone()
+	}
+}
+
+#[async_trait::async_trait]
+impl<TChain, Hash, Data> ChainSource for FixtureSource<TChain, Hash, Data>
+where
+	TChain: ExternalChain,
+	Hash: aliases::Hash + DeserializeOwned,
+	Data: aliases::Data + FixtureData,
+{
+	type Index = TChain::ChainBlockNumber;
+	type Hash = Hash;
+	type Data = Data;
+
+	type Client = FixtureClient<TChain::ChainBlockNumber, Hash, Data>;
+
+	async fn stream_and_client(
+		&self,
+	) -> (BoxChainStream<'_, Self::Index, Self::Hash, Self::Data>, Self::Client) {
+		(
+			// The data was checked when the fixture was loaded, so this doesn't skip any headers.
+			Box::pin(stream::iter(self.streamed.iter().filter_map(|header| {
+				header
+					.replay()
+					.map_err(|error| {
+						tracing::error!("Failed to replay header {:?}: {error:#}", header.index)
+					})
+					.ok()
+			}))),
+			self.client.clone(),
+		)
+	}
+}
+
+impl<TChain, Hash, Data> ExternalChainSource for FixtureSource<TChain, Hash, Data>
+where
+	TChain: ExternalChain,
+	Hash: aliases::Hash + DeserializeOwned,
+	Data: aliases::Data + FixtureData,
+{
+	type Chain = TChain;
+}
+
+/// Returns the headers recorded in a fixture. A header that wasn't recorded means the pipeline has
+/// diverged from the recording, in which case `header_at_index` logs an error and never completes,
+/// the same as a request to a node that never succeeds.
+pub struct FixtureClient<Index, Hash, Data> {
+	headers: Arc<BTreeMap<Index, FixtureHeader<Index, Hash>>>,
+	_phantom: PhantomData<fn() -> Data>,
+}
+impl<Index, Hash, Data> Clone for FixtureClient<Index, Hash, Data> {
+	fn clone(&self) -> Self {
+		Self { headers: self.headers.clone(), _phantom: PhantomData }
+	}
+}
+impl<Index, Hash, Data> FixtureClient<Index, Hash, Data>
+where
+	Index: aliases::Index,
+	Hash: aliases::Hash,
+	Data: aliases::Data + FixtureData,
+{
+	pub fn try_header_at_index(&self, index: Index) -> Result<Header<Index, Hash, Data>> {
+		self.headers
+			.get(&index)
+			.ok_or_else(|| {
+				anyhow!("Header {index:?} was not recorded in the fixture, the pipeline has diverged from the recording")
+			})?
+			.replay()
+	}
+}
+
+#[async_trait::async_trait]
+impl<Index, Hash, Data> ChainClient for FixtureClient<Index, Hash, Data>
+where
+	Index: aliases::Index,
+	Hash: aliases::Hash,
+	Data: aliases::Data + FixtureData,
+{
+	type Index = Index;
+	type Hash = Hash;
+	type Data = Data;
+
+	async fn header_at_index(
+		&self,
+		index: Self::Index,
+	) -> Header<Self::Index, Self::Hash, Self::Data> {
+		match self.try_header_at_index(index) {
+			Ok(header) => header,
+			Err(error) => {
+				tracing::error!("{error:#}");
+				futures::future::pending().await
+			},
+		}
+	}
+}
+
+/// The RPC responses recorded in a fixture, keyed by the method and request they were returned
+/// for. Used by the fixture RPC clients of each chain.
+#[derive(Clone, Default)]
+pub struct FixtureResponses {
+	responses: Arc<HashMap<(String, String), serde_json::Value>>,
+}
+impl FixtureResponses {
+	pub fn try_get<Response: DeserializeOwned>(
+		&self,
+		method: &str,
+		request: &str,
+	) -> Result<Response> {
+		let response = self
+			.responses
+			.get(&(method.to_string(), request.to_string()))
+			.ok_or_else(|| {
+				anyhow!("The response to {method}({request}) was not recorded in the fixture, the pipeline has diverged from the recording")
+			})?;
+		serde_json::from_value(response.clone())
+			.with_context(|| format!("Invalid response to {method}({request}) in the fixture"))
+	}
+
+	/// Returns the recorded response. If there isn't one, this logs an error and never completes,
+	/// the same as a request to a node that never succeeds.
+	pub async fn get<Response: DeserializeOwned>(&self, method: &str, request: String) -> Response {
+		match self.try_get(method, &request) {
+			Ok(response) => response,
+			Err(error) => {
+				tracing::error!("{error:#}");
+				futures::future::pending().await
+			},
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use cf_chains::Bitcoin;
+	use futures::FutureExt;
+
+	use crate::witness::common::chain_source::{extension::ChainSourceExt, replay::ReplaySource};
+
+	use super::*;
+
+	#[derive(Clone)]
+	struct MockChainClient;
+
+	#[async_trait::async_trait]
+	impl ChainClient for MockChainClient {
+		type Index = u64;
+		type Hash = u64;
+		type Data = ();
+
+		async fn header_at_index(
+			&self,
+			index: Self::Index,
+		) -> Header<Self::Index, Self::Hash, Self::Data> {
+			Header { index, hash: index * 10, parent_hash: Some((index - 1) * 10), data: () }
+		}
+	}
+
+	#[tokio::test]
+	async fn recorded_headers_are_replayed() {
+		let fixture = tempfile::NamedTempFile::new().unwrap();
+
+		let recorded = {
+			let recorder = FixtureRecorder::create(fixture.path()).unwrap();
+			let source = ReplaySource::<_, Bitcoin>::new(MockChainClient, 5..=8)
+				.recording(Some(recorder.clone()));
+			let (stream, client) = source.stream_and_client().await;
+			let streamed = stream.collect::<Vec<_>>().await;
+			let queried = client.header_at_index(2).await;
+			recorder.record_response("block_hash", "7".to_string(), &70u64);
+			(streamed, queried)
+		};
+
+		let source = FixtureSource::<Bitcoin, u64, ()>::load(fixture.path()).unwrap();
+		let (stream, client) = source.stream_and_client().await;
+		assert_eq!(stream.collect::<Vec<_>>().await, recorded.0);
+		assert_eq!(client.header_at_index(2).await, recorded.1);
+		assert_eq!(client.header_at_index(7).await, recorded.0[2]);
+		assert_eq!(source.responses().get::<u64>("block_hash", "7".to_string()).await, 70);
+	}
+
+	#[tokio::test]
+	async fn requests_that_were_not_recorded_never_complete() {
+		let fixture = tempfile::NamedTempFile::new().unwrap();
+		let source = ReplaySource::<_, Bitcoin>::new(MockChainClient, 5..=6)
+			.recording(Some(FixtureRecorder::create(fixture.path()).unwrap()));
+		source.stream_and_client().await.0.collect::<Vec<_>>().await;
+
+		let source = FixtureSource::<Bitcoin, u64, ()>::load(fixture.path()).unwrap();
+		let client = source.stream_and_client().await.1;
+		assert!(client.try_header_at_index(4).is_err());
+		assert!(client.header_at_index(4).now_or_never().is_none());
+
+		assert!(source.responses().try_get::<u64>("block_hash", "4").is_err());
+		assert!(source
+			.responses()
+			.get::<u64>("block_hash", "4".to_string())
+			.now_or_never()
+			.is_none());
+	}
+}
@@ -0,0 +1,339 @@
+use std::{collections::VecDeque, iter::Step};
+
+use cf_chains::Chain;
+use futures::stream;
+use futures_util::StreamExt;
+use utilities::metrics::{CHAIN_REORGS, CHAIN_REORG_DEPTH};
+
+use crate::witness::common::{chain_source::ChainClient, ExternalChainSource};
+
+use super::{BoxChainStream, ChainSource, Header};
+
+/// Remembers the hashes of the last `history` headers it has output, and uses the parent hash of
+/// each new header to detect when any of them have been reorged out of the chain. When that
+/// happens the reorg is logged and counted, and the canonical headers replacing the reorged ones
+/// are queried from the client and output again, before the new header. This means downstream
+/// adapters will see the canonical version of each block, even if a reorg was deeper than the
+/// safety margin applied upstream. Reorgs deeper than `history` are still detected, but only the
+/// last `history` headers are re-delivered.
+#[derive(Clone)]
+pub struct ReorgDetection<InnerSource> {
+	inner_source: InnerSource,
+	history: usize,
+}
+impl<InnerSource> ReorgDetection<InnerSource> {
+	pub fn new(inner_source: InnerSource, history: usize) -> Self {
+		Self { inner_source, history }
+	}
+}
+
+#[async_trait::async_trait]
+impl<InnerSource: ExternalChainSource> ChainSource for ReorgDetection<InnerSource>
+where
+	InnerSource::Client: Clone,
+{
+	type Index = InnerSource::Index;
+	type Hash = InnerSource::Hash;
+	type Data = InnerSource::Data;
+
+	type Client = InnerSource::Client;
+
+	async fn stream_and_client(
+		&self,
+	) -> (BoxChainStream<'_, Self::Index, Self::Hash, Self::Data>, Self::Client) {
+		let (chain_stream, chain_client) = self.inner_source.stream_and_client().await;
+		let history = self.history;
+
+		(
+			Box::pin(stream::unfold(
+				(
+					chain_stream,
+					chain_client.clone(),
+					VecDeque::<(<Self as ChainSource>::Index, <Self as ChainSource>::Hash)>::new(),
+					VecDeque::<
+						Header<
+							<Self as ChainSource>::Index,
+							<Self as ChainSource>::Hash,
+							<Self as ChainSource>::Data,
+						>,
+					>::new(),
+				),
+				move |(mut chain_stream, chain_client, mut output_hashes, mut pending_headers)| async move {
+					let header = loop {
+						if let Some(header) = pending_headers.pop_front() {
+							break Some(header)
+						} else if let Some(header) = chain_stream.next().await {
+							pending_headers = canonical_headers(
+								<InnerSource::Chain as Chain>::NAME,
+								&chain_client,
+								&mut output_hashes,
+								history,
+								header,
+							)
+							.await;
+						} else {
+							break None
+						}
+					};
+					header.map(move |header| {
+						(header, (chain_stream, chain_client, output_hashes, pending_headers))
+					})
+				},
+			)),
+			chain_client,
+		)
+	}
+}
+
+impl<InnerSource: ExternalChainSource> ExternalChainSource for ReorgDetection<InnerSource>
+where
+	InnerSource::Client: Clone,
+{
+	type Chain = InnerSource::Chain;
+}
+
+/// Returns the headers that should be output for a header pulled from the inner source, i.e. the
+/// header itself, preceded by the canonical versions of any previously output headers it has
+/// reorged out. `output_hashes` contains the indices and hashes of the consecutive headers that
+/// have previously been output.
+async fn canonical_headers<Client: ChainClient>(
+	chain_name: &'static str,
+	chain_client: &Client,
+	output_hashes: &mut VecDeque<(Client::Index, Client::Hash)>,
+	history: usize,
+	header: Header<Client::Index, Client::Hash, Client::Data>,
+) -> VecDeque<Header<Client::Index, Client::Hash, Client::Data>> {
+	// The inner source has repeated a header we already output, so there is nothing to recover.
+	if output_hashes.contains(&(header.index, header.hash)) {
+		return VecDeque::from([header])
+	}
+
+	// Any output headers at or above the new header's index are no longer part of the chain.
+	let mut depth = {
+		let still_canonical =
+			output_hashes.iter().take_while(|(index, _)| *index < header.index).count();
+		let reorged = output_hashes.len() - still_canonical;
+		output_hashes.truncate(still_canonical);
+		reorged
+	};
+
+	// Walk back through the previously output headers until we find the new header's ancestor.
+	// We don't check the queried headers link up with each other, as another reorg could happen
+	// while we query them, in which case it will be detected by a later header.
+	let mut headers = VecDeque::from([header]);
+	let mut found_common_ancestor = false;
+	while let Some((index, hash)) = output_hashes.back().copied() {
+		let child = headers.front().unwrap();
+		if Step::forward_checked(index, 1) != Some(child.index) || child.parent_hash.is_none() {
+			// We can't tell if the header is the parent of the child, so forget the old headers.
+			output_hashes.clear();
+			found_common_ancestor = true;
+		} else if child.parent_hash == Some(hash) {
+			found_common_ancestor = true;
+		} else {
+			output_hashes.pop_back();
+			depth += 1;
+			headers.push_front(chain_client.header_at_index(index).await);
+			continue
+		}
+		break
+	}
+
+	if depth > 0 {
+		let (first_index, last_index) =
+			(headers.front().unwrap().index, headers.back().unwrap().index);
+		CHAIN_REORGS.inc(&[chain_name]);
+		CHAIN_REORG_DEPTH.set(&[chain_name], depth);
+		if found_common_ancestor {
+			tracing::warn!(
+				"{chain_name} | Detected a reorg of depth {depth}, re-delivering the canonical blocks {first_index:?} to {last_index:?}",
+			);
+		} else {
+			tracing::error!(
+				"{chain_name} | Detected a reorg deeper than the last {history} blocks, re-delivering the canonical blocks {first_index:?} to {last_index:?}",
+			);
+		}
+	}
+
+	output_hashes.extend(headers.iter().map(|header| (header.index, header.hash)));
+	while output_hashes.len() > history {
+		output_hashes.pop_front();
+	}
+
+	headers
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+
+	use crate::common::Mutex;
+
+	use super::*;
+
+	use futures::Stream;
+
+	// The canonical chain is the one where the hash of each header is its index.
+	#[derive(Clone)]
+	struct MockChainClient {
+		queried_indices: Arc<Mutex<Vec<u64>>>,
+	}
+
+	#[async_trait::async_trait]
+	impl ChainClient for MockChainClient {
+		type Index = u64;
+		type Hash = u64;
+		type Data = ();
+
+		async fn header_at_index(
+			&self,
+			index: Self::Index,
+		) -> Header<Self::Index, Self::Hash, Self::Data> {
+			self.queried_indices.lock().await.push(index);
+			test_header(index, 0)
+		}
+	}
+
+	struct MockChainSource<HeaderStream: Stream<Item = Header<u64, u64, ()>> + Send + Sync> {
+		stream: Arc<Mutex<Option<HeaderStream>>>,
+		client: MockChainClient,
+	}
+
+	#[async_trait::async_trait]
+	impl<HeaderStream: Stream<Item = Header<u64, u64, ()>> + Send + Sync> ChainSource
+		for MockChainSource<HeaderStream>
+	{
+		type Index = u64;
+		type Hash = u64;
+		type Data = ();
+
+		type Client = MockChainClient;
+
+		async fn stream_and_client(
+			&self,
+		) -> (BoxChainStream<'_, Self::Index, Self::Hash, Self::Data>, Self::Client) {
+			let stream = self.stream.lock().await.take().expect("should only be called once");
+			(Box::pin(stream), self.client.clone())
+		}
+	}
+
+	impl<HeaderStream: Stream<Item = Header<u64, u64, ()>> + Send + Sync> ExternalChainSource
+		for MockChainSource<HeaderStream>
+	{
+		type Chain = cf_chains::Ethereum;
+	}
+
+	/// A header on the fork identified by `offset`, whose parent is on the same fork.
+	fn test_header(index: u64, offset: u64) -> Header<u64, u64, ()> {
+		Header { index, hash: index + offset, parent_hash: Some(index - 1 + offset), data: () }
+	}
+
+	async fn output_and_queried_indices(
+		headers: Vec<Header<u64, u64, ()>>,
+		history: usize,
+	) -> (Vec<Header<u64, u64, ()>>, Vec<u64>) {
+		let client = MockChainClient { queried_indices: Default::default() };
+		let reorg_detection = ReorgDetection::new(
+			MockChainSource {
+				stream: Arc::new(Mutex::new(Some(stream::iter(headers)))),
+				client: client.clone(),
+			},
+			history,
+		);
+
+		let (chain_stream, _) = reorg_detection.stream_and_client().await;
+		let output = chain_stream.collect().await;
+		let queried_indices = client.queried_indices.lock().await.clone();
+		(output, queried_indices)
+	}
+
+	#[tokio::test]
+	async fn passes_through_headers_without_reorgs() {
+		let headers = (5..10).map(|index| test_header(index, 0)).collect::<Vec<_>>();
+
+		let (output, queried_indices) = output_and_queried_indices(headers.clone(), 3).await;
+
+		assert_eq!(output, headers);
+		assert!(queried_indices.is_empty());
+	}
+
+	#[tokio::test]
+	async fn reorg_redelivers_canonical_headers() {
+		const FORK: u64 = 1000;
+
+		let (output, queried_indices) = output_and_queried_indices(
+			vec![
+				test_header(5, 0),
+				// these are on a bad fork
+				Header { index: 6, hash: 6 + FORK, parent_hash: Some(5), data: () },
+				test_header(7, FORK),
+				// canonical chain
+				test_header(8, 0),
+				test_header(9, 0),
+			],
+			10,
+		)
+		.await;
+
+		assert_eq!(
+			output.iter().map(|header| (header.index, header.hash)).collect::<Vec<_>>(),
+			vec![(5, 5), (6, 6 + FORK), (7, 7 + FORK), (6, 6), (7, 7), (8, 8), (9, 9)]
+		);
+		assert_eq!(queried_indices, vec![7, 6]);
+	}
+
+	#[tokio::test]
+	async fn replaced_header_at_the_same_index_is_detected() {
+		const FORK: u64 = 1000;
+
+		let (output, queried_indices) = output_and_queried_indices(
+			vec![
+				test_header(5, 0),
+				Header { index: 6, hash: 6 + FORK, parent_hash: Some(5), data: () },
+				test_header(6, 0),
+			],
+			10,
+		)
+		.await;
+
+		assert_eq!(output, vec![test_header(5, 0), output[1], test_header(6, 0)]);
+		assert!(queried_indices.is_empty());
+	}
+
+	#[tokio::test]
+	async fn reorg_deeper_than_history_redelivers_the_history() {
+		const FORK: u64 = 1000;
+
+		let (output, queried_indices) = output_and_queried_indices(
+			vec![
+				test_header(5, FORK),
+				test_header(6, FORK),
+				test_header(7, FORK),
+				test_header(8, 0),
+			],
+			2,
+		)
+		.await;
+
+		assert_eq!(
+			output.iter().map(|header| (header.index, header.hash)).collect::<Vec<_>>(),
+			vec![(5, 5 + FORK), (6, 6 + FORK), (7, 7 + FORK), (6, 6), (7, 7), (8, 8)]
+		);
+		assert_eq!(queried_indices, vec![7, 6]);
+	}
+
+	#[tokio::test]
+	async fn repeated_and_non_consecutive_headers_are_not_reorgs() {
+		let (output, queried_indices) = output_and_queried_indices(
+			vec![test_header(5, 0), test_header(5, 0), test_header(8, 0), test_header(9, 0)],
+			10,
+		)
+		.await;
+
+		assert_eq!(
+			output,
+			vec![test_header(5, 0), test_header(5, 0), test_header(8, 0), test_header(9, 0)]
+		);
+		assert!(queried_indices.is_empty());
+	}
+}
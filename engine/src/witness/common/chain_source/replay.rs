@@ -0,0 +1,97 @@
+use std::{marker::PhantomData, ops::RangeInclusive};
+
+use futures::stream;
+use futures_util::StreamExt;
+
+use crate::witness::common::{ExternalChain, ExternalChainSource};
+
+use super::{BoxChainStream, ChainClient, ChainSource};
+
+/// Produces the headers of a fixed range of blocks, fetched using the client, and then ends. This
+/// is used to re-witness blocks that are already behind the head of the chain, so unlike the
+/// sources that follow the head there is no reorg or safety handling.
+pub struct ReplaySource<Client: ChainClient, TChain> {
+	client: Client,
+	blocks: RangeInclusive<Client::Index>,
+	_phantom: PhantomData<TChain>,
+}
+
+impl<Client: ChainClient, TChain> Clone for ReplaySource<Client, TChain> {
+	fn clone(&self) -> Self {
+		Self { client: self.client.clone(), blocks: self.blocks.clone(), _phantom: PhantomData }
+	}
+}
+
+impl<Client: ChainClient, TChain> ReplaySource<Client, TChain> {
+	pub fn new(client: Client, blocks: RangeInclusive<Client::Index>) -> Self {
+		Self { client, blocks, _phantom: PhantomData }
+	}
+}
+
+#[async_trait::async_trait]
+impl<Client: ChainClient, TChain: Send + Sync> ChainSource for ReplaySource<Client, TChain> {
+	type Index = Client::Index;
+	type Hash = Client::Hash;
+	type Data = Client::Data;
+
+	type Client = Client;
+
+	async fn stream_and_client(
+		&self,
+	) -> (BoxChainStream<'_, Self::Index, Self::Hash, Self::Data>, Self::Client) {
+		let client = self.client.clone();
+		(
+			Box::pin(stream::iter(self.blocks.clone()).then(move |index| {
+				let client = client.clone();
+				async move { client.header_at_index(index).await }
+			})),
+			self.client.clone(),
+		)
+	}
+}
+
+impl<Client, TChain> ExternalChainSource for ReplaySource<Client, TChain>
+where
+	Client: ChainClient<Index = <TChain as cf_chains::Chain>::ChainBlockNumber>,
+	TChain: ExternalChain,
+{
+	type Chain = TChain;
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::witness::common::chain_source::Header;
+
+	#[derive(Clone)]
+	struct MockChainClient;
+
+	#[async_trait::async_trait]
+	impl ChainClient for MockChainClient {
+		type Index = u64;
+		type Hash = u64;
+		type Data = ();
+
+		async fn header_at_index(
+			&self,
+			index: Self::Index,
+		) -> Header<Self::Index, Self::Hash, Self::Data> {
+			Header { index, hash: index, parent_hash: Some(index - 1), data: () }
+		}
+	}
+
+	#[tokio::test]
+	async fn produces_the_headers_in_the_range_and_ends() {
+		let source = ReplaySource::<_, cf_chains::Ethereum>::new(MockChainClient, 5..=9);
+
+		let (stream, _client) = source.stream_and_client().await;
+		assert_eq!(
+			stream.map(|header| header.index).collect::<Vec<_>>().await,
+			vec![5, 6, 7, 8, 9]
+		);
+
+		// Each stream replays the whole range.
+		let (stream, _client) = source.stream_and_client().await;
+		assert_eq!(stream.count().await, 5);
+	}
+}
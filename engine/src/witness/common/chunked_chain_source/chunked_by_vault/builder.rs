@@ -8,7 +8,7 @@ use crate::witness::common::{
 	epoch_source::Vault,
 };
 
-use crate::witness::common::BoxActiveAndFuture;
+use crate::witness::common::{ActiveAndFuture, BoxActiveAndFuture};
 use cf_chains::Chain;
 
 use super::ChunkedByVault;
@@ -48,6 +48,17 @@ impl<Inner: ChunkedByVault> ChunkedByVaultBuilder<Inner> {
 			Ok(())
 		});
 	}
+
+	/// Drives the chain streams of the vaults that are active when this is called until they end,
+	/// ignoring any vaults that become active later. This only completes if the underlying chain
+	/// source ends, for example when replaying a fixed range of blocks.
+	pub async fn run_until_exhausted(self) {
+		let ActiveAndFuture { active, future: _ } = self.source.stream(self.parameters).await;
+		futures::stream::iter(active)
+			.flat_map_unordered(None, |(_epoch, chain_stream, _chain_client)| chain_stream)
+			.for_each(|_| futures::future::ready(()))
+			.await;
+	}
 }
 
 impl<T: ChunkedByVault> ChunkedByVaultBuilder<T> {
@@ -0,0 +1,232 @@
+use std::{
+	sync::{Arc, Mutex},
+	time::Duration,
+};
+
+use anyhow::anyhow;
+use cf_chains::{ChainCrypto, ChainState};
+use cf_primitives::EpochIndex;
+use futures::{future::BoxFuture, stream, FutureExt};
+use pallet_cf_ingress_egress::DepositChannelDetails;
+use state_chain_runtime::{PalletInstanceAlias, Runtime, RuntimeCall};
+use utilities::{
+	task_scope::{task_scope, Scope},
+	InnerCachedStream, MakeCachedStream,
+};
+
+use crate::state_chain_observer::client::{
+	finalized_stream::FinalizedCachedStream, mocks::MockStateChainClient, BlockInfo,
+};
+
+use super::{
+	epoch_source::{EpochSource, EpochSourceBuilder},
+	ExternalChain, RuntimeHasChain,
+};
+
+/// The epoch of the vaults the pipelines witness for.
+pub const EPOCH: EpochIndex = 1;
+
+/// A pipeline that hasn't completed by then is waiting for a request that wasn't recorded in its
+/// fixture, which never completes.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Used to cancel the tasks the pipeline leaves running, such as those following the State Chain,
+/// once the pipeline has completed.
+const PIPELINE_COMPLETED: &str = "The pipeline has completed";
+
+/// A State Chain stream that never produces another block, so the pipelines see the same storage
+/// throughout. It never ends either, as the pipelines expect the State Chain to keep running.
+pub type StateChainStream = FinalizedCachedStream<
+	InnerCachedStream<stream::Pending<BlockInfo>, BlockInfo, fn(&BlockInfo) -> BlockInfo>,
+>;
+
+/// The storage of a State Chain in epoch [EPOCH], with no expired epochs. Only the storage set
+/// here can be queried by a pipeline, any other query fails the test.
+pub struct MockStateChain {
+	client: MockStateChainClient,
+}
+
+impl Default for MockStateChain {
+	fn default() -> Self {
+		let mut client = MockStateChainClient::new();
+		client
+			.expect_storage_value::<pallet_cf_validator::CurrentEpoch<Runtime>>()
+			.returning(|_| Ok(EPOCH));
+		client
+			.expect_storage_map::<pallet_cf_validator::EpochExpiries<Runtime>, Vec<(
+				state_chain_runtime::BlockNumber,
+				EpochIndex,
+			)>>()
+			.returning(|_| Ok(Vec::new()));
+		Self { client }
+	}
+}
+
+impl MockStateChain {
+	/// Sets the vault of [EPOCH], and the block the chain tracking has reached. Blocks are only
+	/// witnessed once the chain tracking has passed them.
+	pub fn with_vault<TChain: ExternalChain>(
+		mut self,
+		vault: pallet_cf_vaults::Vault<TChain>,
+		block_height: TChain::ChainBlockNumber,
+	) -> Self
+	where
+		Runtime: RuntimeHasChain<TChain>,
+	{
+		self.client
+			.expect_storage_map_entry::<pallet_cf_vaults::Vaults<
+				Runtime,
+				<TChain as PalletInstanceAlias>::Instance,
+			>>()
+			.returning(move |_, epoch| Ok((*epoch == EPOCH).then(|| vault.clone())));
+		self.client
+			.expect_storage_value::<pallet_cf_chain_tracking::CurrentChainState<
+				Runtime,
+				<TChain as PalletInstanceAlias>::Instance,
+			>>()
+			.returning(move |_| {
+				Ok(Some(ChainState { block_height, tracked_data: Default::default() }))
+			});
+		self
+	}
+
+	pub fn with_deposit_channels<TChain: ExternalChain>(
+		mut self,
+		deposit_channels: Vec<
+			DepositChannelDetails<Runtime, <TChain as PalletInstanceAlias>::Instance>,
+		>,
+	) -> Self
+	where
+		Runtime: RuntimeHasChain<TChain>,
+	{
+		self.client
+			.expect_storage_map::<pallet_cf_ingress_egress::DepositChannelLookup<
+				Runtime,
+				<TChain as PalletInstanceAlias>::Instance,
+			>, Vec<(
+				TChain::ChainAccount,
+				DepositChannelDetails<Runtime, <TChain as PalletInstanceAlias>::Instance>,
+			)>>()
+			.returning(move |_| {
+				Ok(deposit_channels
+					.iter()
+					.map(|details| (details.deposit_channel.address.clone(), details.clone()))
+					.collect())
+			});
+		self
+	}
+
+	/// Sets the transactions being broadcast, along with the block their broadcast was initiated
+	/// at.
+	pub fn with_egress_items<TChain: ExternalChain>(
+		mut self,
+		egress_items: Vec<(
+			<TChain::ChainCrypto as ChainCrypto>::TransactionOutId,
+			TChain::ChainBlockNumber,
+		)>,
+	) -> Self
+	where
+		Runtime: RuntimeHasChain<TChain>,
+	{
+		self.client
+			.expect_storage_map::<pallet_cf_broadcast::TransactionOutIdToBroadcastId<
+				Runtime,
+				<TChain as PalletInstanceAlias>::Instance,
+			>, Vec<(
+				<TChain::ChainCrypto as ChainCrypto>::TransactionOutId,
+				(cf_primitives::BroadcastId, TChain::ChainBlockNumber),
+			)>>()
+			.returning(move |_| {
+				Ok((1..)
+					.zip(&egress_items)
+					.map(|(broadcast_id, (tx_out_id, initiated_at))| {
+						(tx_out_id.clone(), (broadcast_id, *initiated_at))
+					})
+					.collect())
+			});
+		self
+	}
+
+	/// For the storage only queried by the pipelines of a particular chain.
+	pub fn with(mut self, f: impl FnOnce(&mut MockStateChainClient)) -> Self {
+		f(&mut self.client);
+		self
+	}
+}
+
+/// What a pipeline needs from the State Chain, as passed to the chains' `start` and `replay`.
+pub struct Context<'a> {
+	pub scope: &'a Scope<'static, anyhow::Error>,
+	pub state_chain_client: Arc<MockStateChainClient>,
+	pub state_chain_stream: StateChainStream,
+	pub epoch_source: EpochSourceBuilder<'a, 'static, MockStateChainClient, (), ()>,
+	witnessed_calls: Arc<Mutex<Vec<RuntimeCall>>>,
+}
+
+impl Context<'_> {
+	/// Records the calls instead of submitting them, see [witnessed_calls].
+	pub fn process_call(
+		&self,
+	) -> impl Fn(RuntimeCall, EpochIndex) -> futures::future::Ready<()> + Send + Sync + Clone + 'static
+	{
+		let witnessed_calls = self.witnessed_calls.clone();
+		move |call, _epoch_index| {
+			witnessed_calls.lock().unwrap().push(call);
+			futures::future::ready(())
+		}
+	}
+}
+
+/// Runs the pipeline against the State Chain until it completes, which it does once its chain
+/// source ends, as a `FixtureSource` does, and returns the calls it witnessed in order. A request
+/// that wasn't recorded in the fixture never completes, so if the pipeline diverges from the
+/// recording the test fails here.
+pub async fn witnessed_calls(
+	state_chain: MockStateChain,
+	pipeline: impl for<'a> FnOnce(Context<'a>) -> BoxFuture<'a, anyhow::Result<()>> + Send + 'static,
+) -> Vec<RuntimeCall> {
+	let state_chain_client = Arc::new(state_chain.client);
+	let witnessed_calls = Arc::new(Mutex::new(Vec::new()));
+
+	let error = tokio::time::timeout(
+		TIMEOUT,
+		task_scope(|scope| {
+			let witnessed_calls = witnessed_calls.clone();
+			async move {
+				let state_chain_stream = FinalizedCachedStream::new(stream::pending().make_cached(
+					BlockInfo {
+						parent_hash: Default::default(),
+						hash: Default::default(),
+						number: 0,
+					},
+					(|block: &BlockInfo| *block) as fn(&BlockInfo) -> BlockInfo,
+				));
+				let epoch_source = EpochSource::builder(
+					scope,
+					state_chain_stream.clone(),
+					state_chain_client.clone(),
+				)
+				.await;
+
+				pipeline(Context {
+					scope,
+					state_chain_client,
+					state_chain_stream,
+					epoch_source,
+					witnessed_calls,
+				})
+				.await?;
+
+				Err::<(), _>(anyhow!(PIPELINE_COMPLETED))
+			}
+			.boxed()
+		}),
+	)
+	.await
+	.expect("The pipeline diverged from the fixture")
+	.unwrap_err();
+	assert_eq!(error.to_string(), PIPELINE_COMPLETED);
+
+	let witnessed_calls = witnessed_calls.lock().unwrap();
+	witnessed_calls.clone()
+}
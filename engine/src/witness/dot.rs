@@ -2,22 +2,25 @@ mod dot_chain_tracking;
 mod dot_deposits;
 mod dot_source;
 
-use cf_chains::dot::{
-	PolkadotAccountId, PolkadotBalance, PolkadotExtrinsicIndex, PolkadotHash, PolkadotSignature,
-	PolkadotUncheckedExtrinsic,
+use cf_chains::{
+	dot::{
+		PolkadotAccountId, PolkadotBalance, PolkadotExtrinsicIndex, PolkadotHash,
+		PolkadotSignature, PolkadotUncheckedExtrinsic,
+	},
+	Polkadot,
 };
 use cf_primitives::{EpochIndex, PolkadotBlockNumber};
 use futures_core::Future;
 use state_chain_runtime::PolkadotInstance;
 use subxt::{
 	config::PolkadotConfig,
-	events::{EventDetails, Phase, StaticEvent},
+	events::{EventDetails, Events, Phase, StaticEvent},
 	utils::AccountId32,
 };
 
 use tracing::error;
 
-use std::{collections::BTreeSet, sync::Arc};
+use std::{collections::BTreeSet, ops::RangeInclusive, sync::Arc};
 
 use utilities::task_scope::Scope;
 
@@ -30,10 +33,15 @@ use crate::{
 	witness::common::chain_source::extension::ChainSourceExt,
 };
 use anyhow::Result;
+use codec::{Decode, Encode};
 pub use dot_source::{DotFinalisedSource, DotUnfinalisedSource};
 
 use super::common::{
-	chain_source::Header,
+	chain_source::{
+		fixture::{FixtureData, FixtureRecorder},
+		replay::ReplaySource,
+		ChainClient, Header,
+	},
 	epoch_source::{EpochSourceBuilder, Vault},
 	STATE_CHAIN_CONNECTION,
 };
@@ -44,7 +52,7 @@ use super::common::{
 #[subxt::subxt(runtime_metadata_path = "metadata.polkadot.scale")]
 pub mod polkadot {}
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Encode, Decode)]
 pub enum EventWrapper {
 	ProxyAdded { delegator: AccountId32, delegatee: AccountId32 },
 	Transfer { to: AccountId32, from: AccountId32, amount: PolkadotBalance },
@@ -57,6 +65,18 @@ use polkadot::{
 	transaction_payment::events::TransactionFeePaid,
 };
 
+// Subxt's event types don't support serde, so the events are stored in fixtures SCALE encoded.
+impl FixtureData for Vec<(Phase, EventWrapper)> {
+	fn to_fixture(&self) -> Result<serde_json::Value> {
+		Ok(serde_json::Value::String(hex::encode(self.encode())))
+	}
+
+	fn from_fixture(value: serde_json::Value) -> Result<Self> {
+		let encoded = serde_json::from_value::<String>(value)?;
+		Ok(Self::decode(&mut &hex::decode(encoded)?[..])?)
+	}
+}
+
 pub fn filter_map_events(
 	res_event_details: Result<EventDetails<PolkadotConfig>, subxt::Error>,
 ) -> Option<(Phase, EventWrapper)> {
@@ -103,7 +123,7 @@ pub async fn proxy_added_witnessing(
 }
 
 #[allow(clippy::type_complexity)]
-pub async fn process_egress<ProcessCall, ProcessingFut>(
+pub async fn process_egress<ProcessCall, ProcessingFut, DotRpcClient>(
 	epoch: Vault<cf_chains::Polkadot, PolkadotAccountId, ()>,
 	header: Header<
 		PolkadotBlockNumber,
@@ -114,8 +134,9 @@ pub async fn process_egress<ProcessCall, ProcessingFut>(
 		),
 	>,
 	process_call: ProcessCall,
-	dot_client: DotRetryRpcClient,
+	dot_client: DotRpcClient,
 ) where
+	DotRpcClient: DotRetryRpcApi,
 	ProcessCall: Fn(state_chain_runtime::RuntimeCall, EpochIndex) -> ProcessingFut
 		+ Send
 		+ Sync
@@ -170,9 +191,32 @@ pub async fn process_egress<ProcessCall, ProcessingFut>(
 	}
 }
 
+/// Only keeps the epochs with a Polkadot vault account, along with that account.
+async fn vault_accounts<'a, 'env, StateChainClient>(
+	epoch_source: EpochSourceBuilder<'a, 'env, StateChainClient, (), ()>,
+) -> EpochSourceBuilder<'a, 'env, StateChainClient, PolkadotAccountId, ()>
+where
+	StateChainClient: StorageApi + Send + Sync + 'static,
+{
+	epoch_source
+		.filter_map(
+			|state_chain_client, _epoch_index, hash, _info| async move {
+				state_chain_client
+					.storage_value::<pallet_cf_environment::PolkadotVaultAccountId<state_chain_runtime::Runtime>>(
+						hash,
+					)
+					.await
+					.expect(STATE_CHAIN_CONNECTION)
+			},
+			|_state_chain_client, _epoch, _block_hash, historic_info| async move { historic_info },
+		)
+		.await
+}
+
 pub async fn start<StateChainClient, ProcessCall, ProcessingFut, PrewitnessCall, PrewitnessFut>(
 	scope: &Scope<'_, anyhow::Error>,
 	dot_client: DotRetryRpcClient,
+	fixture_recorder: Option<FixtureRecorder>,
 	process_call: ProcessCall,
 	prewitness_call: PrewitnessCall,
 	state_chain_client: Arc<StateChainClient>,
@@ -196,6 +240,8 @@ where
 		+ 'static,
 	PrewitnessFut: Future<Output = ()> + Send + 'static,
 {
+	let dot_client = dot_client.with_recorder(fixture_recorder.clone());
+
 	let unfinalised_source = DotUnfinalisedSource::new(dot_client.clone())
 		.then(|header| async move { header.data.iter().filter_map(filter_map_events).collect() })
 		.shared(scope);
@@ -207,21 +253,7 @@ where
 		.logging("chain tracking")
 		.spawn(scope);
 
-	let epoch_source = epoch_source
-		.filter_map(
-			|state_chain_client, _epoch_index, hash, _info| async move {
-				state_chain_client
-					.storage_value::<pallet_cf_environment::PolkadotVaultAccountId<state_chain_runtime::Runtime>>(
-						hash,
-					)
-					.await
-					.expect(STATE_CHAIN_CONNECTION)
-			},
-			|_state_chain_client, _epoch, _block_hash, historic_info| async move { historic_info },
-		)
-		.await;
-
-	let vaults = epoch_source.vaults().await;
+	let vaults = vault_accounts(epoch_source).await.vaults().await;
 
 	// Pre-witnessing
 	unfinalised_source
@@ -240,6 +272,9 @@ where
 		.then(|header| async move {
 			header.data.iter().filter_map(filter_map_events).collect::<Vec<_>>()
 		})
+		// The raw events can only be decoded using the metadata, so the decoded events are
+		// recorded.
+		.recording(fixture_recorder)
 		.chunk_by_vault(vaults, scope)
 		.deposit_addresses(scope, state_chain_stream.clone(), state_chain_client.clone())
 		.await
@@ -264,6 +299,56 @@ where
 	Ok(())
 }
 
+/// Runs the full witnessing pipeline over a fixed range of already finalised blocks, for the
+/// epochs in `epoch_source`. Nothing is persisted, so this doesn't affect the progress of the
+/// regular witnessers. Completes once all the blocks have been witnessed.
+pub async fn replay<DotClient, StateChainClient, StateChainStream, ProcessCall, ProcessingFut>(
+	scope: &Scope<'_, anyhow::Error>,
+	dot_client: DotClient,
+	process_call: ProcessCall,
+	state_chain_client: Arc<StateChainClient>,
+	state_chain_stream: StateChainStream,
+	epoch_source: EpochSourceBuilder<'_, '_, StateChainClient, (), ()>,
+	blocks: RangeInclusive<PolkadotBlockNumber>,
+) -> Result<()>
+where
+	DotClient: DotRetryRpcApi
+		+ ChainClient<Index = PolkadotBlockNumber, Hash = PolkadotHash, Data = Events<PolkadotConfig>>
+		+ Send
+		+ Sync
+		+ 'static,
+	StateChainClient: StorageApi + SignedExtrinsicApi + 'static + Send + Sync,
+	StateChainStream: StateChainStreamApi + Clone,
+	ProcessCall: Fn(state_chain_runtime::RuntimeCall, EpochIndex) -> ProcessingFut
+		+ Send
+		+ Sync
+		+ Clone
+		+ 'static,
+	ProcessingFut: Future<Output = ()> + Send + 'static,
+{
+	let vaults = vault_accounts(epoch_source).await.vaults().await;
+
+	ReplaySource::<_, Polkadot>::new(dot_client.clone(), blocks)
+		.logging("replayed block")
+		.then(|header| async move {
+			header.data.iter().filter_map(filter_map_events).collect::<Vec<_>>()
+		})
+		.chunk_by_vault(vaults, scope)
+		.deposit_addresses(scope, state_chain_stream.clone(), state_chain_client.clone())
+		.await
+		.dot_deposits(process_call.clone())
+		.then(proxy_added_witnessing)
+		.egress_items(scope, state_chain_stream, state_chain_client)
+		.await
+		.then(move |epoch, header| {
+			process_egress(epoch, header, process_call.clone(), dot_client.clone())
+		})
+		.run_until_exhausted()
+		.await;
+
+	Ok(())
+}
+
 fn transaction_fee_paids(
 	indices: &BTreeSet<PolkadotExtrinsicIndex>,
 	events: &[(Phase, EventWrapper)],
@@ -315,7 +400,19 @@ fn proxy_addeds(
 
 #[cfg(test)]
 pub mod test {
+	use cf_chains::{assets::dot, dot::PolkadotChannelState, DepositChannel};
+	use futures::FutureExt;
+	use pallet_cf_ingress_egress::{ChannelAction, DepositChannelDetails, DepositWitness};
+	use sp_runtime::AccountId32;
+
 	use super::*;
+	use crate::{
+		dot::retry_rpc::fixture::FixtureDotRpcClient,
+		witness::common::{
+			chain_source::fixture::FixtureSource,
+			test_helpers::{witnessed_calls, MockStateChain},
+		},
+	};
 
 	pub fn phase_and_events(
 		events: Vec<(PolkadotExtrinsicIndex, EventWrapper)>,
@@ -361,6 +458,28 @@ pub mod test {
 		assert!(extrinsic_indices.contains(&our_proxy_added_index));
 	}
 
+	#[test]
+	fn events_round_trip_through_fixtures() {
+		let events = phase_and_events(vec![
+			(
+				1u32,
+				mock_proxy_added(
+					&PolkadotAccountId::from_aliased([0; 32]),
+					&PolkadotAccountId::from_aliased([1; 32]),
+				),
+			),
+			(1u32, mock_tx_fee_paid(10000)),
+			(2u32, EventWrapper::ExtrinsicSuccess),
+		]);
+
+		assert_eq!(
+			Vec::<(Phase, EventWrapper)>::from_fixture(events.to_fixture().unwrap())
+				.unwrap()
+				.encode(),
+			events.encode()
+		);
+	}
+
 	#[tokio::test]
 	async fn test_extrinsic_success_filtering() {
 		let events = phase_and_events(vec![
@@ -372,4 +491,90 @@ pub mod test {
 
 		assert_eq!(extrinsic_success_indices(&events), BTreeSet::from([1, 2]));
 	}
+
+	#[tokio::test]
+	async fn deposits_are_witnessed_from_fixture() {
+		let source = FixtureSource::<Polkadot, PolkadotHash, Vec<(Phase, EventWrapper)>>::load(
+			concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/polkadot.jsonl"),
+		)
+		.unwrap();
+		let our_vault = PolkadotAccountId::from_aliased([0xaa; 32]);
+		let deposit_address = PolkadotAccountId::from_aliased([0xdd; 32]);
+
+		let witnessed_calls = witnessed_calls(
+			MockStateChain::default()
+				.with_vault::<Polkadot>(
+					pallet_cf_vaults::Vault { public_key: our_vault, active_from_block: 0 },
+					302,
+				)
+				.with_deposit_channels::<Polkadot>(vec![DepositChannelDetails {
+					deposit_channel: DepositChannel {
+						channel_id: 1,
+						address: deposit_address,
+						asset: dot::Asset::Dot,
+						state: PolkadotChannelState,
+					},
+					opened_at: 0,
+					expires_at: 1000,
+					action: ChannelAction::LiquidityProvision {
+						lp_account: AccountId32::new([0xab; 32]),
+					},
+				}])
+				.with_egress_items::<Polkadot>(vec![])
+				.with(|state_chain_client| {
+					state_chain_client
+						.expect_storage_value::<pallet_cf_environment::PolkadotVaultAccountId<
+							state_chain_runtime::Runtime,
+						>>()
+						.returning(move |_| Ok(Some(our_vault)));
+				}),
+			move |context| {
+				async move {
+					let process_call = context.process_call();
+					let dot_client = FixtureDotRpcClient::new(source.responses());
+					source
+						.chunk_by_vault(
+							vault_accounts(context.epoch_source).await.vaults().await,
+							context.scope,
+						)
+						.deposit_addresses(
+							context.scope,
+							context.state_chain_stream.clone(),
+							context.state_chain_client.clone(),
+						)
+						.await
+						.dot_deposits(process_call.clone())
+						.then(proxy_added_witnessing)
+						.egress_items(
+							context.scope,
+							context.state_chain_stream,
+							context.state_chain_client,
+						)
+						.await
+						.then(move |epoch, header| {
+							process_egress(epoch, header, process_call.clone(), dot_client.clone())
+						})
+						.run_until_exhausted()
+						.await;
+					Ok(())
+				}
+				.boxed()
+			},
+		)
+		.await;
+
+		assert_eq!(
+			witnessed_calls,
+			vec![pallet_cf_ingress_egress::Call::<_, PolkadotInstance>::process_deposits {
+				deposit_witnesses: vec![DepositWitness {
+					deposit_address,
+					asset: dot::Asset::Dot,
+					amount: 10_000_000_000,
+					deposit_details: (),
+				}],
+				block_height: 301,
+			}
+			.into()]
+		);
+	}
 }
@@ -4,19 +4,24 @@ mod eth_chain_tracking;
 mod eth_source;
 mod ethereum_deposits;
 mod key_manager;
+mod logs_prefetch;
 mod state_chain_gateway;
 pub mod vault;
 
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, ops::RangeInclusive, sync::Arc};
 
+use cf_chains::Ethereum;
 use cf_primitives::{chains::assets::eth, EpochIndex};
+use ethers::types::Bloom;
 use futures_core::Future;
-use sp_core::H160;
+use sp_core::{H160, H256};
 use utilities::task_scope::Scope;
 
 use crate::{
 	db::PersistentKeyDB,
-	eth::retry_rpc::EthersRetryRpcClient,
+	eth::retry_rpc::{
+		address_checker::AddressCheckerRetryRpcApi, EthersRetryRpcApi, EthersRetryRpcClient,
+	},
 	state_chain_observer::client::{
 		chain_api::ChainApi, extrinsic_api::signed::SignedExtrinsicApi, storage_api::StorageApi,
 		StateChainStreamApi,
@@ -25,14 +30,96 @@ use crate::{
 };
 
 use super::common::{
-	chain_source::extension::ChainSourceExt, epoch_source::EpochSourceBuilder,
+	chain_source::{
+		extension::ChainSourceExt, fixture::FixtureRecorder, replay::ReplaySource, ChainClient,
+	},
+	epoch_source::EpochSourceBuilder,
 	STATE_CHAIN_CONNECTION,
 };
 pub use eth_source::EthSource;
+use logs_prefetch::LogsPrefetch;
 
 use anyhow::{Context, Result};
 
 const SAFETY_MARGIN: usize = 6;
+/// The number of safe blocks whose hashes are remembered to detect reorgs deeper than the
+/// safety margin.
+const REORG_DETECTION_HISTORY: usize = 64;
+
+/// The addresses of the contracts the Ethereum witnessers watch, as set on the State Chain.
+struct EthContractAddresses {
+	state_chain_gateway_address: H160,
+	key_manager_address: H160,
+	vault_address: H160,
+	address_checker_address: H160,
+	usdc_contract_address: H160,
+	flip_contract_address: H160,
+	supported_erc20_tokens: HashMap<H160, cf_primitives::Asset>,
+}
+
+impl EthContractAddresses {
+	async fn fetch<StateChainClient: StorageApi + ChainApi + Send + Sync>(
+		state_chain_client: &StateChainClient,
+	) -> Result<Self> {
+		let state_chain_gateway_address =
+			state_chain_client
+				.storage_value::<pallet_cf_environment::EthereumStateChainGatewayAddress<
+					state_chain_runtime::Runtime,
+				>>(state_chain_client.latest_finalized_block().hash)
+				.await
+				.context("Failed to get StateChainGateway address from SC")?;
+
+		let key_manager_address = state_chain_client
+			.storage_value::<pallet_cf_environment::EthereumKeyManagerAddress<state_chain_runtime::Runtime>>(
+				state_chain_client.latest_finalized_block().hash,
+			)
+			.await
+			.context("Failed to get KeyManager address from SC")?;
+
+		let vault_address = state_chain_client
+			.storage_value::<pallet_cf_environment::EthereumVaultAddress<state_chain_runtime::Runtime>>(
+				state_chain_client.latest_finalized_block().hash,
+			)
+			.await
+			.context("Failed to get Vault contract address from SC")?;
+
+		let address_checker_address = state_chain_client
+			.storage_value::<pallet_cf_environment::EthereumAddressCheckerAddress<state_chain_runtime::Runtime>>(
+				state_chain_client.latest_finalized_block().hash,
+			)
+			.await
+			.expect(STATE_CHAIN_CONNECTION);
+
+		let supported_erc20_tokens: HashMap<cf_primitives::chains::assets::eth::Asset, H160> =
+			state_chain_client
+				.storage_map::<pallet_cf_environment::EthereumSupportedAssets<state_chain_runtime::Runtime>, _>(
+					state_chain_client.latest_finalized_block().hash,
+				)
+				.await
+				.context("Failed to fetch Ethereum supported assets")?;
+
+		let usdc_contract_address =
+			*supported_erc20_tokens.get(&eth::Asset::Usdc).context("USDC not supported")?;
+
+		let flip_contract_address =
+			*supported_erc20_tokens.get(&eth::Asset::Flip).context("FLIP not supported")?;
+
+		let supported_erc20_tokens: HashMap<H160, cf_primitives::Asset> = supported_erc20_tokens
+			.into_iter()
+			.map(|(asset, address)| (address, asset.into()))
+			.collect();
+
+		Ok(Self {
+			state_chain_gateway_address,
+			key_manager_address,
+			vault_address,
+			address_checker_address,
+			usdc_contract_address,
+			flip_contract_address,
+			supported_erc20_tokens,
+		})
+	}
+}
 
 pub async fn start<
 	StateChainClient,
@@ -44,6 +131,7 @@ pub async fn start<
 >(
 	scope: &Scope<'_, anyhow::Error>,
 	eth_client: EthersRetryRpcClient,
+	fixture_recorder: Option<FixtureRecorder>,
 	process_call: ProcessCall,
 	prewitness_call: PrewitnessCall,
 	state_chain_client: Arc<StateChainClient>,
@@ -68,54 +156,19 @@ where
 		+ 'static,
 	PrewitnessFut: Future<Output = ()> + Send + 'static,
 {
-	let state_chain_gateway_address = state_chain_client
-        .storage_value::<pallet_cf_environment::EthereumStateChainGatewayAddress<state_chain_runtime::Runtime>>(
-            state_chain_client.latest_finalized_block().hash,
-        )
-        .await
-        .context("Failed to get StateChainGateway address from SC")?;
-
-	let key_manager_address = state_chain_client
-		.storage_value::<pallet_cf_environment::EthereumKeyManagerAddress<state_chain_runtime::Runtime>>(
-			state_chain_client.latest_finalized_block().hash,
-		)
-		.await
-		.context("Failed to get KeyManager address from SC")?;
-
-	let vault_address = state_chain_client
-		.storage_value::<pallet_cf_environment::EthereumVaultAddress<state_chain_runtime::Runtime>>(
-			state_chain_client.latest_finalized_block().hash,
-		)
-		.await
-		.context("Failed to get Vault contract address from SC")?;
-
-	let address_checker_address = state_chain_client
-		.storage_value::<pallet_cf_environment::EthereumAddressCheckerAddress<state_chain_runtime::Runtime>>(
-			state_chain_client.latest_finalized_block().hash,
-		)
-		.await
-		.expect(STATE_CHAIN_CONNECTION);
-
-	let supported_erc20_tokens: HashMap<cf_primitives::chains::assets::eth::Asset, H160> =
-		state_chain_client
-			.storage_map::<pallet_cf_environment::EthereumSupportedAssets<state_chain_runtime::Runtime>, _>(
-				state_chain_client.latest_finalized_block().hash,
-			)
-			.await
-			.context("Failed to fetch Ethereum supported assets")?;
+	let EthContractAddresses {
+		state_chain_gateway_address,
+		key_manager_address,
+		vault_address,
+		address_checker_address,
+		usdc_contract_address,
+		flip_contract_address,
+		supported_erc20_tokens,
+	} = EthContractAddresses::fetch(&*state_chain_client).await?;
 
-	let usdc_contract_address =
-		*supported_erc20_tokens.get(&eth::Asset::Usdc).context("USDC not supported")?;
+	let eth_client = eth_client.with_recorder(fixture_recorder.clone());
 
-	let flip_contract_address =
-		*supported_erc20_tokens.get(&eth::Asset::Flip).context("FLIP not supported")?;
-
-	let supported_erc20_tokens: HashMap<H160, cf_primitives::Asset> = supported_erc20_tokens
-		.into_iter()
-		.map(|(asset, address)| (address, asset.into()))
-		.collect();
-
-	let eth_source = EthSource::new(eth_client.clone()).shared(scope);
+	let eth_source = EthSource::new(eth_client.clone()).recording(fixture_recorder).shared(scope);
 
 	eth_source
 		.clone()
@@ -186,11 +239,22 @@ where
 
 	// ===== Full witnessing stream =====
 
-	let eth_safe_vault_source = eth_source
-		.strictly_monotonic()
-		.lag_safety(SAFETY_MARGIN)
-		.logging("safe block produced")
-		.chunk_by_vault(vaults, scope);
+	let eth_safe_vault_source = LogsPrefetch::new(
+		eth_source
+			.strictly_monotonic()
+			.lag_safety(SAFETY_MARGIN)
+			.reorg_detection(REORG_DETECTION_HISTORY),
+		eth_client.clone(),
+		vec![
+			key_manager_address,
+			state_chain_gateway_address,
+			usdc_contract_address,
+			flip_contract_address,
+			vault_address,
+		],
+	)
+	.logging("safe block produced")
+	.chunk_by_vault(vaults, scope);
 
 	let eth_safe_vault_source_deposit_addresses = eth_safe_vault_source
 		.clone()
@@ -270,3 +334,280 @@ where
 
 	Ok(())
 }
+
+/// Runs the full witnessing pipelines over a fixed range of already finalised blocks, for the
+/// epochs in `epoch_source`. Nothing is persisted, so this doesn't affect the progress of the
+/// regular witnessers. Completes once all the blocks have been witnessed.
+pub async fn replay<EthClient, StateChainClient, StateChainStream, ProcessCall, ProcessingFut>(
+	scope: &Scope<'_, anyhow::Error>,
+	eth_client: EthClient,
+	process_call: ProcessCall,
+	state_chain_client: Arc<StateChainClient>,
+	state_chain_stream: StateChainStream,
+	epoch_source: EpochSourceBuilder<'_, '_, StateChainClient, (), ()>,
+	blocks: RangeInclusive<u64>,
+) -> Result<()>
+where
+	EthClient: EthersRetryRpcApi
+		+ AddressCheckerRetryRpcApi
+		+ ChainClient<Index = u64, Hash = H256, Data = Bloom>
+		+ Send
+		+ Sync
+		+ 'static,
+	StateChainClient: StorageApi + ChainApi + SignedExtrinsicApi + 'static + Send + Sync,
+	StateChainStream: StateChainStreamApi + Clone,
+	ProcessCall: Fn(state_chain_runtime::RuntimeCall, EpochIndex) -> ProcessingFut
+		+ Send
+		+ Sync
+		+ Clone
+		+ 'static,
+	ProcessingFut: Future<Output = ()> + Send + 'static,
+{
+	let EthContractAddresses {
+		state_chain_gateway_address,
+		key_manager_address,
+		vault_address,
+		address_checker_address,
+		usdc_contract_address,
+		flip_contract_address,
+		supported_erc20_tokens,
+	} = EthContractAddresses::fetch(&*state_chain_client).await?;
+
+	let vaults = epoch_source.vaults().await;
+
+	// Each pipeline gets its own source, as a shared source only passes on the blocks produced
+	// after a consumer subscribes, and a replay can't be restarted from the head.
+	let vault_source = || {
+		ReplaySource::<_, Ethereum>::new(eth_client.clone(), blocks.clone())
+			.logging("replayed block")
+			.chunk_by_vault(vaults.clone(), scope)
+	};
+
+	let key_manager = vault_source().key_manager_witnessing(
+		process_call.clone(),
+		eth_client.clone(),
+		key_manager_address,
+	);
+
+	let state_chain_gateway = vault_source().state_chain_gateway_witnessing(
+		process_call.clone(),
+		eth_client.clone(),
+		state_chain_gateway_address,
+	);
+
+	let usdc_deposits = vault_source()
+		.deposit_addresses(scope, state_chain_stream.clone(), state_chain_client.clone())
+		.await
+		.erc20_deposits::<_, _, _, UsdcEvents>(
+			process_call.clone(),
+			eth_client.clone(),
+			eth::Asset::Usdc,
+			usdc_contract_address,
+		)
+		.await?;
+
+	let flip_deposits = vault_source()
+		.deposit_addresses(scope, state_chain_stream.clone(), state_chain_client.clone())
+		.await
+		.erc20_deposits::<_, _, _, FlipEvents>(
+			process_call.clone(),
+			eth_client.clone(),
+			eth::Asset::Flip,
+			flip_contract_address,
+		)
+		.await?;
+
+	let ethereum_deposits = vault_source()
+		.deposit_addresses(scope, state_chain_stream, state_chain_client)
+		.await
+		.ethereum_deposits(
+			process_call.clone(),
+			eth_client.clone(),
+			eth::Asset::Eth,
+			address_checker_address,
+			vault_address,
+		)
+		.await;
+
+	let vault = vault_source().vault_witnessing(
+		process_call,
+		eth_client.clone(),
+		vault_address,
+		cf_primitives::Asset::Eth,
+		cf_primitives::ForeignChain::Ethereum,
+		supported_erc20_tokens,
+	);
+
+	futures::join!(
+		key_manager.run_until_exhausted(),
+		state_chain_gateway.run_until_exhausted(),
+		usdc_deposits.run_until_exhausted(),
+		flip_deposits.run_until_exhausted(),
+		ethereum_deposits.run_until_exhausted(),
+		vault.run_until_exhausted(),
+	);
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{collections::HashMap, str::FromStr};
+
+	use cf_chains::{evm::DeploymentStatus, DepositChannel, Ethereum};
+	use ethers::types::Bloom;
+	use futures::FutureExt;
+	use pallet_cf_ingress_egress::{ChannelAction, DepositChannelDetails, DepositWitness};
+	use sp_core::{H160, H256};
+	use sp_runtime::AccountId32;
+	use state_chain_runtime::{EthereumInstance, Runtime, RuntimeCall};
+
+	use super::{erc20_deposits::usdc::UsdcEvents, eth, replay};
+	use crate::{
+		eth::retry_rpc::fixture::FixtureEthRpcClient,
+		state_chain_observer::client::BlockInfo,
+		witness::common::{
+			chain_source::{extension::ChainSourceExt, fixture::FixtureSource},
+			test_helpers::{witnessed_calls, MockStateChain},
+		},
+	};
+
+	fn load_fixture() -> FixtureSource<Ethereum, H256, Bloom> {
+		FixtureSource::load(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/ethereum.jsonl"))
+			.unwrap()
+	}
+
+	fn usdc_contract_address() -> H160 {
+		H160::from_str("a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap()
+	}
+
+	/// The fixture has a USDC transfer to this address in block 101.
+	const DEPOSIT_ADDRESS: H160 = H160::repeat_byte(0x11);
+
+	fn state_chain_with_usdc_deposit_channel() -> MockStateChain {
+		MockStateChain::default()
+			.with_vault::<Ethereum>(
+				pallet_cf_vaults::Vault { public_key: Default::default(), active_from_block: 0 },
+				103,
+			)
+			.with_deposit_channels::<Ethereum>(vec![DepositChannelDetails {
+				deposit_channel: DepositChannel {
+					channel_id: 1,
+					address: DEPOSIT_ADDRESS,
+					asset: eth::Asset::Usdc,
+					state: DeploymentStatus::Undeployed,
+				},
+				opened_at: 0,
+				expires_at: 1000,
+				action: ChannelAction::LiquidityProvision {
+					lp_account: AccountId32::new([0xab; 32]),
+				},
+			}])
+	}
+
+	fn usdc_deposit() -> RuntimeCall {
+		pallet_cf_ingress_egress::Call::<_, EthereumInstance>::process_deposits {
+			deposit_witnesses: vec![DepositWitness {
+				deposit_address: DEPOSIT_ADDRESS,
+				asset: eth::Asset::Usdc,
+				amount: 1_000_000,
+				deposit_details: (),
+			}],
+			block_height: 101,
+		}
+		.into()
+	}
+
+	#[tokio::test]
+	async fn usdc_deposits_are_witnessed_from_fixture() {
+		let source = load_fixture();
+
+		let witnessed_calls =
+			witnessed_calls(state_chain_with_usdc_deposit_channel(), move |context| {
+				async move {
+					let process_call = context.process_call();
+					let eth_rpc = FixtureEthRpcClient::new(&source);
+					source
+						.chunk_by_vault(context.epoch_source.vaults().await, context.scope)
+						.deposit_addresses(
+							context.scope,
+							context.state_chain_stream,
+							context.state_chain_client,
+						)
+						.await
+						.erc20_deposits::<_, _, _, UsdcEvents>(
+							process_call,
+							eth_rpc,
+							eth::Asset::Usdc,
+							usdc_contract_address(),
+						)
+						.await?
+						.run_until_exhausted()
+						.await;
+					Ok(())
+				}
+				.boxed()
+			})
+			.await;
+
+		assert_eq!(witnessed_calls, vec![usdc_deposit()]);
+	}
+
+	#[tokio::test]
+	async fn replays_a_range_of_blocks_from_fixture() {
+		let source = load_fixture();
+
+		// The other contracts have no events in the fixture.
+		let state_chain = state_chain_with_usdc_deposit_channel().with(|state_chain_client| {
+			state_chain_client.expect_latest_finalized_block().returning(|| BlockInfo {
+				parent_hash: Default::default(),
+				hash: Default::default(),
+				number: 0,
+			});
+			state_chain_client
+				.expect_storage_value::<pallet_cf_environment::EthereumKeyManagerAddress<Runtime>>()
+				.returning(|_| Ok(H160::repeat_byte(0x01)));
+			state_chain_client
+				.expect_storage_value::<pallet_cf_environment::EthereumStateChainGatewayAddress<Runtime>>(
+				)
+				.returning(|_| Ok(H160::repeat_byte(0x02)));
+			state_chain_client
+				.expect_storage_value::<pallet_cf_environment::EthereumVaultAddress<Runtime>>()
+				.returning(|_| Ok(H160::repeat_byte(0x03)));
+			state_chain_client
+				.expect_storage_value::<pallet_cf_environment::EthereumAddressCheckerAddress<Runtime>>(
+				)
+				.returning(|_| Ok(H160::repeat_byte(0x04)));
+			state_chain_client
+				.expect_storage_map::<pallet_cf_environment::EthereumSupportedAssets<Runtime>, HashMap<
+					eth::Asset,
+					H160,
+				>>()
+				.returning(|_| {
+					Ok(HashMap::from([
+						(eth::Asset::Usdc, usdc_contract_address()),
+						(eth::Asset::Flip, H160::repeat_byte(0x05)),
+					]))
+				});
+		});
+
+		let witnessed_calls = witnessed_calls(state_chain, move |context| {
+			async move {
+				replay(
+					context.scope,
+					FixtureEthRpcClient::new(&source),
+					context.process_call(),
+					context.state_chain_client,
+					context.state_chain_stream,
+					context.epoch_source,
+					100..=102,
+				)
+				.await
+			}
+			.boxed()
+		})
+		.await;
+
+		assert_eq!(witnessed_calls, vec![usdc_deposit()]);
+	}
+}
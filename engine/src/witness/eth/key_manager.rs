@@ -204,6 +204,8 @@ mod tests {
 							http_endpoint: "http://localhost:8545".into(),
 						},
 						backup: None,
+						additional: vec![],
+						quorum: None,
 					},
 					private_key_file: PathBuf::from_str("/some/key/file").unwrap(),
 				};
@@ -0,0 +1,181 @@
+use std::sync::{
+	atomic::{AtomicU64, Ordering},
+	Arc,
+};
+
+use futures_util::StreamExt;
+use sp_core::H160;
+
+use crate::{
+	eth::retry_rpc::EthersRetryRpcApi,
+	witness::common::{
+		chain_source::{BoxChainStream, ChainClient, ChainSource, Header},
+		ExternalChainSource,
+	},
+};
+
+/// The number of blocks whose logs are fetched with each `eth_getLogs` request.
+const PREFETCH_RANGE: u64 = 64;
+
+/// Speeds up catching up on blocks behind the head of the chain, by fetching the logs of all the
+/// witnessed contracts for ranges of `PREFETCH_RANGE` blocks at once, instead of each witnesser
+/// requesting the logs of each block separately. The headers are passed through unchanged, so the
+/// witnessers still process each block individually, and their requests for the logs of a block
+/// are answered from the prefetched logs by the Ethereum client.
+///
+/// Catching up happens through the client (i.e. by `continuous`), so only the headers queried
+/// from the client trigger prefetching, and only once the whole range is behind the latest
+/// streamed header. The headers near the head are fetched one at a time as before, as their range
+/// is incomplete.
+#[derive(Clone)]
+pub struct LogsPrefetch<InnerSource, EthRpcClient> {
+	inner_source: InnerSource,
+	eth_client: EthRpcClient,
+	contract_addresses: Vec<H160>,
+}
+impl<InnerSource, EthRpcClient> LogsPrefetch<InnerSource, EthRpcClient> {
+	pub fn new(
+		inner_source: InnerSource,
+		eth_client: EthRpcClient,
+		contract_addresses: Vec<H160>,
+	) -> Self {
+		Self { inner_source, eth_client, contract_addresses }
+	}
+}
+
+#[async_trait::async_trait]
+impl<InnerSource, EthRpcClient> ChainSource for LogsPrefetch<InnerSource, EthRpcClient>
+where
+	InnerSource: ChainSource<Index = u64>,
+	EthRpcClient: EthersRetryRpcApi + Send + Sync + 'static,
+{
+	type Index = InnerSource::Index;
+	type Hash = InnerSource::Hash;
+	type Data = InnerSource::Data;
+
+	type Client = LogsPrefetchClient<InnerSource::Client, EthRpcClient>;
+
+	async fn stream_and_client(
+		&self,
+	) -> (BoxChainStream<'_, Self::Index, Self::Hash, Self::Data>, Self::Client) {
+		let (chain_stream, chain_client) = self.inner_source.stream_and_client().await;
+		let latest_streamed_index = Arc::new(AtomicU64::new(0));
+
+		(
+			Box::pin(chain_stream.map({
+				let latest_streamed_index = latest_streamed_index.clone();
+				move |header| {
+					latest_streamed_index.fetch_max(header.index, Ordering::Relaxed);
+					header
+				}
+			})),
+			LogsPrefetchClient {
+				inner_client: chain_client,
+				eth_client: self.eth_client.clone(),
+				contract_addresses: self.contract_addresses.clone(),
+				latest_streamed_index,
+			},
+		)
+	}
+}
+
+impl<InnerSource, EthRpcClient> ExternalChainSource for LogsPrefetch<InnerSource, EthRpcClient>
+where
+	InnerSource: ExternalChainSource<Index = u64>,
+	EthRpcClient: EthersRetryRpcApi + Send + Sync + 'static,
+{
+	type Chain = InnerSource::Chain;
+}
+
+#[derive(Clone)]
+pub struct LogsPrefetchClient<InnerClient, EthRpcClient> {
+	inner_client: InnerClient,
+	eth_client: EthRpcClient,
+	contract_addresses: Vec<H160>,
+	latest_streamed_index: Arc<AtomicU64>,
+}
+
+#[async_trait::async_trait]
+impl<InnerClient, EthRpcClient> ChainClient for LogsPrefetchClient<InnerClient, EthRpcClient>
+where
+	InnerClient: ChainClient<Index = u64>,
+	EthRpcClient: EthersRetryRpcApi + Send + Sync + 'static,
+{
+	type Index = InnerClient::Index;
+	type Hash = InnerClient::Hash;
+	type Data = InnerClient::Data;
+
+	async fn header_at_index(
+		&self,
+		index: Self::Index,
+	) -> Header<Self::Index, Self::Hash, Self::Data> {
+		let range_start = index - index % PREFETCH_RANGE;
+		let range_end = range_start + (PREFETCH_RANGE - 1);
+		if range_end < self.latest_streamed_index.load(Ordering::Relaxed) {
+			self.eth_client
+				.prefetch_logs(range_start..=range_end, self.contract_addresses.clone())
+				.await;
+		}
+
+		self.inner_client.header_at_index(index).await
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use cf_chains::Ethereum;
+	use mockall::predicate::eq;
+
+	use crate::{
+		eth::retry_rpc::mocks::MockEthRetryRpcClient,
+		witness::common::chain_source::replay::ReplaySource,
+	};
+
+	use super::*;
+
+	#[derive(Clone)]
+	struct MockChainClient;
+
+	#[async_trait::async_trait]
+	impl ChainClient for MockChainClient {
+		type Index = u64;
+		type Hash = u64;
+		type Data = ();
+
+		async fn header_at_index(
+			&self,
+			index: Self::Index,
+		) -> Header<Self::Index, Self::Hash, Self::Data> {
+			Header { index, hash: index, parent_hash: Some(index - 1), data: () }
+		}
+	}
+
+	#[tokio::test]
+	async fn only_ranges_behind_the_latest_streamed_header_are_prefetched() {
+		let contract_addresses = vec![H160::repeat_byte(1), H160::repeat_byte(2)];
+
+		let mut eth_client = MockEthRetryRpcClient::new();
+		eth_client.expect_clone().returning(|| {
+			let mut eth_client = MockEthRetryRpcClient::new();
+			eth_client
+				.expect_prefetch_logs()
+				.with(eq(64..=127), eq(vec![H160::repeat_byte(1), H160::repeat_byte(2)]))
+				.times(2)
+				.returning(|_, _| ());
+			eth_client
+		});
+
+		let source = LogsPrefetch::new(
+			ReplaySource::<_, Ethereum>::new(MockChainClient, 130..=140),
+			eth_client,
+			contract_addresses,
+		);
+		let (stream, client) = source.stream_and_client().await;
+		assert_eq!(stream.count().await, 11);
+
+		assert_eq!(client.header_at_index(100).await.index, 100);
+		assert_eq!(client.header_at_index(127).await.index, 127);
+		// The range 128..=191 isn't complete yet.
+		assert_eq!(client.header_at_index(135).await.index, 135);
+	}
+}
@@ -0,0 +1,164 @@
+use std::{future::Future, sync::Arc};
+
+use anyhow::{bail, Context, Result};
+use cf_primitives::ForeignChain;
+use utilities::task_scope::Scope;
+
+use crate::{
+	btc::retry_rpc::BtcRetryRpcClient,
+	dot::retry_rpc::DotRetryRpcClient,
+	eth::retry_rpc::EthersRetryRpcClient,
+	settings::ReplayOptions,
+	state_chain_observer::client::{
+		chain_api::ChainApi,
+		extrinsic_api::signed::{SignedExtrinsicApi, UntilFinalized},
+		storage_api::StorageApi,
+		StateChainStreamApi,
+	},
+};
+
+use super::common::epoch_source::EpochSource;
+
+/// Re-witnesses a historical range of blocks of a single chain, for a single epoch, using the same
+/// pipelines as the regular witnessers. This is used to recover witnesses that were missed, for
+/// example due to a bug in a witnesser.
+///
+/// By default the resulting calls are only logged. If `submit` is set, they are submitted to the
+/// State Chain as witnesses for the epoch, which requires this node to have been an authority in
+/// that epoch. Only deposit channels that still exist on the State Chain are witnessed.
+///
+/// Only the client of the replayed chain is awaited, so the other chains' nodes don't need to be
+/// reachable.
+pub async fn replay<StateChainClient>(
+	scope: &Scope<'_, anyhow::Error>,
+	options: ReplayOptions,
+	eth_client: impl Future<Output = Result<EthersRetryRpcClient>>,
+	btc_client: impl Future<Output = Result<BtcRetryRpcClient>>,
+	dot_client: impl Future<Output = Result<DotRetryRpcClient>>,
+	state_chain_client: Arc<StateChainClient>,
+	state_chain_stream: impl StateChainStreamApi + Clone,
+) -> Result<()>
+where
+	StateChainClient: StorageApi + ChainApi + SignedExtrinsicApi + 'static + Send + Sync,
+{
+	options.validate()?;
+	let ReplayOptions { chain, epoch, from_block, to_block, submit } = options;
+
+	let block_hash = state_chain_client.latest_finalized_block().hash;
+	let last_expired_epoch = state_chain_client
+		.storage_value::<pallet_cf_validator::LastExpiredEpoch<state_chain_runtime::Runtime>>(
+			block_hash,
+		)
+		.await
+		.context("Failed to get the last expired epoch from SC")?;
+	let current_epoch = state_chain_client
+		.storage_value::<pallet_cf_validator::CurrentEpoch<state_chain_runtime::Runtime>>(
+			block_hash,
+		)
+		.await
+		.context("Failed to get the current epoch from SC")?;
+	if epoch <= last_expired_epoch || epoch > current_epoch {
+		bail!(
+			"Epoch {epoch} can't be witnessed, only epochs {} to {current_epoch} are active",
+			last_expired_epoch + 1
+		);
+	}
+
+	if submit &&
+		!state_chain_client
+			.storage_map_entry::<pallet_cf_validator::HistoricalActiveEpochs<state_chain_runtime::Runtime>>(
+				block_hash,
+				&state_chain_client.account_id(),
+			)
+			.await
+			.context("Failed to get the active epochs of this node from SC")?
+			.contains(&epoch)
+	{
+		bail!("Can't submit witnesses for epoch {epoch}, as this node wasn't an authority in it");
+	}
+
+	let epoch_source =
+		EpochSource::builder(scope, state_chain_stream.clone(), state_chain_client.clone())
+			.await
+			.filter_map(
+				move |_state_chain_client, epoch_index, _block_hash, info| async move {
+					(epoch_index == epoch).then_some(info)
+				},
+				|_state_chain_client, _epoch_index, _block_hash, historic_info| async move {
+					historic_info
+				},
+			)
+			.await;
+
+	let process_call = {
+		let state_chain_client = state_chain_client.clone();
+		move |call: state_chain_runtime::RuntimeCall, epoch_index| {
+			let state_chain_client = state_chain_client.clone();
+			async move {
+				if submit {
+					tracing::info!("Submitting replayed witness for epoch {epoch_index}: {call:?}");
+					if let Err(error) = state_chain_client
+						.finalize_signed_extrinsic(pallet_cf_witnesser::Call::witness_at_epoch {
+							call: Box::new(call),
+							epoch_index,
+						})
+						.await
+						.until_finalized()
+						.await
+					{
+						tracing::error!(
+							"Failed to submit replayed witness for epoch {epoch_index}: {error}"
+						);
+					}
+				} else {
+					tracing::info!("Witnessed for epoch {epoch_index}: {call:?}");
+				}
+			}
+		}
+	};
+
+	tracing::info!(
+		"Replaying {chain} blocks {from_block} to {to_block} for epoch {epoch}{}",
+		if submit { "" } else { " (dry run)" }
+	);
+
+	match chain {
+		ForeignChain::Ethereum =>
+			super::eth::replay(
+				scope,
+				eth_client.await?,
+				process_call,
+				state_chain_client,
+				state_chain_stream,
+				epoch_source,
+				from_block..=to_block,
+			)
+			.await?,
+		ForeignChain::Bitcoin =>
+			super::btc::replay(
+				scope,
+				btc_client.await?,
+				process_call,
+				state_chain_client,
+				state_chain_stream,
+				epoch_source,
+				from_block..=to_block,
+			)
+			.await?,
+		ForeignChain::Polkadot =>
+			super::dot::replay(
+				scope,
+				dot_client.await?,
+				process_call,
+				state_chain_client,
+				state_chain_stream,
+				epoch_source,
+				from_block.try_into()?..=to_block.try_into()?,
+			)
+			.await?,
+	}
+
+	tracing::info!("Finished replaying {chain} blocks {from_block} to {to_block}");
+
+	Ok(())
+}
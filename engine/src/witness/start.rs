@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 
 use utilities::task_scope::Scope;
 
@@ -14,7 +14,7 @@ use crate::{
 
 use crate::state_chain_observer::client::chain_api::ChainApi;
 
-use super::common::epoch_source::EpochSource;
+use super::common::{chain_source::fixture::FixtureRecorder, epoch_source::EpochSource};
 
 use anyhow::Result;
 
@@ -27,7 +27,9 @@ pub async fn start<StateChainClient>(
 	scope: &Scope<'_, anyhow::Error>,
 	eth_client: EthersRetryRpcClient,
 	btc_client: BtcRetryRpcClient,
+	btc_use_block_filters: bool,
 	dot_client: DotRetryRpcClient,
+	fixture_dir: Option<PathBuf>,
 	state_chain_client: Arc<StateChainClient>,
 	state_chain_stream: impl StateChainStreamApi + Clone,
 	unfinalised_state_chain_stream: impl StateChainStreamApi<false> + Clone,
@@ -36,6 +38,16 @@ pub async fn start<StateChainClient>(
 where
 	StateChainClient: StorageApi + ChainApi + SignedExtrinsicApi + 'static + Send + Sync,
 {
+	let fixture_recorder = |chain: &str| {
+		fixture_dir
+			.as_ref()
+			.map(|fixture_dir| FixtureRecorder::create(fixture_dir.join(format!("{chain}.jsonl"))))
+			.transpose()
+	};
+	let eth_fixture_recorder = fixture_recorder("ethereum")?;
+	let btc_fixture_recorder = fixture_recorder("bitcoin")?;
+	let dot_fixture_recorder = fixture_recorder("polkadot")?;
+
 	let epoch_source =
 		EpochSource::builder(scope, state_chain_stream.clone(), state_chain_client.clone())
 			.await
@@ -77,6 +89,7 @@ where
 	let start_eth = super::eth::start(
 		scope,
 		eth_client,
+		eth_fixture_recorder,
 		witness_call.clone(),
 		prewitness_call.clone(),
 		state_chain_client.clone(),
@@ -89,6 +102,8 @@ where
 	let start_btc = super::btc::start(
 		scope,
 		btc_client,
+		btc_use_block_filters,
+		btc_fixture_recorder,
 		witness_call.clone(),
 		prewitness_call.clone(),
 		state_chain_client.clone(),
@@ -101,6 +116,7 @@ where
 	let start_dot = super::dot::start(
 		scope,
 		dot_client,
+		dot_fixture_recorder,
 		witness_call,
 		prewitness_call,
 		state_chain_client,
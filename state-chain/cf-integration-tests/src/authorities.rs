@@ -8,10 +8,11 @@ use sp_runtime::AccountId32;
 use std::collections::{BTreeSet, HashMap};
 
 use cf_primitives::{AuthorityCount, FlipBalance, GENESIS_EPOCH};
-use cf_traits::{AsyncResult, EpochInfo, SafeMode, VaultRotator, VaultStatus};
+use cf_traits::{AsyncResult, EpochInfo, QualifyNode, SafeMode, VaultRotator, VaultStatus};
 use pallet_cf_environment::SafeModeUpdate;
 use pallet_cf_validator::{CurrentRotationPhase, RotationPhase};
 use state_chain_runtime::{
+	chainflip::keygen_disqualification, runtime_apis::BidderExclusionReason,
 	safe_mode::RuntimeSafeMode, BitcoinVault, Environment, EthereumInstance, EthereumVault, Flip,
 	PolkadotInstance, PolkadotVault, Runtime, RuntimeOrigin, Validator,
 };
@@ -456,3 +457,39 @@ fn authority_rotation_can_recover_after_key_handover_fails() {
 			assert_eq!(GENESIS_EPOCH + 2, Validator::epoch_index(), "We should be in a new epoch");
 		});
 }
+
+#[test]
+fn keygen_disqualification_follows_the_keygen_qualification_checks() {
+	super::genesis::default().build().execute_with(|| {
+		let (mut testnet, _) = network::Network::create(0, &Validator::current_authorities());
+		let node = testnet.create_engine();
+		testnet.state_chain_gateway_contract.fund_account(
+			node.clone(),
+			genesis::GENESIS_BALANCE,
+			GENESIS_EPOCH,
+		);
+		testnet.move_forward_blocks(2);
+
+		let assert_disqualification = |expected: Option<BidderExclusionReason>| {
+			assert!(keygen_disqualification(&node) == expected);
+			assert_eq!(
+				<<Runtime as pallet_cf_validator::Config>::KeygenQualification as QualifyNode<
+					_,
+				>>::is_qualified(&node),
+				expected.is_none()
+			);
+		};
+
+		network::Cli::register_as_validator(&node);
+		assert_disqualification(Some(BidderExclusionReason::Offline));
+
+		testnet.submit_heartbeat_all_engines(true);
+		assert_disqualification(Some(BidderExclusionReason::NoPeerId));
+
+		network::setup_peer_mapping(&node);
+		assert_disqualification(Some(BidderExclusionReason::SessionKeysNotRegistered));
+
+		network::setup_account(&node);
+		assert_disqualification(None);
+	});
+}
@@ -0,0 +1,156 @@
+use crate::{genesis, network, NodeId};
+
+use frame_support::assert_ok;
+use sp_runtime::Permill;
+
+use cf_chains::{
+	eth::api::EthereumApi,
+	evm::{EvmCrypto, TransactionFee},
+	Ethereum, UpdateFlipSupply,
+};
+use cf_primitives::BroadcastId;
+use cf_traits::{Broadcaster, EpochInfo};
+use pallet_cf_broadcast::{
+	AwaitingBroadcast, BroadcastAttemptCount, BroadcastAttemptId, BroadcastSlashingSettings,
+	ConsecutiveBroadcastFailures, TransactionMetadata,
+};
+use state_chain_runtime::{
+	chainflip::EthEnvironment, EthereumBroadcaster, EthereumInstance, Flip, Runtime, RuntimeEvent,
+	RuntimeOrigin, System, Validator,
+};
+
+const BLOCKS_PER_FAILURE: u32 = 100;
+const BLOCKS_FOR_EXCESSIVE_FEE: u32 = 1_000;
+
+fn set_slashing_settings(failure_threshold: u32, max_fee_percentage: Option<u32>) {
+	assert_ok!(Flip::set_slashing_rate(
+		pallet_cf_governance::RawOrigin::GovernanceApproval.into(),
+		Permill::from_percent(1),
+	));
+	assert_ok!(EthereumBroadcaster::update_slashing_settings(
+		pallet_cf_governance::RawOrigin::GovernanceApproval.into(),
+		BroadcastSlashingSettings {
+			failure_threshold,
+			blocks_slashed_per_failure: BLOCKS_PER_FAILURE,
+			max_fee_percentage,
+			blocks_slashed_for_excessive_fee: BLOCKS_FOR_EXCESSIVE_FEE,
+		},
+	));
+}
+
+/// Requests a broadcast and moves forward until the threshold signature is ready and the first
+/// attempt has been handed to a nominee.
+fn start_broadcast(testnet: &mut network::Network) -> BroadcastId {
+	let (broadcast_id, _) =
+		<EthereumBroadcaster as Broadcaster<Ethereum>>::threshold_sign_and_broadcast(
+			<EthereumApi<EthEnvironment> as UpdateFlipSupply<EvmCrypto>>::new_unsigned(1_000, 1),
+		);
+	testnet.move_forward_blocks(2);
+	broadcast_id
+}
+
+fn current_attempt(broadcast_id: BroadcastId) -> (BroadcastAttemptId, NodeId) {
+	let broadcast_attempt_id = BroadcastAttemptId {
+		broadcast_id,
+		attempt_count: BroadcastAttemptCount::<Runtime, EthereumInstance>::get(broadcast_id),
+	};
+	let attempt = AwaitingBroadcast::<Runtime, EthereumInstance>::get(broadcast_attempt_id)
+		.expect("Broadcast attempt should be awaiting a broadcaster.");
+	(broadcast_attempt_id, attempt.nominee)
+}
+
+fn witness_success(broadcast_attempt_id: BroadcastAttemptId, tx_fee: TransactionFee) {
+	let tx_out_id = AwaitingBroadcast::<Runtime, EthereumInstance>::get(broadcast_attempt_id)
+		.unwrap()
+		.broadcast_attempt
+		.transaction_out_id;
+	let tx_metadata =
+		TransactionMetadata::<Runtime, EthereumInstance>::get(broadcast_attempt_id.broadcast_id)
+			.unwrap();
+	assert_ok!(EthereumBroadcaster::transaction_succeeded(
+		pallet_cf_witnesser::RawOrigin::CurrentEpochWitnessThreshold.into(),
+		tx_out_id,
+		Default::default(),
+		tx_fee,
+		tx_metadata,
+	));
+}
+
+fn slashed_accounts() -> Vec<NodeId> {
+	System::events()
+		.into_iter()
+		.filter_map(|record| match record.event {
+			RuntimeEvent::Flip(pallet_cf_flip::Event::SlashingPerformed { who, .. }) => Some(who),
+			_ => None,
+		})
+		.collect()
+}
+
+#[test]
+fn failing_broadcasters_are_slashed_once_threshold_is_exceeded() {
+	const FAILURE_THRESHOLD: u32 = 1;
+	const REASONABLE_FEE: TransactionFee =
+		TransactionFee { effective_gas_price: 1_000_100, gas_used: 21_000 };
+
+	genesis::default().build().execute_with(|| {
+		let (mut testnet, _) = network::Network::create(0, &Validator::current_authorities());
+		set_slashing_settings(FAILURE_THRESHOLD, None);
+
+		// Every authority has already failed up to the threshold, so the next failure is slashed.
+		for authority in Validator::current_authorities() {
+			ConsecutiveBroadcastFailures::<Runtime, EthereumInstance>::insert(
+				&authority,
+				FAILURE_THRESHOLD,
+			);
+		}
+
+		let broadcast_id = start_broadcast(&mut testnet);
+		let (broadcast_attempt_id, failed_nominee) = current_attempt(broadcast_id);
+		assert_ok!(EthereumBroadcaster::transaction_signing_failure(
+			RuntimeOrigin::signed(failed_nominee.clone()),
+			broadcast_attempt_id,
+		));
+
+		// The failed attempt is retried with another nominee.
+		testnet.move_forward_blocks(1);
+		let (broadcast_attempt_id, successful_nominee) = current_attempt(broadcast_id);
+		assert_ne!(failed_nominee, successful_nominee);
+
+		witness_success(broadcast_attempt_id, REASONABLE_FEE);
+
+		assert_eq!(slashed_accounts(), vec![failed_nominee.clone()]);
+		assert_eq!(
+			ConsecutiveBroadcastFailures::<Runtime, EthereumInstance>::get(&failed_nominee),
+			FAILURE_THRESHOLD + 1
+		);
+		assert_eq!(
+			ConsecutiveBroadcastFailures::<Runtime, EthereumInstance>::get(&successful_nominee),
+			0
+		);
+	});
+}
+
+#[test]
+fn broadcasters_paying_excessive_fees_are_slashed() {
+	// Well above the tracked base fee + priority fee set at genesis.
+	const EXCESSIVE_FEE: TransactionFee =
+		TransactionFee { effective_gas_price: 10_000_000, gas_used: 21_000 };
+
+	genesis::default().build().execute_with(|| {
+		let (mut testnet, _) = network::Network::create(0, &Validator::current_authorities());
+		set_slashing_settings(u32::MAX, Some(300));
+
+		let broadcast_id = start_broadcast(&mut testnet);
+		let (broadcast_attempt_id, nominee) = current_attempt(broadcast_id);
+
+		witness_success(broadcast_attempt_id, EXCESSIVE_FEE);
+
+		assert_eq!(slashed_accounts(), vec![nominee.clone()]);
+		assert!(System::events().into_iter().any(|record| matches!(
+			record.event,
+			RuntimeEvent::EthereumBroadcaster(
+				pallet_cf_broadcast::Event::ExcessiveTransactionFee { nominee: ref slashed, .. }
+			) if *slashed == nominee
+		)));
+	});
+}
@@ -11,6 +11,7 @@ mod threshold_signing;
 
 mod account;
 mod authorities;
+mod broadcasting;
 mod funding;
 mod genesis;
 mod governance;
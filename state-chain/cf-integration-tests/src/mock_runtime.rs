@@ -180,6 +180,7 @@ impl ExtBuilder {
 				deployment_block: 0,
 				keygen_response_timeout: 4,
 				amount_to_slash: FLIPPERINOS_PER_FLIP,
+				max_keygen_attempts: MAX_KEYGEN_ATTEMPTS,
 			},
 			emissions: EmissionsConfig {
 				current_authority_emission_inflation: CURRENT_AUTHORITY_EMISSION_INFLATION_PERBILL,
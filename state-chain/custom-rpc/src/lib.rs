@@ -7,7 +7,8 @@ use cf_chains::{
 	eth::Address as EthereumAddress,
 };
 use cf_primitives::{
-	AccountRole, Asset, AssetAmount, ForeignChain, NetworkEnvironment, SemVer, SwapOutput,
+	AccountRole, Asset, AssetAmount, BroadcastId, EgressId, ForeignChain, NetworkEnvironment,
+	SemVer, SwapOutput,
 };
 use cf_utilities::rpc::NumberOrHex;
 use core::ops::Range;
@@ -17,19 +18,32 @@ use jsonrpsee::{
 	types::error::{CallError, SubscriptionEmptyError},
 	SubscriptionSink,
 };
-use pallet_cf_governance::GovCallHash;
-use pallet_cf_pools::{AssetsMap, PoolInfo, PoolLiquidity, PoolOrders, UnidirectionalPoolDepth};
+use pallet_cf_governance::{
+	GovCallHash, ProposalId, ProposalOutcome, ProposalPreview, ProposalRecord, ProposalStatus,
+};
+use pallet_cf_pools::{
+	AssetsMap, FlipBurn, FlipBurnRecord, PoolInfo, PoolLiquidity, PoolOrders,
+	UnidirectionalPoolDepth,
+};
+use pallet_cf_validator::{
+	AuctionError, ExclusionReason, RotationSimulationFailure, RotationStage,
+};
+use pallet_cf_vaults::KeyActivationPreview;
 use sc_client_api::{BlockchainEvents, HeaderBackend};
 use serde::{Deserialize, Serialize};
 use sp_api::BlockT;
-use sp_runtime::DispatchError;
+use sp_runtime::{codec::Decode, DispatchError};
 use state_chain_runtime::{
 	chainflip::Offence,
 	constants::common::TX_FEE_MULTIPLIER,
-	runtime_apis::{CustomRuntimeApi, LiquidityProviderInfo, RuntimeApiAccountInfoV2},
+	runtime_apis::{
+		AuthoritySetPreview, BidderExclusionReason, CustomRuntimeApi, LiquidityProviderInfo,
+		RestrictedBalance, RotationDryRun, RuntimeApiAccountInfoV2,
+		RuntimeApiRedemptionQueuePosition, ValidatorLiveness,
+	},
 };
 use std::{
-	collections::{BTreeMap, HashMap},
+	collections::{BTreeMap, BTreeSet, HashMap},
 	marker::PhantomData,
 	sync::Arc,
 };
@@ -187,6 +201,7 @@ pub struct RpcAccountInfoV2 {
 	pub bound_redeem_address: Option<EthereumAddress>,
 	pub apy_bp: Option<u32>,
 	pub restricted_balances: BTreeMap<EthereumAddress, u128>,
+	pub restrictions: BTreeMap<EthereumAddress, RestrictedBalance>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -197,6 +212,79 @@ pub struct RpcPenalty {
 
 type RpcSuspensions = Vec<(Offence, Vec<(u32, state_chain_runtime::AccountId)>)>;
 
+#[derive(Serialize, Deserialize)]
+pub struct RpcEpochLiveness {
+	epoch: u32,
+	heartbeat_intervals: u32,
+	missed_heartbeats: u32,
+	uptime_percent: u8,
+	reputation_points: i32,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RpcValidatorLiveness {
+	last_heartbeat: Option<u32>,
+	is_online: bool,
+	reputation_points: i32,
+	/// Liveness over the most recent epochs, oldest first.
+	epochs: Vec<RpcEpochLiveness>,
+}
+
+impl From<ValidatorLiveness> for RpcValidatorLiveness {
+	fn from(liveness: ValidatorLiveness) -> Self {
+		Self {
+			last_heartbeat: liveness.last_heartbeat,
+			is_online: liveness.is_online,
+			reputation_points: liveness.reputation_points,
+			epochs: liveness
+				.epochs
+				.into_iter()
+				.map(|epoch| RpcEpochLiveness {
+					epoch: epoch.epoch,
+					heartbeat_intervals: epoch.heartbeat_intervals,
+					missed_heartbeats: epoch.missed_heartbeats,
+					uptime_percent: epoch.uptime_percent,
+					reputation_points: epoch.reputation_points,
+				})
+				.collect(),
+		}
+	}
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RpcRedemptionQueuePosition {
+	/// The number of redemptions ahead in the queue.
+	position: u32,
+	amount: NumberOrHex,
+	/// The total amount of the redemptions ahead in the queue.
+	amount_ahead: NumberOrHex,
+	queued_at: u32,
+}
+
+impl From<RuntimeApiRedemptionQueuePosition> for RpcRedemptionQueuePosition {
+	fn from(queued: RuntimeApiRedemptionQueuePosition) -> Self {
+		Self {
+			position: queued.position,
+			amount: queued.amount.into(),
+			amount_ahead: queued.amount_ahead.into(),
+			queued_at: queued.queued_at,
+		}
+	}
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RpcPenaltyRecord {
+	offence: Offence,
+	reported_at: u32,
+	reputation_points: i32,
+	suspended_until: Option<u32>,
+	/// The number of nodes reported for the offence in the same report.
+	reported_with: u32,
+	/// The hex-encoded time slot of offences reported by the consensus engine.
+	time_slot: Option<String>,
+	pardoned: bool,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct RpcAuctionState {
 	blocks_per_epoch: u32,
@@ -207,6 +295,276 @@ pub struct RpcAuctionState {
 	min_active_bid: Option<NumberOrHex>,
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct RpcRotationDryRun {
+	pub new_epoch_index: u32,
+	/// The stage at which the rotation would fail. A failure at the `Activation` stage is only
+	/// an estimate, derived from `estimated_key_activations`.
+	pub failed_stage: Option<RotationStage>,
+	pub failure: Option<RotationSimulationFailure>,
+	pub bond: Option<NumberOrHex>,
+	pub new_authorities: BTreeSet<state_chain_runtime::AccountId>,
+	pub sharing_participants: BTreeSet<state_chain_runtime::AccountId>,
+	pub excluded: BTreeMap<state_chain_runtime::AccountId, ExclusionReason>,
+	pub keygen_attempts: u32,
+	pub key_handover_attempts: u32,
+	/// Estimated using the current key in place of the new one: the activation stage is not
+	/// simulated, so the activation transactions are not built for the new key.
+	pub estimated_key_activations: HashMap<ForeignChain, RpcKeyActivation>,
+}
+
+/// How a vault would activate its new key. See [KeyActivationPreview].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum RpcKeyActivation {
+	/// The SCALE-encoded activation call that would be broadcast.
+	Broadcast {
+		call: sp_core::Bytes,
+	},
+	NotRequired,
+	AwaitingGovernanceActivation,
+	Failed,
+}
+
+impl From<KeyActivationPreview> for RpcKeyActivation {
+	fn from(activation: KeyActivationPreview) -> Self {
+		match activation {
+			KeyActivationPreview::Broadcast { call } => Self::Broadcast { call: call.into() },
+			KeyActivationPreview::NotRequired => Self::NotRequired,
+			KeyActivationPreview::AwaitingGovernanceActivation =>
+				Self::AwaitingGovernanceActivation,
+			KeyActivationPreview::Failed => Self::Failed,
+		}
+	}
+}
+
+impl From<RotationDryRun> for RpcRotationDryRun {
+	fn from(RotationDryRun { simulation, key_activations }: RotationDryRun) -> Self {
+		let failed_stage = simulation.failure.map(|failure| failure.stage()).or_else(|| {
+			key_activations
+				.iter()
+				.any(|(_, activation)| *activation == KeyActivationPreview::Failed)
+				.then_some(RotationStage::Activation)
+		});
+		Self {
+			new_epoch_index: simulation.new_epoch_index,
+			failed_stage,
+			failure: simulation.failure,
+			bond: simulation.bond.map(Into::into),
+			new_authorities: simulation.new_authorities,
+			sharing_participants: simulation.sharing_participants,
+			excluded: simulation.excluded,
+			keygen_attempts: simulation.keygen_attempts,
+			key_handover_attempts: simulation.key_handover_attempts,
+			estimated_key_activations: key_activations
+				.into_iter()
+				.map(|(chain, activation)| (chain, activation.into()))
+				.collect(),
+		}
+	}
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RpcAuthoritySetPreview {
+	pub projected_authorities: Vec<(state_chain_runtime::AccountId, NumberOrHex)>,
+	pub min_active_bid: Option<NumberOrHex>,
+	pub incoming: BTreeSet<state_chain_runtime::AccountId>,
+	pub outgoing: BTreeSet<state_chain_runtime::AccountId>,
+	pub excluded: BTreeMap<state_chain_runtime::AccountId, BidderExclusionReason>,
+	pub auction_error: Option<AuctionError>,
+}
+
+impl From<AuthoritySetPreview> for RpcAuthoritySetPreview {
+	fn from(preview: AuthoritySetPreview) -> Self {
+		Self {
+			projected_authorities: preview
+				.projected_authorities
+				.into_iter()
+				.map(|(id, bid)| (id, bid.into()))
+				.collect(),
+			min_active_bid: preview.min_active_bid.map(Into::into),
+			incoming: preview.incoming,
+			outgoing: preview.outgoing,
+			excluded: preview.excluded,
+			auction_error: preview.auction_error,
+		}
+	}
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "status")]
+pub enum RpcGovernanceProposalStatus {
+	AwaitingApproval { expiry_time: u64 },
+	Timelocked { unlocks_at: u64 },
+	AwaitingDispatch,
+}
+
+impl From<ProposalStatus> for RpcGovernanceProposalStatus {
+	fn from(status: ProposalStatus) -> Self {
+		match status {
+			ProposalStatus::AwaitingApproval { expiry_time } =>
+				Self::AwaitingApproval { expiry_time },
+			ProposalStatus::Timelocked { unlocks_at } => Self::Timelocked { unlocks_at },
+			ProposalStatus::AwaitingDispatch => Self::AwaitingDispatch,
+		}
+	}
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RpcGovernanceProposal {
+	pub proposal_id: ProposalId,
+	#[serde(flatten)]
+	pub status: RpcGovernanceProposalStatus,
+	pub pallet: Option<String>,
+	pub call_name: Option<String>,
+	/// The arguments of the decoded call, e.g. `{ accounts: [..] }`.
+	pub arguments: Option<String>,
+	/// The decoded call including its arguments, or the hex-encoded call if it can't be decoded.
+	pub call: String,
+	pub approved: BTreeSet<state_chain_runtime::AccountId>,
+}
+
+impl From<ProposalPreview<state_chain_runtime::AccountId>> for RpcGovernanceProposal {
+	fn from(preview: ProposalPreview<state_chain_runtime::AccountId>) -> Self {
+		let (pallet, call_name) = preview
+			.call_name
+			.map(|name| {
+				(
+					Some(String::from_utf8_lossy(&name.pallet).into_owned()),
+					Some(String::from_utf8_lossy(&name.function).into_owned()),
+				)
+			})
+			.unwrap_or_default();
+		let (call, arguments) =
+			match state_chain_runtime::RuntimeCall::decode(&mut &preview.call[..]) {
+				Ok(call) => {
+					let call = format!("{call:?}");
+					let arguments = call_arguments(&call).to_owned();
+					(call, Some(arguments))
+				},
+				Err(_) => (format!("0x{}", hex::encode(&preview.call)), None),
+			};
+		Self {
+			proposal_id: preview.proposal_id,
+			status: preview.status.into(),
+			pallet,
+			call_name,
+			arguments,
+			call,
+			approved: preview.approved,
+		}
+	}
+}
+
+/// Extracts the arguments from the debug rendering of a runtime call, which has the form
+/// `Pallet(call_name { arg: value, .. })`. Calls without arguments render as `{}`.
+fn call_arguments(call: &str) -> &str {
+	call.split_once('(')
+		.and_then(|(_, pallet_call)| pallet_call.strip_suffix(')'))
+		.and_then(|pallet_call| pallet_call.split_once(' '))
+		.map(|(_, arguments)| arguments)
+		.unwrap_or("{}")
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "outcome")]
+pub enum RpcProposalOutcome {
+	Pending,
+	Executed { error: Option<String> },
+	DecodeOfCallFailed,
+	Expired,
+	Cancelled,
+}
+
+impl From<ProposalOutcome> for RpcProposalOutcome {
+	fn from(outcome: ProposalOutcome) -> Self {
+		match outcome {
+			ProposalOutcome::Pending => Self::Pending,
+			ProposalOutcome::Executed { result } =>
+				Self::Executed { error: result.err().map(|error| format!("{error:?}")) },
+			ProposalOutcome::DecodeOfCallFailed => Self::DecodeOfCallFailed,
+			ProposalOutcome::Expired => Self::Expired,
+			ProposalOutcome::Cancelled => Self::Cancelled,
+		}
+	}
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RpcProposalRecord {
+	pub proposal_id: ProposalId,
+	pub proposer: state_chain_runtime::AccountId,
+	/// Hex-encoded blake2-256 hash of the encoded call.
+	pub call_hash: String,
+	pub pallet: String,
+	pub call_name: String,
+	pub proposed_at: state_chain_runtime::BlockNumber,
+	pub approvals: Vec<(state_chain_runtime::AccountId, state_chain_runtime::BlockNumber)>,
+	#[serde(flatten)]
+	pub outcome: RpcProposalOutcome,
+	pub resolved_at: Option<state_chain_runtime::BlockNumber>,
+}
+
+impl
+	From<(
+		ProposalId,
+		ProposalRecord<state_chain_runtime::AccountId, state_chain_runtime::BlockNumber>,
+	)> for RpcProposalRecord
+{
+	fn from(
+		(proposal_id, record): (
+			ProposalId,
+			ProposalRecord<state_chain_runtime::AccountId, state_chain_runtime::BlockNumber>,
+		),
+	) -> Self {
+		Self {
+			proposal_id,
+			proposer: record.proposer,
+			call_hash: format!("0x{}", hex::encode(record.call_hash)),
+			pallet: String::from_utf8_lossy(&record.call_name.pallet).into_owned(),
+			call_name: String::from_utf8_lossy(&record.call_name.function).into_owned(),
+			proposed_at: record.proposed_at,
+			approvals: record.approvals,
+			outcome: record.outcome.into(),
+			resolved_at: record.resolved_at,
+		}
+	}
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RpcFlipBurn {
+	pub burned_at: state_chain_runtime::BlockNumber,
+	pub egress_id: EgressId,
+	pub supply_update_broadcast_id: BroadcastId,
+}
+
+impl From<FlipBurn<state_chain_runtime::BlockNumber>> for RpcFlipBurn {
+	fn from(burn: FlipBurn<state_chain_runtime::BlockNumber>) -> Self {
+		Self {
+			burned_at: burn.burned_at,
+			egress_id: burn.egress_id,
+			supply_update_broadcast_id: burn.supply_update_broadcast_id,
+		}
+	}
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RpcFlipBurnRecord {
+	pub bought_at: state_chain_runtime::BlockNumber,
+	pub usdc_in: NumberOrHex,
+	pub flip_out: NumberOrHex,
+	pub burn: Option<RpcFlipBurn>,
+}
+
+impl From<FlipBurnRecord<state_chain_runtime::BlockNumber>> for RpcFlipBurnRecord {
+	fn from(record: FlipBurnRecord<state_chain_runtime::BlockNumber>) -> Self {
+		Self {
+			bought_at: record.bought_at,
+			usdc_in: record.usdc_in.into(),
+			flip_out: record.flip_out.into(),
+			burn: record.burn.map(Into::into),
+		}
+	}
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct RpcSwapOutput {
 	// Intermediary amount, if there's any
@@ -325,6 +683,17 @@ pub trait CustomApi {
 		&self,
 		at: Option<state_chain_runtime::Hash>,
 	) -> RpcResult<(NumberOrHex, NumberOrHex)>;
+	#[method(name = "projected_flip_supply")]
+	fn cf_projected_flip_supply(
+		&self,
+		epochs: u32,
+		at: Option<state_chain_runtime::Hash>,
+	) -> RpcResult<Vec<(u32, NumberOrHex)>>;
+	#[method(name = "flip_burn_history")]
+	fn cf_flip_burn_history(
+		&self,
+		at: Option<state_chain_runtime::Hash>,
+	) -> RpcResult<Vec<RpcFlipBurnRecord>>;
 	#[method(name = "accounts")]
 	fn cf_accounts(
 		&self,
@@ -349,6 +718,24 @@ pub trait CustomApi {
 	) -> RpcResult<Vec<(Offence, RpcPenalty)>>;
 	#[method(name = "suspensions")]
 	fn cf_suspensions(&self, at: Option<state_chain_runtime::Hash>) -> RpcResult<RpcSuspensions>;
+	#[method(name = "penalty_history")]
+	fn cf_penalty_history(
+		&self,
+		account_id: state_chain_runtime::AccountId,
+		at: Option<state_chain_runtime::Hash>,
+	) -> RpcResult<Vec<RpcPenaltyRecord>>;
+	#[method(name = "validator_liveness")]
+	fn cf_validator_liveness(
+		&self,
+		account_id: state_chain_runtime::AccountId,
+		at: Option<state_chain_runtime::Hash>,
+	) -> RpcResult<RpcValidatorLiveness>;
+	#[method(name = "redemption_queue_position")]
+	fn cf_redemption_queue_position(
+		&self,
+		account_id: state_chain_runtime::AccountId,
+		at: Option<state_chain_runtime::Hash>,
+	) -> RpcResult<Option<RpcRedemptionQueuePosition>>;
 	#[method(name = "generate_gov_key_call_hash")]
 	fn cf_generate_gov_key_call_hash(
 		&self,
@@ -447,6 +834,27 @@ pub trait CustomApi {
 	fn cf_current_compatibility_version(&self) -> RpcResult<SemVer>;
 	#[method(name = "min_swap_amount")]
 	fn cf_min_swap_amount(&self, asset: RpcAsset) -> RpcResult<AssetAmount>;
+	#[method(name = "rotation_dry_run")]
+	fn cf_rotation_dry_run(
+		&self,
+		offline: Option<Vec<state_chain_runtime::AccountId>>,
+		at: Option<state_chain_runtime::Hash>,
+	) -> RpcResult<RpcRotationDryRun>;
+	#[method(name = "authority_set_preview")]
+	fn cf_authority_set_preview(
+		&self,
+		at: Option<state_chain_runtime::Hash>,
+	) -> RpcResult<RpcAuthoritySetPreview>;
+	#[method(name = "governance_proposals")]
+	fn cf_governance_proposals(
+		&self,
+		at: Option<state_chain_runtime::Hash>,
+	) -> RpcResult<Vec<RpcGovernanceProposal>>;
+	#[method(name = "governance_audit_log")]
+	fn cf_governance_audit_log(
+		&self,
+		at: Option<state_chain_runtime::Hash>,
+	) -> RpcResult<Vec<RpcProposalRecord>>;
 	#[subscription(name = "subscribe_pool_price", item = Price)]
 	fn cf_subscribe_pool_price(&self, from_asset: RpcAsset, to_asset: RpcAsset);
 
@@ -612,6 +1020,29 @@ where
 			.map_err(to_rpc_error)
 			.map(|(issuance, offchain)| (issuance.into(), offchain.into()))
 	}
+	fn cf_projected_flip_supply(
+		&self,
+		epochs: u32,
+		at: Option<<B as BlockT>::Hash>,
+	) -> RpcResult<Vec<(u32, NumberOrHex)>> {
+		self.client
+			.runtime_api()
+			.cf_projected_flip_supply(self.unwrap_or_best(at), epochs)
+			.map_err(to_rpc_error)
+			.map(|projection| {
+				projection.into_iter().map(|(epoch, supply)| (epoch, supply.into())).collect()
+			})
+	}
+	fn cf_flip_burn_history(
+		&self,
+		at: Option<<B as BlockT>::Hash>,
+	) -> RpcResult<Vec<RpcFlipBurnRecord>> {
+		self.client
+			.runtime_api()
+			.cf_flip_burn_history(self.unwrap_or_best(at))
+			.map_err(to_rpc_error)
+			.map(|history| history.into_iter().map(RpcFlipBurnRecord::from).collect())
+	}
 	fn cf_accounts(
 		&self,
 		at: Option<<B as BlockT>::Hash>,
@@ -695,6 +1126,7 @@ where
 			bound_redeem_address: account_info.bound_redeem_address,
 			apy_bp: account_info.apy_bp,
 			restricted_balances: account_info.restricted_balances,
+			restrictions: account_info.restrictions,
 		})
 	}
 
@@ -726,6 +1158,55 @@ where
 			.map_err(to_rpc_error)
 	}
 
+	fn cf_penalty_history(
+		&self,
+		account_id: state_chain_runtime::AccountId,
+		at: Option<<B as BlockT>::Hash>,
+	) -> RpcResult<Vec<RpcPenaltyRecord>> {
+		Ok(self
+			.client
+			.runtime_api()
+			.cf_penalty_history(self.unwrap_or_best(at), &account_id)
+			.map_err(to_rpc_error)?
+			.into_iter()
+			.map(|record| RpcPenaltyRecord {
+				offence: record.offence,
+				reported_at: record.reported_at,
+				reputation_points: record.reputation_points,
+				suspended_until: record.suspended_until,
+				reported_with: record.reported_with,
+				time_slot: record
+					.time_slot
+					.map(|time_slot| format!("0x{}", hex::encode(time_slot))),
+				pardoned: record.pardoned,
+			})
+			.collect())
+	}
+
+	fn cf_validator_liveness(
+		&self,
+		account_id: state_chain_runtime::AccountId,
+		at: Option<<B as BlockT>::Hash>,
+	) -> RpcResult<RpcValidatorLiveness> {
+		self.client
+			.runtime_api()
+			.cf_validator_liveness(self.unwrap_or_best(at), &account_id)
+			.map_err(to_rpc_error)
+			.map(Into::into)
+	}
+
+	fn cf_redemption_queue_position(
+		&self,
+		account_id: state_chain_runtime::AccountId,
+		at: Option<<B as BlockT>::Hash>,
+	) -> RpcResult<Option<RpcRedemptionQueuePosition>> {
+		self.client
+			.runtime_api()
+			.cf_redemption_queue_position(self.unwrap_or_best(at), &account_id)
+			.map_err(to_rpc_error)
+			.map(|position| position.map(Into::into))
+	}
+
 	fn cf_generate_gov_key_call_hash(
 		&self,
 		call: Vec<u8>,
@@ -1001,6 +1482,54 @@ where
 			.map_err(to_rpc_error)
 	}
 
+	fn cf_rotation_dry_run(
+		&self,
+		offline: Option<Vec<state_chain_runtime::AccountId>>,
+		at: Option<state_chain_runtime::Hash>,
+	) -> RpcResult<RpcRotationDryRun> {
+		self.client
+			.runtime_api()
+			.cf_rotation_dry_run(
+				self.unwrap_or_best(at),
+				offline.unwrap_or_default().into_iter().collect(),
+			)
+			.map_err(to_rpc_error)
+			.map(RpcRotationDryRun::from)
+	}
+
+	fn cf_authority_set_preview(
+		&self,
+		at: Option<state_chain_runtime::Hash>,
+	) -> RpcResult<RpcAuthoritySetPreview> {
+		self.client
+			.runtime_api()
+			.cf_authority_set_preview(self.unwrap_or_best(at))
+			.map_err(to_rpc_error)
+			.map(RpcAuthoritySetPreview::from)
+	}
+
+	fn cf_governance_proposals(
+		&self,
+		at: Option<state_chain_runtime::Hash>,
+	) -> RpcResult<Vec<RpcGovernanceProposal>> {
+		self.client
+			.runtime_api()
+			.cf_governance_proposals(self.unwrap_or_best(at))
+			.map_err(to_rpc_error)
+			.map(|proposals| proposals.into_iter().map(RpcGovernanceProposal::from).collect())
+	}
+
+	fn cf_governance_audit_log(
+		&self,
+		at: Option<state_chain_runtime::Hash>,
+	) -> RpcResult<Vec<RpcProposalRecord>> {
+		self.client
+			.runtime_api()
+			.cf_governance_audit_log(self.unwrap_or_best(at))
+			.map_err(to_rpc_error)
+			.map(|records| records.into_iter().map(RpcProposalRecord::from).collect())
+	}
+
 	fn cf_subscribe_pool_price(
 		&self,
 		sink: SubscriptionSink,
@@ -1158,6 +1687,34 @@ mod test {
 		stale the review and get a new review from someone on product.
 	*/
 
+	#[test]
+	fn governance_proposal_arguments_are_decoded() {
+		use sp_runtime::codec::Encode;
+
+		let call = state_chain_runtime::RuntimeCall::Governance(
+			pallet_cf_governance::Call::cancel_proposal { proposal_id: 7 },
+		);
+		let proposal = RpcGovernanceProposal::from(ProposalPreview {
+			proposal_id: 1,
+			status: ProposalStatus::AwaitingDispatch,
+			call_name: None,
+			call: call.encode(),
+			approved: Default::default(),
+		});
+		assert_eq!(proposal.arguments.as_deref(), Some("{ proposal_id: 7 }"));
+		assert_eq!(proposal.call, format!("{call:?}"));
+
+		let proposal = RpcGovernanceProposal::from(ProposalPreview {
+			proposal_id: 2,
+			status: ProposalStatus::AwaitingDispatch,
+			call_name: None,
+			call: vec![0xff],
+			approved: Default::default(),
+		});
+		assert_eq!(proposal.arguments, None);
+		assert_eq!(proposal.call, "0xff");
+	}
+
 	#[test]
 	fn test_no_account_serialization() {
 		insta::assert_display_snapshot!(
@@ -1217,6 +1774,7 @@ mod test {
 				H160::from([1; 20]),
 				FLIPPERINOS_PER_FLIP,
 			)]),
+			restrictions: Default::default(),
 		});
 
 		insta::assert_display_snapshot!(serde_json::to_value(validator).unwrap());
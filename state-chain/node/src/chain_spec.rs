@@ -13,7 +13,7 @@ use cf_chains::{
 	eth::EthereumTrackedData,
 	Bitcoin, Ethereum, Polkadot,
 };
-use common::FLIPPERINOS_PER_FLIP;
+use common::{FLIPPERINOS_PER_FLIP, MAX_KEYGEN_ATTEMPTS};
 use frame_benchmarking::sp_std::collections::btree_set::BTreeSet;
 pub use sc_service::{ChainType, Properties};
 use sc_telemetry::serde_json::json;
@@ -606,6 +606,7 @@ fn testnet_genesis(
 			deployment_block: ethereum_deployment_block,
 			keygen_response_timeout: keygen_ceremony_timeout_blocks,
 			amount_to_slash: FLIPPERINOS_PER_FLIP,
+			max_keygen_attempts: MAX_KEYGEN_ATTEMPTS,
 		},
 
 		polkadot_vault: PolkadotVaultConfig {
@@ -613,12 +614,14 @@ fn testnet_genesis(
 			deployment_block: 0,
 			keygen_response_timeout: keygen_ceremony_timeout_blocks,
 			amount_to_slash: FLIPPERINOS_PER_FLIP,
+			max_keygen_attempts: MAX_KEYGEN_ATTEMPTS,
 		},
 		bitcoin_vault: BitcoinVaultConfig {
 			vault_key: None,
 			deployment_block: 0,
 			keygen_response_timeout: keygen_ceremony_timeout_blocks,
 			amount_to_slash: FLIPPERINOS_PER_FLIP,
+			max_keygen_attempts: MAX_KEYGEN_ATTEMPTS,
 		},
 		ethereum_threshold_signer: EthereumThresholdSignerConfig {
 			threshold_signature_response_timeout: threshold_signature_ceremony_timeout_blocks,
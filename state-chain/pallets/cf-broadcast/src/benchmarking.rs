@@ -157,4 +157,16 @@ benchmarks_instance_pallet! {
 		// We expect the unwrap to error if the extrinsic didn't fire an event - if an event has been emitted we reached the end of the extrinsic
 		let _ = frame_system::Pallet::<T>::events().pop().expect("No event has been emitted from the transaction_succeeded extrinsic").event;
 	}
+	update_slashing_settings {
+		let settings = BroadcastSlashingSettings {
+			failure_threshold: 3,
+			blocks_slashed_per_failure: 100u32.into(),
+			max_fee_percentage: Some(300),
+			blocks_slashed_for_excessive_fee: 1_000u32.into(),
+		};
+		let call = Call::<T, I>::update_slashing_settings { settings };
+	} : { call.dispatch_bypass_filter(T::EnsureGovernance::try_successful_origin().unwrap())? }
+	verify {
+		assert_eq!(SlashingSettings::<T, I>::get(), settings);
+	}
 }
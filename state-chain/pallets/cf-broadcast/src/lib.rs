@@ -8,7 +8,7 @@ mod tests;
 
 pub mod migrations;
 pub mod weights;
-use cf_primitives::{BroadcastId, ThresholdSignatureRequestId};
+use cf_primitives::{BroadcastId, EpochIndex, ThresholdSignatureRequestId};
 use cf_traits::{GetBlockHeight, SafeMode};
 use frame_support::RuntimeDebug;
 use sp_std::marker;
@@ -33,13 +33,14 @@ use cf_chains::{
 };
 use cf_traits::{
 	offence_reporting::OffenceReporter, BroadcastNomination, Broadcaster, Chainflip, EpochInfo,
-	EpochKey, OnBroadcastReady, ThresholdSigner,
+	EpochKey, EpochTransitionHandler, OnBroadcastReady, Slashing, ThresholdSigner,
+	TransactionFeeAudit,
 };
 use codec::{Decode, Encode, MaxEncodedLen};
 use frame_support::{
 	dispatch::DispatchResultWithPostInfo,
 	pallet_prelude::DispatchResult,
-	sp_runtime::traits::Saturating,
+	sp_runtime::traits::{Saturating, Zero},
 	traits::{Get, StorageVersion, UnfilteredDispatchable},
 	Twox64Concat,
 };
@@ -83,6 +84,27 @@ pub enum PalletOffence {
 	FailedToBroadcastTransaction,
 }
 
+/// Governance-controlled thresholds for slashing authorities that misbehave when broadcasting.
+///
+/// Slash amounts are expressed as an equivalent number of blocks, and are converted into FLIP
+/// using the network's slashing rate.
+#[derive(
+	Copy, Clone, RuntimeDebug, Default, PartialEq, Eq, Encode, Decode, TypeInfo, MaxEncodedLen,
+)]
+pub struct BroadcastSlashingSettings<BlockNumber> {
+	/// The number of consecutive broadcast failures that are tolerated before an authority is
+	/// slashed.
+	pub failure_threshold: u32,
+	/// The slash applied for each failure beyond the threshold. The slash grows with every
+	/// additional failure until the authority broadcasts successfully.
+	pub blocks_slashed_per_failure: BlockNumber,
+	/// The fee paid, as a percentage of the fee implied by chain tracking, above which we consider
+	/// the signer to be griefing the vault. `None` disables the check.
+	pub max_fee_percentage: Option<u32>,
+	/// The slash applied when a transaction is broadcast with an excessive fee.
+	pub blocks_slashed_for_excessive_fee: BlockNumber,
+}
+
 pub const PALLET_VERSION: StorageVersion = StorageVersion::new(1);
 
 #[frame_support::pallet]
@@ -197,6 +219,13 @@ pub mod pallet {
 			Offence = Self::Offence,
 		>;
 
+		/// For slashing authorities that repeatedly fail to broadcast, or that broadcast with an
+		/// excessive fee.
+		type Slasher: Slashing<AccountId = Self::ValidatorId, BlockNumber = BlockNumberFor<Self>>;
+
+		/// Compares the fees paid for broadcasts against the tracked chain fees.
+		type TransactionFeeAudit: TransactionFeeAudit<Self::TargetChain>;
+
 		/// Ensure that only threshold signature consensus can trigger a broadcast.
 		type EnsureThresholdSigned: EnsureOrigin<<Self as frame_system::Config>::RuntimeOrigin>;
 
@@ -305,6 +334,17 @@ pub mod pallet {
 	pub type TransactionFeeDeficit<T: Config<I>, I: 'static = ()> =
 		StorageMap<_, Twox64Concat, SignerIdFor<T, I>, ChainAmountFor<T, I>, ValueQuery>;
 
+	/// The thresholds at which authorities are slashed for bad broadcasting behaviour.
+	#[pallet::storage]
+	#[pallet::getter(fn slashing_settings)]
+	pub type SlashingSettings<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, BroadcastSlashingSettings<BlockNumberFor<T>>, ValueQuery>;
+
+	/// The number of broadcasts each authority has failed since their last successful broadcast.
+	#[pallet::storage]
+	pub type ConsecutiveBroadcastFailures<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Twox64Concat, T::ValidatorId, u32, ValueQuery>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config<I>, I: 'static = ()> {
@@ -339,6 +379,14 @@ pub mod pallet {
 		},
 		/// The fee paid for broadcasting a transaction has been refused.
 		TransactionFeeDeficitRefused { beneficiary: SignerIdFor<T, I> },
+		/// The broadcast slashing settings have been updated.
+		SlashingSettingsUpdated { settings: BroadcastSlashingSettings<BlockNumberFor<T>> },
+		/// A transaction was broadcast with a fee far above the tracked chain fee.
+		ExcessiveTransactionFee {
+			broadcast_id: BroadcastId,
+			nominee: T::ValidatorId,
+			fee_paid_percentage: u32,
+		},
 	}
 
 	#[pallet::error]
@@ -459,10 +507,12 @@ pub mod pallet {
 					.expect("We must have at least one authority")
 			{
 				// We want to keep the broadcast details, but we don't need the list of failed
-				// broadcasters any more.
-				FailedBroadcasters::<T, I>::remove(
+				// broadcasters any more once they have been slashed.
+				if let Some(failed_signers) = FailedBroadcasters::<T, I>::take(
 					signing_attempt.broadcast_attempt.broadcast_attempt_id.broadcast_id,
-				);
+				) {
+					Self::slash_failed_broadcasters(&failed_signers);
+				}
 				Self::deposit_event(Event::<T, I>::BroadcastAborted {
 					broadcast_id: signing_attempt
 						.broadcast_attempt
@@ -552,16 +602,20 @@ pub mod pallet {
 				TransactionOutIdToBroadcastId::<T, I>::take(&tx_out_id)
 					.ok_or(Error::<T, I>::InvalidPayload)?;
 
+			let latest_signing_attempt = AwaitingBroadcast::<T, I>::get(BroadcastAttemptId {
+				broadcast_id,
+				attempt_count: BroadcastAttemptCount::<T, I>::get(broadcast_id),
+			});
+			let fee_paid_percentage = T::TransactionFeeAudit::fee_paid_percentage(&tx_fee);
+
 			if let Some(expected_tx_metadata) = TransactionMetadata::<T, I>::take(broadcast_id) {
 				if tx_metadata.verify_metadata(&expected_tx_metadata) {
-					let to_refund = AwaitingBroadcast::<T, I>::get(BroadcastAttemptId {
-						broadcast_id,
-						attempt_count: BroadcastAttemptCount::<T, I>::get(broadcast_id),
-					})
-					.ok_or(Error::<T, I>::InvalidBroadcastAttemptId)?
-					.broadcast_attempt
-					.transaction_payload
-					.return_fee_refund(tx_fee);
+					let to_refund = latest_signing_attempt
+						.as_ref()
+						.ok_or(Error::<T, I>::InvalidBroadcastAttemptId)?
+						.broadcast_attempt
+						.transaction_payload
+						.return_fee_refund(tx_fee);
 
 					TransactionFeeDeficit::<T, I>::mutate(signer_id.clone(), |fee_deficit| {
 						*fee_deficit = fee_deficit.saturating_add(to_refund);
@@ -608,6 +662,14 @@ pub mod pallet {
 					PalletOffence::FailedToBroadcastTransaction,
 					&failed_signers,
 				);
+				Self::slash_failed_broadcasters(&failed_signers);
+			}
+
+			if let Some(TransactionSigningAttempt { nominee, .. }) = latest_signing_attempt {
+				ConsecutiveBroadcastFailures::<T, I>::remove(&nominee);
+				if let Some(fee_paid_percentage) = fee_paid_percentage {
+					Self::check_for_excessive_fee(broadcast_id, nominee, fee_paid_percentage);
+				}
 			}
 
 			Self::clean_up_broadcast_storage(broadcast_id);
@@ -633,6 +695,29 @@ pub mod pallet {
 
 			Ok(())
 		}
+
+		/// Sets the thresholds at which authorities are slashed for bad broadcasting behaviour.
+		///
+		/// ## Events
+		///
+		/// - [SlashingSettingsUpdated](Event::SlashingSettingsUpdated)
+		///
+		/// ## Errors
+		///
+		/// - [BadOrigin](frame_support::error::BadOrigin)
+		#[pallet::call_index(4)]
+		#[pallet::weight(T::DbWeight::get().writes(1))]
+		pub fn update_slashing_settings(
+			origin: OriginFor<T>,
+			settings: BroadcastSlashingSettings<BlockNumberFor<T>>,
+		) -> DispatchResult {
+			T::EnsureGovernance::ensure_origin(origin)?;
+
+			SlashingSettings::<T, I>::put(settings);
+			Self::deposit_event(Event::<T, I>::SlashingSettingsUpdated { settings });
+
+			Ok(())
+		}
 	}
 }
 
@@ -848,6 +933,55 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		}
 	}
 
+	/// Records a failure against each of the failed broadcasters. Authorities that have failed more
+	/// consecutive broadcasts than the governance threshold are slashed, with the slash increasing
+	/// for every further failure. Does nothing while slashing is disabled.
+	fn slash_failed_broadcasters(failed_broadcasters: &[T::ValidatorId]) {
+		let settings = SlashingSettings::<T, I>::get();
+		if settings.blocks_slashed_per_failure.is_zero() {
+			return
+		}
+		for validator_id in failed_broadcasters {
+			let failures = ConsecutiveBroadcastFailures::<T, I>::mutate(validator_id, |failures| {
+				*failures = failures.saturating_add(1);
+				*failures
+			});
+			if failures > settings.failure_threshold {
+				T::Slasher::slash(
+					validator_id,
+					settings
+						.blocks_slashed_per_failure
+						.saturating_mul((failures - settings.failure_threshold).into()),
+				);
+			}
+		}
+	}
+
+	/// Slashes the nominee if the fee they paid exceeds the governance threshold.
+	fn check_for_excessive_fee(
+		broadcast_id: BroadcastId,
+		nominee: T::ValidatorId,
+		fee_paid_percentage: u32,
+	) {
+		let settings = SlashingSettings::<T, I>::get();
+		if settings
+			.max_fee_percentage
+			.is_some_and(|max_fee_percentage| fee_paid_percentage > max_fee_percentage)
+		{
+			log::warn!(
+				"Broadcast {} paid {}% of the tracked fee. Slashing nominee.",
+				broadcast_id,
+				fee_paid_percentage
+			);
+			T::Slasher::slash(&nominee, settings.blocks_slashed_for_excessive_fee);
+			Self::deposit_event(Event::<T, I>::ExcessiveTransactionFee {
+				broadcast_id,
+				nominee,
+				fee_paid_percentage,
+			});
+		}
+	}
+
 	fn schedule_for_retry(broadcast_attempt: &BroadcastAttempt<T, I>) {
 		BroadcastRetryQueue::<T, I>::append(broadcast_attempt);
 		Self::deposit_event(Event::<T, I>::BroadcastRetryScheduled {
@@ -856,6 +990,20 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 	}
 }
 
+impl<T: Config<I>, I: 'static> EpochTransitionHandler for Pallet<T, I> {
+	/// Removes the failure counts of the validators that are no longer authorities, as they are no
+	/// longer nominated to broadcast. This stops the storage from growing indefinitely.
+	fn on_expired_epoch(_expired: EpochIndex) {
+		let current_authorities = T::EpochInfo::current_authorities();
+		for validator_id in ConsecutiveBroadcastFailures::<T, I>::iter_keys()
+			.filter(|validator_id| !current_authorities.contains(validator_id))
+			.collect::<Vec<_>>()
+		{
+			ConsecutiveBroadcastFailures::<T, I>::remove(validator_id);
+		}
+	}
+}
+
 impl<T: Config<I>, I: 'static> Broadcaster<T::TargetChain> for Pallet<T, I> {
 	type ApiCall = T::ApiCall;
 	type Callback = <T as Config<I>>::BroadcastCallable;
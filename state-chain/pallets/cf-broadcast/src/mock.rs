@@ -17,7 +17,7 @@ use cf_traits::{
 		block_height_provider::BlockHeightProvider, signer_nomination::MockNominator,
 		threshold_signer::MockThresholdSigner,
 	},
-	AccountRoleRegistry, EpochKey, KeyState, OnBroadcastReady,
+	AccountRoleRegistry, EpochKey, KeyState, OnBroadcastReady, Slashing, TransactionFeeAudit,
 };
 use codec::{Decode, Encode};
 use frame_support::{parameter_types, traits::UnfilteredDispatchable};
@@ -130,6 +130,54 @@ impl MockKeyProvider {
 	}
 }
 
+thread_local! {
+	pub static SLASHES: RefCell<Vec<(u64, BlockNumberFor<Test>)>> = RefCell::new(vec![]);
+	pub static FEE_PAID_PERCENTAGE: RefCell<Option<u32>> = RefCell::new(None);
+}
+
+pub struct MockSlasher;
+
+impl MockSlasher {
+	pub fn slashes() -> Vec<(u64, BlockNumberFor<Test>)> {
+		SLASHES.with(|slashes| slashes.borrow().clone())
+	}
+}
+
+impl Slashing for MockSlasher {
+	type AccountId = u64;
+	type BlockNumber = BlockNumberFor<Test>;
+	type Balance = u128;
+
+	fn slash(validator_id: &Self::AccountId, blocks: Self::BlockNumber) {
+		SLASHES.with(|slashes| slashes.borrow_mut().push((*validator_id, blocks)));
+	}
+
+	fn slash_balance(_account_id: &Self::AccountId, _amount: cf_primitives::FlipBalance) {
+		unimplemented!()
+	}
+
+	fn calculate_slash_amount(
+		_account_id: &Self::AccountId,
+		_blocks: Self::BlockNumber,
+	) -> Self::Balance {
+		unimplemented!()
+	}
+}
+
+pub struct MockTransactionFeeAudit;
+
+impl MockTransactionFeeAudit {
+	pub fn set_fee_paid_percentage(percentage: Option<u32>) {
+		FEE_PAID_PERCENTAGE.with(|cell| *cell.borrow_mut() = percentage);
+	}
+}
+
+impl TransactionFeeAudit<MockEthereum> for MockTransactionFeeAudit {
+	fn fee_paid_percentage(_fee_paid: &<MockEthereum as Chain>::TransactionFee) -> Option<u32> {
+		FEE_PAID_PERCENTAGE.with(|cell| *cell.borrow())
+	}
+}
+
 pub struct MockBroadcastReadyProvider;
 impl OnBroadcastReady<MockEthereum> for MockBroadcastReadyProvider {
 	type ApiCall = MockApiCall<MockEthereumChainCrypto>;
@@ -147,6 +195,8 @@ impl pallet_cf_broadcast::Config<Instance1> for Test {
 	type ThresholdSigner = MockThresholdSigner<MockEthereumChainCrypto, RuntimeCall>;
 	type BroadcastSignerNomination = MockNominator;
 	type OffenceReporter = MockOffenceReporter;
+	type Slasher = MockSlasher;
+	type TransactionFeeAudit = MockTransactionFeeAudit;
 	type EnsureThresholdSigned = NeverFailingOriginCheck<Self>;
 	type BroadcastTimeout = BroadcastTimeout;
 	type WeightInfo = ();
@@ -2,9 +2,10 @@
 
 use crate::{
 	mock::*, AwaitingBroadcast, BroadcastAttemptCount, BroadcastAttemptId, BroadcastId,
-	BroadcastRetryQueue, Error, Event as BroadcastEvent, FailedBroadcasters, Instance1,
-	PalletOffence, RequestCallbacks, ThresholdSignatureData, Timeouts, TransactionFeeDeficit,
-	TransactionMetadata, TransactionOutIdToBroadcastId, WeightInfo,
+	BroadcastRetryQueue, BroadcastSlashingSettings, ConsecutiveBroadcastFailures, Error,
+	Event as BroadcastEvent, FailedBroadcasters, Instance1, PalletOffence, RequestCallbacks,
+	ThresholdSignatureData, Timeouts, TransactionFeeDeficit, TransactionMetadata,
+	TransactionOutIdToBroadcastId, WeightInfo,
 };
 use cf_chains::{
 	evm::SchnorrVerificationComponents,
@@ -17,10 +18,11 @@ use cf_chains::{
 };
 use cf_traits::{
 	mocks::{signer_nomination::MockNominator, threshold_signer::MockThresholdSigner},
-	AsyncResult, Chainflip, EpochInfo, SetSafeMode, ThresholdSigner,
+	AsyncResult, Chainflip, EpochInfo, EpochTransitionHandler, SetSafeMode, ThresholdSigner,
 };
 use frame_support::{assert_noop, assert_ok, dispatch::Weight, traits::Hooks};
 use frame_system::RawOrigin;
+use sp_std::collections::{btree_map::BTreeMap, btree_set::BTreeSet};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 enum Scenario {
@@ -579,3 +581,190 @@ fn transaction_succeeded_results_in_refund_refuse_for_signer() {
 		);
 	});
 }
+
+fn set_slashing_settings(settings: BroadcastSlashingSettings<u64>) {
+	assert_ok!(Broadcaster::update_slashing_settings(RuntimeOrigin::root(), settings));
+	assert_eq!(
+		System::events().pop().expect("an event").event,
+		RuntimeEvent::Broadcaster(crate::Event::SlashingSettingsUpdated { settings })
+	);
+	System::reset_events();
+}
+
+#[test]
+fn failed_broadcasters_are_slashed_when_broadcast_succeeds() {
+	new_test_ext().execute_with(|| {
+		set_slashing_settings(BroadcastSlashingSettings {
+			failure_threshold: 0,
+			blocks_slashed_per_failure: 10,
+			..Default::default()
+		});
+		start_mock_broadcast_tx_out_id(MOCK_TRANSACTION_OUT_ID);
+
+		let mut failed_authorities = vec![];
+		for _ in 0..MockEpochInfo::current_authority_count() - 1 {
+			MockCfe::respond(Scenario::SigningFailure);
+			failed_authorities.push(MockNominator::get_last_nominee().unwrap());
+			Broadcaster::on_idle(0, LARGE_EXCESS_WEIGHT);
+		}
+		assert!(
+			MockSlasher::slashes().is_empty(),
+			"Nobody is slashed until the broadcast succeeds."
+		);
+
+		let successful_nominee = MockNominator::get_last_nominee().unwrap();
+		ConsecutiveBroadcastFailures::<Test, Instance1>::insert(successful_nominee, 5);
+
+		assert_ok!(Broadcaster::transaction_succeeded(
+			RuntimeOrigin::root(),
+			MOCK_TRANSACTION_OUT_ID,
+			Default::default(),
+			ETH_TX_FEE,
+			MOCK_TX_METADATA,
+		));
+
+		assert_eq!(
+			MockSlasher::slashes(),
+			failed_authorities.iter().map(|id| (*id, 10)).collect::<Vec<_>>()
+		);
+		for id in failed_authorities {
+			assert_eq!(ConsecutiveBroadcastFailures::<Test, Instance1>::get(id), 1);
+		}
+		assert_eq!(
+			ConsecutiveBroadcastFailures::<Test, Instance1>::get(successful_nominee),
+			0,
+			"A successful broadcast resets the failure count."
+		);
+	});
+}
+
+#[test]
+fn broadcast_failure_slashes_increase_beyond_threshold() {
+	const FAILING_BROADCASTER: u64 = 1;
+	new_test_ext().execute_with(|| {
+		set_slashing_settings(BroadcastSlashingSettings {
+			failure_threshold: 2,
+			blocks_slashed_per_failure: 10,
+			..Default::default()
+		});
+
+		for _ in 0..5 {
+			Broadcaster::slash_failed_broadcasters(&[FAILING_BROADCASTER]);
+		}
+
+		assert_eq!(
+			MockSlasher::slashes(),
+			vec![(FAILING_BROADCASTER, 10), (FAILING_BROADCASTER, 20), (FAILING_BROADCASTER, 30)]
+		);
+	});
+}
+
+#[test]
+fn broadcasting_with_excessive_fee_is_slashed() {
+	new_test_ext().execute_with(|| {
+		set_slashing_settings(BroadcastSlashingSettings {
+			max_fee_percentage: Some(200),
+			blocks_slashed_for_excessive_fee: 100,
+			..Default::default()
+		});
+
+		// Within the threshold: no slash.
+		MockTransactionFeeAudit::set_fee_paid_percentage(Some(200));
+		start_mock_broadcast_tx_out_id(MOCK_TRANSACTION_OUT_ID);
+		assert_ok!(Broadcaster::transaction_succeeded(
+			RuntimeOrigin::root(),
+			MOCK_TRANSACTION_OUT_ID,
+			Default::default(),
+			ETH_TX_FEE,
+			MOCK_TX_METADATA,
+		));
+		assert!(MockSlasher::slashes().is_empty());
+
+		// Above the threshold: the nominee is slashed.
+		MockTransactionFeeAudit::set_fee_paid_percentage(Some(201));
+		let broadcast_attempt_id = start_mock_broadcast_tx_out_id(MOCK_TRANSACTION_OUT_ID);
+		let nominee = MockNominator::get_last_nominee().unwrap();
+		assert_ok!(Broadcaster::transaction_succeeded(
+			RuntimeOrigin::root(),
+			MOCK_TRANSACTION_OUT_ID,
+			Default::default(),
+			ETH_TX_FEE,
+			MOCK_TX_METADATA,
+		));
+		assert_eq!(MockSlasher::slashes(), vec![(nominee, 100)]);
+		assert!(System::events().iter().any(|record| record.event ==
+			RuntimeEvent::Broadcaster(crate::Event::ExcessiveTransactionFee {
+				broadcast_id: broadcast_attempt_id.broadcast_id,
+				nominee,
+				fee_paid_percentage: 201,
+			})));
+	});
+}
+
+#[test]
+fn broadcast_slashing_is_disabled_by_default() {
+	new_test_ext().execute_with(|| {
+		MockTransactionFeeAudit::set_fee_paid_percentage(Some(u32::MAX));
+		start_mock_broadcast_tx_out_id(MOCK_TRANSACTION_OUT_ID);
+		MockCfe::respond(Scenario::SigningFailure);
+		Broadcaster::on_idle(0, LARGE_EXCESS_WEIGHT);
+
+		assert_ok!(Broadcaster::transaction_succeeded(
+			RuntimeOrigin::root(),
+			MOCK_TRANSACTION_OUT_ID,
+			Default::default(),
+			ETH_TX_FEE,
+			MOCK_TX_METADATA,
+		));
+
+		assert!(MockSlasher::slashes().is_empty());
+	});
+}
+
+#[test]
+fn failed_broadcasters_are_slashed_when_broadcast_is_aborted() {
+	new_test_ext().execute_with(|| {
+		set_slashing_settings(BroadcastSlashingSettings {
+			failure_threshold: 0,
+			blocks_slashed_per_failure: 10,
+			..Default::default()
+		});
+		let broadcast_attempt_id = start_mock_broadcast();
+
+		let mut failed_authorities = vec![];
+		for _ in 0..MockEpochInfo::current_authority_count() {
+			failed_authorities.push(MockNominator::get_last_nominee().unwrap());
+			MockCfe::respond(Scenario::SigningFailure);
+			Broadcaster::on_idle(0, LARGE_EXCESS_WEIGHT);
+		}
+
+		assert!(System::events().iter().any(|record| record.event ==
+			RuntimeEvent::Broadcaster(crate::Event::BroadcastAborted {
+				broadcast_id: broadcast_attempt_id.broadcast_id
+			})));
+		assert_eq!(
+			MockSlasher::slashes(),
+			failed_authorities.iter().map(|id| (*id, 10)).collect::<Vec<_>>()
+		);
+		assert!(
+			FailedBroadcasters::<Test, Instance1>::get(broadcast_attempt_id.broadcast_id).is_none()
+		);
+	});
+}
+
+#[test]
+fn failure_counts_of_former_authorities_are_removed_when_the_epoch_expires() {
+	new_test_ext().execute_with(|| {
+		MockEpochInfo::next_epoch(BTreeSet::from([1, 2]));
+		for id in [1, 2, 3] {
+			ConsecutiveBroadcastFailures::<Test, Instance1>::insert(id, 2);
+		}
+
+		<Broadcaster as EpochTransitionHandler>::on_expired_epoch(0);
+
+		assert_eq!(
+			ConsecutiveBroadcastFailures::<Test, Instance1>::iter().collect::<BTreeMap<_, _>>(),
+			BTreeMap::from([(1, 2), (2, 2)])
+		);
+	});
+}
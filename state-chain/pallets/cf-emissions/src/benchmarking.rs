@@ -69,6 +69,42 @@ benchmarks! {
 		 assert_eq!(supply_update_interval, (100_u32).into());
 	}
 
+	set_emission_schedule {
+		let b in 1 .. MAX_EMISSION_BREAKPOINTS;
+		let schedule: EmissionSchedule = (0..b)
+			.map(|i| EmissionBreakpoint {
+				epoch: i,
+				current_authority_inflation: INFLATION_RATE + i,
+				backup_node_inflation: INFLATION_RATE,
+			})
+			.collect::<Vec<_>>()
+			.try_into()
+			.unwrap();
+		let call = Call::<T>::set_emission_schedule { schedule: schedule.clone() };
+	}: {
+		let _ = call.dispatch_bypass_filter(T::EnsureGovernance::try_successful_origin().unwrap());
+	}
+	verify {
+		assert_eq!(EmissionScheduleBreakpoints::<T>::get(), schedule);
+	}
+	apply_emission_schedule {
+		let schedule: EmissionSchedule = (0..MAX_EMISSION_BREAKPOINTS)
+			.map(|i| EmissionBreakpoint {
+				epoch: i * 10,
+				current_authority_inflation: INFLATION_RATE + i,
+				backup_node_inflation: INFLATION_RATE + i,
+			})
+			.collect::<Vec<_>>()
+			.try_into()
+			.unwrap();
+		EmissionScheduleBreakpoints::<T>::put(schedule);
+	}: {
+		Pallet::<T>::apply_emission_schedule(MAX_EMISSION_BREAKPOINTS * 5 + 5);
+	}
+	verify {
+		assert_eq!(EmissionScheduleEpoch::<T>::get(), MAX_EMISSION_BREAKPOINTS * 5 + 5);
+	}
+
 	impl_benchmark_test_suite!(
 		Pallet,
 		crate::mock::new_test_ext(),
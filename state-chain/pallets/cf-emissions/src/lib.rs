@@ -4,11 +4,14 @@
 
 use cf_chains::{address::ForeignChainAddress, evm::api::EthEnvironmentProvider, UpdateFlipSupply};
 use cf_traits::{
-	impl_pallet_safe_mode, BlockEmissions, Broadcaster, EgressApi, FlipBurnInfo, Issuance,
-	RewardsDistribution,
+	impl_pallet_safe_mode, BlockEmissions, Broadcaster, EgressApi, EpochInfo, FlipBurnInfo,
+	Issuance, RewardsDistribution,
 };
 use codec::MaxEncodedLen;
-use frame_support::dispatch::Weight;
+use frame_support::{
+	dispatch::{DispatchResult, Weight},
+	ensure,
+};
 use frame_system::pallet_prelude::BlockNumberFor;
 pub use pallet::*;
 
@@ -25,13 +28,20 @@ use frame_support::{
 };
 use sp_arithmetic::traits::UniqueSaturatedFrom;
 
-use cf_primitives::{chains::AnyChain, Asset};
+use cf_primitives::{chains::AnyChain, Asset, BroadcastId, EpochIndex};
+use sp_std::vec::Vec;
 
 pub mod weights;
 pub use weights::WeightInfo;
 
 impl_pallet_safe_mode!(PalletSafeMode; emissions_sync_enabled);
 
+/// The maximum number of breakpoints in the emission schedule.
+pub const MAX_EMISSION_BREAKPOINTS: u32 = 32;
+
+/// The maximum number of epochs for which the supply can be projected.
+pub const MAX_PROJECTED_EPOCHS: u32 = 100;
+
 #[frame_support::pallet]
 pub mod pallet {
 
@@ -107,6 +117,17 @@ pub mod pallet {
 		type WeightInfo: WeightInfo;
 	}
 
+	/// The inflation rates, in parts per billion per `COMPOUNDING_INTERVAL`, that should apply from
+	/// the given epoch onwards.
+	#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Clone, Copy, RuntimeDebug, PartialEq, Eq)]
+	pub struct EmissionBreakpoint {
+		pub epoch: EpochIndex,
+		pub current_authority_inflation: u32,
+		pub backup_node_inflation: u32,
+	}
+
+	pub type EmissionSchedule = BoundedVec<EmissionBreakpoint, ConstU32<MAX_EMISSION_BREAKPOINTS>>;
+
 	#[pallet::pallet]
 	pub struct Pallet<T>(_);
 
@@ -142,6 +163,15 @@ pub mod pallet {
 	pub(super) type SupplyUpdateInterval<T: Config> =
 		StorageValue<_, BlockNumberFor<T>, ValueQuery>;
 
+	#[pallet::storage]
+	#[pallet::getter(fn emission_schedule)]
+	/// Inflation breakpoints, ordered by epoch. Inflation is interpolated linearly between them.
+	pub type EmissionScheduleBreakpoints<T: Config> = StorageValue<_, EmissionSchedule, ValueQuery>;
+
+	#[pallet::storage]
+	/// The epoch for which the emission schedule was last applied.
+	pub(super) type EmissionScheduleEpoch<T: Config> = StorageValue<_, EpochIndex, ValueQuery>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
@@ -153,6 +183,8 @@ pub mod pallet {
 		BackupNodeInflationEmissionsUpdated(u32),
 		/// SupplyUpdateInterval has been updated [block_number]
 		SupplyUpdateIntervalUpdated(BlockNumberFor<T>),
+		/// The emission schedule has been updated.
+		EmissionScheduleUpdated { schedule: EmissionSchedule },
 	}
 
 	// Errors inform users that something went wrong.
@@ -162,17 +194,29 @@ pub mod pallet {
 		Overflow,
 		/// Invalid percentage
 		InvalidPercentage,
+		/// The breakpoints of the emission schedule must be in strictly increasing epoch order.
+		InvalidEmissionSchedule,
+		/// Inflation rates can't be set directly while the emission schedule covers the current
+		/// epoch.
+		EmissionScheduleActive,
 	}
 
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
 		fn on_initialize(current_block: BlockNumberFor<T>) -> Weight {
+			let current_epoch = T::EpochInfo::epoch_index();
+			let schedule_weight = if EmissionScheduleEpoch::<T>::get() != current_epoch {
+				Self::apply_emission_schedule(current_epoch);
+				T::DbWeight::get().reads_writes(3, 3)
+			} else {
+				Weight::zero()
+			};
 			T::RewardsDistribution::distribute();
 			if Self::should_update_supply_at(current_block) {
 				if T::SafeMode::get().emissions_sync_enabled {
 					let flip_to_burn = T::FlipToBurn::take_flip_to_burn();
-					if flip_to_burn > Zero::zero() {
-						T::EgressHandler::schedule_egress(
+					let burn_egress_id = (flip_to_burn > Zero::zero()).then(|| {
+						let egress_id = T::EgressHandler::schedule_egress(
 							Asset::Flip,
 							flip_to_burn,
 							ForeignChainAddress::Eth(
@@ -181,19 +225,23 @@ pub mod pallet {
 							None,
 						);
 						T::Issuance::burn(flip_to_burn.into());
-					}
-					Self::broadcast_update_total_supply(
+						egress_id
+					});
+					let broadcast_id = Self::broadcast_update_total_supply(
 						T::Issuance::total_issuance(),
 						current_block,
 					);
+					if let Some(egress_id) = burn_egress_id {
+						T::FlipToBurn::on_flip_burned(egress_id, broadcast_id);
+					}
 					Self::deposit_event(Event::SupplyUpdateBroadcastRequested(current_block));
 					LastSupplyUpdateBlock::<T>::set(current_block);
-					return T::WeightInfo::rewards_minted()
+					return T::WeightInfo::rewards_minted().saturating_add(schedule_weight)
 				} else {
 					log::info!("Runtime Safe Mode is CODE RED: Flip total issuance update broadcast are paused for now.");
 				}
 			}
-			T::WeightInfo::rewards_not_minted()
+			T::WeightInfo::rewards_not_minted().saturating_add(schedule_weight)
 		}
 	}
 
@@ -211,6 +259,7 @@ pub mod pallet {
 		/// ## Errors
 		///
 		/// - [BadOrigin](frame_support::error::BadOrigin)
+		/// - [EmissionScheduleActive](Error::EmissionScheduleActive)
 		#[pallet::call_index(0)]
 		#[pallet::weight(T::WeightInfo::update_current_authority_emission_inflation())]
 		pub fn update_current_authority_emission_inflation(
@@ -218,6 +267,7 @@ pub mod pallet {
 			inflation: u32,
 		) -> DispatchResultWithPostInfo {
 			T::EnsureGovernance::ensure_origin(origin)?;
+			Self::ensure_no_active_emission_schedule()?;
 			CurrentAuthorityEmissionInflation::<T>::set(inflation);
 			Self::deposit_event(Event::<T>::CurrentAuthorityInflationEmissionsUpdated(inflation));
 			Ok(().into())
@@ -232,6 +282,7 @@ pub mod pallet {
 		/// ## Errors
 		///
 		/// - [BadOrigin](frame_support::error::BadOrigin)
+		/// - [EmissionScheduleActive](Error::EmissionScheduleActive)
 		#[pallet::call_index(1)]
 		#[pallet::weight(T::WeightInfo::update_backup_node_emission_inflation())]
 		pub fn update_backup_node_emission_inflation(
@@ -239,6 +290,7 @@ pub mod pallet {
 			inflation: u32,
 		) -> DispatchResultWithPostInfo {
 			T::EnsureGovernance::ensure_origin(origin)?;
+			Self::ensure_no_active_emission_schedule()?;
 			BackupNodeEmissionInflation::<T>::set(inflation);
 			Self::deposit_event(Event::<T>::BackupNodeInflationEmissionsUpdated(inflation));
 			Ok(().into())
@@ -264,6 +316,38 @@ pub mod pallet {
 			Self::deposit_event(Event::<T>::SupplyUpdateIntervalUpdated(value));
 			Ok(().into())
 		}
+
+		/// Sets the schedule of inflation breakpoints. The inflation rates are interpolated
+		/// linearly between breakpoints and updated automatically at the start of each epoch. After
+		/// the last breakpoint, its rates continue to apply. Before the first breakpoint, the rates
+		/// are left unchanged. An empty schedule disables automatic updates.
+		///
+		/// The rates for the current epoch are applied immediately.
+		///
+		/// ## Events
+		///
+		/// - [EmissionScheduleUpdated](Event::EmissionScheduleUpdated)
+		///
+		/// ## Errors
+		///
+		/// - [BadOrigin](frame_support::error::BadOrigin)
+		/// - [InvalidEmissionSchedule](Error::InvalidEmissionSchedule)
+		#[pallet::call_index(3)]
+		#[pallet::weight(T::DbWeight::get().reads_writes(4, 4))]
+		pub fn set_emission_schedule(
+			origin: OriginFor<T>,
+			schedule: EmissionSchedule,
+		) -> DispatchResultWithPostInfo {
+			T::EnsureGovernance::ensure_origin(origin)?;
+			ensure!(
+				schedule.windows(2).all(|pair| pair[0].epoch < pair[1].epoch),
+				Error::<T>::InvalidEmissionSchedule
+			);
+			EmissionScheduleBreakpoints::<T>::put(&schedule);
+			Self::deposit_event(Event::<T>::EmissionScheduleUpdated { schedule });
+			Self::apply_emission_schedule(T::EpochInfo::epoch_index());
+			Ok(().into())
+		}
 	}
 
 	#[pallet::genesis_config]
@@ -288,6 +372,123 @@ pub mod pallet {
 }
 
 impl<T: Config> Pallet<T> {
+	/// Returns the (current authority, backup node) inflation rates that the emission schedule
+	/// defines for the given epoch, or `None` if the schedule doesn't cover the epoch.
+	pub fn scheduled_inflation(epoch: EpochIndex) -> Option<(u32, u32)> {
+		interpolate_inflation(&EmissionScheduleBreakpoints::<T>::get(), epoch)
+	}
+
+	/// Inflation rates set directly would be overwritten at the next epoch while the emission
+	/// schedule covers the current epoch, so they are rejected.
+	fn ensure_no_active_emission_schedule() -> DispatchResult {
+		ensure!(
+			Self::scheduled_inflation(T::EpochInfo::epoch_index()).is_none(),
+			Error::<T>::EmissionScheduleActive
+		);
+		Ok(())
+	}
+
+	/// Updates the inflation rates according to the emission schedule. The block emissions are
+	/// recalculated with the new rates at the next heartbeat.
+	fn apply_emission_schedule(epoch: EpochIndex) {
+		EmissionScheduleEpoch::<T>::put(epoch);
+		if let Some((current_authority_inflation, backup_node_inflation)) =
+			Self::scheduled_inflation(epoch)
+		{
+			if CurrentAuthorityEmissionInflation::<T>::get() != current_authority_inflation {
+				CurrentAuthorityEmissionInflation::<T>::put(current_authority_inflation);
+				Self::deposit_event(Event::<T>::CurrentAuthorityInflationEmissionsUpdated(
+					current_authority_inflation,
+				));
+			}
+			if BackupNodeEmissionInflation::<T>::get() != backup_node_inflation {
+				BackupNodeEmissionInflation::<T>::put(backup_node_inflation);
+				Self::deposit_event(Event::<T>::BackupNodeInflationEmissionsUpdated(
+					backup_node_inflation,
+				));
+			}
+		}
+	}
+
+	/// Projects the FLIP supply for the next `epochs` epochs, starting with the current one.
+	///
+	/// For each epoch, returns the total supply reported by the last `UpdateFlipSupply` broadcast
+	/// of that epoch, assuming every epoch lasts `blocks_per_epoch` blocks, emissions follow the
+	/// emission schedule and no FLIP is burned.
+	pub fn project_flip_supply(
+		epochs: u32,
+		epoch_started_at: BlockNumberFor<T>,
+		blocks_per_epoch: BlockNumberFor<T>,
+	) -> Vec<(EpochIndex, T::FlipBalance)> {
+		let to_u64 = |block: BlockNumberFor<T>| -> u64 { block.unique_saturated_into() };
+		let compounding_interval = to_u64(T::CompoundingInterval::get()).max(1);
+		let supply_update_interval = to_u64(SupplyUpdateInterval::<T>::get()).max(1);
+		let blocks_per_epoch = to_u64(blocks_per_epoch).max(1);
+		let epoch_started_at = to_u64(epoch_started_at);
+		let current_epoch = T::EpochInfo::epoch_index();
+		let schedule = EmissionScheduleBreakpoints::<T>::get();
+		let unscheduled_inflation = (
+			CurrentAuthorityEmissionInflation::<T>::get(),
+			BackupNodeEmissionInflation::<T>::get(),
+		);
+
+		let inflation_at = |block: u64| {
+			let epoch = current_epoch.saturating_add(
+				(block.saturating_sub(epoch_started_at) / blocks_per_epoch).saturated_into(),
+			);
+			interpolate_inflation(&schedule, epoch).unwrap_or(unscheduled_inflation)
+		};
+
+		let mut block = to_u64(frame_system::Pallet::<T>::block_number());
+		let mut issuance: u128 = T::Issuance::total_issuance().into();
+		let mut emission_per_block: u128 = CurrentAuthorityEmissionPerBlock::<T>::get()
+			.saturating_add(BackupNodeEmissionPerBlock::<T>::get())
+			.into();
+		let mut next_heartbeat = (block / compounding_interval + 1) * compounding_interval;
+		let last_supply_update = to_u64(LastSupplyUpdateBlock::<T>::get());
+
+		// Emissions are recalculated on every heartbeat, so issuance grows linearly in between.
+		let mut advance_to = |target: u64| {
+			while block < target {
+				let next = target.min(next_heartbeat);
+				issuance = issuance
+					.saturating_add(emission_per_block.saturating_mul((next - block).into()));
+				block = next;
+				if block == next_heartbeat {
+					let (current_authority_inflation, backup_node_inflation) = inflation_at(block);
+					emission_per_block = [current_authority_inflation, backup_node_inflation]
+						.into_iter()
+						.map(|inflation| {
+							calculate_inflation_to_block_reward(
+								issuance,
+								inflation.into(),
+								compounding_interval.into(),
+							)
+						})
+						.fold(0, u128::saturating_add);
+					next_heartbeat = next_heartbeat.saturating_add(compounding_interval);
+				}
+			}
+			issuance
+		};
+
+		(0..epochs.min(MAX_PROJECTED_EPOCHS))
+			.map(|n| {
+				let epoch_end = epoch_started_at
+					.saturating_add(blocks_per_epoch.saturating_mul(n as u64 + 1))
+					.saturating_sub(1);
+				let last_update_in_epoch = last_supply_update.saturating_add(
+					epoch_end.saturating_sub(last_supply_update) / supply_update_interval *
+						supply_update_interval,
+				);
+				(
+					current_epoch.saturating_add(n),
+					T::FlipBalance::from(advance_to(last_update_in_epoch)),
+				)
+			})
+			.collect()
+	}
+
 	/// Determines if we should broadcast supply update at block number `block_number`.
 	fn should_update_supply_at(block_number: BlockNumberFor<T>) -> bool {
 		let supply_update_interval = SupplyUpdateInterval::<T>::get();
@@ -299,13 +500,15 @@ impl<T: Config> Pallet<T> {
 	fn broadcast_update_total_supply(
 		total_supply: T::FlipBalance,
 		block_number: BlockNumberFor<T>,
-	) {
+	) -> BroadcastId {
 		// Emit a threshold signature request.
 		// TODO: See if we can replace an old request if there is one.
-		T::Broadcaster::threshold_sign_and_broadcast(T::ApiCall::new_unsigned(
-			total_supply.unique_saturated_into(),
-			block_number.saturated_into(),
-		));
+		let (broadcast_id, _) =
+			T::Broadcaster::threshold_sign_and_broadcast(T::ApiCall::new_unsigned(
+				total_supply.unique_saturated_into(),
+				block_number.saturated_into(),
+			));
+		broadcast_id
 	}
 }
 
@@ -339,6 +542,29 @@ impl<T: Config> BlockEmissions for Pallet<T> {
 	}
 }
 
+/// Linearly interpolates the inflation rates between the breakpoints surrounding `epoch`.
+fn interpolate_inflation(
+	schedule: &[pallet::EmissionBreakpoint],
+	epoch: EpochIndex,
+) -> Option<(u32, u32)> {
+	let next = schedule.partition_point(|breakpoint| breakpoint.epoch <= epoch);
+	let from = schedule.get(next.checked_sub(1)?)?;
+	Some(match schedule.get(next) {
+		Some(to) => {
+			let interpolate = |from_rate: u32, to_rate: u32| -> u32 {
+				let elapsed = (epoch - from.epoch) as i128;
+				let span = (to.epoch - from.epoch) as i128;
+				(from_rate as i128 + (to_rate as i128 - from_rate as i128) * elapsed / span) as u32
+			};
+			(
+				interpolate(from.current_authority_inflation, to.current_authority_inflation),
+				interpolate(from.backup_node_inflation, to.backup_node_inflation),
+			)
+		},
+		None => (from.current_authority_inflation, from.backup_node_inflation),
+	})
+}
+
 fn calculate_inflation_to_block_reward<T>(
 	issuance: T,
 	inflation_per_bill: T,
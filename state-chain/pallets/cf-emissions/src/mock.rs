@@ -5,7 +5,7 @@ use cf_chains::{
 	mocks::{MockEthereum, MockEthereumChainCrypto},
 	AnyChain, ApiCall, ChainCrypto, UpdateFlipSupply,
 };
-use cf_primitives::{BroadcastId, FlipBalance, ThresholdSignatureRequestId};
+use cf_primitives::{BroadcastId, EgressId, FlipBalance, ThresholdSignatureRequestId};
 use cf_traits::{
 	impl_mock_callback, impl_mock_chainflip, impl_mock_runtime_safe_mode, impl_mock_waived_fees,
 	mocks::{egress_handler::MockEgressHandler, eth_environment_provider::MockEthEnvironment},
@@ -157,6 +157,20 @@ impl FlipBurnInfo for MockFlipToBurn {
 	fn take_flip_to_burn() -> cf_primitives::AssetAmount {
 		FLIP_TO_BURN
 	}
+
+	fn on_flip_burned(egress_id: EgressId, supply_update_broadcast_id: BroadcastId) {
+		storage::hashed::put(
+			&<Twox64Concat as StorageHasher>::hash,
+			b"MockFlipBurned",
+			&(egress_id, supply_update_broadcast_id),
+		);
+	}
+}
+
+impl MockFlipToBurn {
+	pub fn get_burned() -> Option<(EgressId, BroadcastId)> {
+		storage::hashed::get(&<Twox64Concat as StorageHasher>::hash, b"MockFlipBurned")
+	}
 }
 
 impl MockBroadcast {
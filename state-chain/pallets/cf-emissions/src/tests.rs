@@ -174,5 +174,179 @@ fn burn_flip() {
 		let egresses = MockEgressHandler::<AnyChain>::get_scheduled_egresses();
 		assert!(egresses.len() == 1);
 		assert_eq!(egresses.first().expect("should exist").amount(), FLIP_TO_BURN);
+		// The burn is linked to its egress and the supply update broadcast.
+		assert_eq!(
+			MockFlipToBurn::get_burned(),
+			Some(((cf_primitives::ForeignChain::Ethereum, 1), 1))
+		);
 	});
 }
+
+mod emission_schedule {
+	use super::*;
+	use crate::{
+		interpolate_inflation, EmissionBreakpoint, EmissionSchedule, Error, Event,
+		MAX_PROJECTED_EPOCHS,
+	};
+	use cf_traits::EpochInfo;
+	use frame_support::{assert_noop, assert_ok};
+
+	const BLOCKS_PER_EPOCH: u64 = 100;
+
+	fn schedule(breakpoints: &[(u32, u32, u32)]) -> EmissionSchedule {
+		breakpoints
+			.iter()
+			.map(|&(epoch, current_authority_inflation, backup_node_inflation)| {
+				EmissionBreakpoint { epoch, current_authority_inflation, backup_node_inflation }
+			})
+			.collect::<Vec<_>>()
+			.try_into()
+			.unwrap()
+	}
+
+	#[test]
+	fn inflation_is_interpolated_between_breakpoints() {
+		let schedule = schedule(&[(10, 1000, 100), (20, 500, 200), (30, 500, 200)]);
+		assert_eq!(interpolate_inflation(&schedule, 5), None);
+		assert_eq!(interpolate_inflation(&schedule, 10), Some((1000, 100)));
+		assert_eq!(interpolate_inflation(&schedule, 15), Some((750, 150)));
+		assert_eq!(interpolate_inflation(&schedule, 19), Some((550, 190)));
+		assert_eq!(interpolate_inflation(&schedule, 20), Some((500, 200)));
+		assert_eq!(interpolate_inflation(&schedule, 100), Some((500, 200)));
+		assert_eq!(interpolate_inflation(&[], 100), None);
+	}
+
+	#[test]
+	fn breakpoints_must_be_ordered() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				Emissions::set_emission_schedule(
+					RuntimeOrigin::root(),
+					schedule(&[(2, 1000, 100), (2, 500, 50)])
+				),
+				Error::<Test>::InvalidEmissionSchedule
+			);
+			assert_noop!(
+				Emissions::set_emission_schedule(
+					RuntimeOrigin::root(),
+					schedule(&[(3, 1000, 100), (2, 500, 50)])
+				),
+				Error::<Test>::InvalidEmissionSchedule
+			);
+		});
+	}
+
+	#[test]
+	fn schedule_applies_immediately_and_at_every_new_epoch() {
+		new_test_ext().execute_with(|| {
+			MockEpochInfo::set_epoch(1);
+			assert_ok!(Emissions::set_emission_schedule(
+				RuntimeOrigin::root(),
+				schedule(&[(1, 1000, 100), (3, 2000, 300)])
+			));
+			assert_eq!(Emissions::current_authority_emission_inflation(), 1000);
+			assert_eq!(Emissions::backup_node_emission_inflation(), 100);
+
+			// Nothing changes within the epoch.
+			Emissions::on_initialize(1);
+			assert_eq!(Emissions::current_authority_emission_inflation(), 1000);
+
+			MockEpochInfo::set_epoch(2);
+			Emissions::on_initialize(2);
+			assert_eq!(Emissions::current_authority_emission_inflation(), 1500);
+			assert_eq!(Emissions::backup_node_emission_inflation(), 200);
+			System::assert_has_event(RuntimeEvent::Emissions(
+				Event::CurrentAuthorityInflationEmissionsUpdated(1500),
+			));
+
+			MockEpochInfo::set_epoch(5);
+			Emissions::on_initialize(3);
+			assert_eq!(Emissions::current_authority_emission_inflation(), 2000);
+			assert_eq!(Emissions::backup_node_emission_inflation(), 300);
+		});
+	}
+
+	#[test]
+	fn rates_are_unchanged_before_the_first_breakpoint() {
+		new_test_ext().execute_with(|| {
+			let before = (
+				Emissions::current_authority_emission_inflation(),
+				Emissions::backup_node_emission_inflation(),
+			);
+			assert_ok!(Emissions::set_emission_schedule(
+				RuntimeOrigin::root(),
+				schedule(&[(10, 1000, 100)])
+			));
+			assert_eq!(
+				(
+					Emissions::current_authority_emission_inflation(),
+					Emissions::backup_node_emission_inflation(),
+				),
+				before
+			);
+		});
+	}
+
+	#[test]
+	fn inflation_cannot_be_set_directly_while_the_schedule_is_active() {
+		new_test_ext().execute_with(|| {
+			MockEpochInfo::set_epoch(1);
+			assert_ok!(Emissions::set_emission_schedule(
+				RuntimeOrigin::root(),
+				schedule(&[(2, 1000, 100)])
+			));
+			assert_ok!(Emissions::update_current_authority_emission_inflation(
+				RuntimeOrigin::root(),
+				500
+			));
+
+			MockEpochInfo::set_epoch(2);
+			Emissions::on_initialize(2);
+			assert_noop!(
+				Emissions::update_current_authority_emission_inflation(RuntimeOrigin::root(), 500),
+				Error::<Test>::EmissionScheduleActive
+			);
+			assert_noop!(
+				Emissions::update_backup_node_emission_inflation(RuntimeOrigin::root(), 50),
+				Error::<Test>::EmissionScheduleActive
+			);
+
+			assert_ok!(Emissions::set_emission_schedule(RuntimeOrigin::root(), schedule(&[])));
+			assert_ok!(Emissions::update_backup_node_emission_inflation(RuntimeOrigin::root(), 50));
+		});
+	}
+
+	#[test]
+	fn flip_supply_projection_follows_the_schedule() {
+		new_test_ext().execute_with(|| {
+			let project = || {
+				Emissions::project_flip_supply(5, System::block_number(), BLOCKS_PER_EPOCH)
+					.into_iter()
+					.map(|(_, supply)| supply)
+					.collect::<Vec<_>>()
+			};
+
+			let default_projection = project();
+			assert_eq!(default_projection.len(), 5);
+			assert!(default_projection.windows(2).all(|pair| pair[0] < pair[1]));
+
+			let epoch = MockEpochInfo::epoch_index();
+			assert_ok!(Emissions::set_emission_schedule(
+				RuntimeOrigin::root(),
+				schedule(&[(epoch, 2720, 284), (epoch + 4, 27200, 2840)])
+			));
+			let increasing_projection = project();
+			assert!(increasing_projection
+				.iter()
+				.zip(&default_projection)
+				.skip(2)
+				.all(|(increasing, default)| increasing > default));
+
+			assert_eq!(
+				Emissions::project_flip_supply(u32::MAX, System::block_number(), BLOCKS_PER_EPOCH)
+					.len(),
+				MAX_PROJECTED_EPOCHS as usize
+			);
+		});
+	}
+}
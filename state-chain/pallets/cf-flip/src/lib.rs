@@ -226,7 +226,18 @@ impl<Balance: AtLeast32BitUnsigned + Copy> FlipAccount<Balance> {
 
 	/// Account can only be slashed if its balance is higher than 20% of the bond.
 	pub fn can_be_slashed(&self, slash_amount: Balance) -> bool {
-		self.balance.saturating_sub(slash_amount) > self.bond / 5u32.into()
+		self.can_be_slashed_with_delegation(slash_amount, Zero::zero())
+	}
+
+	/// As [Self::can_be_slashed], for an account whose bond is partly backed by funds delegated
+	/// to it. Only the share of the bond backed by the account's own funds is taken into account.
+	pub fn can_be_slashed_with_delegation(
+		&self,
+		slash_amount: Balance,
+		delegated_stake: Balance,
+	) -> bool {
+		self.balance.saturating_sub(slash_amount) >
+			self.bond.saturating_sub(delegated_stake) / 5u32.into()
 	}
 }
 
@@ -551,8 +562,11 @@ impl<T: Config> FlipSlasher<T> {
 		account_id: &T::AccountId,
 		account: FlipAccount<T::Balance>,
 		slash_amount: T::Balance,
+		delegated_stake: T::Balance,
 	) {
-		if !slash_amount.is_zero() && account.can_be_slashed(slash_amount) {
+		if !slash_amount.is_zero() &&
+			account.can_be_slashed_with_delegation(slash_amount, delegated_stake)
+		{
 			Pallet::<T>::settle(account_id, Pallet::<T>::burn(slash_amount).into());
 			Pallet::<T>::deposit_event(Event::<T>::SlashingPerformed {
 				who: account_id.clone(),
@@ -573,12 +587,21 @@ where
 	fn slash(account_id: &Self::AccountId, blocks: Self::BlockNumber) {
 		let account = Account::<T>::get(account_id);
 		let slash_amount = Self::calculate_slash_amount(account_id, blocks);
-		Self::attempt_slash(account_id, account, slash_amount);
+		Self::attempt_slash(account_id, account, slash_amount, Zero::zero());
 	}
 
 	fn slash_balance(account_id: &Self::AccountId, slash_amount: FlipBalance) {
 		let account = Account::<T>::get(account_id);
-		Self::attempt_slash(account_id, account, slash_amount.into());
+		Self::attempt_slash(account_id, account, slash_amount.into(), Zero::zero());
+	}
+
+	fn slash_operator_balance(
+		account_id: &Self::AccountId,
+		slash_amount: FlipBalance,
+		delegated_stake: FlipBalance,
+	) {
+		let account = Account::<T>::get(account_id);
+		Self::attempt_slash(account_id, account, slash_amount.into(), delegated_stake.into());
 	}
 
 	fn calculate_slash_amount(
@@ -194,7 +194,8 @@ benchmarks! {
 			let some_balance = FlipBalance::<T>::from(100_u32);
 			let some_account: AccountId<T> = account("doogle", 0, i);
 			let balances: BTreeMap<EthereumAddress, FlipBalance<T>> = BTreeMap::from([(Default::default(), some_balance)]);
-			RestrictedBalances::<T>::insert(some_account, balances);
+			RestrictedBalances::<T>::insert(&some_account, &balances);
+			OriginalRestrictedBalances::<T>::insert(some_account, balances);
 		}
 		let call = Call::<T>::update_restricted_addresses {
 			addresses_to_add: (1 .. a as u32).map(|_| Default::default()).collect::<Vec<_>>(),
@@ -211,5 +212,110 @@ benchmarks! {
 		assert!(BoundExecutorAddress::<T>::contains_key(&caller));
 	}
 
+	set_delegation_commission {
+		let caller: T::AccountId = whitelisted_caller();
+		<T as frame_system::Config>::OnNewAccount::on_new_account(&caller);
+		T::AccountRoleRegistry::register_as_validator(&caller).unwrap();
+	}:_(RawOrigin::Signed(caller.clone()), Permill::from_percent(10))
+	verify {
+		assert_eq!(OperatorCommission::<T>::get(&caller), Some(Permill::from_percent(10)));
+	}
+
+	delegate {
+		let operator: T::AccountId = account("operator", 0, 0);
+		OperatorCommission::<T>::insert(&operator, Permill::from_percent(10));
+		let caller: T::AccountId = whitelisted_caller();
+		let amount = MinimumFunding::<T>::get();
+		Call::<T>::funded {
+			account_id: caller.clone(),
+			amount,
+			funder: Default::default(),
+			tx_hash: [211u8; 32],
+		}.dispatch_bypass_filter(T::EnsureWitnessed::try_successful_origin().unwrap())?;
+	}:_(RawOrigin::Signed(caller.clone()), operator.clone(), amount)
+	verify {
+		assert_eq!(Delegations::<T>::get(&operator, &caller), amount);
+	}
+
+	undelegate {
+		let operator: T::AccountId = account("operator", 0, 0);
+		let caller: T::AccountId = whitelisted_caller();
+		Delegations::<T>::insert(&operator, &caller, MinimumFunding::<T>::get());
+		DelegatedTo::<T>::insert(&caller, &operator);
+	}:_(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert!(!DelegatedTo::<T>::contains_key(&caller));
+	}
+
+	update_redemption_rate_limit {
+		let rate_limit = Some(RedemptionRateLimit {
+			window: 100u32.into(),
+			max_redeemed: MinimumFunding::<T>::get(),
+		});
+		let call = Call::<T>::update_redemption_rate_limit { rate_limit };
+	}: { call.dispatch_bypass_filter(T::EnsureGovernance::try_successful_origin().unwrap())? }
+	verify {
+		assert_eq!(RedemptionRateLimitConfig::<T>::get(), rate_limit);
+	}
+
+	process_redemption_queue {
+		let n in 0 .. MAX_QUEUED_REDEMPTIONS_PER_BLOCK;
+		for i in 0 .. n {
+			let account_id: T::AccountId = account("redeemer", i, 0);
+			Call::<T>::funded {
+				account_id: account_id.clone(),
+				amount: MinimumFunding::<T>::get() * T::Amount::from(2u32),
+				funder: Default::default(),
+				tx_hash: [i as u8; 32],
+			}.dispatch_bypass_filter(T::EnsureWitnessed::try_successful_origin().unwrap())?;
+			let amount = MinimumFunding::<T>::get();
+			T::Flip::try_initiate_redemption(&account_id, amount)?;
+			PendingRedemptions::<T>::insert(&account_id, (amount, EthereumAddress::default()));
+			Pallet::<T>::enqueue_redemption(QueuedRedemption {
+				account_id,
+				amount,
+				address: Default::default(),
+				executor: None,
+				queued_at: Default::default(),
+			});
+		}
+	}: {
+		assert_eq!(Pallet::<T>::process_redemption_queue(), n);
+	}
+	verify {
+		assert_eq!(Pallet::<T>::redemption_queue_len(), 0);
+	}
+
+	schedule_unbond {
+		let caller: T::AccountId = whitelisted_caller();
+		<T as frame_system::Config>::OnNewAccount::on_new_account(&caller);
+		T::AccountRoleRegistry::register_as_validator(&caller).unwrap();
+		let call = Call::<T>::schedule_unbond { amount: MinimumFunding::<T>::get() };
+	}: {
+		// Not an authority, so the call fails after the authority check.
+		let _ = call.dispatch_bypass_filter(RawOrigin::Signed(caller.clone()).into());
+	}
+
+	cancel_unbond {
+		let caller: T::AccountId = whitelisted_caller();
+		<T as frame_system::Config>::OnNewAccount::on_new_account(&caller);
+		T::AccountRoleRegistry::register_as_validator(&caller).unwrap();
+		ScheduledUnbonds::<T>::insert(&caller, MinimumFunding::<T>::get());
+	}:_(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert!(!ScheduledUnbonds::<T>::contains_key(&caller));
+	}
+
+	set_restriction {
+		let restriction = Restriction {
+			reason: RestrictionReason::Vesting,
+			unlock_schedule: Some(UnlockSchedule { start: 0, cliff: 100, end: 200 }),
+		};
+		let call = Call::<T>::set_restriction { address: Default::default(), restriction };
+	}: { call.dispatch_bypass_filter(T::EnsureGovernance::try_successful_origin().unwrap())? }
+	verify {
+		assert_eq!(RestrictedAddresses::<T>::get(EthereumAddress::default()), Some(restriction));
+	}
+
 	impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test,);
 }
@@ -19,7 +19,7 @@ use cf_chains::{eth::Address as EthereumAddress, RegisterRedemption};
 use cf_primitives::AccountRole;
 use cf_traits::{
 	impl_pallet_safe_mode, AccountInfo, AccountRoleRegistry, Bid, BidderProvider, Broadcaster,
-	Chainflip, EpochInfo, FeePayment, Funding,
+	Chainflip, EpochInfo, FeePayment, Funding, Slashing, UnbondingSchedule,
 };
 use codec::{Decode, Encode};
 use frame_support::{
@@ -27,20 +27,76 @@ use frame_support::{
 	ensure,
 	pallet_prelude::Weight,
 	sp_runtime::{
-		traits::{CheckedSub, UniqueSaturatedInto, Zero},
-		Saturating,
+		helpers_128bit::multiply_by_rational_with_rounding,
+		traits::{AtLeast32BitUnsigned, CheckedSub, UniqueSaturatedInto, Zero},
+		Permill, Perquintill, Rounding, Saturating,
 	},
 	traits::{EnsureOrigin, HandleLifetime, IsType, OnKilledAccount, StorageVersion, UnixTime},
 };
-use frame_system::pallet_prelude::OriginFor;
+use frame_system::pallet_prelude::{BlockNumberFor, OriginFor};
 pub use pallet::*;
 use scale_info::TypeInfo;
-use sp_std::{cmp::max, collections::btree_map::BTreeMap, prelude::*};
+use serde::{Deserialize, Serialize};
+use sp_std::{cmp::max, collections::btree_map::BTreeMap, marker::PhantomData, prelude::*};
 #[derive(Encode, Decode, PartialEq, Debug, TypeInfo)]
 pub enum Pending {
 	Pending,
 }
-pub const PALLET_VERSION: StorageVersion = StorageVersion::new(2);
+pub const PALLET_VERSION: StorageVersion = StorageVersion::new(3);
+
+/// The maximum number of queued redemptions that are registered in a single block.
+pub const MAX_QUEUED_REDEMPTIONS_PER_BLOCK: u32 = 20;
+
+/// Why funds from an address are restricted.
+#[derive(
+	Copy, Clone, Debug, Default, PartialEq, Eq, Encode, Decode, TypeInfo, Serialize, Deserialize,
+)]
+pub enum RestrictionReason {
+	#[default]
+	Unspecified,
+	/// The address is a vesting contract.
+	Vesting,
+	/// The address was restricted by a Governance decision.
+	Governance,
+}
+
+/// Releases restricted funds linearly between `start` and `end`. Nothing is released before the
+/// `cliff`. All times are unix timestamps in seconds.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo, Serialize, Deserialize)]
+pub struct UnlockSchedule {
+	pub start: u64,
+	pub cliff: u64,
+	pub end: u64,
+}
+
+impl UnlockSchedule {
+	pub fn is_valid(&self) -> bool {
+		self.start <= self.cliff && self.cliff <= self.end && self.start < self.end
+	}
+
+	/// The portion of `amount` that is unlocked at unix time `now`.
+	pub fn unlocked<Amount: AtLeast32BitUnsigned + Copy>(
+		&self,
+		amount: Amount,
+		now: u64,
+	) -> Amount {
+		if now < self.cliff {
+			Zero::zero()
+		} else if now >= self.end {
+			amount
+		} else {
+			Perquintill::from_rational(now - self.start, self.end - self.start) * amount
+		}
+	}
+}
+
+/// The restriction that applies to funds originating from a restricted address. Unlocked funds
+/// can be redeemed to any address.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct Restriction {
+	pub reason: RestrictionReason,
+	pub unlock_schedule: Option<UnlockSchedule>,
+}
 
 impl_pallet_safe_mode!(PalletSafeMode; redeem_enabled, start_bidding_enabled, stop_bidding_enabled);
 
@@ -75,6 +131,36 @@ pub mod pallet {
 		}
 	}
 
+	/// Limits the total amount of FLIP that can be redeemed within a window of blocks.
+	#[derive(Copy, Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+	pub struct RedemptionRateLimit<BlockNumber, Amount> {
+		/// The length of the window, in blocks.
+		pub window: BlockNumber,
+		/// The maximum amount that can be redeemed within a single window.
+		pub max_redeemed: Amount,
+	}
+
+	/// A redemption request that exceeded the rate limit and is waiting to be registered.
+	#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+	pub struct QueuedRedemption<AccountId, Amount, BlockNumber> {
+		pub account_id: AccountId,
+		pub amount: Amount,
+		pub address: EthereumAddress,
+		pub executor: Option<EthereumAddress>,
+		pub queued_at: BlockNumber,
+	}
+
+	/// The position of an account's redemption in the redemption queue.
+	#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+	pub struct RedemptionQueuePosition<Amount, BlockNumber> {
+		/// The number of redemptions ahead in the queue.
+		pub position: u32,
+		pub amount: Amount,
+		/// The total amount of the redemptions ahead in the queue.
+		pub amount_ahead: Amount,
+		pub queued_at: BlockNumber,
+	}
+
 	#[pallet::config]
 	#[pallet::disable_frame_system_supertrait_check]
 	pub trait Config: Chainflip {
@@ -105,6 +191,10 @@ pub mod pallet {
 		/// Safe Mode access.
 		type SafeMode: Get<PalletSafeMode>;
 
+		/// The maximum number of delegators that can delegate to a single operator.
+		#[pallet::constant]
+		type MaxDelegatorsPerOperator: Get<u32>;
+
 		/// Benchmark stuff
 		type WeightInfo: WeightInfo;
 	}
@@ -144,10 +234,10 @@ pub mod pallet {
 	pub type BoundExecutorAddress<T: Config> =
 		StorageMap<_, Blake2_128Concat, AccountId<T>, EthereumAddress, OptionQuery>;
 
-	/// List of restricted addresses
+	/// List of restricted addresses, and the restriction that applies to funds from each.
 	#[pallet::storage]
 	pub type RestrictedAddresses<T: Config> =
-		StorageMap<_, Blake2_128Concat, EthereumAddress, (), ValueQuery>;
+		StorageMap<_, Blake2_128Concat, EthereumAddress, Restriction, OptionQuery>;
 
 	/// Map that bookkeeps the restricted balances for each address
 	#[pallet::storage]
@@ -159,6 +249,19 @@ pub mod pallet {
 		ValueQuery,
 	>;
 
+	/// The total amount each account has been funded from each restricted address. Unlock
+	/// schedules apply to this amount, so that redeeming restricted funds doesn't speed up the
+	/// release of the rest. The amount released so far is the difference with the
+	/// [RestrictedBalances].
+	#[pallet::storage]
+	pub type OriginalRestrictedBalances<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		AccountId<T>,
+		BTreeMap<EthereumAddress, FlipBalance<T>>,
+		ValueQuery,
+	>;
+
 	/// Map of bound addresses for accounts.
 	#[pallet::storage]
 	pub type BoundRedeemAddress<T: Config> =
@@ -168,6 +271,67 @@ pub mod pallet {
 	#[pallet::storage]
 	pub type RedemptionTax<T: Config> = StorageValue<_, T::Amount, ValueQuery>;
 
+	/// The commission charged by operators on the rewards earned by their delegators. Validators
+	/// only accept delegations once they have set a commission.
+	#[pallet::storage]
+	pub type OperatorCommission<T: Config> =
+		StorageMap<_, Blake2_128Concat, AccountId<T>, Permill, OptionQuery>;
+
+	/// The amount of FLIP delegated to each operator, by delegator.
+	#[pallet::storage]
+	pub type Delegations<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		AccountId<T>,
+		Blake2_128Concat,
+		AccountId<T>,
+		FlipBalance<T>,
+		ValueQuery,
+	>;
+
+	/// The operator each delegator has delegated to.
+	#[pallet::storage]
+	pub type DelegatedTo<T: Config> =
+		StorageMap<_, Blake2_128Concat, AccountId<T>, AccountId<T>, OptionQuery>;
+
+	/// Limits the amount of FLIP redeemed per window. Redemptions are not rate limited if unset.
+	/// Can be updated by Governance.
+	#[pallet::storage]
+	pub type RedemptionRateLimitConfig<T: Config> =
+		StorageValue<_, RedemptionRateLimit<BlockNumberFor<T>, FlipBalance<T>>, OptionQuery>;
+
+	/// The block at which the current rate limiting window started, and the amount redeemed
+	/// within it.
+	#[pallet::storage]
+	pub type CurrentRedemptionWindow<T: Config> =
+		StorageValue<_, (BlockNumberFor<T>, FlipBalance<T>), ValueQuery>;
+
+	/// Redemptions that exceeded the rate limit, keyed by their index in the queue. The funds are
+	/// already pending, but the redemption is only registered once there is capacity.
+	#[pallet::storage]
+	pub type RedemptionQueue<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		u32,
+		QueuedRedemption<AccountId<T>, FlipBalance<T>, BlockNumberFor<T>>,
+		OptionQuery,
+	>;
+
+	/// The index of the oldest redemption in the [RedemptionQueue] and the index at which the
+	/// next one will be queued. The queue is empty if they are equal.
+	#[pallet::storage]
+	pub type RedemptionQueueIndices<T: Config> = StorageValue<_, (u32, u32), ValueQuery>;
+
+	/// The index in the [RedemptionQueue] of each queued account's redemption.
+	#[pallet::storage]
+	pub type QueuedRedemptionIndex<T: Config> =
+		StorageMap<_, Blake2_128Concat, AccountId<T>, u32, OptionQuery>;
+
+	/// Amounts authorities have scheduled to unbond at the next rotation.
+	#[pallet::storage]
+	pub type ScheduledUnbonds<T: Config> =
+		StorageMap<_, Blake2_128Concat, AccountId<T>, FlipBalance<T>, OptionQuery>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
@@ -229,6 +393,38 @@ pub mod pallet {
 
 		/// An account has been bound to an executor address.
 		BoundExecutorAddress { account_id: AccountId<T>, address: EthereumAddress },
+
+		/// An operator has set the commission charged to its delegators.
+		DelegationCommissionUpdated { operator: AccountId<T>, commission: Permill },
+
+		/// FLIP has been delegated to an operator.
+		Delegated {
+			delegator: AccountId<T>,
+			operator: AccountId<T>,
+			amount: FlipBalance<T>,
+			total_delegation: FlipBalance<T>,
+		},
+
+		/// A delegator has withdrawn its delegation from an operator.
+		Undelegated { delegator: AccountId<T>, operator: AccountId<T>, amount: FlipBalance<T> },
+
+		/// A redemption exceeded the rate limit and has been queued. `position` is the number of
+		/// redemptions ahead of it in the queue.
+		RedemptionQueued { account_id: AccountId<T>, amount: FlipBalance<T>, position: u32 },
+
+		/// The redemption rate limit has been updated.
+		RedemptionRateLimitUpdated {
+			rate_limit: Option<RedemptionRateLimit<BlockNumberFor<T>, FlipBalance<T>>>,
+		},
+
+		/// An authority has scheduled part of its bond to be released at the next rotation.
+		UnbondScheduled { account_id: AccountId<T>, amount: FlipBalance<T> },
+
+		/// An authority has cancelled its scheduled unbond.
+		UnbondCancelled { account_id: AccountId<T> },
+
+		/// The restriction on a restricted address has been set.
+		RestrictionUpdated { address: EthereumAddress, restriction: Restriction },
 	}
 
 	#[pallet::error]
@@ -283,6 +479,56 @@ pub mod pallet {
 
 		/// The account is already bound to an executor address.
 		ExecutorAddressAlreadyBound,
+
+		/// The account is not a validator that accepts delegations.
+		NotAnOperator,
+
+		/// Validators can't delegate to other validators.
+		ValidatorsCannotDelegate,
+
+		/// The account is already delegating to a different operator.
+		AlreadyDelegating,
+
+		/// The account is not delegating to any operator.
+		NotDelegating,
+
+		/// The operator has reached the maximum number of delegators.
+		TooManyDelegators,
+
+		/// Delegated funds can't be withdrawn while the operator is an authority, or is still
+		/// bonded for an epoch that hasn't expired.
+		OperatorIsAuthority,
+
+		/// Delegated funds can't be redeemed.
+		DelegatedFundsLocked,
+
+		/// Can't delegate an amount of zero.
+		InvalidDelegationAmount,
+
+		/// The rate limit window and the maximum redeemed amount must be greater than zero.
+		InvalidRedemptionRateLimit,
+
+		/// Only current authorities have bonded funds that can be unbonded.
+		NotAnAuthority,
+
+		/// The unbond amount must be greater than zero and can't exceed the current bond.
+		InvalidUnbondAmount,
+
+		/// The account has not scheduled an unbond.
+		NoScheduledUnbond,
+
+		/// The unlock schedule must end after it starts, with the cliff in between.
+		InvalidUnlockSchedule,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(_n: BlockNumberFor<T>) -> Weight {
+			let processed = Self::process_redemption_queue();
+			T::DbWeight::get()
+				.reads_writes(2, 1)
+				.saturating_add(T::WeightInfo::redeem().saturating_mul(processed.into()))
+		}
 	}
 
 	#[pallet::call]
@@ -318,6 +564,9 @@ pub mod pallet {
 				RestrictedBalances::<T>::mutate(account_id.clone(), |map| {
 					map.entry(funder).and_modify(|balance| *balance += amount).or_insert(amount);
 				});
+				OriginalRestrictedBalances::<T>::mutate(account_id.clone(), |map| {
+					map.entry(funder).and_modify(|balance| *balance += amount).or_insert(amount);
+				});
 			}
 
 			Self::deposit_event(Event::Funded {
@@ -338,6 +587,9 @@ pub mod pallet {
 		/// An account can only have one pending redemption at a time, the funds wrapped up in the
 		/// pending redemption are inaccessible and are not counted towards a Validator's Auction
 		/// Bid.
+		///
+		/// If the redemption exceeds the [RedemptionRateLimitConfig], or other redemptions are
+		/// already queued, the redemption is queued and only registered once there is capacity.
 		#[pallet::call_index(1)]
 		#[pallet::weight({ if matches!(amount, RedemptionAmount::Exact(_)) { T::WeightInfo::redeem() } else { T::WeightInfo::redeem_all() }})]
 		pub fn redeem(
@@ -383,15 +635,18 @@ pub mod pallet {
 				);
 			}
 
+			let delegated_funds = Self::delegation_of(&account_id);
+			let locked_balances = Self::locked_balances(&account_id, &restricted_balances);
+
 			// The available funds are the total balance minus whichever is larger from:
-			// - The bond.
-			// - The total restricted funds that need to remain in the account after the redemption.
-			let liquid_balance = T::Flip::balance(&account_id) -
-				max(
-					T::Flip::bond(&account_id),
-					restricted_balances.values().copied().sum::<FlipBalance<T>>() -
-						restricted_balances.get(&address).copied().unwrap_or_default(),
-				);
+			// - The bond, plus any funds delegated to an operator.
+			// - The locked restricted funds that need to remain in the account after the
+			//   redemption.
+			let liquid_balance = T::Flip::balance(&account_id).saturating_sub(max(
+				T::Flip::bond(&account_id).saturating_add(delegated_funds),
+				locked_balances.values().copied().sum::<FlipBalance<T>>() -
+					locked_balances.get(&address).copied().unwrap_or_default(),
+			));
 
 			let (debit_amount, redeem_amount) = match amount {
 				RedemptionAmount::Max =>
@@ -410,6 +665,9 @@ pub mod pallet {
 				restricted_balance.saturating_reduce(debit_amount);
 				if restricted_balance.is_zero() {
 					restricted_balances.remove(&address);
+					OriginalRestrictedBalances::<T>::mutate(&account_id, |original_balances| {
+						original_balances.remove(&address);
+					});
 				}
 				RestrictedBalances::<T>::insert(&account_id, &restricted_balances);
 			}
@@ -424,33 +682,39 @@ pub mod pallet {
 				Error::<T>::BelowMinimumFunding
 			);
 			ensure!(
-				remaining_balance >= restricted_balances.values().copied().sum::<FlipBalance<T>>(),
+				remaining_balance >=
+					Self::locked_balances(&account_id, &restricted_balances)
+						.values()
+						.copied()
+						.sum::<FlipBalance<T>>(),
 				Error::<T>::InsufficientUnrestrictedFunds
 			);
+			ensure!(remaining_balance >= delegated_funds, Error::<T>::DelegatedFundsLocked);
 
 			// Update the account balance.
 			if redeem_amount > Zero::zero() {
 				T::Flip::try_initiate_redemption(&account_id, redeem_amount)?;
-
-				// Send the transaction.
-				let contract_expiry =
-					T::TimeSource::now().as_secs() + RedemptionTTLSeconds::<T>::get();
-				let call = T::RegisterRedemption::new_unsigned(
-					<T as Config>::FunderId::from_ref(&account_id).as_ref(),
-					redeem_amount.unique_saturated_into(),
-					address.as_fixed_bytes(),
-					contract_expiry,
-					executor,
-				);
-
 				PendingRedemptions::<T>::insert(&account_id, (redeem_amount, address));
 
-				Self::deposit_event(Event::RedemptionRequested {
-					account_id,
-					amount: redeem_amount,
-					broadcast_id: T::Broadcaster::threshold_sign_and_broadcast(call).0,
-					expiry_time: contract_expiry,
-				});
+				// Earlier requests that exceeded the rate limit are processed first.
+				if Self::redemption_queue_len() == 0 &&
+					Self::try_consume_redemption_allowance(redeem_amount)
+				{
+					Self::register_redemption(account_id, redeem_amount, address, executor);
+				} else {
+					let position = Self::enqueue_redemption(QueuedRedemption {
+						account_id: account_id.clone(),
+						amount: redeem_amount,
+						address,
+						executor,
+						queued_at: frame_system::Pallet::<T>::block_number(),
+					});
+					Self::deposit_event(Event::RedemptionQueued {
+						account_id,
+						amount: redeem_amount,
+						position,
+					});
+				}
 			} else {
 				Self::deposit_event(Event::RedemptionAmountZero { account_id })
 			}
@@ -535,6 +799,10 @@ pub mod pallet {
 						.and_modify(|balance| *balance += amount)
 						.or_insert(amount);
 				});
+				// If the funds had been fully released, the schedule applies to them afresh.
+				OriginalRestrictedBalances::<T>::mutate(&account_id, |original_balances| {
+					original_balances.entry(address).or_insert(amount);
+				});
 			}
 
 			Self::deposit_event(Event::<T>::RedemptionExpired { account_id });
@@ -639,7 +907,10 @@ pub mod pallet {
 		) -> DispatchResultWithPostInfo {
 			T::EnsureGovernance::ensure_origin(origin)?;
 			for address in addresses_to_add {
-				RestrictedAddresses::<T>::insert(address, ());
+				// Keep the restriction of addresses that are already restricted.
+				RestrictedAddresses::<T>::mutate(address, |restriction| {
+					restriction.get_or_insert_with(Default::default);
+				});
 				Self::deposit_event(Event::AddedRestrictedAddress { address });
 			}
 			for address in addresses_to_remove {
@@ -650,6 +921,9 @@ pub mod pallet {
 							balances.remove(&address);
 						}
 					});
+					OriginalRestrictedBalances::<T>::mutate(&account_id, |balances| {
+						balances.remove(&address);
+					});
 				}
 				Self::deposit_event(Event::RemovedRestrictedAddress { address });
 			}
@@ -724,6 +998,254 @@ pub mod pallet {
 			});
 			Ok(().into())
 		}
+
+		/// Sets the commission a validator charges on the rewards earned by its delegators. A
+		/// validator only accepts delegations once it has set a commission.
+		///
+		/// ## Events
+		///
+		/// - [DelegationCommissionUpdated](Event::DelegationCommissionUpdated)
+		///
+		/// ## Errors
+		///
+		/// - [BadOrigin](frame_support::error::BadOrigin)
+		#[pallet::call_index(11)]
+		#[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+		pub fn set_delegation_commission(
+			origin: OriginFor<T>,
+			commission: Permill,
+		) -> DispatchResultWithPostInfo {
+			let operator = T::AccountRoleRegistry::ensure_validator(origin)?;
+			OperatorCommission::<T>::insert(&operator, commission);
+			Self::deposit_event(Event::DelegationCommissionUpdated { operator, commission });
+			Ok(().into())
+		}
+
+		/// Delegates FLIP to an operator. Delegated funds remain in the delegator's account but
+		/// count towards the operator's auction bid, so they can't be redeemed until they are
+		/// undelegated. Delegators earn a share of the operator's rewards, minus the operator's
+		/// commission, and are slashed pro-rata with the operator.
+		///
+		/// A delegator can only delegate to a single operator, but can add to its delegation.
+		///
+		/// ## Events
+		///
+		/// - [Delegated](Event::Delegated)
+		///
+		/// ## Errors
+		///
+		/// - [AuctionPhase](Error::AuctionPhase)
+		/// - [ValidatorsCannotDelegate](Error::ValidatorsCannotDelegate)
+		/// - [NotAnOperator](Error::NotAnOperator)
+		/// - [AlreadyDelegating](Error::AlreadyDelegating)
+		/// - [TooManyDelegators](Error::TooManyDelegators)
+		/// - [PendingRedemption](Error::PendingRedemption)
+		/// - [InsufficientBalance](Error::InsufficientBalance)
+		/// - [InsufficientUnrestrictedFunds](Error::InsufficientUnrestrictedFunds)
+		#[pallet::call_index(12)]
+		#[pallet::weight(T::DbWeight::get().reads_writes(9, 2))]
+		pub fn delegate(
+			origin: OriginFor<T>,
+			operator: AccountId<T>,
+			amount: FlipBalance<T>,
+		) -> DispatchResultWithPostInfo {
+			let delegator = ensure_signed(origin)?;
+
+			ensure!(!T::EpochInfo::is_auction_phase(), Error::<T>::AuctionPhase);
+			ensure!(!amount.is_zero(), Error::<T>::InvalidDelegationAmount);
+			ensure!(
+				!T::AccountRoleRegistry::has_account_role(&delegator, AccountRole::Validator),
+				Error::<T>::ValidatorsCannotDelegate
+			);
+			ensure!(OperatorCommission::<T>::contains_key(&operator), Error::<T>::NotAnOperator);
+			match DelegatedTo::<T>::get(&delegator) {
+				Some(current_operator) =>
+					ensure!(current_operator == operator, Error::<T>::AlreadyDelegating),
+				None => ensure!(
+					(Delegations::<T>::iter_prefix(&operator).count() as u32) <
+						T::MaxDelegatorsPerOperator::get(),
+					Error::<T>::TooManyDelegators
+				),
+			}
+			ensure!(
+				!PendingRedemptions::<T>::contains_key(&delegator),
+				Error::<T>::PendingRedemption
+			);
+
+			let total_delegation =
+				Delegations::<T>::get(&operator, &delegator).saturating_add(amount);
+			ensure!(
+				total_delegation <= T::Flip::balance(&delegator),
+				Error::<T>::InsufficientBalance
+			);
+			// Restricted funds can only be redeemed to their originating address, so they can't
+			// back a delegation.
+			ensure!(
+				total_delegation <=
+					T::Flip::balance(&delegator).saturating_sub(
+						RestrictedBalances::<T>::get(&delegator)
+							.values()
+							.copied()
+							.sum::<FlipBalance<T>>()
+					),
+				Error::<T>::InsufficientUnrestrictedFunds
+			);
+
+			Delegations::<T>::insert(&operator, &delegator, total_delegation);
+			DelegatedTo::<T>::insert(&delegator, &operator);
+
+			Self::deposit_event(Event::Delegated { delegator, operator, amount, total_delegation });
+			Ok(().into())
+		}
+
+		/// Withdraws a delegation in full, making the funds available for redemption.
+		///
+		/// Not possible during the auction phase, or while the operator is an authority. Operators
+		/// remain bonded until every epoch they were an authority in has expired, so this includes
+		/// the period between a rotation and the expiry of the previous epoch.
+		///
+		/// ## Events
+		///
+		/// - [Undelegated](Event::Undelegated)
+		///
+		/// ## Errors
+		///
+		/// - [AuctionPhase](Error::AuctionPhase)
+		/// - [NotDelegating](Error::NotDelegating)
+		/// - [OperatorIsAuthority](Error::OperatorIsAuthority)
+		#[pallet::call_index(13)]
+		#[pallet::weight(T::DbWeight::get().reads_writes(4, 2))]
+		pub fn undelegate(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+			let delegator = ensure_signed(origin)?;
+
+			ensure!(!T::EpochInfo::is_auction_phase(), Error::<T>::AuctionPhase);
+			let operator = DelegatedTo::<T>::get(&delegator).ok_or(Error::<T>::NotDelegating)?;
+			ensure!(
+				!T::EpochInfo::current_authorities().contains(&operator.clone().into()) &&
+					T::Flip::bond(&operator).is_zero(),
+				Error::<T>::OperatorIsAuthority
+			);
+
+			let amount = Self::remove_delegation(&delegator, &operator);
+
+			Self::deposit_event(Event::Undelegated { delegator, operator, amount });
+			Ok(().into())
+		}
+
+		/// Sets the maximum amount of FLIP that can be redeemed within a window of blocks.
+		/// Redemptions exceeding the limit are queued and registered in order once there is
+		/// capacity. Setting `None` removes the limit.
+		///
+		/// Requires Governance.
+		///
+		/// ## Events
+		///
+		/// - [RedemptionRateLimitUpdated](Event::RedemptionRateLimitUpdated)
+		///
+		/// ## Errors
+		///
+		/// - [InvalidRedemptionRateLimit](Error::InvalidRedemptionRateLimit)
+		/// - [BadOrigin](frame_support::error::BadOrigin)
+		#[pallet::call_index(14)]
+		#[pallet::weight(T::DbWeight::get().writes(1))]
+		pub fn update_redemption_rate_limit(
+			origin: OriginFor<T>,
+			rate_limit: Option<RedemptionRateLimit<BlockNumberFor<T>, FlipBalance<T>>>,
+		) -> DispatchResultWithPostInfo {
+			T::EnsureGovernance::ensure_origin(origin)?;
+			if let Some(RedemptionRateLimit { window, max_redeemed }) = rate_limit {
+				ensure!(
+					!window.is_zero() && !max_redeemed.is_zero(),
+					Error::<T>::InvalidRedemptionRateLimit
+				);
+			}
+			RedemptionRateLimitConfig::<T>::set(rate_limit);
+			Self::deposit_event(Event::RedemptionRateLimitUpdated { rate_limit });
+			Ok(().into())
+		}
+
+		/// Schedules part of an authority's bond to be released at the next rotation, so that it
+		/// can be redeemed before the authority's previous epochs expire. If the authority is part
+		/// of the new set, its bond is reduced by the unbonded amount, but never below the new
+		/// epoch's bond. Replaces any previously scheduled unbond.
+		///
+		/// ## Events
+		///
+		/// - [UnbondScheduled](Event::UnbondScheduled)
+		///
+		/// ## Errors
+		///
+		/// - [NotAnAuthority](Error::NotAnAuthority)
+		/// - [InvalidUnbondAmount](Error::InvalidUnbondAmount)
+		#[pallet::call_index(15)]
+		#[pallet::weight(T::DbWeight::get().reads_writes(4, 1))]
+		pub fn schedule_unbond(
+			origin: OriginFor<T>,
+			amount: FlipBalance<T>,
+		) -> DispatchResultWithPostInfo {
+			let account_id = T::AccountRoleRegistry::ensure_validator(origin)?;
+
+			ensure!(
+				T::EpochInfo::current_authorities().contains(&account_id.clone().into()),
+				Error::<T>::NotAnAuthority
+			);
+			ensure!(
+				!amount.is_zero() && amount <= T::Flip::bond(&account_id),
+				Error::<T>::InvalidUnbondAmount
+			);
+
+			ScheduledUnbonds::<T>::insert(&account_id, amount);
+			Self::deposit_event(Event::UnbondScheduled { account_id, amount });
+			Ok(().into())
+		}
+
+		/// Cancels a scheduled unbond.
+		///
+		/// ## Events
+		///
+		/// - [UnbondCancelled](Event::UnbondCancelled)
+		///
+		/// ## Errors
+		///
+		/// - [NoScheduledUnbond](Error::NoScheduledUnbond)
+		#[pallet::call_index(16)]
+		#[pallet::weight(T::DbWeight::get().reads_writes(2, 1))]
+		pub fn cancel_unbond(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+			let account_id = T::AccountRoleRegistry::ensure_validator(origin)?;
+
+			ScheduledUnbonds::<T>::take(&account_id).ok_or(Error::<T>::NoScheduledUnbond)?;
+			Self::deposit_event(Event::UnbondCancelled { account_id });
+			Ok(().into())
+		}
+
+		/// Sets the reason and unlock schedule of a restricted address, restricting the address
+		/// if it isn't already. Funds unlocked by the schedule can be redeemed to any address.
+		///
+		/// Requires Governance.
+		///
+		/// ## Events
+		///
+		/// - [RestrictionUpdated](Event::RestrictionUpdated)
+		///
+		/// ## Errors
+		///
+		/// - [InvalidUnlockSchedule](Error::InvalidUnlockSchedule)
+		/// - [BadOrigin](frame_support::error::BadOrigin)
+		#[pallet::call_index(17)]
+		#[pallet::weight(T::DbWeight::get().writes(1))]
+		pub fn set_restriction(
+			origin: OriginFor<T>,
+			address: EthereumAddress,
+			restriction: Restriction,
+		) -> DispatchResultWithPostInfo {
+			T::EnsureGovernance::ensure_origin(origin)?;
+			if let Some(unlock_schedule) = restriction.unlock_schedule {
+				ensure!(unlock_schedule.is_valid(), Error::<T>::InvalidUnlockSchedule);
+			}
+			RestrictedAddresses::<T>::insert(address, restriction);
+			Self::deposit_event(Event::RestrictionUpdated { address, restriction });
+			Ok(().into())
+		}
 	}
 
 	#[pallet::genesis_config]
@@ -794,6 +1316,285 @@ impl<T: Config> Pallet<T> {
 	}
 }
 
+impl<T: Config> Pallet<T> {
+	/// The portion of each restricted balance that hasn't been unlocked by the restriction's
+	/// unlock schedule, and so can only be redeemed to the restricted address. The schedule
+	/// applies to the original restricted amount, and the locked funds are capped by what is left.
+	fn locked_balances(
+		account_id: &AccountId<T>,
+		restricted_balances: &BTreeMap<EthereumAddress, FlipBalance<T>>,
+	) -> BTreeMap<EthereumAddress, FlipBalance<T>> {
+		let now = T::TimeSource::now().as_secs();
+		let original_balances = OriginalRestrictedBalances::<T>::get(account_id);
+		restricted_balances
+			.iter()
+			.map(|(address, balance)| {
+				let original = original_balances.get(address).copied().unwrap_or(*balance);
+				let unlocked = RestrictedAddresses::<T>::get(address)
+					.and_then(|restriction| restriction.unlock_schedule)
+					.map(|schedule| schedule.unlocked(original, now))
+					.unwrap_or_default();
+				(*address, original.saturating_sub(unlocked).min(*balance))
+			})
+			.collect()
+	}
+
+	/// The account's restricted balances by address, as the reason for the restriction, the total
+	/// restricted balance, and the amount that is currently unlocked.
+	pub fn restricted_balances(
+		account_id: &AccountId<T>,
+	) -> BTreeMap<EthereumAddress, (RestrictionReason, FlipBalance<T>, FlipBalance<T>)> {
+		let restricted_balances = RestrictedBalances::<T>::get(account_id);
+		let locked_balances = Self::locked_balances(account_id, &restricted_balances);
+		restricted_balances
+			.into_iter()
+			.map(|(address, balance)| {
+				let reason = RestrictedAddresses::<T>::get(address).unwrap_or_default().reason;
+				let locked = locked_balances.get(&address).copied().unwrap_or(balance);
+				(address, (reason, balance, balance.saturating_sub(locked)))
+			})
+			.collect()
+	}
+
+	/// Broadcasts the `RegisterRedemption` call for a redemption whose funds are already pending.
+	fn register_redemption(
+		account_id: AccountId<T>,
+		amount: FlipBalance<T>,
+		address: EthereumAddress,
+		executor: Option<EthereumAddress>,
+	) {
+		let contract_expiry = T::TimeSource::now().as_secs() + RedemptionTTLSeconds::<T>::get();
+		let call = T::RegisterRedemption::new_unsigned(
+			<T as Config>::FunderId::from_ref(&account_id).as_ref(),
+			amount.unique_saturated_into(),
+			address.as_fixed_bytes(),
+			contract_expiry,
+			executor,
+		);
+
+		Self::deposit_event(Event::RedemptionRequested {
+			account_id,
+			amount,
+			broadcast_id: T::Broadcaster::threshold_sign_and_broadcast(call).0,
+			expiry_time: contract_expiry,
+		});
+	}
+
+	/// Adds the amount to the current rate limiting window if there is capacity, starting a new
+	/// window if the current one has elapsed. A redemption larger than the limit is allowed if
+	/// nothing else has been redeemed in the window, so that it can't block the queue.
+	///
+	/// Returns false if the redemption should wait for the next window.
+	fn try_consume_redemption_allowance(amount: FlipBalance<T>) -> bool {
+		let Some(RedemptionRateLimit { window, max_redeemed }) =
+			RedemptionRateLimitConfig::<T>::get()
+		else {
+			return true
+		};
+		let current_block = frame_system::Pallet::<T>::block_number();
+		CurrentRedemptionWindow::<T>::mutate(|(started_at, redeemed)| {
+			if current_block >= started_at.saturating_add(window) {
+				*started_at = current_block;
+				*redeemed = Zero::zero();
+			}
+			if redeemed.is_zero() || redeemed.saturating_add(amount) <= max_redeemed {
+				redeemed.saturating_accrue(amount);
+				true
+			} else {
+				false
+			}
+		})
+	}
+
+	/// Registers queued redemptions, in order, for as long as the rate limit allows. Nothing is
+	/// registered while redemptions are disabled by Safe Mode.
+	///
+	/// Returns the number of redemptions registered.
+	fn process_redemption_queue() -> u32 {
+		let (mut head, tail) = RedemptionQueueIndices::<T>::get();
+		if head == tail || !T::SafeMode::get().redeem_enabled {
+			return 0
+		}
+
+		let mut processed = 0;
+		while head != tail && processed < MAX_QUEUED_REDEMPTIONS_PER_BLOCK {
+			let Some(QueuedRedemption { account_id, amount, address, executor, .. }) =
+				RedemptionQueue::<T>::get(head)
+			else {
+				break
+			};
+			if !Self::try_consume_redemption_allowance(amount) {
+				break
+			}
+			RedemptionQueue::<T>::remove(head);
+			QueuedRedemptionIndex::<T>::remove(&account_id);
+			head = head.wrapping_add(1);
+			processed += 1;
+			Self::register_redemption(account_id, amount, address, executor);
+		}
+
+		if processed > 0 {
+			RedemptionQueueIndices::<T>::put((head, tail));
+		}
+		processed
+	}
+
+	/// Adds a redemption to the back of the [RedemptionQueue]. Returns the number of redemptions
+	/// ahead of it.
+	fn enqueue_redemption(
+		queued: QueuedRedemption<AccountId<T>, FlipBalance<T>, BlockNumberFor<T>>,
+	) -> u32 {
+		let (head, tail) = RedemptionQueueIndices::<T>::get();
+		QueuedRedemptionIndex::<T>::insert(&queued.account_id, tail);
+		RedemptionQueue::<T>::insert(tail, queued);
+		RedemptionQueueIndices::<T>::put((head, tail.wrapping_add(1)));
+		tail.wrapping_sub(head)
+	}
+
+	/// The number of redemptions in the [RedemptionQueue].
+	pub fn redemption_queue_len() -> u32 {
+		let (head, tail) = RedemptionQueueIndices::<T>::get();
+		tail.wrapping_sub(head)
+	}
+
+	/// The position of the account's redemption in the redemption queue, if it has been queued.
+	pub fn redemption_queue_position(
+		account_id: &AccountId<T>,
+	) -> Option<RedemptionQueuePosition<FlipBalance<T>, BlockNumberFor<T>>> {
+		let index = QueuedRedemptionIndex::<T>::get(account_id)?;
+		let queued = RedemptionQueue::<T>::get(index)?;
+		let (head, _) = RedemptionQueueIndices::<T>::get();
+		let position = index.wrapping_sub(head);
+		Some(RedemptionQueuePosition {
+			position,
+			amount: queued.amount,
+			amount_ahead: (0..position)
+				.filter_map(|offset| RedemptionQueue::<T>::get(head.wrapping_add(offset)))
+				.fold(Zero::zero(), |total: FlipBalance<T>, ahead| {
+					total.saturating_add(ahead.amount)
+				}),
+			queued_at: queued.queued_at,
+		})
+	}
+
+	/// The amount the account has delegated to an operator, if any.
+	pub fn delegation_of(delegator: &AccountId<T>) -> FlipBalance<T> {
+		DelegatedTo::<T>::get(delegator)
+			.map(|operator| Delegations::<T>::get(operator, delegator))
+			.unwrap_or_default()
+	}
+
+	/// The total amount delegated to an operator.
+	pub fn delegated_stake(operator: &AccountId<T>) -> FlipBalance<T> {
+		Delegations::<T>::iter_prefix_values(operator).sum()
+	}
+
+	fn remove_delegation(delegator: &AccountId<T>, operator: &AccountId<T>) -> FlipBalance<T> {
+		DelegatedTo::<T>::remove(delegator);
+		Delegations::<T>::take(operator, delegator)
+	}
+
+	/// Splits an amount between an operator and its delegators in proportion to their stake. The
+	/// operator's share is returned first and includes any rounding remainder.
+	fn pro_rata_shares(
+		operator: &AccountId<T>,
+		amount: FlipBalance<T>,
+	) -> (FlipBalance<T>, Vec<(AccountId<T>, FlipBalance<T>)>) {
+		let delegations = Delegations::<T>::iter_prefix(operator).collect::<Vec<_>>();
+		let total_stake = T::Flip::balance(operator)
+			.saturating_add(delegations.iter().map(|(_, delegation)| *delegation).sum());
+		if total_stake.is_zero() {
+			return (amount, Default::default())
+		}
+
+		let delegator_shares = delegations
+			.into_iter()
+			.map(|(delegator, delegation)| {
+				let share = multiply_by_rational_with_rounding(
+					delegation.unique_saturated_into(),
+					amount.unique_saturated_into(),
+					total_stake.unique_saturated_into(),
+					Rounding::Down,
+				)
+				// Can't overflow: the delegation is part of the total stake, so the share is at
+				// most the amount.
+				.unwrap_or_default();
+				(delegator, share.unique_saturated_into())
+			})
+			.collect::<Vec<(AccountId<T>, FlipBalance<T>)>>();
+		let operator_share = amount.saturating_sub(
+			delegator_shares.iter().map(|(_, share)| *share).sum::<FlipBalance<T>>(),
+		);
+		(operator_share, delegator_shares)
+	}
+
+	/// Splits a reward earned by an operator with its delegators. Each delegator earns a share of
+	/// the reward in proportion to its stake, minus the operator's commission.
+	pub fn split_reward(
+		operator: &AccountId<T>,
+		reward: FlipBalance<T>,
+	) -> Vec<(AccountId<T>, FlipBalance<T>)> {
+		let commission = OperatorCommission::<T>::get(operator).unwrap_or_default();
+		let (mut operator_reward, delegator_shares) = Self::pro_rata_shares(operator, reward);
+		let mut rewards = delegator_shares
+			.into_iter()
+			.map(|(delegator, share)| {
+				let operator_commission = commission * share;
+				operator_reward.saturating_accrue(operator_commission);
+				(delegator, share - operator_commission)
+			})
+			.collect::<Vec<_>>();
+		rewards.insert(0, (operator.clone(), operator_reward));
+		rewards
+	}
+}
+
+/// Wraps a [Slashing] implementation so that slashes applied to an operator are shared with its
+/// delegators in proportion to their stake. Delegations are reduced by the amount slashed.
+pub struct DelegationSlasher<T, S>(PhantomData<(T, S)>);
+
+impl<T, S> Slashing for DelegationSlasher<T, S>
+where
+	T: Config,
+	S: Slashing<AccountId = AccountId<T>, Balance = FlipBalance<T>>,
+{
+	type AccountId = S::AccountId;
+	type BlockNumber = S::BlockNumber;
+	type Balance = S::Balance;
+
+	fn slash(account_id: &Self::AccountId, blocks: Self::BlockNumber) {
+		Self::slash_balance(
+			account_id,
+			S::calculate_slash_amount(account_id, blocks).unique_saturated_into(),
+		);
+	}
+
+	fn slash_balance(account_id: &Self::AccountId, slash_amount: cf_primitives::FlipBalance) {
+		let (operator_share, delegator_shares) =
+			Pallet::<T>::pro_rata_shares(account_id, slash_amount.unique_saturated_into());
+		S::slash_operator_balance(
+			account_id,
+			operator_share.unique_saturated_into(),
+			Pallet::<T>::delegated_stake(account_id).unique_saturated_into(),
+		);
+		for (delegator, share) in delegator_shares {
+			let balance_before = T::Flip::balance(&delegator);
+			S::slash_balance(&delegator, share.unique_saturated_into());
+			let slashed = balance_before.saturating_sub(T::Flip::balance(&delegator));
+			Delegations::<T>::mutate(account_id, &delegator, |delegation| {
+				delegation.saturating_reduce(slashed)
+			});
+		}
+	}
+
+	fn calculate_slash_amount(
+		account_id: &Self::AccountId,
+		blocks: Self::BlockNumber,
+	) -> Self::Balance {
+		S::calculate_slash_amount(account_id, blocks)
+	}
+}
+
 impl<T: Config> BidderProvider for Pallet<T> {
 	type ValidatorId = <T as frame_system::Config>::AccountId;
 	type Amount = T::Amount;
@@ -802,7 +1603,8 @@ impl<T: Config> BidderProvider for Pallet<T> {
 		ActiveBidder::<T>::iter()
 			.filter_map(|(bidder_id, active)| {
 				if active {
-					let amount = T::Flip::balance(&bidder_id);
+					let amount = T::Flip::balance(&bidder_id)
+						.saturating_add(Self::delegated_stake(&bidder_id));
 					Some(Bid { bidder_id, amount })
 				} else {
 					None
@@ -812,13 +1614,34 @@ impl<T: Config> BidderProvider for Pallet<T> {
 	}
 }
 
+impl<T: Config> UnbondingSchedule for Pallet<T> {
+	type ValidatorId = <T as Chainflip>::ValidatorId;
+	type Amount = T::Amount;
+
+	fn take_scheduled_unbonds() -> Vec<(Self::ValidatorId, Self::Amount)> {
+		ScheduledUnbonds::<T>::drain()
+			.map(|(account_id, amount)| (account_id.into(), amount))
+			.collect()
+	}
+}
+
 /// Ensure we clean up account specific items that definitely won't be required once the account
 /// leaves the network.
 impl<T: Config> OnKilledAccount<T::AccountId> for Pallet<T> {
 	fn on_killed_account(account_id: &T::AccountId) {
 		ActiveBidder::<T>::remove(account_id);
 		RestrictedBalances::<T>::remove(account_id);
+		OriginalRestrictedBalances::<T>::remove(account_id);
 		BoundExecutorAddress::<T>::remove(account_id);
 		BoundRedeemAddress::<T>::remove(account_id);
+		ScheduledUnbonds::<T>::remove(account_id);
+		if let Some(operator) = DelegatedTo::<T>::get(account_id) {
+			Self::remove_delegation(account_id, &operator);
+		}
+		// The account may be an operator, in which case its delegators are released.
+		OperatorCommission::<T>::remove(account_id);
+		for (delegator, _) in Delegations::<T>::drain_prefix(account_id) {
+			DelegatedTo::<T>::remove(delegator);
+		}
 	}
 }
@@ -0,0 +1,85 @@
+use crate::*;
+use frame_support::traits::OnRuntimeUpgrade;
+use sp_std::marker::PhantomData;
+
+#[cfg(feature = "try-runtime")]
+use codec::{Decode, Encode};
+#[cfg(feature = "try-runtime")]
+use frame_support::dispatch::DispatchError;
+
+/// Runtime Migration for migrating from V2 to V3: restricted addresses store the reason and
+/// unlock schedule of the restriction, and the original restricted balances are recorded.
+pub struct Migration<T: Config>(PhantomData<T>);
+
+mod old {
+
+	use super::*;
+
+	use frame_support::{pallet_prelude::ValueQuery, Blake2_128Concat};
+
+	#[frame_support::storage_alias]
+	pub type RestrictedAddresses<T: Config> =
+		StorageMap<Pallet<T>, Blake2_128Concat, EthereumAddress, (), ValueQuery>;
+}
+
+impl<T: Config> OnRuntimeUpgrade for Migration<T> {
+	fn on_runtime_upgrade() -> frame_support::weights::Weight {
+		RestrictedAddresses::<T>::translate::<(), _>(|_address, ()| Some(Default::default()));
+		for (account_id, restricted_balances) in RestrictedBalances::<T>::iter() {
+			OriginalRestrictedBalances::<T>::insert(account_id, restricted_balances);
+		}
+		Weight::zero()
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<Vec<u8>, DispatchError> {
+		Ok(old::RestrictedAddresses::<T>::iter_keys().collect::<Vec<_>>().encode())
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade(state: Vec<u8>) -> Result<(), DispatchError> {
+		let restricted_addresses = <Vec<EthereumAddress>>::decode(&mut &state[..])
+			.map_err(|_| "Failed to decode pre-upgrade state.")?;
+		for address in restricted_addresses {
+			ensure!(
+				RestrictedAddresses::<T>::get(address) == Some(Default::default()),
+				"Missing restricted address"
+			)
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod test_runtime_upgrade {
+	use super::*;
+	use mock::Test;
+
+	#[test]
+	fn test() {
+		let address = EthereumAddress::repeat_byte(1);
+		let restricted_balances = BTreeMap::from([(address, 1_000)]);
+
+		mock::new_test_ext().execute_with(|| {
+			// pre upgrade
+			old::RestrictedAddresses::<Test>::insert(address, ());
+			RestrictedBalances::<Test>::insert(mock::ALICE, &restricted_balances);
+
+			#[cfg(feature = "try-runtime")]
+			let state = Migration::<Test>::pre_upgrade().unwrap();
+
+			// upgrade
+			Migration::<Test>::on_runtime_upgrade();
+
+			// post upgrade
+			#[cfg(feature = "try-runtime")]
+			Migration::<Test>::post_upgrade(state).unwrap();
+
+			assert_eq!(
+				RestrictedAddresses::<Test>::get(address),
+				Some(Restriction { reason: RestrictionReason::Unspecified, unlock_schedule: None })
+			);
+			assert_eq!(OriginalRestrictedBalances::<Test>::get(mock::ALICE), restricted_balances);
+		});
+	}
+}
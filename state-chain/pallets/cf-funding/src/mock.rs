@@ -69,6 +69,7 @@ parameter_types! {
 
 parameter_types! {
 	pub const BlocksPerDay: u64 = 14400;
+	pub const MaxDelegatorsPerOperator: u32 = 2;
 }
 
 // Implement mock for RestrictionHandler
@@ -183,6 +184,7 @@ impl pallet_cf_funding::Config for Test {
 	type EnsureThresholdSigned = NeverFailingOriginCheck<Self>;
 	type SafeMode = MockRuntimeSafeMode;
 	type RegisterRedemption = MockRegisterRedemption;
+	type MaxDelegatorsPerOperator = MaxDelegatorsPerOperator;
 }
 
 pub const REDEMPTION_TTL_SECS: u64 = 10;
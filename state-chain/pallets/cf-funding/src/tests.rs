@@ -1,6 +1,7 @@
 use crate::{
 	mock::*, pallet, ActiveBidder, BoundExecutorAddress, Error, EthereumAddress,
 	PendingRedemptions, RedemptionAmount, RedemptionTax, RestrictedAddresses, RestrictedBalances,
+	Restriction,
 };
 use cf_primitives::FlipBalance;
 use cf_test_utilities::assert_event_sequence;
@@ -555,7 +556,7 @@ fn redemption_expiry_removes_redemption() {
 		const RESTRICTED_AMOUNT: u128 = 60;
 		const RESTRICTED_ADDRESS: EthereumAddress = EthereumAddress::repeat_byte(0x02);
 
-		RestrictedAddresses::<Test>::insert(RESTRICTED_ADDRESS, ());
+		RestrictedAddresses::<Test>::insert(RESTRICTED_ADDRESS, Restriction::default());
 		assert_ok!(Funding::funded(
 			RuntimeOrigin::root(),
 			ALICE,
@@ -657,7 +658,7 @@ fn restricted_funds_getting_recorded() {
 		const RESTRICTED_ADDRESS: EthereumAddress = H160([0xff; 20]);
 
 		// Add Address to list of restricted contracts
-		RestrictedAddresses::<Test>::insert(RESTRICTED_ADDRESS, ());
+		RestrictedAddresses::<Test>::insert(RESTRICTED_ADDRESS, Restriction::default());
 
 		// Add some funds, we use the zero address here to denote that we should be
 		// able to redeem to any address in future
@@ -698,7 +699,7 @@ fn restricted_funds_pay_redemption_tax() {
 		const UNRESTRICTED_AMOUNT: FlipBalance = 20;
 		const REDEEM_AMOUNT: FlipBalance = 10;
 
-		RestrictedAddresses::<Test>::insert(RESTRICTED_ADDRESS, ());
+		RestrictedAddresses::<Test>::insert(RESTRICTED_ADDRESS, Restriction::default());
 		assert_ok!(Funding::funded(
 			RuntimeOrigin::root(),
 			ALICE,
@@ -752,8 +753,8 @@ fn vesting_contracts_test_case() {
 		const CONTRACT_2_FUNDS: u128 = 800;
 		const EARNED_REWARDS: u128 = 100;
 		// Add contract address to list of restricted contracts
-		RestrictedAddresses::<Test>::insert(VESTING_CONTRACT_1, ());
-		RestrictedAddresses::<Test>::insert(VESTING_CONTRACT_2, ());
+		RestrictedAddresses::<Test>::insert(VESTING_CONTRACT_1, Restriction::default());
+		RestrictedAddresses::<Test>::insert(VESTING_CONTRACT_2, Restriction::default());
 		assert_ok!(Funding::funded(
 			RuntimeOrigin::root(),
 			ALICE,
@@ -821,8 +822,8 @@ fn can_withdraw_unrestricted_to_restricted() {
 		// Balances
 		const AMOUNT: u128 = 100;
 		// Add restricted addresses.
-		RestrictedAddresses::<Test>::insert(RESTRICTED_ADDRESS_1, ());
-		RestrictedAddresses::<Test>::insert(RESTRICTED_ADDRESS_2, ());
+		RestrictedAddresses::<Test>::insert(RESTRICTED_ADDRESS_1, Restriction::default());
+		RestrictedAddresses::<Test>::insert(RESTRICTED_ADDRESS_2, Restriction::default());
 		assert_ok!(Funding::funded(
 			RuntimeOrigin::root(),
 			ALICE,
@@ -854,7 +855,7 @@ fn can_withdrawal_also_free_funds_to_restricted_address() {
 		const UNRESTRICTED_ADDRESS: EthereumAddress = H160([0x03; 20]);
 		const AMOUNT_1: u128 = 100;
 		const AMOUNT_2: u128 = 50;
-		RestrictedAddresses::<Test>::insert(RESTRICTED_ADDRESS_1, ());
+		RestrictedAddresses::<Test>::insert(RESTRICTED_ADDRESS_1, Restriction::default());
 		assert_ok!(Funding::funded(
 			RuntimeOrigin::root(),
 			ALICE,
@@ -886,7 +887,7 @@ fn can_only_redeem_funds_to_bound_address() {
 		const BOUND_ADDRESS: EthereumAddress = H160([0x02; 20]);
 		const UNRESTRICTED_ADDRESS: EthereumAddress = H160([0x03; 20]);
 		const AMOUNT: u128 = 100;
-		RestrictedAddresses::<Test>::insert(RESTRICTED_ADDRESS_1, ());
+		RestrictedAddresses::<Test>::insert(RESTRICTED_ADDRESS_1, Restriction::default());
 		BoundRedeemAddress::<Test>::insert(ALICE, BOUND_ADDRESS);
 		assert_ok!(Funding::funded(
 			RuntimeOrigin::root(),
@@ -927,7 +928,7 @@ fn redeem_funds_until_restricted_balance_is_zero_and_then_redeem_to_redeem_addre
 		const REDEEM_ADDRESS: EthereumAddress = H160([0x02; 20]);
 		const UNRESTRICTED_ADDRESS: EthereumAddress = H160([0x03; 20]);
 		const AMOUNT: u128 = 100;
-		RestrictedAddresses::<Test>::insert(RESTRICTED_ADDRESS, ());
+		RestrictedAddresses::<Test>::insert(RESTRICTED_ADDRESS, Restriction::default());
 		BoundRedeemAddress::<Test>::insert(ALICE, REDEEM_ADDRESS);
 		assert_ok!(Funding::funded(
 			RuntimeOrigin::root(),
@@ -979,7 +980,7 @@ fn redeem_funds_to_restricted_address_overrides_bound_and_executor_restrictions(
 		const EXECUTOR_ADDRESS: EthereumAddress = H160([0x04; 20]);
 		const RANDOM_ADDRESS: EthereumAddress = H160([0x12; 20]);
 		const AMOUNT: u128 = 100;
-		RestrictedAddresses::<Test>::insert(RESTRICTED_ADDRESS, ());
+		RestrictedAddresses::<Test>::insert(RESTRICTED_ADDRESS, Restriction::default());
 		BoundRedeemAddress::<Test>::insert(ALICE, REDEEM_ADDRESS);
 		BoundExecutorAddress::<Test>::insert(ALICE, EXECUTOR_ADDRESS);
 
@@ -1050,8 +1051,8 @@ mod test_restricted_balances {
 		maybe_error: Option<E>,
 	) {
 		new_test_ext().execute_with(|| {
-			RestrictedAddresses::<Test>::insert(RESTRICTED_ADDRESS_1, ());
-			RestrictedAddresses::<Test>::insert(RESTRICTED_ADDRESS_2, ());
+			RestrictedAddresses::<Test>::insert(RESTRICTED_ADDRESS_1, Restriction::default());
+			RestrictedAddresses::<Test>::insert(RESTRICTED_ADDRESS_2, Restriction::default());
 
 			for (address, amount) in [
 				(RESTRICTED_ADDRESS_1, RESTRICTED_BALANCE_1),
@@ -1316,7 +1317,7 @@ mod test_restricted_balances {
 		new_test_ext().execute_with(|| {
 			const RESTRICTED_ADDRESS: EthereumAddress = H160([0x01; 20]);
 			const AMOUNT: u128 = 100;
-			RestrictedAddresses::<Test>::insert(RESTRICTED_ADDRESS, ());
+			RestrictedAddresses::<Test>::insert(RESTRICTED_ADDRESS, Restriction::default());
 			assert_ok!(Funding::funded(
 				RuntimeOrigin::root(),
 				ALICE,
@@ -1610,3 +1611,710 @@ fn detect_wrong_executor_address() {
 		);
 	});
 }
+
+mod delegation {
+	use super::*;
+	use crate::{DelegatedTo, DelegationSlasher, Delegations, OperatorCommission};
+	use cf_traits::{BidderProvider, Slashing};
+	use frame_support::traits::OnKilledAccount;
+	use sp_runtime::Permill;
+
+	const OPERATOR_FUNDS: FlipBalance = 100;
+	const DELEGATED_FUNDS: FlipBalance = 300;
+	const COMMISSION: Permill = Permill::from_percent(10);
+
+	type TestSlasher = DelegationSlasher<Test, pallet_cf_flip::FlipSlasher<Test>>;
+
+	fn fund(account_id: AccountId, amount: FlipBalance) {
+		assert_ok!(Funding::funded(
+			RuntimeOrigin::root(),
+			account_id,
+			amount,
+			ETH_ZERO_ADDRESS,
+			TX_HASH
+		));
+	}
+
+	fn setup_operator() {
+		fund(ALICE, OPERATOR_FUNDS);
+		assert_ok!(<MockAccountRoleRegistry as AccountRoleRegistry<Test>>::register_as_validator(
+			&ALICE
+		));
+		assert_ok!(Funding::start_bidding(RuntimeOrigin::signed(ALICE)));
+		assert_ok!(Funding::set_delegation_commission(RuntimeOrigin::signed(ALICE), COMMISSION));
+	}
+
+	fn setup_delegation() {
+		setup_operator();
+		fund(BOB, DELEGATED_FUNDS);
+		assert_ok!(Funding::delegate(RuntimeOrigin::signed(BOB), ALICE, DELEGATED_FUNDS));
+	}
+
+	#[test]
+	fn delegated_stake_counts_towards_operator_bid() {
+		new_test_ext().execute_with(|| {
+			setup_delegation();
+
+			assert_eq!(Delegations::<Test>::get(ALICE, BOB), DELEGATED_FUNDS);
+			assert_eq!(DelegatedTo::<Test>::get(BOB), Some(ALICE));
+			assert_eq!(
+				Funding::get_bidders()
+					.into_iter()
+					.find(|bid| bid.bidder_id == ALICE)
+					.map(|bid| bid.amount),
+				Some(OPERATOR_FUNDS + DELEGATED_FUNDS)
+			);
+			System::assert_has_event(RuntimeEvent::Funding(
+				crate::Event::DelegationCommissionUpdated {
+					operator: ALICE,
+					commission: COMMISSION,
+				},
+			));
+			System::assert_last_event(RuntimeEvent::Funding(crate::Event::Delegated {
+				delegator: BOB,
+				operator: ALICE,
+				amount: DELEGATED_FUNDS,
+				total_delegation: DELEGATED_FUNDS,
+			}));
+		});
+	}
+
+	#[test]
+	fn delegation_requirements() {
+		new_test_ext().execute_with(|| {
+			fund(BOB, DELEGATED_FUNDS);
+			assert_noop!(
+				Funding::delegate(RuntimeOrigin::signed(BOB), ALICE, DELEGATED_FUNDS),
+				Error::<Test>::NotAnOperator
+			);
+
+			setup_operator();
+			assert_noop!(
+				Funding::delegate(RuntimeOrigin::signed(BOB), ALICE, 0),
+				Error::<Test>::InvalidDelegationAmount
+			);
+			assert_noop!(
+				Funding::delegate(RuntimeOrigin::signed(BOB), ALICE, DELEGATED_FUNDS + 1),
+				Error::<Test>::InsufficientBalance
+			);
+			assert_noop!(
+				Funding::delegate(RuntimeOrigin::signed(CHARLIE), ALICE, MIN_FUNDING),
+				Error::<Test>::ValidatorsCannotDelegate
+			);
+
+			MockEpochInfo::set_is_auction_phase(true);
+			assert_noop!(
+				Funding::delegate(RuntimeOrigin::signed(BOB), ALICE, DELEGATED_FUNDS),
+				Error::<Test>::AuctionPhase
+			);
+			MockEpochInfo::set_is_auction_phase(false);
+
+			// Delegations can be topped up, but not beyond the delegator's balance.
+			assert_ok!(Funding::delegate(RuntimeOrigin::signed(BOB), ALICE, 100));
+			assert_ok!(Funding::delegate(RuntimeOrigin::signed(BOB), ALICE, 200));
+			assert_eq!(Delegations::<Test>::get(ALICE, BOB), DELEGATED_FUNDS);
+			assert_noop!(
+				Funding::delegate(RuntimeOrigin::signed(BOB), ALICE, 1),
+				Error::<Test>::InsufficientBalance
+			);
+
+			// Only one operator per delegator.
+			assert_ok!(Funding::set_delegation_commission(
+				RuntimeOrigin::signed(CHARLIE),
+				COMMISSION
+			));
+			assert_noop!(
+				Funding::delegate(RuntimeOrigin::signed(BOB), CHARLIE, 1),
+				Error::<Test>::AlreadyDelegating
+			);
+		});
+	}
+
+	#[test]
+	fn operator_delegator_limit_is_enforced() {
+		new_test_ext().execute_with(|| {
+			setup_operator();
+			let delegators = (0..=MaxDelegatorsPerOperator::get())
+				.map(|i| AccountId::new([0xd0 + i as u8; 32]))
+				.collect::<Vec<_>>();
+			let (last, others) = delegators.split_last().unwrap();
+			for delegator in others {
+				fund(delegator.clone(), DELEGATED_FUNDS);
+				assert_ok!(Funding::delegate(
+					RuntimeOrigin::signed(delegator.clone()),
+					ALICE,
+					DELEGATED_FUNDS
+				));
+			}
+			fund(last.clone(), DELEGATED_FUNDS);
+			assert_noop!(
+				Funding::delegate(RuntimeOrigin::signed(last.clone()), ALICE, DELEGATED_FUNDS),
+				Error::<Test>::TooManyDelegators
+			);
+		});
+	}
+
+	#[test]
+	fn delegated_funds_cannot_be_redeemed_until_undelegated() {
+		new_test_ext().execute_with(|| {
+			setup_delegation();
+
+			assert_noop!(
+				Funding::redeem(RuntimeOrigin::signed(BOB), 50.into(), ETH_DUMMY_ADDR, None),
+				Error::<Test>::DelegatedFundsLocked
+			);
+
+			// Can't undelegate while the operator is an authority.
+			MockEpochInfo::add_authorities(ALICE);
+			assert_noop!(
+				Funding::undelegate(RuntimeOrigin::signed(BOB)),
+				Error::<Test>::OperatorIsAuthority
+			);
+			MockEpochInfo::set_authorities(Default::default());
+
+			// Nor after a rotation, while the operator is still bonded for the previous epoch.
+			Bonder::<Test>::update_bond(&ALICE, 100);
+			assert_noop!(
+				Funding::undelegate(RuntimeOrigin::signed(BOB)),
+				Error::<Test>::OperatorIsAuthority
+			);
+			// The bond is released once the previous epoch expires.
+			Bonder::<Test>::update_bond(&ALICE, 0);
+
+			assert_ok!(Funding::undelegate(RuntimeOrigin::signed(BOB)));
+			assert!(!Delegations::<Test>::contains_key(ALICE, BOB));
+			assert!(!DelegatedTo::<Test>::contains_key(BOB));
+			assert_noop!(
+				Funding::undelegate(RuntimeOrigin::signed(BOB)),
+				Error::<Test>::NotDelegating
+			);
+			assert_ok!(Funding::redeem(
+				RuntimeOrigin::signed(BOB),
+				50.into(),
+				ETH_DUMMY_ADDR,
+				None
+			));
+		});
+	}
+
+	#[test]
+	fn rewards_are_split_by_commission() {
+		new_test_ext().execute_with(|| {
+			setup_delegation();
+			const REWARD: FlipBalance = 1_000;
+
+			// Bob holds 3/4 of the stake, minus Alice's 10% commission.
+			assert_eq!(Funding::split_reward(&ALICE, REWARD), vec![(ALICE, 325), (BOB, 675)]);
+
+			// Operators without delegators keep the full reward.
+			assert_eq!(Funding::split_reward(&CHARLIE, REWARD), vec![(CHARLIE, REWARD)]);
+			assert!(OperatorCommission::<Test>::get(CHARLIE).is_none());
+		});
+	}
+
+	#[test]
+	fn slashes_are_shared_pro_rata() {
+		new_test_ext().execute_with(|| {
+			setup_delegation();
+
+			TestSlasher::slash_balance(&ALICE, 40);
+
+			assert_eq!(Flip::total_balance_of(&ALICE), OPERATOR_FUNDS - 10);
+			assert_eq!(Flip::total_balance_of(&BOB), DELEGATED_FUNDS - 30);
+			assert_eq!(Delegations::<Test>::get(ALICE, BOB), DELEGATED_FUNDS - 30);
+		});
+	}
+
+	#[test]
+	fn operator_slashing_threshold_is_based_on_its_own_stake() {
+		new_test_ext().execute_with(|| {
+			setup_delegation();
+			// The bond is mostly backed by the delegated funds.
+			Bonder::<Test>::update_bond(&ALICE, OPERATOR_FUNDS + DELEGATED_FUNDS);
+
+			// Alice's share would take her below 20% of the full bond, but not below 20% of her
+			// own share of it.
+			TestSlasher::slash_balance(&ALICE, 200);
+
+			assert_eq!(Flip::total_balance_of(&ALICE), OPERATOR_FUNDS - 50);
+			assert_eq!(Flip::total_balance_of(&BOB), DELEGATED_FUNDS - 150);
+		});
+	}
+
+	#[test]
+	fn shares_are_calculated_exactly() {
+		new_test_ext().execute_with(|| {
+			const REWARD: FlipBalance = 3_000_000_000_000;
+			setup_operator();
+			fund(BOB, 2 * OPERATOR_FUNDS);
+			assert_ok!(Funding::delegate(RuntimeOrigin::signed(BOB), ALICE, 2 * OPERATOR_FUNDS));
+
+			// Bob holds exactly 2/3 of the stake, minus Alice's 10% commission.
+			assert_eq!(
+				Funding::split_reward(&ALICE, REWARD),
+				vec![(ALICE, 1_200_000_000_000), (BOB, 1_800_000_000_000)]
+			);
+		});
+	}
+
+	#[test]
+	fn restricted_funds_cannot_be_delegated() {
+		new_test_ext().execute_with(|| {
+			const RESTRICTED_ADDRESS: EthereumAddress = H160([0x02; 20]);
+			const RESTRICTED_FUNDS: FlipBalance = 200;
+			RestrictedAddresses::<Test>::insert(RESTRICTED_ADDRESS, Restriction::default());
+			setup_operator();
+			assert_ok!(Funding::funded(
+				RuntimeOrigin::root(),
+				BOB,
+				RESTRICTED_FUNDS,
+				RESTRICTED_ADDRESS,
+				TX_HASH
+			));
+			fund(BOB, DELEGATED_FUNDS - RESTRICTED_FUNDS);
+
+			assert_noop!(
+				Funding::delegate(RuntimeOrigin::signed(BOB), ALICE, DELEGATED_FUNDS),
+				Error::<Test>::InsufficientUnrestrictedFunds
+			);
+			assert_ok!(Funding::delegate(
+				RuntimeOrigin::signed(BOB),
+				ALICE,
+				DELEGATED_FUNDS - RESTRICTED_FUNDS
+			));
+		});
+	}
+
+	#[test]
+	fn delegations_are_removed_when_the_operator_account_is_killed() {
+		new_test_ext().execute_with(|| {
+			setup_delegation();
+
+			<Funding as OnKilledAccount<_>>::on_killed_account(&ALICE);
+
+			assert!(!OperatorCommission::<Test>::contains_key(ALICE));
+			assert!(!Delegations::<Test>::contains_key(ALICE, BOB));
+			assert!(!DelegatedTo::<Test>::contains_key(BOB));
+			assert_eq!(Funding::delegation_of(&BOB), 0);
+		});
+	}
+}
+
+mod redemption_rate_limit {
+	use super::*;
+	use crate::{
+		CurrentRedemptionWindow, QueuedRedemptionIndex, RedemptionQueuePosition,
+		RedemptionRateLimit, RedemptionRateLimitConfig,
+	};
+	use sp_runtime::AccountId32;
+
+	const DAVE: AccountId = AccountId32::new([0xd0; 32]);
+	const WINDOW: u64 = 10;
+	const MAX_REDEEMED: FlipBalance = 100;
+	const REDEMPTION: FlipBalance = 60;
+
+	fn fund(account_id: AccountId) {
+		assert_ok!(Funding::funded(
+			RuntimeOrigin::root(),
+			account_id,
+			1_000,
+			ETH_ZERO_ADDRESS,
+			TX_HASH
+		));
+	}
+
+	fn redeem(account_id: AccountId, amount: FlipBalance) {
+		assert_ok!(Funding::redeem(
+			RuntimeOrigin::signed(account_id),
+			amount.into(),
+			ETH_DUMMY_ADDR,
+			Default::default()
+		));
+	}
+
+	fn set_rate_limit() {
+		assert_ok!(Funding::update_redemption_rate_limit(
+			RuntimeOrigin::root(),
+			Some(RedemptionRateLimit { window: WINDOW, max_redeemed: MAX_REDEEMED }),
+		));
+	}
+
+	#[test]
+	fn redemptions_exceeding_the_limit_are_queued_until_the_next_window() {
+		new_test_ext()
+			.execute_with(|| {
+				set_rate_limit();
+				for account_id in [ALICE, BOB, DAVE] {
+					fund(account_id);
+				}
+
+				redeem(ALICE, REDEMPTION);
+				assert_eq!(MockBroadcaster::received_requests(), vec![REDEMPTION]);
+
+				redeem(BOB, REDEMPTION);
+				System::assert_last_event(RuntimeEvent::Funding(crate::Event::RedemptionQueued {
+					account_id: BOB,
+					amount: REDEMPTION,
+					position: 0,
+				}));
+				// The funds are pending even though the redemption hasn't been registered yet.
+				assert!(PendingRedemptions::<Test>::contains_key(BOB));
+				assert_eq!(Flip::total_balance_of(&BOB), 1_000 - REDEMPTION - REDEMPTION_TAX);
+				assert_eq!(MockBroadcaster::received_requests().len(), 1);
+
+				// Later requests are queued behind, even if they would fit within the limit.
+				redeem(DAVE, MAX_REDEEMED - REDEMPTION);
+				assert_eq!(
+					Funding::redemption_queue_position(&DAVE),
+					Some(RedemptionQueuePosition {
+						position: 1,
+						amount: MAX_REDEEMED - REDEMPTION,
+						amount_ahead: REDEMPTION,
+						queued_at: 1,
+					})
+				);
+				assert_eq!(Funding::redemption_queue_position(&ALICE), None);
+			})
+			.then_execute_at_block(WINDOW - 1, |_| {
+				assert_eq!(Funding::redemption_queue_len(), 2);
+			})
+			.then_execute_at_block(WINDOW, |_| {
+				assert_eq!(Funding::redemption_queue_len(), 0);
+				assert_eq!(Funding::redemption_queue_position(&DAVE), None);
+				assert!(!QueuedRedemptionIndex::<Test>::contains_key(DAVE));
+				assert_eq!(
+					MockBroadcaster::received_requests(),
+					vec![REDEMPTION, REDEMPTION, MAX_REDEEMED - REDEMPTION]
+				);
+				assert_eq!(CurrentRedemptionWindow::<Test>::get(), (WINDOW, MAX_REDEEMED));
+			});
+	}
+
+	#[test]
+	fn redemptions_larger_than_the_limit_use_a_whole_window() {
+		new_test_ext()
+			.execute_with(|| {
+				set_rate_limit();
+				fund(ALICE);
+				fund(BOB);
+
+				redeem(ALICE, REDEMPTION);
+				redeem(BOB, MAX_REDEEMED * 2);
+				assert_eq!(MockBroadcaster::received_requests(), vec![REDEMPTION]);
+			})
+			.then_execute_at_block(WINDOW, |_| {
+				assert_eq!(
+					MockBroadcaster::received_requests(),
+					vec![REDEMPTION, MAX_REDEEMED * 2]
+				);
+				assert_eq!(CurrentRedemptionWindow::<Test>::get(), (WINDOW, MAX_REDEEMED * 2));
+			});
+	}
+
+	#[test]
+	fn queue_is_not_processed_while_redemptions_are_disabled() {
+		new_test_ext()
+			.execute_with(|| {
+				set_rate_limit();
+				fund(ALICE);
+				fund(BOB);
+
+				redeem(ALICE, REDEMPTION);
+				redeem(BOB, REDEMPTION);
+				<MockRuntimeSafeMode as SetSafeMode<MockRuntimeSafeMode>>::set_code_red();
+			})
+			.then_execute_at_block(WINDOW, |_| {
+				assert_eq!(Funding::redemption_queue_len(), 1);
+				<MockRuntimeSafeMode as SetSafeMode<MockRuntimeSafeMode>>::set_code_green();
+			})
+			.then_execute_at_next_block(|_| {
+				assert_eq!(Funding::redemption_queue_len(), 0);
+				assert_eq!(MockBroadcaster::received_requests(), vec![REDEMPTION, REDEMPTION]);
+			});
+	}
+
+	#[test]
+	fn governance_can_update_the_rate_limit() {
+		new_test_ext()
+			.execute_with(|| {
+				assert_noop!(
+					Funding::update_redemption_rate_limit(RuntimeOrigin::signed(ALICE), None),
+					BadOrigin
+				);
+				for invalid_limit in [
+					RedemptionRateLimit { window: 0, max_redeemed: MAX_REDEEMED },
+					RedemptionRateLimit { window: WINDOW, max_redeemed: 0 },
+				] {
+					assert_noop!(
+						Funding::update_redemption_rate_limit(
+							RuntimeOrigin::root(),
+							Some(invalid_limit)
+						),
+						Error::<Test>::InvalidRedemptionRateLimit
+					);
+				}
+
+				set_rate_limit();
+				fund(ALICE);
+				fund(BOB);
+				redeem(ALICE, REDEMPTION);
+				redeem(BOB, REDEMPTION);
+
+				// Removing the limit releases the queue in the next block.
+				assert_ok!(Funding::update_redemption_rate_limit(RuntimeOrigin::root(), None));
+				System::assert_last_event(RuntimeEvent::Funding(
+					crate::Event::RedemptionRateLimitUpdated { rate_limit: None },
+				));
+				assert_eq!(RedemptionRateLimitConfig::<Test>::get(), None);
+			})
+			.then_execute_at_next_block(|_| {
+				assert_eq!(Funding::redemption_queue_len(), 0);
+				assert_eq!(MockBroadcaster::received_requests(), vec![REDEMPTION, REDEMPTION]);
+			});
+	}
+}
+
+mod unbonding {
+	use super::*;
+	use crate::ScheduledUnbonds;
+	use cf_traits::UnbondingSchedule;
+
+	const BOND: FlipBalance = 500;
+
+	fn setup_authority() {
+		assert_ok!(Funding::funded(RuntimeOrigin::root(), ALICE, 1_000, ETH_ZERO_ADDRESS, TX_HASH));
+		assert_ok!(<MockAccountRoleRegistry as AccountRoleRegistry<Test>>::register_as_validator(
+			&ALICE
+		));
+		MockEpochInfo::add_authorities(ALICE);
+		Bonder::<Test>::update_bond(&ALICE, BOND);
+	}
+
+	#[test]
+	fn authorities_can_schedule_and_cancel_unbonds() {
+		new_test_ext().execute_with(|| {
+			setup_authority();
+
+			assert_ok!(Funding::schedule_unbond(RuntimeOrigin::signed(ALICE), 100));
+			System::assert_last_event(RuntimeEvent::Funding(crate::Event::UnbondScheduled {
+				account_id: ALICE,
+				amount: 100,
+			}));
+			// Scheduling again replaces the previous unbond.
+			assert_ok!(Funding::schedule_unbond(RuntimeOrigin::signed(ALICE), 200));
+			assert_eq!(ScheduledUnbonds::<Test>::get(ALICE), Some(200));
+
+			assert_ok!(Funding::cancel_unbond(RuntimeOrigin::signed(ALICE)));
+			System::assert_last_event(RuntimeEvent::Funding(crate::Event::UnbondCancelled {
+				account_id: ALICE,
+			}));
+			assert_noop!(
+				Funding::cancel_unbond(RuntimeOrigin::signed(ALICE)),
+				Error::<Test>::NoScheduledUnbond
+			);
+		});
+	}
+
+	#[test]
+	fn unbond_requirements() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				Funding::schedule_unbond(RuntimeOrigin::signed(CHARLIE), 1),
+				Error::<Test>::NotAnAuthority
+			);
+
+			setup_authority();
+			for invalid_amount in [0, BOND + 1] {
+				assert_noop!(
+					Funding::schedule_unbond(RuntimeOrigin::signed(ALICE), invalid_amount),
+					Error::<Test>::InvalidUnbondAmount
+				);
+			}
+		});
+	}
+
+	#[test]
+	fn scheduled_unbonds_are_taken_at_rotation() {
+		new_test_ext().execute_with(|| {
+			setup_authority();
+			assert_ok!(Funding::schedule_unbond(RuntimeOrigin::signed(ALICE), BOND));
+
+			assert_eq!(Funding::take_scheduled_unbonds(), vec![(ALICE, BOND)]);
+			assert!(Funding::take_scheduled_unbonds().is_empty());
+		});
+	}
+}
+
+mod unlock_schedules {
+	use super::*;
+	use crate::{RestrictionReason, UnlockSchedule};
+	use cf_traits::mocks::time_source;
+	use std::{collections::BTreeMap, time::Duration};
+
+	const VESTING_CONTRACT: EthereumAddress = H160([0x01; 20]);
+	const UNRESTRICTED_ADDRESS: EthereumAddress = H160([0x03; 20]);
+	const SCHEDULE: UnlockSchedule = UnlockSchedule { start: 0, cliff: 100, end: 200 };
+	const VESTING: Restriction =
+		Restriction { reason: RestrictionReason::Vesting, unlock_schedule: Some(SCHEDULE) };
+
+	#[test]
+	fn funds_are_released_linearly_after_the_cliff() {
+		assert_eq!(SCHEDULE.unlocked(1_000u128, 99), 0);
+		assert_eq!(SCHEDULE.unlocked(1_000u128, 100), 500);
+		assert_eq!(SCHEDULE.unlocked(1_000u128, 150), 750);
+		assert_eq!(SCHEDULE.unlocked(1_000u128, 200), 1_000);
+		assert_eq!(SCHEDULE.unlocked(1_000u128, 1_000), 1_000);
+
+		assert!(SCHEDULE.is_valid());
+		assert!(!UnlockSchedule { start: 0, cliff: 300, end: 200 }.is_valid());
+		assert!(!UnlockSchedule { start: 200, cliff: 200, end: 200 }.is_valid());
+	}
+
+	#[test]
+	fn governance_can_set_restrictions() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				Funding::set_restriction(RuntimeOrigin::signed(ALICE), VESTING_CONTRACT, VESTING),
+				BadOrigin
+			);
+			assert_noop!(
+				Funding::set_restriction(
+					RuntimeOrigin::root(),
+					VESTING_CONTRACT,
+					Restriction {
+						reason: RestrictionReason::Vesting,
+						unlock_schedule: Some(UnlockSchedule { start: 0, cliff: 300, end: 200 }),
+					}
+				),
+				Error::<Test>::InvalidUnlockSchedule
+			);
+
+			assert_ok!(Funding::set_restriction(RuntimeOrigin::root(), VESTING_CONTRACT, VESTING));
+			System::assert_last_event(RuntimeEvent::Funding(crate::Event::RestrictionUpdated {
+				address: VESTING_CONTRACT,
+				restriction: VESTING,
+			}));
+			assert_eq!(RestrictedAddresses::<Test>::get(VESTING_CONTRACT), Some(VESTING));
+
+			// Restricting the address again doesn't reset the restriction.
+			assert_ok!(Funding::update_restricted_addresses(
+				RuntimeOrigin::root(),
+				vec![VESTING_CONTRACT],
+				vec![]
+			));
+			assert_eq!(RestrictedAddresses::<Test>::get(VESTING_CONTRACT), Some(VESTING));
+		});
+	}
+
+	#[test]
+	fn unlocked_funds_can_be_redeemed_to_any_address() {
+		new_test_ext().execute_with(|| {
+			const FUNDS: FlipBalance = 1_000;
+
+			assert_ok!(Funding::set_restriction(RuntimeOrigin::root(), VESTING_CONTRACT, VESTING));
+			assert_ok!(Funding::funded(
+				RuntimeOrigin::root(),
+				ALICE,
+				FUNDS,
+				VESTING_CONTRACT,
+				TX_HASH
+			));
+
+			// Nothing is unlocked before the cliff.
+			time_source::Mock::reset_to(Duration::from_secs(50));
+			assert_noop!(
+				Funding::redeem(
+					RuntimeOrigin::signed(ALICE),
+					100.into(),
+					UNRESTRICTED_ADDRESS,
+					Default::default()
+				),
+				Error::<Test>::InsufficientUnrestrictedFunds
+			);
+
+			time_source::Mock::reset_to(Duration::from_secs(150));
+			assert_eq!(
+				Funding::restricted_balances(&ALICE),
+				BTreeMap::from([(VESTING_CONTRACT, (RestrictionReason::Vesting, FUNDS, 750))])
+			);
+			assert_ok!(Funding::redeem(
+				RuntimeOrigin::signed(ALICE),
+				700.into(),
+				UNRESTRICTED_ADDRESS,
+				Default::default()
+			));
+			assert_ok!(Funding::redeemed(RuntimeOrigin::root(), ALICE, 700, TX_HASH));
+
+			// The remaining locked funds can't be redeemed yet.
+			assert_noop!(
+				Funding::redeem(
+					RuntimeOrigin::signed(ALICE),
+					100.into(),
+					UNRESTRICTED_ADDRESS,
+					Default::default()
+				),
+				Error::<Test>::InsufficientUnrestrictedFunds
+			);
+
+			// Once fully unlocked, everything can be redeemed.
+			time_source::Mock::reset_to(Duration::from_secs(200));
+			assert_ok!(Funding::redeem(
+				RuntimeOrigin::signed(ALICE),
+				RedemptionAmount::Max,
+				UNRESTRICTED_ADDRESS,
+				Default::default()
+			));
+			assert_eq!(Flip::total_balance_of(&ALICE), 0);
+		});
+	}
+
+	#[test]
+	fn redeeming_restricted_funds_does_not_speed_up_the_schedule() {
+		new_test_ext().execute_with(|| {
+			const FUNDS: FlipBalance = 1_000;
+
+			assert_ok!(Funding::set_restriction(RuntimeOrigin::root(), VESTING_CONTRACT, VESTING));
+			assert_ok!(Funding::funded(
+				RuntimeOrigin::root(),
+				ALICE,
+				FUNDS,
+				VESTING_CONTRACT,
+				TX_HASH
+			));
+
+			// 750 is unlocked, so 250 stays locked however much is redeemed to the contract.
+			time_source::Mock::reset_to(Duration::from_secs(150));
+			for _ in 0..2 {
+				assert_ok!(Funding::redeem(
+					RuntimeOrigin::signed(ALICE),
+					300.into(),
+					VESTING_CONTRACT,
+					Default::default()
+				));
+				assert_ok!(Funding::redeemed(RuntimeOrigin::root(), ALICE, 300, TX_HASH));
+			}
+			assert_eq!(Flip::total_balance_of(&ALICE), FUNDS - 2 * (300 + REDEMPTION_TAX));
+			assert_eq!(
+				Funding::restricted_balances(&ALICE),
+				BTreeMap::from([(VESTING_CONTRACT, (RestrictionReason::Vesting, 390, 140))])
+			);
+
+			assert_noop!(
+				Funding::redeem(
+					RuntimeOrigin::signed(ALICE),
+					136.into(),
+					UNRESTRICTED_ADDRESS,
+					Default::default()
+				),
+				Error::<Test>::InsufficientUnrestrictedFunds
+			);
+			assert_ok!(Funding::redeem(
+				RuntimeOrigin::signed(ALICE),
+				135.into(),
+				UNRESTRICTED_ADDRESS,
+				Default::default()
+			));
+			assert_eq!(Flip::total_balance_of(&ALICE), 250);
+		});
+	}
+}
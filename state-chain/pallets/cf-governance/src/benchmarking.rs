@@ -116,5 +116,51 @@ benchmarks! {
 		PreAuthorisedGovCalls::<T>::insert(1, call.encode());
 	}: _(RawOrigin::Signed(caller.clone()), 1)
 
+	set_call_timelock {
+		let call = Call::<T>::set_call_timelock { pallet_name: Some(b"Swapping".to_vec()), timelock: 100 };
+	}: {
+		call.dispatch_bypass_filter(T::EnsureGovernance::try_successful_origin().unwrap())?;
+	}
+	verify {
+		assert_eq!(CallTimelocks::<T>::get(b"Swapping".to_vec()), Some(100));
+	}
+
+	cancel_proposal {
+		let call: <T as Config>::RuntimeCall = frame_system::Call::remark{remark: vec![]}.into();
+		let cancel = Call::<T>::cancel_proposal { proposal_id: 1 };
+		TimelockedProposals::<T>::insert(1, TimelockedProposal {
+			call: call.encode(),
+			approved: Default::default(),
+			execution: ExecutionMode::Automatic,
+			unlocks_at: 100,
+		});
+		TimelockQueue::<T>::try_append((100, 1)).unwrap();
+	}: {
+		cancel.dispatch_bypass_filter(T::EnsureGovernance::try_successful_origin().unwrap())?;
+	}
+	verify {
+		assert!(!TimelockedProposals::<T>::contains_key(1));
+		assert!(TimelockQueue::<T>::get().is_empty());
+	}
+
+	release_timelocked_proposals {
+		let b in 0 .. MAX_TIMELOCKED_PROPOSALS;
+		let call: <T as Config>::RuntimeCall = frame_system::Call::remark{remark: vec![]}.into();
+		for proposal_id in 0 .. b {
+			TimelockedProposals::<T>::insert(proposal_id, TimelockedProposal {
+				call: call.encode(),
+				approved: Default::default(),
+				execution: ExecutionMode::Automatic,
+				unlocks_at: 0,
+			});
+			TimelockQueue::<T>::try_append((0, proposal_id)).unwrap();
+		}
+	}: {
+		Pallet::<T>::release_timelocked_proposals();
+	}
+	verify {
+		assert_eq!(ExecutionPipeline::<T>::decode_len().unwrap_or_default(), b as usize);
+	}
+
 	impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test,);
 }
@@ -12,8 +12,12 @@ use frame_support::{
 	pallet_prelude::DispatchResultWithPostInfo,
 	sp_runtime::{DispatchError, Percent, TransactionOutcome},
 	storage::with_transaction,
-	traits::{EnsureOrigin, Get, OnRuntimeUpgrade, StorageVersion, UnixTime},
+	traits::{
+		EnsureOrigin, Get, GetCallMetadata, IsSubType, OnRuntimeUpgrade, StorageVersion, UnixTime,
+	},
+	BoundedVec,
 };
+use frame_system::pallet_prelude::BlockNumberFor;
 pub use pallet::*;
 use sp_std::{boxed::Box, ops::Add, vec::Vec};
 
@@ -27,6 +31,9 @@ pub type GovCallHash = [u8; 32];
 
 pub const PALLET_VERSION: StorageVersion = StorageVersion::new(1);
 
+/// The maximum number of proposals that can be timelocked at once.
+pub const MAX_TIMELOCKED_PROPOSALS: u32 = 100;
+
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
@@ -46,7 +53,7 @@ pub mod pallet {
 		dispatch::GetDispatchInfo,
 		error::BadOrigin,
 		pallet_prelude::*,
-		traits::{UnfilteredDispatchable, UnixTime},
+		traits::{GetCallMetadata, IsSubType, UnfilteredDispatchable, UnixTime},
 	};
 	use frame_system::pallet_prelude::*;
 	use sp_std::{boxed::Box, collections::btree_set::BTreeSet, vec::Vec};
@@ -77,9 +84,83 @@ pub mod pallet {
 		pub execution: ExecutionMode,
 	}
 
+	/// An approved proposal waiting for its timelock to end before it can be executed.
+	#[derive(Encode, Decode, TypeInfo, Clone, RuntimeDebug, PartialEq, Eq)]
+	pub struct TimelockedProposal<AccountId> {
+		/// Encoded representation of a extrinsic.
+		pub call: OpaqueCall,
+		/// Accounts who approved the proposal.
+		pub approved: BTreeSet<AccountId>,
+		/// Proposal is pre authorised.
+		pub execution: ExecutionMode,
+		/// The time at which the timelock ends.
+		pub unlocks_at: Timestamp,
+	}
+
+	/// The stage of a proposal that has not yet been executed.
+	#[derive(Encode, Decode, TypeInfo, Clone, Copy, RuntimeDebug, PartialEq, Eq)]
+	pub enum ProposalStatus {
+		/// The proposal is collecting approvals.
+		AwaitingApproval { expiry_time: Timestamp },
+		/// The proposal was approved and will be executed once the timelock ends.
+		Timelocked { unlocks_at: Timestamp },
+		/// The proposal was approved and pre-authorised, waiting to be dispatched by a member.
+		AwaitingDispatch,
+	}
+
+	/// The pallet and function name of a proposed call.
+	#[derive(Encode, Decode, TypeInfo, Clone, RuntimeDebug, PartialEq, Eq)]
+	pub struct CallName {
+		pub pallet: Vec<u8>,
+		pub function: Vec<u8>,
+	}
+
+	/// A summary of a proposal that has not yet been executed.
+	#[derive(Encode, Decode, TypeInfo, Clone, RuntimeDebug, PartialEq, Eq)]
+	pub struct ProposalPreview<AccountId> {
+		pub proposal_id: ProposalId,
+		pub status: ProposalStatus,
+		/// The name of the proposed call, if it can be decoded.
+		pub call_name: Option<CallName>,
+		/// Encoded representation of a extrinsic.
+		pub call: OpaqueCall,
+		/// Accounts who have approved the proposal.
+		pub approved: BTreeSet<AccountId>,
+	}
+
+	/// How a proposal was resolved.
+	#[derive(Encode, Decode, TypeInfo, Clone, RuntimeDebug, PartialEq, Eq)]
+	pub enum ProposalOutcome {
+		/// The proposal has not been resolved yet.
+		Pending,
+		/// The proposed call was dispatched, with the given result.
+		Executed { result: Result<(), DispatchError> },
+		/// The proposed call could not be decoded.
+		DecodeOfCallFailed,
+		/// The proposal did not raise enough approvals before its expiry.
+		Expired,
+		/// The proposal was cancelled before it was executed.
+		Cancelled,
+	}
+
+	/// The audit record of a proposal.
+	#[derive(Encode, Decode, TypeInfo, Clone, RuntimeDebug, PartialEq, Eq)]
+	pub struct ProposalRecord<AccountId, BlockNumber> {
+		pub proposer: AccountId,
+		/// Blake2-256 hash of the encoded call.
+		pub call_hash: GovCallHash,
+		pub call_name: CallName,
+		pub proposed_at: BlockNumber,
+		/// Accounts who approved the proposal, with the block of their approval.
+		pub approvals: Vec<(AccountId, BlockNumber)>,
+		pub outcome: ProposalOutcome,
+		/// The block at which the outcome was recorded.
+		pub resolved_at: Option<BlockNumber>,
+	}
+
 	type AccountId<T> = <T as frame_system::Config>::AccountId;
 	type OpaqueCall = Vec<u8>;
-	type Timestamp = u64;
+	pub type Timestamp = u64;
 
 	#[pallet::config]
 	#[pallet::disable_frame_system_supertrait_check]
@@ -95,7 +176,9 @@ pub mod pallet {
 			+ UnfilteredDispatchable<RuntimeOrigin = <Self as Config>::RuntimeOrigin>
 			+ From<frame_system::Call<Self>>
 			+ From<Call<Self>>
-			+ GetDispatchInfo;
+			+ GetDispatchInfo
+			+ GetCallMetadata
+			+ IsSubType<Call<Self>>;
 		/// UnixTime implementation for TimeSource
 		type TimeSource: UnixTime;
 		/// Benchmark weights
@@ -108,6 +191,9 @@ pub mod pallet {
 		type CompatibleCfeVersions: CompatibleCfeVersions;
 		/// For getting current authorities' CFE versions.
 		type AuthoritiesCfeVersions: AuthoritiesCfeVersions;
+		/// The number of most recent proposals kept in the [AuditLog].
+		#[pallet::constant]
+		type MaxAuditLogLength: Get<u32>;
 	}
 
 	#[pallet::pallet]
@@ -162,6 +248,35 @@ pub mod pallet {
 	#[pallet::getter(fn members)]
 	pub(super) type Members<T> = StorageValue<_, BTreeSet<AccountId<T>>, ValueQuery>;
 
+	/// Time in seconds between the approval of a proposal and its execution, by the name of the
+	/// pallet of the proposed call. Calls to other pallets use the [DefaultTimelock].
+	#[pallet::storage]
+	pub type CallTimelocks<T> = StorageMap<_, Blake2_128Concat, Vec<u8>, Timestamp, OptionQuery>;
+
+	/// Time in seconds between the approval of a proposal and its execution, for calls to pallets
+	/// that don't have a timelock set in [CallTimelocks].
+	#[pallet::storage]
+	pub type DefaultTimelock<T> = StorageValue<_, Timestamp, ValueQuery>;
+
+	/// Audit records of the most recent proposals, bounded by [Config::MaxAuditLogLength].
+	#[pallet::storage]
+	pub type AuditLog<T: Config> =
+		StorageMap<_, Twox64Concat, ProposalId, ProposalRecord<T::AccountId, BlockNumberFor<T>>>;
+
+	/// Approved proposals waiting for their timelock to end.
+	#[pallet::storage]
+	pub(super) type TimelockedProposals<T: Config> =
+		StorageMap<_, Blake2_128Concat, ProposalId, TimelockedProposal<T::AccountId>>;
+
+	/// The ids of the [TimelockedProposals], sorted by the time at which their timelock ends, so
+	/// that only the proposals that are due need to be read each block.
+	#[pallet::storage]
+	pub(super) type TimelockQueue<T> = StorageValue<
+		_,
+		BoundedVec<(Timestamp, ProposalId), ConstU32<MAX_TIMELOCKED_PROPOSALS>>,
+		ValueQuery,
+	>;
+
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
 		/// on_initialize hook - check the ActiveProposals
@@ -169,8 +284,9 @@ pub mod pallet {
 		fn on_initialize(_n: BlockNumberFor<T>) -> Weight {
 			// Check expiry and expire the proposals if needed
 			let active_proposal_weight = Self::check_expiry();
+			let timelock_weight = Self::release_timelocked_proposals();
 			let execution_weight = Self::execute_pending_proposals();
-			active_proposal_weight + execution_weight
+			active_proposal_weight + timelock_weight + execution_weight
 		}
 	}
 
@@ -195,6 +311,15 @@ pub mod pallet {
 		GovKeyCallHashWhitelisted { call_hash: GovCallHash },
 		/// Failed GovKey call
 		GovKeyCallExecutionFailed { call_hash: GovCallHash, error: DispatchError },
+		/// An approved proposal will be executed once its timelock ends.
+		TimelockStarted { proposal_id: ProposalId, unlocks_at: Timestamp },
+		/// The timelock of an approved proposal has ended.
+		TimelockEnded { proposal_id: ProposalId },
+		/// A proposal was cancelled before it was executed.
+		ProposalCancelled { proposal_id: ProposalId },
+		/// The timelock for calls to a pallet was updated. No pallet name means the default
+		/// timelock was updated.
+		CallTimelockUpdated { pallet_name: Option<Vec<u8>>, timelock: Timestamp },
 	}
 
 	#[pallet::error]
@@ -215,6 +340,8 @@ pub mod pallet {
 		CallHashNotWhitelisted,
 		/// Insufficient number of CFEs are at the target version to receive the runtime upgrade.
 		NotEnoughAuthoritiesCfesAtTargetVersion,
+		/// The proposal can't be timelocked because too many proposals are already timelocked.
+		TooManyTimelockedProposals,
 	}
 
 	#[pallet::call]
@@ -238,7 +365,10 @@ pub mod pallet {
 			let who = ensure_signed(origin)?;
 			ensure!(Members::<T>::get().contains(&who), Error::<T>::NotMember);
 
+			let call_hash = frame_support::Hashable::blake2_256(&*call);
+			let call_name = Self::call_name_of(&call);
 			let id = Self::push_proposal(call, execution);
+			Self::record_proposal(id, who.clone(), call_hash, call_name);
 			Self::deposit_event(Event::Proposed(id));
 
 			Self::inner_approve(who, id)?;
@@ -428,7 +558,14 @@ pub mod pallet {
 			ensure!(Members::<T>::get().contains(&who), Error::<T>::NotMember);
 			if let Some(call) = PreAuthorisedGovCalls::<T>::take(approved_id) {
 				if let Ok(call) = <T as Config>::RuntimeCall::decode(&mut &(*call)) {
-					Self::deposit_event(match Self::dispatch_governance_call(call) {
+					let result = Self::dispatch_governance_call(call);
+					Self::record_outcome(
+						approved_id,
+						ProposalOutcome::Executed {
+							result: result.map(|_| ()).map_err(|err| err.error),
+						},
+					);
+					Self::deposit_event(match result {
 						Ok(_) => Event::Executed(approved_id),
 						Err(err) => Event::FailedExecution(err.error),
 					});
@@ -440,6 +577,68 @@ pub mod pallet {
 				Err(Error::<T>::ProposalNotFound.into())
 			}
 		}
+
+		/// **Can only be called via the Governance Origin**
+		///
+		/// Sets the time in seconds between the approval of a proposal and its execution, for
+		/// proposed calls to the given pallet. If no pallet name is provided, sets the default
+		/// timelock for calls to pallets without their own timelock.
+		///
+		/// ## Events
+		///
+		/// - [CallTimelockUpdated](Event::CallTimelockUpdated)
+		///
+		/// ## Errors
+		///
+		/// - [BadOrigin](frame_support::error::BadOrigin)
+		#[pallet::call_index(8)]
+		#[pallet::weight(T::DbWeight::get().writes(1))]
+		pub fn set_call_timelock(
+			origin: OriginFor<T>,
+			pallet_name: Option<Vec<u8>>,
+			timelock: Timestamp,
+		) -> DispatchResult {
+			T::EnsureGovernance::ensure_origin(origin)?;
+			match pallet_name {
+				Some(ref pallet_name) => CallTimelocks::<T>::insert(pallet_name, timelock),
+				None => DefaultTimelock::<T>::put(timelock),
+			}
+			Self::deposit_event(Event::CallTimelockUpdated { pallet_name, timelock });
+			Ok(())
+		}
+
+		/// **Can only be called via the Governance Origin**
+		///
+		/// Cancels a proposal that has not yet been executed, whether it is still awaiting
+		/// approval, timelocked, or pre-authorised. Proposals to cancel other proposals are not
+		/// subject to a timelock.
+		///
+		/// ## Events
+		///
+		/// - [ProposalCancelled](Event::ProposalCancelled)
+		///
+		/// ## Errors
+		///
+		/// - [BadOrigin](frame_support::error::BadOrigin)
+		/// - [ProposalNotFound](Error::ProposalNotFound)
+		#[pallet::call_index(9)]
+		#[pallet::weight(T::DbWeight::get().reads_writes(5, 5))]
+		pub fn cancel_proposal(origin: OriginFor<T>, proposal_id: ProposalId) -> DispatchResult {
+			T::EnsureGovernance::ensure_origin(origin)?;
+			ensure!(
+				Proposals::<T>::take(proposal_id).is_some() ||
+					TimelockedProposals::<T>::take(proposal_id).is_some() ||
+					PreAuthorisedGovCalls::<T>::take(proposal_id).is_some(),
+				Error::<T>::ProposalNotFound
+			);
+			ActiveProposals::<T>::mutate(|proposals| {
+				proposals.retain(|active_proposal| active_proposal.proposal_id != proposal_id)
+			});
+			TimelockQueue::<T>::mutate(|queue| queue.retain(|(_, id)| *id != proposal_id));
+			Self::record_outcome(proposal_id, ProposalOutcome::Cancelled);
+			Self::deposit_event(Event::ProposalCancelled { proposal_id });
+			Ok(())
+		}
 	}
 
 	/// Genesis definition
@@ -509,9 +708,14 @@ impl<T: Config> Pallet<T> {
 		let proposal = Proposals::<T>::try_mutate(approved_id, |proposal| {
 			let proposal = proposal.as_mut().ok_or(Error::<T>::ProposalNotFound)?;
 
-			if !proposal.approved.insert(who) {
+			if !proposal.approved.insert(who.clone()) {
 				return Err(Error::<T>::AlreadyApproved)
 			}
+			AuditLog::<T>::mutate(approved_id, |record| {
+				if let Some(record) = record {
+					record.approvals.push((who, frame_system::Pallet::<T>::block_number()));
+				}
+			});
 			Self::deposit_event(Event::Approved(approved_id));
 			Ok(proposal.clone())
 		})?;
@@ -519,10 +723,30 @@ impl<T: Config> Pallet<T> {
 		if proposal.approved.len() >
 			(Members::<T>::decode_len().ok_or(Error::<T>::DecodeMembersLenFailed)? / 2)
 		{
-			if proposal.execution == ExecutionMode::Manual {
-				PreAuthorisedGovCalls::<T>::insert(approved_id, proposal.call);
+			let timelock = Self::timelock_for(&proposal.call);
+			if timelock == 0 {
+				Self::schedule_execution(approved_id, proposal.call, proposal.execution);
 			} else {
-				ExecutionPipeline::<T>::append((proposal.call, approved_id));
+				let unlocks_at = T::TimeSource::now().as_secs().saturating_add(timelock);
+				TimelockQueue::<T>::try_mutate(|queue| {
+					let index = queue.partition_point(|(time, _)| *time <= unlocks_at);
+					queue
+						.try_insert(index, (unlocks_at, approved_id))
+						.map_err(|_| Error::<T>::TooManyTimelockedProposals)
+				})?;
+				TimelockedProposals::<T>::insert(
+					approved_id,
+					TimelockedProposal {
+						call: proposal.call,
+						approved: proposal.approved,
+						execution: proposal.execution,
+						unlocks_at,
+					},
+				);
+				Self::deposit_event(Event::TimelockStarted {
+					proposal_id: approved_id,
+					unlocks_at,
+				});
 			}
 			Proposals::<T>::remove(approved_id);
 			ActiveProposals::<T>::mutate(|proposals| {
@@ -532,6 +756,60 @@ impl<T: Config> Pallet<T> {
 		Ok(())
 	}
 
+	fn schedule_execution(proposal_id: ProposalId, call: Vec<u8>, execution: ExecutionMode) {
+		if execution == ExecutionMode::Manual {
+			PreAuthorisedGovCalls::<T>::insert(proposal_id, call);
+		} else {
+			ExecutionPipeline::<T>::append((call, proposal_id));
+		}
+	}
+
+	/// The timelock that applies to an encoded call, based on the pallet it belongs to.
+	/// Cancellations are never timelocked, so that they can take effect before the proposal they
+	/// cancel is executed.
+	fn timelock_for(call: &[u8]) -> Timestamp {
+		match <T as Config>::RuntimeCall::decode(&mut &call[..]) {
+			Ok(call) if matches!(call.is_sub_type(), Some(Call::cancel_proposal { .. })) => 0,
+			Ok(call) => CallTimelocks::<T>::get(call.get_call_metadata().pallet_name.as_bytes())
+				.unwrap_or_else(DefaultTimelock::<T>::get),
+			Err(_) => DefaultTimelock::<T>::get(),
+		}
+	}
+
+	/// Summaries of all proposals that have not yet been executed, ordered by proposal id.
+	pub fn proposal_previews() -> Vec<ProposalPreview<T::AccountId>> {
+		let mut previews = ActiveProposals::<T>::get()
+			.into_iter()
+			.filter_map(|ActiveProposal { proposal_id, expiry_time }| {
+				Proposals::<T>::get(proposal_id).map(|proposal| ProposalPreview {
+					proposal_id,
+					status: ProposalStatus::AwaitingApproval { expiry_time },
+					call_name: Self::call_name(&proposal.call),
+					call: proposal.call,
+					approved: proposal.approved,
+				})
+			})
+			.chain(TimelockedProposals::<T>::iter().map(|(proposal_id, proposal)| {
+				ProposalPreview {
+					proposal_id,
+					status: ProposalStatus::Timelocked { unlocks_at: proposal.unlocks_at },
+					call_name: Self::call_name(&proposal.call),
+					call: proposal.call,
+					approved: proposal.approved,
+				}
+			}))
+			.chain(PreAuthorisedGovCalls::<T>::iter().map(|(proposal_id, call)| ProposalPreview {
+				proposal_id,
+				status: ProposalStatus::AwaitingDispatch,
+				call_name: Self::call_name(&call),
+				call,
+				approved: Default::default(),
+			}))
+			.collect::<Vec<_>>();
+		previews.sort_by_key(|preview| preview.proposal_id);
+		previews
+	}
+
 	pub fn compute_gov_key_call_hash<CallData>(data: CallData) -> (GovCallHash, u32)
 	where
 		CallData: Clone + Codec,
@@ -555,17 +833,44 @@ impl<T: Config> Pallet<T> {
 		Self::expire_proposals(expired) + T::WeightInfo::on_initialize(num_proposals as u32)
 	}
 
+	fn release_timelocked_proposals() -> Weight {
+		let now = T::TimeSource::now().as_secs();
+		let queue = TimelockQueue::<T>::get();
+		let num_unlocked = queue.partition_point(|(unlocks_at, _)| *unlocks_at <= now);
+		if num_unlocked > 0 {
+			let (unlocked, remaining) = queue.split_at(num_unlocked);
+			for &(_, proposal_id) in unlocked {
+				if let Some(TimelockedProposal { call, execution, .. }) =
+					TimelockedProposals::<T>::take(proposal_id)
+				{
+					Self::schedule_execution(proposal_id, call, execution);
+					Self::deposit_event(Event::TimelockEnded { proposal_id });
+				}
+			}
+			TimelockQueue::<T>::put(BoundedVec::truncate_from(remaining.to_vec()));
+		}
+		T::DbWeight::get().reads_writes(num_unlocked as u64 + 2, num_unlocked as u64 + 2)
+	}
+
 	fn execute_pending_proposals() -> Weight {
 		let mut execution_weight = Weight::zero();
 		for (call, id) in ExecutionPipeline::<T>::take() {
 			Self::deposit_event(
 				if let Ok(call) = <T as Config>::RuntimeCall::decode(&mut &(*call)) {
 					execution_weight.saturating_accrue(call.get_dispatch_info().weight);
-					match Self::dispatch_governance_call(call) {
+					let result = Self::dispatch_governance_call(call);
+					Self::record_outcome(
+						id,
+						ProposalOutcome::Executed {
+							result: result.map(|_| ()).map_err(|err| err.error),
+						},
+					);
+					match result {
 						Ok(_) => Event::Executed(id),
 						Err(err) => Event::FailedExecution(err.error),
 					}
 				} else {
+					Self::record_outcome(id, ProposalOutcome::DecodeOfCallFailed);
 					Event::DecodeOfCallFailed(id)
 				},
 			)
@@ -576,6 +881,7 @@ impl<T: Config> Pallet<T> {
 	fn expire_proposals(expired: Vec<ActiveProposal>) -> Weight {
 		for ActiveProposal { proposal_id, .. } in &expired {
 			Proposals::<T>::remove(proposal_id);
+			Self::record_outcome(*proposal_id, ProposalOutcome::Expired);
 			Self::deposit_event(Event::Expired(*proposal_id));
 		}
 		T::WeightInfo::expire_proposals(expired.len() as u32)
@@ -595,6 +901,61 @@ impl<T: Config> Pallet<T> {
 		proposal_id
 	}
 
+	fn call_name_of(call: &<T as Config>::RuntimeCall) -> CallName {
+		let metadata = call.get_call_metadata();
+		CallName {
+			pallet: metadata.pallet_name.as_bytes().to_vec(),
+			function: metadata.function_name.as_bytes().to_vec(),
+		}
+	}
+
+	/// The pallet and function name of an encoded call, if it can be decoded.
+	fn call_name(call: &[u8]) -> Option<CallName> {
+		<T as Config>::RuntimeCall::decode(&mut &call[..])
+			.ok()
+			.map(|call| Self::call_name_of(&call))
+	}
+
+	/// Adds a new proposal to the [AuditLog], pruning the oldest record if the log is full.
+	fn record_proposal(
+		proposal_id: ProposalId,
+		proposer: T::AccountId,
+		call_hash: GovCallHash,
+		call_name: CallName,
+	) {
+		if let Some(oldest) = proposal_id.checked_sub(T::MaxAuditLogLength::get()) {
+			AuditLog::<T>::remove(oldest);
+		}
+		AuditLog::<T>::insert(
+			proposal_id,
+			ProposalRecord {
+				proposer,
+				call_hash,
+				call_name,
+				proposed_at: frame_system::Pallet::<T>::block_number(),
+				approvals: Default::default(),
+				outcome: ProposalOutcome::Pending,
+				resolved_at: None,
+			},
+		);
+	}
+
+	fn record_outcome(proposal_id: ProposalId, outcome: ProposalOutcome) {
+		AuditLog::<T>::mutate(proposal_id, |record| {
+			if let Some(record) = record {
+				record.outcome = outcome;
+				record.resolved_at = Some(frame_system::Pallet::<T>::block_number());
+			}
+		});
+	}
+
+	/// All records in the [AuditLog], ordered by proposal id.
+	pub fn audit_log() -> Vec<(ProposalId, ProposalRecord<T::AccountId, BlockNumberFor<T>>)> {
+		let mut records = AuditLog::<T>::iter().collect::<Vec<_>>();
+		records.sort_by_key(|(proposal_id, _)| *proposal_id);
+		records
+	}
+
 	/// Dispatches a call from the governance origin, with transactional semantics, ie. if the call
 	/// dispatch returns `Err`, rolls back any storage updates.
 	fn dispatch_governance_call(call: <T as Config>::RuntimeCall) -> DispatchResultWithPostInfo {
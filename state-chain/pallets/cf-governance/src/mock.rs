@@ -99,6 +99,7 @@ impl RuntimeUpgradeMock {
 cf_traits::impl_mock_ensure_witnessed_for_origin!(RuntimeOrigin);
 
 parameter_types! {
+	pub const MaxAuditLogLength: u32 = 3;
 	pub static PercentCfeAtTargetVersion: Percent = Percent::from_percent(100);
 }
 
@@ -126,6 +127,7 @@ impl pallet_cf_governance::Config for Test {
 	type RuntimeUpgrade = RuntimeUpgradeMock;
 	type AuthoritiesCfeVersions = MockAuthoritiesCfeVersions;
 	type CompatibleCfeVersions = MockCompatibleCfeVersions;
+	type MaxAuditLogLength = MaxAuditLogLength;
 }
 
 pub const ALICE: <Test as frame_system::Config>::AccountId = 123u64;
@@ -318,3 +318,325 @@ fn whitelisted_gov_call() {
 		assert!(!PreAuthorisedGovCalls::<Test>::contains_key(1));
 	});
 }
+
+mod timelocks {
+	use super::*;
+	use crate::{
+		CallName, CallTimelocks, DefaultTimelock, ProposalStatus, TimelockQueue,
+		TimelockedProposals,
+	};
+	use frame_support::BoundedVec;
+
+	const TIMELOCK: u64 = 100;
+	const START_TIME: Duration = Duration::from_secs(10);
+
+	fn propose_and_approve(call: Box<RuntimeCall>) {
+		assert_ok!(Governance::propose_governance_extrinsic(
+			RuntimeOrigin::signed(ALICE),
+			call,
+			ExecutionMode::Automatic,
+		));
+		let proposal_id = ProposalIdCounter::<Test>::get();
+		assert_ok!(Governance::approve(RuntimeOrigin::signed(BOB), proposal_id));
+	}
+
+	#[test]
+	fn approved_proposals_are_executed_once_the_timelock_ends() {
+		new_test_ext()
+			.execute_with(|| {
+				time_source::Mock::reset_to(START_TIME);
+				assert_ok!(Governance::set_call_timelock(
+					RuntimeOrigin::root(),
+					Some(b"Governance".to_vec()),
+					TIMELOCK
+				));
+				assert_eq!(CallTimelocks::<Test>::get(b"Governance".to_vec()), Some(TIMELOCK));
+				propose_and_approve(mock_extrinsic());
+				assert_eq!(
+					last_event::<Test>(),
+					RuntimeEvent::Governance(crate::Event::TimelockStarted {
+						proposal_id: 1,
+						unlocks_at: START_TIME.as_secs() + TIMELOCK,
+					}),
+				);
+				assert!(ExecutionPipeline::<Test>::get().is_empty());
+			})
+			.then_execute_at_next_block(|_| {
+				// Not yet executed.
+				assert!(TimelockedProposals::<Test>::contains_key(1));
+				assert!(!Members::<Test>::get().contains(&EVE));
+				time_source::Mock::tick(Duration::from_secs(TIMELOCK));
+			})
+			.then_execute_at_next_block(|_| {
+				assert!(!TimelockedProposals::<Test>::contains_key(1));
+				assert!(Members::<Test>::get().contains(&EVE));
+				cf_test_utilities::assert_event_sequence!(
+					Test,
+					RuntimeEvent::Governance(crate::Event::TimelockEnded { proposal_id: 1 }),
+					RuntimeEvent::Governance(crate::Event::Executed(1)),
+				);
+			});
+	}
+
+	#[test]
+	fn only_proposals_that_are_due_are_released() {
+		new_test_ext()
+			.execute_with(|| {
+				time_source::Mock::reset_to(START_TIME);
+				assert_ok!(Governance::set_call_timelock(RuntimeOrigin::root(), None, TIMELOCK));
+				assert_ok!(Governance::set_call_timelock(
+					RuntimeOrigin::root(),
+					Some(b"Governance".to_vec()),
+					2 * TIMELOCK
+				));
+				propose_and_approve(mock_extrinsic());
+				propose_and_approve(Box::new(RuntimeCall::System(frame_system::Call::remark {
+					remark: vec![],
+				})));
+
+				// The queue is sorted by unlock time, not by proposal id.
+				assert_eq!(
+					TimelockQueue::<Test>::get(),
+					vec![
+						(START_TIME.as_secs() + TIMELOCK, 2),
+						(START_TIME.as_secs() + 2 * TIMELOCK, 1)
+					]
+				);
+				time_source::Mock::tick(Duration::from_secs(TIMELOCK));
+			})
+			.then_execute_at_next_block(|_| {
+				assert!(!TimelockedProposals::<Test>::contains_key(2));
+				assert!(TimelockedProposals::<Test>::contains_key(1));
+				assert_eq!(
+					TimelockQueue::<Test>::get(),
+					vec![(START_TIME.as_secs() + 2 * TIMELOCK, 1)]
+				);
+				time_source::Mock::tick(Duration::from_secs(TIMELOCK));
+			})
+			.then_execute_at_next_block(|_| {
+				assert!(!TimelockedProposals::<Test>::contains_key(1));
+				assert!(TimelockQueue::<Test>::get().is_empty());
+				assert!(Members::<Test>::get().contains(&EVE));
+			});
+	}
+
+	#[test]
+	fn the_number_of_timelocked_proposals_is_bounded() {
+		new_test_ext().execute_with(|| {
+			time_source::Mock::reset_to(START_TIME);
+			assert_ok!(Governance::set_call_timelock(RuntimeOrigin::root(), None, TIMELOCK));
+			TimelockQueue::<Test>::put(
+				BoundedVec::try_from(
+					(0..crate::MAX_TIMELOCKED_PROPOSALS)
+						.map(|i| (u64::MAX, 1_000 + i))
+						.collect::<Vec<_>>(),
+				)
+				.unwrap(),
+			);
+
+			assert_ok!(Governance::propose_governance_extrinsic(
+				RuntimeOrigin::signed(ALICE),
+				mock_extrinsic(),
+				ExecutionMode::Automatic,
+			));
+			assert_err!(
+				Governance::approve(RuntimeOrigin::signed(BOB), 1),
+				Error::<Test>::TooManyTimelockedProposals
+			);
+			assert!(!TimelockedProposals::<Test>::contains_key(1));
+		});
+	}
+
+	#[test]
+	fn calls_to_pallets_without_a_timelock_use_the_default() {
+		new_test_ext().execute_with(|| {
+			time_source::Mock::reset_to(START_TIME);
+			assert_ok!(Governance::set_call_timelock(RuntimeOrigin::root(), None, TIMELOCK));
+			assert_eq!(DefaultTimelock::<Test>::get(), TIMELOCK);
+			propose_and_approve(Box::new(RuntimeCall::System(frame_system::Call::remark {
+				remark: vec![],
+			})));
+			assert!(TimelockedProposals::<Test>::contains_key(1));
+
+			// Pallet-specific timelocks take precedence.
+			assert_ok!(Governance::set_call_timelock(
+				RuntimeOrigin::root(),
+				Some(b"System".to_vec()),
+				0
+			));
+			propose_and_approve(Box::new(RuntimeCall::System(frame_system::Call::remark {
+				remark: vec![],
+			})));
+			assert!(!TimelockedProposals::<Test>::contains_key(2));
+			assert_eq!(ExecutionPipeline::<Test>::decode_len(), Some(1));
+		});
+	}
+
+	#[test]
+	fn timelocked_proposals_can_be_cancelled_by_majority() {
+		new_test_ext()
+			.execute_with(|| {
+				time_source::Mock::reset_to(START_TIME);
+				assert_ok!(Governance::set_call_timelock(RuntimeOrigin::root(), None, TIMELOCK));
+				propose_and_approve(mock_extrinsic());
+				assert!(TimelockedProposals::<Test>::contains_key(1));
+
+				// The cancellation itself is not timelocked.
+				propose_and_approve(Box::new(RuntimeCall::Governance(
+					pallet_cf_governance::Call::<Test>::cancel_proposal { proposal_id: 1 },
+				)));
+				assert!(!TimelockedProposals::<Test>::contains_key(2));
+			})
+			.then_execute_at_next_block(|_| {
+				assert!(!TimelockedProposals::<Test>::contains_key(1));
+				assert!(TimelockQueue::<Test>::get().is_empty());
+				cf_test_utilities::assert_event_sequence!(
+					Test,
+					RuntimeEvent::Governance(crate::Event::ProposalCancelled { proposal_id: 1 }),
+					RuntimeEvent::Governance(crate::Event::Executed(2)),
+				);
+				time_source::Mock::tick(Duration::from_secs(TIMELOCK));
+			})
+			.then_execute_at_next_block(|_| {
+				assert!(!Members::<Test>::get().contains(&EVE));
+			});
+	}
+
+	#[test]
+	fn cancel_proposal_awaiting_approval() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Governance::propose_governance_extrinsic(
+				RuntimeOrigin::signed(ALICE),
+				mock_extrinsic(),
+				ExecutionMode::Automatic,
+			));
+			assert_ok!(Governance::cancel_proposal(RuntimeOrigin::root(), 1));
+			assert!(ActiveProposals::<Test>::get().is_empty());
+			assert_noop!(
+				Governance::approve(RuntimeOrigin::signed(BOB), 1),
+				<Error<Test>>::ProposalNotFound
+			);
+			assert_noop!(
+				Governance::cancel_proposal(RuntimeOrigin::root(), 1),
+				<Error<Test>>::ProposalNotFound
+			);
+		});
+	}
+
+	#[test]
+	fn proposal_previews() {
+		new_test_ext().execute_with(|| {
+			time_source::Mock::reset_to(START_TIME);
+			assert_ok!(Governance::set_call_timelock(RuntimeOrigin::root(), None, TIMELOCK));
+			propose_and_approve(mock_extrinsic());
+			assert_ok!(Governance::propose_governance_extrinsic(
+				RuntimeOrigin::signed(ALICE),
+				mock_extrinsic(),
+				ExecutionMode::Automatic,
+			));
+
+			let previews = Governance::proposal_previews();
+			assert_eq!(
+				previews
+					.iter()
+					.map(|preview| (preview.proposal_id, preview.status))
+					.collect::<Vec<_>>(),
+				vec![
+					(1, ProposalStatus::Timelocked { unlocks_at: START_TIME.as_secs() + TIMELOCK }),
+					(
+						2,
+						ProposalStatus::AwaitingApproval {
+							expiry_time: START_TIME.as_secs() + ExpiryTime::<Test>::get()
+						}
+					),
+				]
+			);
+			for preview in previews {
+				assert_eq!(
+					preview.call_name,
+					Some(CallName {
+						pallet: b"Governance".to_vec(),
+						function: b"new_membership_set".to_vec(),
+					})
+				);
+			}
+		});
+	}
+}
+
+mod audit_log {
+	use super::*;
+	use crate::{AuditLog, CallName, ProposalOutcome};
+	use codec::Encode;
+	use sp_io::hashing::blake2_256;
+
+	#[test]
+	fn executed_proposals_are_recorded() {
+		new_test_ext()
+			.execute_with(|| {
+				assert_ok!(Governance::propose_governance_extrinsic(
+					RuntimeOrigin::signed(ALICE),
+					mock_extrinsic(),
+					ExecutionMode::Automatic,
+				));
+				let record = AuditLog::<Test>::get(1).unwrap();
+				assert_eq!(record.proposer, ALICE);
+				assert_eq!(record.call_hash, blake2_256(&mock_extrinsic().encode()));
+				assert_eq!(
+					record.call_name,
+					CallName {
+						pallet: b"Governance".to_vec(),
+						function: b"new_membership_set".to_vec()
+					}
+				);
+				assert_eq!(record.outcome, ProposalOutcome::Pending);
+				System::set_block_number(System::block_number() + 1);
+				assert_ok!(Governance::approve(RuntimeOrigin::signed(BOB), 1));
+				System::block_number()
+			})
+			.then_execute_at_next_block(|approved_at| {
+				let record = AuditLog::<Test>::get(1).unwrap();
+				assert_eq!(record.approvals, vec![(ALICE, approved_at - 1), (BOB, approved_at)]);
+				assert_eq!(record.outcome, ProposalOutcome::Executed { result: Ok(()) });
+				assert_eq!(record.resolved_at, Some(System::block_number()));
+			});
+	}
+
+	#[test]
+	fn expired_and_cancelled_proposals_are_recorded() {
+		new_test_ext()
+			.execute_with(|| {
+				time_source::Mock::reset_to(Duration::from_secs(10));
+				for _ in 0..2 {
+					assert_ok!(Governance::propose_governance_extrinsic(
+						RuntimeOrigin::signed(ALICE),
+						mock_extrinsic(),
+						ExecutionMode::Automatic,
+					));
+				}
+				assert_ok!(Governance::cancel_proposal(RuntimeOrigin::root(), 2));
+				assert_eq!(AuditLog::<Test>::get(2).unwrap().outcome, ProposalOutcome::Cancelled);
+				time_source::Mock::reset_to(Duration::from_secs(7300));
+			})
+			.then_execute_at_next_block(|_| {
+				assert_eq!(AuditLog::<Test>::get(1).unwrap().outcome, ProposalOutcome::Expired);
+			});
+	}
+
+	#[test]
+	fn audit_log_is_bounded() {
+		new_test_ext().execute_with(|| {
+			for _ in 0..(MaxAuditLogLength::get() + 2) {
+				assert_ok!(Governance::propose_governance_extrinsic(
+					RuntimeOrigin::signed(ALICE),
+					mock_extrinsic(),
+					ExecutionMode::Automatic,
+				));
+			}
+			assert_eq!(
+				Governance::audit_log().into_iter().map(|(id, _)| id).collect::<Vec<_>>(),
+				vec![3, 4, 5]
+			);
+		});
+	}
+}
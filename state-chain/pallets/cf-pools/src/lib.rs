@@ -9,14 +9,16 @@ use cf_amm::{
 	range_orders::Liquidity,
 	PoolState,
 };
-use cf_primitives::{chains::assets::any, Asset, AssetAmount, SwapOutput, STABLE_ASSET};
+use cf_primitives::{
+	chains::assets::any, Asset, AssetAmount, BroadcastId, EgressId, SwapOutput, STABLE_ASSET,
+};
 use cf_traits::{impl_pallet_safe_mode, Chainflip, LpBalanceApi, PoolApi, SwappingApi};
 use frame_support::{
 	pallet_prelude::*,
 	sp_runtime::{Permill, Saturating},
 	transactional,
 };
-use frame_system::pallet_prelude::OriginFor;
+use frame_system::pallet_prelude::{BlockNumberFor, OriginFor};
 use serde::{Deserialize, Serialize};
 use sp_arithmetic::traits::Zero;
 use sp_std::{collections::btree_set::BTreeSet, vec::Vec};
@@ -385,6 +387,27 @@ pub mod pallet {
 		}
 	}
 
+	/// The Flip bought with the network fees collected over a buy interval.
+	#[derive(Clone, Debug, Encode, Decode, TypeInfo, PartialEq, Eq)]
+	pub struct FlipBurnRecord<BlockNumber> {
+		pub bought_at: BlockNumber,
+		/// The collected network fees, in USDC.
+		pub usdc_in: AssetAmount,
+		/// The Flip bought with the network fees.
+		pub flip_out: AssetAmount,
+		/// Set once the Flip has been burned.
+		pub burn: Option<FlipBurn<BlockNumber>>,
+	}
+
+	#[derive(Clone, Debug, Encode, Decode, TypeInfo, PartialEq, Eq)]
+	pub struct FlipBurn<BlockNumber> {
+		pub burned_at: BlockNumber,
+		/// The egress of the burned Flip to the State Chain Gateway.
+		pub egress_id: EgressId,
+		/// The `UpdateFlipSupply` broadcast that reflects the burn.
+		pub supply_update_broadcast_id: BroadcastId,
+	}
+
 	#[pallet::config]
 	#[pallet::disable_frame_system_supertrait_check]
 	pub trait Config: Chainflip {
@@ -397,6 +420,10 @@ pub mod pallet {
 		#[pallet::constant]
 		type NetworkFee: Get<Permill>;
 
+		/// The number of most recent records kept in the [FlipBurnHistory].
+		#[pallet::constant]
+		type MaxFlipBurnHistory: Get<u32>;
+
 		/// Safe Mode access.
 		type SafeMode: Get<PalletSafeMode>;
 
@@ -425,6 +452,11 @@ pub mod pallet {
 	#[pallet::storage]
 	pub type CollectedNetworkFee<T: Config> = StorageValue<_, AssetAmount, ValueQuery>;
 
+	/// Records of the most recent Flip buys and their burns, oldest first.
+	#[pallet::storage]
+	pub type FlipBurnHistory<T: Config> =
+		StorageValue<_, Vec<FlipBurnRecord<BlockNumberFor<T>>>, ValueQuery>;
+
 	#[pallet::genesis_config]
 	pub struct GenesisConfig<T: Config> {
 		pub flip_buy_interval: BlockNumberFor<T>,
@@ -455,7 +487,8 @@ pub mod pallet {
 				if (current_block % interval).is_zero() &&
 					!CollectedNetworkFee::<T>::get().is_zero()
 				{
-					weight_used.saturating_accrue(T::DbWeight::get().reads_writes(1, 1));
+					// CollectedNetworkFee, FlipToBurn and FlipBurnHistory.
+					weight_used.saturating_accrue(T::DbWeight::get().reads_writes(3, 3));
 					if let Err(e) = CollectedNetworkFee::<T>::try_mutate(|collected_fee| {
 						let flip_to_burn = Self::swap_single_leg(
 							any::Asset::Usdc,
@@ -465,6 +498,7 @@ pub mod pallet {
 						FlipToBurn::<T>::mutate(|total| {
 							total.saturating_accrue(flip_to_burn);
 						});
+						Self::record_flip_buy(current_block, *collected_fee, flip_to_burn);
 						collected_fee.set_zero();
 						Ok::<_, DispatchError>(())
 					}) {
@@ -1102,6 +1136,15 @@ impl<T: Config> cf_traits::FlipBurnInfo for Pallet<T> {
 	fn take_flip_to_burn() -> AssetAmount {
 		FlipToBurn::<T>::take()
 	}
+
+	fn on_flip_burned(egress_id: EgressId, supply_update_broadcast_id: BroadcastId) {
+		let burned_at = frame_system::Pallet::<T>::block_number();
+		FlipBurnHistory::<T>::mutate(|history| {
+			for record in history.iter_mut().rev().take_while(|record| record.burn.is_none()) {
+				record.burn = Some(FlipBurn { burned_at, egress_id, supply_update_broadcast_id });
+			}
+		});
+	}
 }
 
 #[derive(
@@ -1165,6 +1208,18 @@ pub struct UnidirectionalPoolDepth {
 }
 
 impl<T: Config> Pallet<T> {
+	fn record_flip_buy(bought_at: BlockNumberFor<T>, usdc_in: AssetAmount, flip_out: AssetAmount) {
+		FlipBurnHistory::<T>::mutate(|history| {
+			history.push(FlipBurnRecord { bought_at, usdc_in, flip_out, burn: None });
+			let excess = history.len().saturating_sub(T::MaxFlipBurnHistory::get() as usize);
+			history.drain(..excess);
+		});
+	}
+
+	pub fn flip_burn_history() -> Vec<FlipBurnRecord<BlockNumberFor<T>>> {
+		FlipBurnHistory::<T>::get()
+	}
+
 	fn inner_sweep(lp: &T::AccountId, range_order_base_side: Side) -> DispatchResult {
 		// Collect to avoid undefined behaviour (See StorsgeMap::iter_keys documentation)
 		for canonical_asset_pair in Pools::<T>::iter_keys().collect::<Vec<_>>() {
@@ -62,6 +62,7 @@ impl_mock_chainflip!(Test);
 parameter_types! {
 	// 20 Basis Points
 	pub static NetworkFee: Permill = Permill::from_perthousand(2);
+	pub const MaxFlipBurnHistory: u32 = 2;
 	pub static AliceCollectedEth: AssetAmount = Default::default();
 	pub static AliceCollectedUsdc: AssetAmount = Default::default();
 	pub static BobCollectedEth: AssetAmount = Default::default();
@@ -126,6 +127,7 @@ impl pallet_cf_pools::Config for Test {
 	type RuntimeEvent = RuntimeEvent;
 	type LpBalance = MockBalance;
 	type NetworkFee = NetworkFee;
+	type MaxFlipBurnHistory = MaxFlipBurnHistory;
 	type SafeMode = MockRuntimeSafeMode;
 	type WeightInfo = ();
 }
@@ -1,11 +1,12 @@
 use crate::{
 	mock::*, utilities, AssetAmounts, AssetPair, AssetsMap, CanonicalAssetPair,
-	CollectedNetworkFee, Error, Event, FlipBuyInterval, FlipToBurn, PoolInfo, PoolOrders, Pools,
-	RangeOrderSize, STABLE_ASSET,
+	CollectedNetworkFee, Error, Event, FlipBurn, FlipBurnHistory, FlipBurnRecord, FlipBuyInterval,
+	FlipToBurn, PoolInfo, PoolOrders, Pools, RangeOrderSize, STABLE_ASSET,
 };
 use cf_amm::common::{price_at_tick, Tick};
-use cf_primitives::{chains::assets::any::Asset, AssetAmount, SwapOutput};
+use cf_primitives::{chains::assets::any::Asset, AssetAmount, ForeignChain, SwapOutput};
 use cf_test_utilities::{assert_events_match, assert_has_event};
+use cf_traits::FlipBurnInfo;
 use frame_support::{assert_noop, assert_ok, traits::Hooks};
 use frame_system::pallet_prelude::BlockNumberFor;
 use sp_runtime::Permill;
@@ -126,6 +127,27 @@ fn can_enable_disable_trading_pool() {
 	});
 }
 
+#[test]
+fn flip_burn_history_is_bounded_and_links_only_unburned_records() {
+	new_test_ext().execute_with(|| {
+		LiquidityPools::record_flip_buy(1, 100, 10);
+		LiquidityPools::on_flip_burned((ForeignChain::Ethereum, 1), 1);
+		LiquidityPools::record_flip_buy(2, 200, 20);
+		LiquidityPools::record_flip_buy(3, 300, 30);
+		LiquidityPools::on_flip_burned((ForeignChain::Ethereum, 2), 2);
+
+		let history = LiquidityPools::flip_burn_history();
+		assert_eq!(
+			history.iter().map(|record| record.bought_at).collect::<Vec<_>>(),
+			vec![2, 3],
+			"The oldest record should have been pruned."
+		);
+		assert!(history
+			.iter()
+			.all(|record| record.burn.as_ref().unwrap().egress_id == (ForeignChain::Ethereum, 2)));
+	});
+}
+
 #[test]
 fn test_buy_back_flip_no_funds_available() {
 	new_test_ext().execute_with(|| {
@@ -294,6 +316,28 @@ fn test_buy_back_flip() {
 		LiquidityPools::on_initialize(INTERVAL * 3);
 		assert_eq!(0, CollectedNetworkFee::<Test>::get());
 		assert!(FlipToBurn::<Test>::get() > 0);
+
+		// The buy is recorded, and linked to the burn once the flip is burned.
+		let flip_to_burn = FlipToBurn::<Test>::get();
+		assert_eq!(
+			FlipBurnHistory::<Test>::get(),
+			vec![FlipBurnRecord {
+				bought_at: INTERVAL * 3,
+				usdc_in: collected_fee,
+				flip_out: flip_to_burn,
+				burn: None
+			}]
+		);
+		assert_eq!(LiquidityPools::take_flip_to_burn(), flip_to_burn);
+		LiquidityPools::on_flip_burned((ForeignChain::Ethereum, 1), 2);
+		assert_eq!(
+			FlipBurnHistory::<Test>::get()[0].burn,
+			Some(FlipBurn {
+				burned_at: System::block_number(),
+				egress_id: (ForeignChain::Ethereum, 1),
+				supply_update_broadcast_id: 2,
+			})
+		);
 	});
 }
 
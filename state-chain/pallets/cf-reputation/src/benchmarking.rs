@@ -55,5 +55,39 @@ benchmarks! {
 		Pallet::<T>::on_initialize(next_block_number);
 	}
 
+	pardon {
+		let offence: T::Offence = PalletOffence::MissedHeartbeat.into();
+		Penalties::<T>::insert(offence, Penalty::<T> { reputation: 10, suspension: 10u32.into() });
+		let validator_id: T::ValidatorId = account::<T::AccountId>("doogle", 0, 0).into();
+		Pallet::<T>::penalise(offence, &[validator_id.clone()], None);
+		let call = Call::<T>::pardon {
+			validator_id: validator_id.clone(),
+			offence,
+			reported_at: frame_system::Pallet::<T>::block_number(),
+		};
+	} : { let _ = call.dispatch_bypass_filter(T::EnsureGovernance::try_successful_origin().unwrap()); }
+	verify {
+		assert!(PenaltyHistory::<T>::get(&validator_id)[0].pardoned);
+	}
+	pardon_offences {
+		let a in 1 .. MAX_VALIDATOR_COUNT;
+		let offence: T::Offence = PalletOffence::MissedHeartbeat.into();
+		Penalties::<T>::insert(offence, Penalty::<T> { reputation: 10, suspension: 10u32.into() });
+		let validators = (0..a)
+			.map(|i| account::<T::AccountId>("doogle", i, i).into())
+			.collect::<Vec<T::ValidatorId>>();
+		Pallet::<T>::penalise(offence, &validators, None);
+		let block_number = frame_system::Pallet::<T>::block_number();
+		let call = Call::<T>::pardon_offences {
+			offence,
+			from_block: block_number,
+			to_block: block_number,
+			max_pardons: a,
+		};
+	} : { let _ = call.dispatch_bypass_filter(T::EnsureGovernance::try_successful_origin().unwrap()); }
+	verify {
+		assert!(PenaltyHistory::<T>::get(&validators[0])[0].pardoned);
+	}
+
 	impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test,);
 }
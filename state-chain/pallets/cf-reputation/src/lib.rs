@@ -18,13 +18,17 @@ pub use reputation::*;
 pub mod weights;
 pub use weights::WeightInfo;
 
+use cf_primitives::EpochIndex;
 use cf_traits::{
-	impl_pallet_safe_mode, offence_reporting::*, Chainflip, Heartbeat, NetworkState, QualifyNode,
-	ReputationResetter, Slashing,
+	impl_pallet_safe_mode, offence_reporting::*, Chainflip, EpochInfo, Heartbeat, NetworkState,
+	QualifyNode, ReputationResetter, Slashing,
 };
 use frame_support::{
 	pallet_prelude::*,
-	sp_runtime::traits::{BlockNumberProvider, Saturating, Zero},
+	sp_runtime::{
+		traits::{BlockNumberProvider, Saturating, UniqueSaturatedInto, Zero},
+		Percent,
+	},
 	traits::{Get, OnKilledAccount},
 };
 use frame_system::pallet_prelude::*;
@@ -37,6 +41,13 @@ use sp_std::{
 
 impl_pallet_safe_mode!(PalletSafeMode; reporting_enabled);
 
+/// The number of most recent epochs for which liveness is tracked.
+pub const LIVENESS_HISTORY_EPOCHS: usize = 6;
+
+/// The maximum number of blocks whose penalties can be pardoned by a single
+/// [pardon_offences](Call::pardon_offences).
+pub const MAX_PARDON_BLOCK_RANGE: u32 = 1_000;
+
 impl<T: Config> ReputationParameters for T {
 	type BlockNumber = BlockNumberFor<T>;
 
@@ -76,6 +87,48 @@ impl<T: Config> Default for Penalty<T> {
 	}
 }
 
+/// A penalty imposed on a node for an offence, along with the evidence of the report.
+#[derive(Clone, RuntimeDebug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct PenaltyRecord<Offence, BlockNumber> {
+	pub offence: Offence,
+	pub reported_at: BlockNumber,
+	/// The reputation points that were actually deducted.
+	pub reputation: ReputationPoints,
+	/// The block until which the node is suspended, if the penalty includes a suspension.
+	pub suspended_until: Option<BlockNumber>,
+	/// The number of nodes reported for the offence in the same report, including this one.
+	pub reported_with: u32,
+	/// The encoded time slot of offences reported through the
+	/// [ChainflipOffenceReportingAdapter].
+	pub time_slot: Option<OpaqueTimeSlot>,
+	pub pardoned: bool,
+}
+
+/// The liveness of an authority over an epoch, sampled at every heartbeat interval.
+#[derive(
+	Copy, Clone, Default, RuntimeDebug, PartialEq, Eq, Encode, Decode, TypeInfo, MaxEncodedLen,
+)]
+pub struct EpochLiveness {
+	pub epoch: EpochIndex,
+	pub heartbeat_intervals: u32,
+	pub missed_heartbeats: u32,
+	/// The reputation at the most recent heartbeat interval.
+	pub reputation_points: ReputationPoints,
+}
+
+impl EpochLiveness {
+	/// The share of heartbeat intervals in which the authority was online.
+	pub fn uptime(&self) -> Percent {
+		if self.heartbeat_intervals == 0 {
+			return Percent::one()
+		}
+		Percent::from_rational(
+			self.heartbeat_intervals.saturating_sub(self.missed_heartbeats),
+			self.heartbeat_intervals,
+		)
+	}
+}
+
 #[derive(Copy, Clone, RuntimeDebug, PartialEq, Eq, Encode, Decode, TypeInfo, MaxEncodedLen)]
 pub enum PalletOffence {
 	MissedHeartbeat,
@@ -131,6 +184,10 @@ pub mod pallet {
 
 		/// Safe mode access
 		type SafeMode: Get<PalletSafeMode>;
+
+		/// The number of most recent penalties kept in the [PenaltyHistory] of each node.
+		#[pallet::constant]
+		type MaxPenaltyHistory: Get<u32>;
 	}
 
 	#[pallet::hooks]
@@ -140,9 +197,15 @@ pub mod pallet {
 				current_block % T::HeartbeatBlockInterval::get() == Zero::zero()
 			{
 				// Reputation depends on heartbeats
-				Self::penalise_offline_authorities(Self::current_network_state().offline);
+				let network_state = Self::current_network_state();
+				let penalty_weight =
+					Self::penalty_record_weight(network_state.offline.len() as u32);
+				Self::penalise_offline_authorities(network_state.offline.clone());
+				let liveness_weight = Self::record_liveness(network_state);
 				T::Heartbeat::on_heartbeat_interval();
 				return T::WeightInfo::submit_network_state()
+					.saturating_add(liveness_weight)
+					.saturating_add(penalty_weight)
 			}
 			T::WeightInfo::on_initialize_no_action()
 		}
@@ -188,6 +251,36 @@ pub mod pallet {
 	pub type LastHeartbeat<T: Config> =
 		StorageMap<_, Twox64Concat, T::ValidatorId, BlockNumberFor<T>, OptionQuery>;
 
+	/// Liveness of each authority over the most recent epochs, oldest first.
+	#[pallet::storage]
+	#[pallet::getter(fn liveness_history)]
+	pub type LivenessHistory<T: Config> =
+		StorageMap<_, Twox64Concat, T::ValidatorId, VecDeque<EpochLiveness>, ValueQuery>;
+
+	/// The most recent penalties imposed on each node, oldest first.
+	#[pallet::storage]
+	#[pallet::getter(fn penalty_history)]
+	pub type PenaltyHistory<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::ValidatorId,
+		Vec<PenaltyRecord<T::Offence, BlockNumberFor<T>>>,
+		ValueQuery,
+	>;
+
+	/// The nodes with an outstanding penalty for an offence, by the block at which the offence was
+	/// reported. Indexes the unpardoned records of the [PenaltyHistory].
+	#[pallet::storage]
+	pub type PenalisedValidators<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::Offence,
+		Twox64Concat,
+		BlockNumberFor<T>,
+		BTreeSet<T::ValidatorId>,
+		ValueQuery,
+	>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub (super) fn deposit_event)]
 	pub enum Event<T: Config> {
@@ -202,12 +295,25 @@ pub mod pallet {
 		MissedHeartbeatPenaltyUpdated { new_reputation_penalty: ReputationPoints },
 		/// The penalty for some offence has been updated.
 		PenaltyUpdated { offence: T::Offence, old_penalty: Penalty<T>, new_penalty: Penalty<T> },
+		/// A penalty has been pardoned: the reputation has been restored and the suspension
+		/// lifted.
+		PenaltyPardoned {
+			validator_id: T::ValidatorId,
+			offence: T::Offence,
+			reported_at: BlockNumberFor<T>,
+		},
 	}
 
 	#[pallet::error]
 	pub enum Error<T> {
 		/// Tried to set the accrual ration to something invalid.
 		InvalidAccrualRatio,
+		/// There is no penalty to pardon matching the given parameters.
+		PenaltyNotFound,
+		/// The block range is invalid or spans more than [MAX_PARDON_BLOCK_RANGE] blocks.
+		InvalidBlockRange,
+		/// More penalties match than the given maximum number of pardons.
+		TooManyPardons,
 	}
 
 	#[pallet::call]
@@ -322,6 +428,89 @@ pub mod pallet {
 
 			Ok(())
 		}
+
+		/// Pardons the penalties of a node for an offence reported at the given block. The
+		/// deducted reputation is restored and the suspension is lifted. Slashes are not reverted.
+		///
+		/// ## Events
+		///
+		/// - [PenaltyPardoned](Event::PenaltyPardoned)
+		///
+		/// ## Errors
+		///
+		/// - [BadOrigin](frame_support::error::BadOrigin)
+		/// - [PenaltyNotFound](Error::PenaltyNotFound)
+		#[pallet::call_index(4)]
+		#[pallet::weight(Pallet::<T>::pardon_weight(1))]
+		pub fn pardon(
+			origin: OriginFor<T>,
+			validator_id: T::ValidatorId,
+			offence: T::Offence,
+			reported_at: BlockNumberFor<T>,
+		) -> DispatchResult {
+			T::EnsureGovernance::ensure_origin(origin)?;
+
+			ensure!(
+				Self::pardon_penalties(&validator_id, |record| {
+					record.offence == offence && record.reported_at == reported_at
+				}) > 0,
+				Error::<T>::PenaltyNotFound
+			);
+
+			Ok(())
+		}
+
+		/// Pardons all outstanding penalties for an offence reported within the given inclusive
+		/// block range, for example penalties caused by a network-wide outage. The range can span
+		/// at most [MAX_PARDON_BLOCK_RANGE] blocks.
+		///
+		/// `max_pardons` is an upper bound on the number of penalties to pardon, used to weigh the
+		/// call. The unused weight is refunded.
+		///
+		/// ## Events
+		///
+		/// - [PenaltyPardoned](Event::PenaltyPardoned)
+		///
+		/// ## Errors
+		///
+		/// - [BadOrigin](frame_support::error::BadOrigin)
+		/// - [InvalidBlockRange](Error::InvalidBlockRange)
+		/// - [TooManyPardons](Error::TooManyPardons)
+		#[pallet::call_index(5)]
+		#[pallet::weight(Pallet::<T>::pardon_offences_weight(*from_block, *to_block, *max_pardons))]
+		pub fn pardon_offences(
+			origin: OriginFor<T>,
+			offence: T::Offence,
+			from_block: BlockNumberFor<T>,
+			to_block: BlockNumberFor<T>,
+			max_pardons: u32,
+		) -> DispatchResultWithPostInfo {
+			T::EnsureGovernance::ensure_origin(origin)?;
+			ensure!(
+				from_block <= to_block &&
+					to_block - from_block < BlockNumberFor::<T>::from(MAX_PARDON_BLOCK_RANGE),
+				Error::<T>::InvalidBlockRange
+			);
+
+			let block_count: u32 = (to_block - from_block).unique_saturated_into();
+			let penalised = (0..=block_count)
+				.map(|offset| from_block.saturating_add(offset.into()))
+				.map(|block| (block, PenalisedValidators::<T>::get(offence, block)))
+				.collect::<Vec<_>>();
+			let pardons =
+				penalised.iter().map(|(_, validators)| validators.len() as u32).sum::<u32>();
+			ensure!(pardons <= max_pardons, Error::<T>::TooManyPardons);
+
+			for (block, validators) in penalised {
+				for validator_id in validators {
+					Self::pardon_penalties(&validator_id, |record| {
+						record.offence == offence && record.reported_at == block
+					});
+				}
+			}
+
+			Ok(Some(Self::pardon_offences_weight(from_block, to_block, pardons)).into())
+		}
 	}
 
 	impl<T: Config> QualifyNode<T::ValidatorId> for Pallet<T> {
@@ -389,28 +578,7 @@ impl<T: Config> OffenceReporter for Pallet<T> {
 	type Offence = T::Offence;
 
 	fn report_many(offence: impl Into<Self::Offence>, validators: &[Self::ValidatorId]) {
-		if !T::SafeMode::get().reporting_enabled {
-			return
-		}
-		let offence = offence.into();
-		let penalty = Self::resolve_penalty_for(offence);
-
-		if penalty.reputation > 0 {
-			for validator_id in validators {
-				Reputations::<T>::mutate(validator_id, |rep| {
-					rep.deduct_reputation(penalty.reputation);
-				});
-				Self::deposit_event(Event::OffencePenalty {
-					offender: validator_id.clone(),
-					offence,
-					penalty: penalty.reputation,
-				});
-			}
-		}
-
-		if penalty.suspension > Zero::zero() {
-			Self::suspend_all(validators, &offence, penalty.suspension);
-		}
+		Pallet::<T>::penalise(offence.into(), validators, None);
 	}
 
 	fn forgive_all(offence: impl Into<Self::Offence>) {
@@ -442,6 +610,171 @@ impl<T: Config, L: OffenceList<T>> QualifyNode<T::ValidatorId> for ExclusionList
 }
 
 impl<T: Config> Pallet<T> {
+	/// Applies the penalty for an offence to the given validators and records it in their
+	/// [PenaltyHistory]. Does nothing while reporting is disabled by safe mode.
+	fn penalise(
+		offence: T::Offence,
+		validators: &[T::ValidatorId],
+		time_slot: Option<OpaqueTimeSlot>,
+	) {
+		if !T::SafeMode::get().reporting_enabled {
+			return
+		}
+		let penalty = Self::resolve_penalty_for(offence);
+		if penalty.reputation <= 0 && penalty.suspension.is_zero() {
+			return
+		}
+
+		let reported_at = frame_system::Pallet::<T>::current_block_number();
+		for validator_id in validators {
+			let reputation = if penalty.reputation > 0 {
+				let deducted = Reputations::<T>::mutate(validator_id, |rep| {
+					let before = rep.reputation_points;
+					rep.deduct_reputation(penalty.reputation);
+					before.saturating_sub(rep.reputation_points)
+				});
+				Self::deposit_event(Event::OffencePenalty {
+					offender: validator_id.clone(),
+					offence,
+					penalty: penalty.reputation,
+				});
+				deducted
+			} else {
+				0
+			};
+			PenaltyHistory::<T>::mutate(validator_id, |history| {
+				history.push(PenaltyRecord {
+					offence,
+					reported_at,
+					reputation,
+					suspended_until: (!penalty.suspension.is_zero())
+						.then(|| reported_at.saturating_add(penalty.suspension)),
+					reported_with: validators.len() as u32,
+					time_slot: time_slot.clone(),
+					pardoned: false,
+				});
+				let excess = history.len().saturating_sub(T::MaxPenaltyHistory::get() as usize);
+				for record in history.drain(..excess).filter(|record| !record.pardoned) {
+					Self::unindex_penalty(record.offence, record.reported_at, validator_id);
+				}
+			});
+		}
+		PenalisedValidators::<T>::mutate(offence, reported_at, |penalised| {
+			penalised.extend(validators.iter().cloned())
+		});
+
+		if penalty.suspension > Zero::zero() {
+			Self::suspend_all(validators, &offence, penalty.suspension);
+		}
+	}
+
+	/// Pardons all penalties of the validator that match the predicate and have not been pardoned
+	/// yet. Returns the number of pardoned penalties.
+	fn pardon_penalties(
+		validator_id: &T::ValidatorId,
+		matches: impl Fn(&PenaltyRecord<T::Offence, BlockNumberFor<T>>) -> bool,
+	) -> u32 {
+		PenaltyHistory::<T>::mutate(validator_id, |history| {
+			let mut pardoned = 0;
+			for record in history.iter_mut().filter(|record| !record.pardoned && matches(record)) {
+				record.pardoned = true;
+				Self::unindex_penalty(record.offence, record.reported_at, validator_id);
+				Reputations::<T>::mutate(validator_id, |rep| {
+					rep.restore_reputation(record.reputation);
+				});
+				if let Some(suspended_until) = record.suspended_until {
+					Suspensions::<T>::mutate(record.offence, |suspensions| {
+						suspensions.retain(|(block, id)| {
+							!(*block == suspended_until && id == validator_id)
+						});
+					});
+				}
+				Self::deposit_event(Event::PenaltyPardoned {
+					validator_id: validator_id.clone(),
+					offence: record.offence,
+					reported_at: record.reported_at,
+				});
+				pardoned += 1;
+			}
+			pardoned
+		})
+	}
+
+	/// Removes an outstanding penalty from the [PenalisedValidators] index.
+	fn unindex_penalty(
+		offence: T::Offence,
+		reported_at: BlockNumberFor<T>,
+		validator_id: &T::ValidatorId,
+	) {
+		PenalisedValidators::<T>::mutate_exists(offence, reported_at, |maybe_penalised| {
+			if let Some(penalised) = maybe_penalised {
+				penalised.remove(validator_id);
+				if penalised.is_empty() {
+					*maybe_penalised = None;
+				}
+			}
+		});
+	}
+
+	/// The weight of recording a penalty for the given number of nodes: the [PenaltyHistory] of
+	/// each node, including unindexing the record it replaces, and the index of the penalty.
+	fn penalty_record_weight(validator_count: u32) -> Weight {
+		let accesses = (validator_count as u64).saturating_mul(2).saturating_add(1);
+		T::DbWeight::get().reads_writes(accesses, accesses)
+	}
+
+	/// The weight of pardoning the given number of penalties: the [PenaltyHistory], reputation,
+	/// suspensions and index entry of each.
+	fn pardon_weight(pardons: u32) -> Weight {
+		let accesses = (pardons as u64).saturating_mul(4);
+		T::DbWeight::get().reads_writes(accesses, accesses)
+	}
+
+	/// The weight of [pardon_offences](Call::pardon_offences): one index read per block in the
+	/// range, plus the pardons.
+	fn pardon_offences_weight(
+		from_block: BlockNumberFor<T>,
+		to_block: BlockNumberFor<T>,
+		pardons: u32,
+	) -> Weight {
+		let block_count: u64 = to_block.saturating_sub(from_block).unique_saturated_into();
+		T::DbWeight::get()
+			.reads(block_count.saturating_add(1).min(MAX_PARDON_BLOCK_RANGE as u64))
+			.saturating_add(Self::pardon_weight(pardons))
+	}
+
+	/// Updates the liveness of the current authorities for the current epoch. Returns the weight
+	/// of the storage accesses.
+	fn record_liveness(network_state: NetworkState<T::ValidatorId>) -> Weight {
+		let epoch = T::EpochInfo::epoch_index();
+		let authority_count = (network_state.online.len() + network_state.offline.len()) as u64;
+		let authorities = network_state
+			.online
+			.into_iter()
+			.map(|validator_id| (validator_id, false))
+			.chain(network_state.offline.into_iter().map(|validator_id| (validator_id, true)));
+		for (validator_id, missed_heartbeat) in authorities {
+			let reputation_points = Reputations::<T>::get(&validator_id).reputation_points;
+			LivenessHistory::<T>::mutate(&validator_id, |history| {
+				if !matches!(history.back(), Some(liveness) if liveness.epoch == epoch) {
+					history.push_back(EpochLiveness { epoch, ..Default::default() });
+					if history.len() > LIVENESS_HISTORY_EPOCHS {
+						history.pop_front();
+					}
+				}
+				let liveness = history.back_mut().expect("pushed above if empty; qed");
+				liveness.heartbeat_intervals.saturating_accrue(1);
+				if missed_heartbeat {
+					liveness.missed_heartbeats.saturating_accrue(1);
+				}
+				liveness.reputation_points = reputation_points;
+			});
+		}
+		// The epoch, then the reputation and liveness history of each authority.
+		T::DbWeight::get()
+			.reads_writes(authority_count.saturating_mul(2).saturating_add(1), authority_count)
+	}
+
 	pub fn penalise_offline_authorities(offline_authorities: Vec<T::ValidatorId>) {
 		<Self as OffenceReporter>::report_many(
 			PalletOffence::MissedHeartbeat,
@@ -515,5 +848,9 @@ impl<T: Config> OnKilledAccount<T::ValidatorId> for Pallet<T> {
 	fn on_killed_account(who: &T::ValidatorId) {
 		Reputations::<T>::remove(who);
 		LastHeartbeat::<T>::remove(who);
+		for record in PenaltyHistory::<T>::take(who).into_iter().filter(|record| !record.pardoned) {
+			Self::unindex_penalty(record.offence, record.reported_at, who);
+		}
+		LivenessHistory::<T>::remove(who);
 	}
 }
@@ -73,6 +73,7 @@ parameter_types! {
 	pub const HeartbeatBlockInterval: u64 = HEARTBEAT_BLOCK_INTERVAL;
 	pub const ReputationPointFloorAndCeiling: (i32, i32) = (-2880, 2880);
 	pub const MaximumAccruableReputation: ReputationPoints = MAX_ACCRUABLE_REPUTATION;
+	pub const MaxPenaltyHistory: u32 = 3;
 }
 
 // Mocking the `Slasher` trait
@@ -160,6 +161,7 @@ impl Config for Test {
 	type WeightInfo = ();
 	type MaximumAccruableReputation = MaximumAccruableReputation;
 	type SafeMode = MockRuntimeSafeMode;
+	type MaxPenaltyHistory = MaxPenaltyHistory;
 }
 
 cf_test_utilities::impl_test_helpers! {
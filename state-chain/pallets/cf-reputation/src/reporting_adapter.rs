@@ -6,7 +6,6 @@
 //! Hence in this module we simply define [ReportOffence].
 use crate::*;
 use cf_primitives::FLIPPERINOS_PER_FLIP;
-use cf_traits::offence_reporting::OffenceReporter;
 use codec::Encode;
 use frame_support::{traits::OnKilledAccount, Blake2_128Concat, StorageHasher};
 use sp_staking::offence::ReportOffence;
@@ -94,16 +93,14 @@ where
 			sp_staking::offence::OffenceError::DuplicateReport
 		);
 
-		OffenceTimeSlotTracker::<T>::insert(
-			Self::report_id(&offender),
-			offence.time_slot().encode(),
-		);
+		let time_slot = offence.time_slot().encode();
+		OffenceTimeSlotTracker::<T>::insert(Self::report_id(&offender), time_slot.clone());
 
 		// TODO: Reconsider the slashing rate here. For now we assume we are reporting the node
 		// for equivocation, and that each report corresponds to 1 FLIP.
 		T::Slasher::slash_balance(&offender, FLIPPERINOS_PER_FLIP);
 
-		Pallet::<T>::report(offence, offender);
+		Pallet::<T>::penalise(offence.into(), &[offender], Some(time_slot));
 		Ok(())
 	}
 
@@ -54,6 +54,12 @@ impl<P: ReputationParameters> ReputationTracker<P> {
 		self.clamp();
 	}
 
+	/// Restores reputation that was previously deducted, for example when an offence is pardoned.
+	pub fn restore_reputation(&mut self, points: ReputationPoints) {
+		self.reputation_points.saturating_accrue(points);
+		self.clamp();
+	}
+
 	/// Clamp the reputation points to the given bounds.
 	fn clamp(&mut self) {
 		let (floor, ceiling) = P::bounds();
@@ -386,6 +386,147 @@ mod reporting_adapter_test {
 			assert!(GrandpaOffenceReporter::is_known_offence(&[OFFENDER], &NEXT_TIME_SLOT));
 			assert!(GrandpaOffenceReporter::is_known_offence(&[OFFENDER], &FUTURE_TIME_SLOT));
 			assert_eq!(MockSlasher::slash_count(OFFENDER.0), 2);
+
+			// The time slots are recorded as evidence.
+			assert_eq!(
+				ReputationPallet::penalty_history(OFFENDER.0)
+					.into_iter()
+					.map(|record| record.time_slot)
+					.collect::<Vec<_>>(),
+				vec![Some(OFFENCE_TIME_SLOT.encode()), Some(FUTURE_TIME_SLOT.encode())]
+			);
+		});
+	}
+}
+
+mod pardons {
+	use super::*;
+
+	const OFFENCE: AllOffences = AllOffences::ForgettingYourYubiKey;
+
+	#[test]
+	fn penalties_are_recorded_with_evidence() {
+		new_test_ext().execute_with(|| {
+			ReputationPallet::report_many(OFFENCE, &[ALICE, BOB]);
+			let penalty = ReputationPallet::resolve_penalty_for(OFFENCE);
+			assert_eq!(
+				ReputationPallet::penalty_history(ALICE),
+				vec![PenaltyRecord {
+					offence: OFFENCE,
+					reported_at: 1,
+					reputation: penalty.reputation,
+					suspended_until: Some(1 + penalty.suspension),
+					reported_with: 2,
+					time_slot: None,
+					pardoned: false,
+				}]
+			);
+
+			// Only the most recent penalties are kept.
+			for _ in 0..MaxPenaltyHistory::get() {
+				advance_by_block();
+				ReputationPallet::report(OFFENCE, ALICE);
+			}
+			let history = ReputationPallet::penalty_history(ALICE);
+			assert_eq!(history.len(), MaxPenaltyHistory::get() as usize);
+			assert_eq!(history[0].reported_at, 2);
+		});
+	}
+
+	#[test]
+	fn pardon_restores_reputation_and_lifts_suspension() {
+		new_test_ext().execute_with(|| {
+			ReputationPallet::report_many(OFFENCE, &[ALICE, BOB]);
+			assert_reputation!(ALICE, -15);
+
+			assert_noop!(
+				ReputationPallet::pardon(RuntimeOrigin::root(), ALICE, OFFENCE, 2),
+				Error::<Test>::PenaltyNotFound
+			);
+			assert_ok!(ReputationPallet::pardon(RuntimeOrigin::root(), ALICE, OFFENCE, 1));
+			System::assert_last_event(RuntimeEvent::ReputationPallet(Event::PenaltyPardoned {
+				validator_id: ALICE,
+				offence: OFFENCE,
+				reported_at: 1,
+			}));
+			assert_reputation!(ALICE, 0);
+			assert!(ReputationPallet::penalty_history(ALICE)[0].pardoned);
+			assert_eq!(
+				ReputationPallet::validators_suspended_for(&[OFFENCE]),
+				BTreeSet::from([BOB])
+			);
+
+			// A penalty can only be pardoned once.
+			assert_noop!(
+				ReputationPallet::pardon(RuntimeOrigin::root(), ALICE, OFFENCE, 1),
+				Error::<Test>::PenaltyNotFound
+			);
+		});
+	}
+
+	#[test]
+	fn bulk_pardon_by_offence_and_block_range() {
+		new_test_ext().execute_with(|| {
+			ReputationPallet::report_many(OFFENCE, &[ALICE, BOB]);
+			advance_by_block();
+			ReputationPallet::report_many(AllOffences::NotLockingYourComputer, &[ALICE]);
+			advance_by_block();
+			ReputationPallet::report_many(OFFENCE, &[BOB]);
+			assert_eq!(PenalisedValidators::<Test>::get(OFFENCE, 1), BTreeSet::from([ALICE, BOB]));
+
+			assert_noop!(
+				ReputationPallet::pardon_offences(RuntimeOrigin::root(), OFFENCE, 2, 1, 10),
+				Error::<Test>::InvalidBlockRange
+			);
+			assert_noop!(
+				ReputationPallet::pardon_offences(
+					RuntimeOrigin::root(),
+					OFFENCE,
+					1,
+					1 + MAX_PARDON_BLOCK_RANGE as u64,
+					10
+				),
+				Error::<Test>::InvalidBlockRange
+			);
+			assert_noop!(
+				ReputationPallet::pardon_offences(RuntimeOrigin::root(), OFFENCE, 1, 2, 1),
+				Error::<Test>::TooManyPardons
+			);
+
+			let pardoned = |who| {
+				ReputationPallet::penalty_history(who)
+					.into_iter()
+					.map(|record| record.pardoned)
+					.collect::<Vec<_>>()
+			};
+			assert_ok!(ReputationPallet::pardon_offences(RuntimeOrigin::root(), OFFENCE, 1, 2, 2));
+			assert_eq!(pardoned(ALICE), vec![true, false]);
+			assert_eq!(pardoned(BOB), vec![true, false]);
+			assert_reputation!(ALICE, -15);
+			assert_reputation!(BOB, -15);
+			assert!(!PenalisedValidators::<Test>::contains_key(OFFENCE, 1));
+			assert_eq!(PenalisedValidators::<Test>::get(OFFENCE, 3), BTreeSet::from([BOB]));
+
+			// Pardoned penalties are no longer counted.
+			assert_ok!(ReputationPallet::pardon_offences(RuntimeOrigin::root(), OFFENCE, 1, 3, 1));
+			assert_eq!(pardoned(BOB), vec![true, true]);
+		});
+	}
+
+	#[test]
+	fn penalty_index_follows_the_history() {
+		new_test_ext().execute_with(|| {
+			ReputationPallet::report_many(OFFENCE, &[ALICE, BOB]);
+			for _ in 0..MaxPenaltyHistory::get() {
+				advance_by_block();
+				ReputationPallet::report(OFFENCE, ALICE);
+			}
+
+			// Alice's oldest penalty has been dropped from her history.
+			assert_eq!(PenalisedValidators::<Test>::get(OFFENCE, 1), BTreeSet::from([BOB]));
+
+			<ReputationPallet as OnKilledAccount<_>>::on_killed_account(&ALICE);
+			assert_eq!(PenalisedValidators::<Test>::iter().count(), 1);
 		});
 	}
 }
@@ -483,3 +624,59 @@ fn in_safe_mode_you_dont_lose_reputation_for_being_offline() {
 		assert_eq!(reputation, reputation_points(&BOB));
 	});
 }
+
+mod liveness {
+	use super::*;
+
+	#[test]
+	fn liveness_of_authorities_is_recorded_at_each_heartbeat_interval() {
+		new_test_ext().execute_with(|| {
+			advance_by_hearbeat_intervals(1);
+			assert_eq!(
+				ReputationPallet::liveness_history(ALICE),
+				VecDeque::from([EpochLiveness {
+					epoch: MockEpochInfo::epoch_index(),
+					heartbeat_intervals: 1,
+					missed_heartbeats: 1,
+					reputation_points: -MISSED_HEARTBEAT_PENALTY_POINTS,
+				}])
+			);
+			// Only authorities are tracked.
+			assert!(ReputationPallet::liveness_history(BOB).is_empty());
+		});
+	}
+
+	#[test]
+	fn liveness_is_tracked_per_epoch() {
+		new_test_ext().execute_with(|| {
+			let epoch = MockEpochInfo::epoch_index();
+			ReputationPallet::record_liveness(NetworkState {
+				online: vec![ALICE],
+				offline: vec![],
+			});
+			ReputationPallet::record_liveness(NetworkState {
+				online: vec![],
+				offline: vec![ALICE],
+			});
+			let liveness = ReputationPallet::liveness_history(ALICE);
+			assert_eq!(liveness.len(), 1);
+			assert_eq!(liveness[0].heartbeat_intervals, 2);
+			assert_eq!(liveness[0].missed_heartbeats, 1);
+			assert_eq!(liveness[0].uptime(), Percent::from_percent(50));
+
+			for next_epoch in epoch + 1..=epoch + LIVENESS_HISTORY_EPOCHS as u32 {
+				MockEpochInfo::set_epoch(next_epoch);
+				ReputationPallet::record_liveness(NetworkState {
+					online: vec![ALICE],
+					offline: vec![],
+				});
+			}
+			let liveness = ReputationPallet::liveness_history(ALICE);
+			assert_eq!(liveness.len(), LIVENESS_HISTORY_EPOCHS);
+			assert_eq!(liveness.front().unwrap().epoch, epoch + 1);
+			assert!(liveness
+				.iter()
+				.all(|epoch_liveness| epoch_liveness.uptime() == Percent::one()));
+		});
+	}
+}
@@ -4,7 +4,10 @@ use super::*;
 
 use cf_traits::{Chainflip, FeePayment};
 use frame_benchmarking::{account, benchmarks, whitelisted_caller};
-use frame_support::sp_runtime::traits::UniqueSaturatedFrom;
+use frame_support::{
+	sp_runtime::traits::UniqueSaturatedFrom,
+	traits::{EnsureOrigin, UnfilteredDispatchable},
+};
 use frame_system::{pallet_prelude::BlockNumberFor, RawOrigin};
 use sp_std::collections::btree_set::BTreeSet;
 
@@ -59,4 +62,14 @@ benchmarks! {
 	verify {
 		assert!(Backers::<T>::get(proposal).contains(&caller));
 	}
+	veto_proposal {
+		let call = vec![1; 32];
+		WhitelistedCallsAwaitingEnactment::<T>::insert(BlockNumberFor::<T>::from(1u32), call.clone());
+		let veto = Call::<T>::veto_proposal { proposal: Proposal::WhitelistedCall(call) };
+	}: {
+		veto.dispatch_bypass_filter(T::EnsureGovernance::try_successful_origin().unwrap())?;
+	}
+	verify {
+		assert_eq!(WhitelistedCallsAwaitingEnactment::<T>::iter().count(), 0);
+	}
 }
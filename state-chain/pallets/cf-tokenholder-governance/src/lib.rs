@@ -3,9 +3,9 @@ use cf_chains::{eth::Address, ForeignChain};
 use cf_traits::{BroadcastAnyChainGovKey, Chainflip, CommKeyBroadcaster, FeePayment, FundingInfo};
 use codec::{Decode, Encode};
 use frame_support::{
-	dispatch::Weight,
+	dispatch::{GetDispatchInfo, Weight},
 	pallet_prelude::*,
-	traits::{OnRuntimeUpgrade, StorageVersion},
+	traits::{Contains, OnRuntimeUpgrade, StorageVersion, UnfilteredDispatchable},
 	RuntimeDebugNoBound,
 };
 use sp_std::{cmp::PartialEq, vec, vec::Vec};
@@ -27,6 +27,8 @@ pub use weights::WeightInfo;
 pub enum Proposal {
 	SetGovernanceKey(ForeignChain, Vec<u8>),
 	SetCommunityKey(Address),
+	/// An encoded runtime call. Only calls accepted by [Config::WhitelistedCalls] can be proposed.
+	WhitelistedCall(Vec<u8>),
 }
 
 pub const PALLET_VERSION: StorageVersion = StorageVersion::new(1);
@@ -64,6 +66,10 @@ pub mod pallet {
 		/// Delay in blocks after a successfully backed proposal gets executed.
 		#[pallet::constant]
 		type EnactmentDelay: Get<BlockNumberFor<Self>>;
+		/// The runtime calls that can be proposed by token holders.
+		type WhitelistedCalls: Contains<<Self as Chainflip>::RuntimeCall>;
+		/// The origin used to dispatch whitelisted calls once they are enacted.
+		type EnactmentOrigin: Get<<Self as frame_system::Config>::RuntimeOrigin>;
 	}
 
 	/// All unresolved proposals that are open for backing, indexed by the block at which the vote
@@ -90,6 +96,12 @@ pub mod pallet {
 	pub type CommKeyUpdateAwaitingEnactment<T> =
 		StorageValue<_, (BlockNumberFor<T>, Address), OptionQuery>;
 
+	/// Whitelisted call proposals awaiting enactment, indexed by the block number we will attempt
+	/// to enact them. Until then, the proposal can be vetoed by the governance council.
+	#[pallet::storage]
+	pub type WhitelistedCallsAwaitingEnactment<T> =
+		StorageMap<_, Twox64Concat, BlockNumberFor<T>, Vec<u8>>;
+
 	/// Current Governance keys for foreign chains.
 	#[pallet::storage]
 	pub type GovKeys<T> = StorageMap<_, Twox64Concat, ForeignChain, Vec<u8>>;
@@ -109,6 +121,10 @@ pub mod pallet {
 		GovKeyUpdatedHasFailed { chain: ForeignChain, key: Vec<u8> },
 		/// Update of GOV key was successful.
 		GovKeyUpdatedWasSuccessful { chain: ForeignChain, key: Vec<u8> },
+		/// A proposal awaiting enactment was vetoed by the governance council.
+		ProposalVetoed { proposal: Proposal },
+		/// The dispatch of an enacted whitelisted call has failed.
+		WhitelistedCallFailed { error: DispatchError },
 	}
 
 	#[pallet::error]
@@ -119,6 +135,12 @@ pub mod pallet {
 		ProposalDoesntExist,
 		/// The proposed governance key is incompatible with the proposed chain.
 		IncompatibleGovkey,
+		/// The proposed call can't be decoded.
+		UndecodableCall,
+		/// The proposed call is not whitelisted.
+		CallNotWhitelisted,
+		/// The proposal is not awaiting enactment.
+		ProposalNotAwaitingEnactment,
 	}
 
 	#[pallet::hooks]
@@ -169,6 +191,9 @@ pub mod pallet {
 					weight.saturating_accrue(T::WeightInfo::on_initialize_execute_proposal());
 				}
 			}
+			if let Some(call) = WhitelistedCallsAwaitingEnactment::<T>::take(current_block) {
+				weight.saturating_accrue(Self::enact_whitelisted_call(call));
+			}
 			weight
 		}
 	}
@@ -193,11 +218,19 @@ pub mod pallet {
 			proposal: Proposal,
 		) -> DispatchResultWithPostInfo {
 			let proposer = ensure_signed(origin)?;
-			if let Proposal::SetGovernanceKey(chain, ref key) = proposal {
-				ensure!(
+			match proposal {
+				Proposal::SetGovernanceKey(chain, ref key) => ensure!(
 					T::AnyChainGovKeyBroadcaster::is_govkey_compatible(chain, key),
 					Error::<T>::IncompatibleGovkey
-				);
+				),
+				Proposal::WhitelistedCall(ref call) => ensure!(
+					T::WhitelistedCalls::contains(
+						&<T as Chainflip>::RuntimeCall::decode(&mut &call[..])
+							.map_err(|_| Error::<T>::UndecodableCall)?
+					),
+					Error::<T>::CallNotWhitelisted
+				),
+				Proposal::SetCommunityKey(_) => (),
 			}
 			T::FeePayment::try_burn_fee(&proposer, T::ProposalFee::get())?;
 			Proposals::<T>::insert(
@@ -234,6 +267,59 @@ pub mod pallet {
 			})?;
 			Ok(().into())
 		}
+
+		/// Vetoes a proposal that has passed but is still awaiting enactment. Requires the
+		/// approval of the governance council.
+		///
+		/// ## Events
+		///
+		/// - [ProposalVetoed](Event::ProposalVetoed)
+		///
+		/// ## Errors
+		///
+		/// - [BadOrigin](frame_system::BadOrigin)
+		/// - [ProposalNotAwaitingEnactment](Error::ProposalNotAwaitingEnactment)
+		#[pallet::call_index(2)]
+		#[pallet::weight(T::DbWeight::get().reads_writes(2, 2))]
+		pub fn veto_proposal(
+			origin: OriginFor<T>,
+			proposal: Proposal,
+		) -> DispatchResultWithPostInfo {
+			T::EnsureGovernance::ensure_origin(origin)?;
+			match proposal {
+				Proposal::SetGovernanceKey(chain, ref key) => {
+					ensure!(
+						matches!(
+							GovKeyUpdateAwaitingEnactment::<T>::get(),
+							Some((_, (awaiting_chain, ref awaiting_key)))
+								if awaiting_chain == chain && awaiting_key == key
+						),
+						Error::<T>::ProposalNotAwaitingEnactment
+					);
+					GovKeyUpdateAwaitingEnactment::<T>::kill();
+				},
+				Proposal::SetCommunityKey(key) => {
+					ensure!(
+						matches!(
+							CommKeyUpdateAwaitingEnactment::<T>::get(),
+							Some((_, awaiting_key)) if awaiting_key == key
+						),
+						Error::<T>::ProposalNotAwaitingEnactment
+					);
+					CommKeyUpdateAwaitingEnactment::<T>::kill();
+				},
+				Proposal::WhitelistedCall(ref call) => {
+					let enactment_block = WhitelistedCallsAwaitingEnactment::<T>::iter()
+						.find_map(|(block, awaiting_call)| {
+							(awaiting_call == *call).then_some(block)
+						})
+						.ok_or(Error::<T>::ProposalNotAwaitingEnactment)?;
+					WhitelistedCallsAwaitingEnactment::<T>::remove(enactment_block);
+				},
+			}
+			Self::deposit_event(Event::<T>::ProposalVetoed { proposal });
+			Ok(().into())
+		}
 	}
 
 	impl<T: Config> Pallet<T> {
@@ -255,6 +341,9 @@ pub mod pallet {
 						Proposal::SetCommunityKey(key) => {
 							CommKeyUpdateAwaitingEnactment::<T>::put((enactment_block, key));
 						},
+						Proposal::WhitelistedCall(call) => {
+							WhitelistedCallsAwaitingEnactment::<T>::insert(enactment_block, call);
+						},
 					}
 					Event::<T>::ProposalPassed { proposal }
 				} else {
@@ -263,5 +352,30 @@ pub mod pallet {
 			);
 			backers.len()
 		}
+
+		fn enact_whitelisted_call(encoded_call: Vec<u8>) -> Weight {
+			let mut weight = T::WeightInfo::on_initialize_execute_proposal();
+			let result = <T as Chainflip>::RuntimeCall::decode(&mut &encoded_call[..])
+				.map_err(|_| Error::<T>::UndecodableCall.into())
+				.and_then(|call| {
+					let dispatch_info = call.get_dispatch_info();
+					call.dispatch_bypass_filter(T::EnactmentOrigin::get())
+						.map(|post_info| {
+							weight.saturating_accrue(post_info.calc_actual_weight(&dispatch_info))
+						})
+						.map_err(|e| {
+							weight
+								.saturating_accrue(e.post_info.calc_actual_weight(&dispatch_info));
+							e.error
+						})
+				});
+			if let Err(error) = result {
+				Self::deposit_event(Event::<T>::WhitelistedCallFailed { error });
+			}
+			Self::deposit_event(Event::<T>::ProposalEnacted {
+				proposal: Proposal::WhitelistedCall(encoded_call),
+			});
+			weight
+		}
 	}
 }
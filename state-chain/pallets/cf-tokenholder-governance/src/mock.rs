@@ -6,7 +6,10 @@ use cf_traits::{
 	CommKeyBroadcaster, WaivedFees,
 };
 use codec::{Decode, Encode};
-use frame_support::{parameter_types, traits::HandleLifetime};
+use frame_support::{
+	parameter_types,
+	traits::{Contains, Get, HandleLifetime},
+};
 use frame_system as system;
 use sp_core::H256;
 use sp_runtime::traits::{BlakeTwo256, IdentityLookup};
@@ -134,6 +137,22 @@ impl CommKeyBroadcaster for MockBroadcaster {
 	}
 }
 
+pub struct MockWhitelistedCalls;
+
+impl Contains<RuntimeCall> for MockWhitelistedCalls {
+	fn contains(call: &RuntimeCall) -> bool {
+		matches!(call, RuntimeCall::System(frame_system::Call::set_storage { .. }))
+	}
+}
+
+pub struct MockEnactmentOrigin;
+
+impl Get<RuntimeOrigin> for MockEnactmentOrigin {
+	fn get() -> RuntimeOrigin {
+		RuntimeOrigin::root()
+	}
+}
+
 impl pallet_cf_tokenholder_governance::Config for Test {
 	type RuntimeEvent = RuntimeEvent;
 	type FeePayment = MockFeePayment<Self>;
@@ -143,6 +162,8 @@ impl pallet_cf_tokenholder_governance::Config for Test {
 	type VotingPeriod = VotingPeriod;
 	type EnactmentDelay = EnactmentDelay;
 	type ProposalFee = ProposalFee;
+	type WhitelistedCalls = MockWhitelistedCalls;
+	type EnactmentOrigin = MockEnactmentOrigin;
 }
 
 // Accounts
@@ -255,3 +255,102 @@ fn govkey_broadcast_to_correct_chain() {
 		);
 	});
 }
+
+fn set_storage_call() -> Vec<u8> {
+	RuntimeCall::System(frame_system::Call::set_storage {
+		items: vec![(b"key".to_vec(), b"value".to_vec())],
+	})
+	.encode()
+}
+
+#[test]
+fn whitelisted_call_is_enacted_via_onchain_proposal() {
+	new_test_ext().execute_with(|| {
+		let proposal = Proposal::WhitelistedCall(set_storage_call());
+		submit_and_pass_proposal(proposal.clone());
+
+		let enactment_block = <frame_system::Pallet<Test>>::block_number() +
+			<mock::Test as Config>::EnactmentDelay::get();
+		assert_eq!(
+			WhitelistedCallsAwaitingEnactment::<Test>::get(enactment_block),
+			Some(set_storage_call())
+		);
+		let weight = TokenholderGovernance::on_initialize(enactment_block);
+		assert!(!WhitelistedCallsAwaitingEnactment::<Test>::contains_key(enactment_block));
+		// The call doesn't report its actual weight, so its declared weight is counted.
+		let call_weight = RuntimeCall::decode(&mut &set_storage_call()[..])
+			.unwrap()
+			.get_dispatch_info()
+			.weight;
+		assert!(weight.all_gte(
+			call_weight
+				.saturating_add(<Test as Config>::WeightInfo::on_initialize_execute_proposal())
+		));
+		assert_eq!(
+			last_event::<Test>(),
+			mock::RuntimeEvent::TokenholderGovernance(crate::Event::ProposalEnacted { proposal }),
+		);
+		assert_eq!(frame_support::storage::unhashed::get_raw(b"key"), Some(b"value".to_vec()));
+	});
+}
+
+#[test]
+fn only_whitelisted_calls_can_be_proposed() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TokenholderGovernance::submit_proposal(
+				RuntimeOrigin::signed(ALICE),
+				Proposal::WhitelistedCall(
+					RuntimeCall::System(frame_system::Call::remark { remark: vec![] }).encode()
+				),
+			),
+			Error::<Test>::CallNotWhitelisted
+		);
+		assert_noop!(
+			TokenholderGovernance::submit_proposal(
+				RuntimeOrigin::signed(ALICE),
+				Proposal::WhitelistedCall(vec![0xff; 4]),
+			),
+			Error::<Test>::UndecodableCall
+		);
+	});
+}
+
+#[test]
+fn governance_can_veto_proposals_awaiting_enactment() {
+	new_test_ext().execute_with(|| {
+		let proposal = Proposal::WhitelistedCall(set_storage_call());
+
+		assert_noop!(
+			TokenholderGovernance::veto_proposal(RuntimeOrigin::root(), proposal.clone()),
+			Error::<Test>::ProposalNotAwaitingEnactment
+		);
+
+		submit_and_pass_proposal(proposal.clone());
+		assert_ok!(TokenholderGovernance::veto_proposal(RuntimeOrigin::root(), proposal.clone()));
+		assert_eq!(
+			last_event::<Test>(),
+			mock::RuntimeEvent::TokenholderGovernance(crate::Event::ProposalVetoed {
+				proposal: proposal.clone()
+			}),
+		);
+
+		TokenholderGovernance::on_initialize(
+			<frame_system::Pallet<Test>>::block_number() +
+				<mock::Test as Config>::EnactmentDelay::get(),
+		);
+		assert_eq!(frame_support::storage::unhashed::get_raw(b"key"), None);
+
+		let gov_key_proposal = Proposal::SetGovernanceKey(ForeignChain::Ethereum, vec![1; 32]);
+		submit_and_pass_proposal(gov_key_proposal.clone());
+		assert_noop!(
+			TokenholderGovernance::veto_proposal(
+				RuntimeOrigin::root(),
+				Proposal::SetGovernanceKey(ForeignChain::Ethereum, vec![2; 32])
+			),
+			Error::<Test>::ProposalNotAwaitingEnactment
+		);
+		assert_ok!(TokenholderGovernance::veto_proposal(RuntimeOrigin::root(), gov_key_proposal));
+		assert!(GovKeyUpdateAwaitingEnactment::<Test>::get().is_none());
+	});
+}
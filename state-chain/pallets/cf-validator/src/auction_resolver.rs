@@ -32,7 +32,19 @@ pub struct SetSizeParameters {
 	pub max_expansion: u32,
 }
 
-#[derive(Copy, Clone, RuntimeDebug, PartialEq, Eq, Encode, Decode, TypeInfo, MaxEncodedLen)]
+#[derive(
+	Copy,
+	Clone,
+	RuntimeDebug,
+	PartialEq,
+	Eq,
+	Encode,
+	Decode,
+	TypeInfo,
+	MaxEncodedLen,
+	Serialize,
+	Deserialize,
+)]
 pub enum AuctionError {
 	/// Parameters must make sense ie. min <= max. And zero is not a valid size.
 	InvalidParameters,
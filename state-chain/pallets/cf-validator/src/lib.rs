@@ -14,8 +14,10 @@ pub use weights::WeightInfo;
 mod auction_resolver;
 mod benchmarking;
 pub mod migrations;
+mod rotation_simulation;
 mod rotation_state;
 pub use auction_resolver::*;
+pub use rotation_simulation::*;
 
 use cf_primitives::{
 	AuthorityCount, EpochIndex, NodeCFEVersions, SemVer, DEFAULT_MAX_AUTHORITY_SET_CONTRACTION,
@@ -26,7 +28,7 @@ use cf_traits::{
 	impl_pallet_safe_mode, offence_reporting::OffenceReporter, AsyncResult, AuthoritiesCfeVersions,
 	Bid, BidderProvider, Bonding, Chainflip, EpochInfo, EpochTransitionHandler, ExecutionCondition,
 	FundingInfo, HistoricalEpoch, MissedAuthorshipSlots, OnAccountFunded, QualifyNode,
-	ReputationResetter, SetSafeMode, VaultRotator,
+	ReputationResetter, SetSafeMode, UnbondingSchedule, VaultRotator,
 };
 
 use cf_utilities::Port;
@@ -146,6 +148,12 @@ pub mod pallet {
 		/// Updates the bond of an authority.
 		type Bonder: Bonding<ValidatorId = ValidatorIdOf<Self>, Amount = Self::Amount>;
 
+		/// Provides the partial unbonds scheduled by authorities for the next rotation.
+		type UnbondingSchedule: UnbondingSchedule<
+			ValidatorId = ValidatorIdOf<Self>,
+			Amount = Self::Amount,
+		>;
+
 		/// This is used to reset the validator's reputation
 		type ReputationResetter: ReputationResetter<ValidatorId = ValidatorIdOf<Self>>;
 
@@ -251,6 +259,12 @@ pub mod pallet {
 	pub type HistoricalActiveEpochs<T: Config> =
 		StorageMap<_, Twox64Concat, ValidatorIdOf<T>, Vec<EpochIndex>, ValueQuery>;
 
+	/// The amount each authority has unbonded from the bonds of its previous epochs. The bond
+	/// never drops below the bond of the authority's latest epoch.
+	#[pallet::storage]
+	pub type Unbonded<T: Config> =
+		StorageMap<_, Twox64Concat, ValidatorIdOf<T>, T::Amount, OptionQuery>;
+
 	/// Backups, validator nodes who are not in the authority set.
 	#[pallet::storage]
 	#[pallet::getter(fn backups)]
@@ -389,6 +403,8 @@ pub mod pallet {
 					let num_primary_candidates = rotation_state.num_primary_candidates();
 					match T::VaultRotator::status() {
 						AsyncResult::Ready(VaultStatus::KeygenComplete) => {
+							// Candidates excluded from a keygen retry don't hold a share of the new key.
+							rotation_state.ban(T::VaultRotator::keygen_exclusions());
 							Self::try_start_key_handover(rotation_state, block_number);
 						},
 						AsyncResult::Ready(VaultStatus::Failed(offenders)) => {
@@ -963,7 +979,12 @@ impl<T: Config> Pallet<T> {
 		for authority in EpochHistory::<T>::epoch_authorities(epoch).iter() {
 			num_expired_authorities += 1;
 			EpochHistory::<T>::deactivate_epoch(authority, epoch);
-			if EpochHistory::<T>::number_of_active_epochs_for_authority(authority) == 0 {
+			let active_epochs = EpochHistory::<T>::number_of_active_epochs_for_authority(authority);
+			if active_epochs <= 1 {
+				// Only the latest epoch's bond applies, so the unbonded amount no longer matters.
+				Unbonded::<T>::remove(authority);
+			}
+			if active_epochs == 0 {
 				T::ReputationResetter::reset_reputation(authority);
 			}
 			T::Bonder::update_bond(authority, EpochHistory::<T>::active_bond(authority));
@@ -986,6 +1007,18 @@ impl<T: Config> Pallet<T> {
 		HistoricalAuthorities::<T>::insert(new_epoch, new_authorities);
 
 		Bond::<T>::set(new_bond);
+		HistoricalBonds::<T>::insert(new_epoch, new_bond);
+
+		// Unbonds only apply to authorities that remain in the set. Authorities leaving the set
+		// stay bonded until their epochs expire.
+		for (account_id, amount) in T::UnbondingSchedule::take_scheduled_unbonds() {
+			if new_authorities.contains(&account_id) {
+				Unbonded::<T>::mutate(&account_id, |unbonded| {
+					*unbonded =
+						Some(unbonded.map_or(amount, |unbonded| unbonded.saturating_add(amount)))
+				});
+			}
+		}
 
 		new_authorities.iter().enumerate().for_each(|(index, account_id)| {
 			AuthorityIndex::<T>::insert(new_epoch, account_id, index as AuthorityCount);
@@ -995,8 +1028,6 @@ impl<T: Config> Pallet<T> {
 
 		CurrentEpochStartedAt::<T>::set(frame_system::Pallet::<T>::current_block_number());
 
-		HistoricalBonds::<T>::insert(new_epoch, new_bond);
-
 		// We've got new authorities, which means the backups may have changed.
 		Backups::<T>::put(backup_map);
 	}
@@ -1095,13 +1126,7 @@ impl<T: Config> Pallet<T> {
 
 	fn try_start_keygen(rotation_state: RuntimeRotationState<T>) {
 		let candidates = rotation_state.authority_candidates();
-		let SetSizeParameters { min_size, .. } = AuctionParameters::<T>::get();
-
-		let min_size = sp_std::cmp::max(
-			min_size,
-			(Percent::one().saturating_sub(MaxAuthoritySetContractionPercentage::<T>::get())) *
-				Self::current_authority_count(),
-		);
+		let min_size = Self::minimum_authority_set_size();
 
 		if (candidates.len() as u32) < min_size {
 			log::warn!(
@@ -1212,6 +1237,17 @@ impl<T: Config> Pallet<T> {
 		Ok(())
 	}
 
+	/// The minimum number of candidates required for a rotation to go ahead.
+	pub fn minimum_authority_set_size() -> AuthorityCount {
+		let SetSizeParameters { min_size, .. } = AuctionParameters::<T>::get();
+
+		sp_std::cmp::max(
+			min_size,
+			(Percent::one().saturating_sub(MaxAuthoritySetContractionPercentage::<T>::get())) *
+				Self::current_authority_count(),
+		)
+	}
+
 	/// The smallest number of parties that can generate a signature.
 	fn current_consensus_success_threshold() -> AuthorityCount {
 		cf_utilities::success_threshold_from_share_count(Self::current_authority_count())
@@ -1251,11 +1287,17 @@ impl<T: Config> HistoricalEpoch for EpochHistory<T> {
 	}
 
 	fn active_bond(authority: &Self::ValidatorId) -> Self::Amount {
-		Self::active_epochs_for_authority(authority)
+		let active_epochs = Self::active_epochs_for_authority(authority);
+		let bond = active_epochs
 			.iter()
 			.map(|epoch| Self::epoch_bond(*epoch))
 			.max()
-			.unwrap_or_else(|| Self::Amount::from(0_u32))
+			.unwrap_or_else(|| Self::Amount::from(0_u32));
+		match (Unbonded::<T>::get(authority), active_epochs.iter().max()) {
+			(Some(unbonded), Some(latest_epoch)) =>
+				bond.saturating_sub(unbonded).max(Self::epoch_bond(*latest_epoch)),
+			_ => bond,
+		}
 	}
 }
 
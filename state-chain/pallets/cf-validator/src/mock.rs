@@ -106,6 +106,7 @@ impl EpochTransitionHandler for TestEpochTransitionHandler {}
 thread_local! {
 	pub static MISSED_SLOTS: RefCell<(u64, u64)> = RefCell::new(Default::default());
 	pub static BIDDERS: RefCell<Vec<Bid<ValidatorId, Amount>>> = RefCell::new(Default::default());
+	pub static SCHEDULED_UNBONDS: RefCell<Vec<(ValidatorId, Amount)>> = RefCell::new(Default::default());
 }
 
 pub struct MockMissedAuthorshipSlots;
@@ -168,6 +169,23 @@ impl BidderProvider for MockBidderProvider {
 	}
 }
 
+pub struct MockUnbondingSchedule;
+
+impl MockUnbondingSchedule {
+	pub fn schedule_unbond(validator_id: ValidatorId, amount: Amount) {
+		SCHEDULED_UNBONDS.with(|cell| cell.borrow_mut().push((validator_id, amount)));
+	}
+}
+
+impl UnbondingSchedule for MockUnbondingSchedule {
+	type ValidatorId = ValidatorId;
+	type Amount = Amount;
+
+	fn take_scheduled_unbonds() -> Vec<(Self::ValidatorId, Self::Amount)> {
+		SCHEDULED_UNBONDS.with(|cell| cell.take())
+	}
+}
+
 impl_mock_runtime_safe_mode!(validator: PalletSafeMode);
 impl Config for Test {
 	type RuntimeEvent = RuntimeEvent;
@@ -178,6 +196,7 @@ impl Config for Test {
 	type BidderProvider = MockBidderProvider;
 	type OffenceReporter = MockOffenceReporter;
 	type Bonder = MockBonder;
+	type UnbondingSchedule = MockUnbondingSchedule;
 	type ReputationResetter = MockReputationResetter<Self>;
 	type KeygenQualification = QualifyAll<ValidatorId>;
 	type SafeMode = MockRuntimeSafeMode;
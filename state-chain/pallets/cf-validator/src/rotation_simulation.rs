@@ -0,0 +1,332 @@
+use crate::*;
+use serde::{Deserialize, Serialize};
+
+/// The stages of an authority rotation.
+#[derive(
+	Copy, Clone, RuntimeDebug, PartialEq, Eq, Encode, Decode, TypeInfo, Serialize, Deserialize,
+)]
+pub enum RotationStage {
+	Auction,
+	Keygen,
+	KeyHandover,
+	/// Key activation is handled by the vaults, so is not covered by the simulation in this
+	/// pallet. Any outcome reported for this stage is an estimate.
+	Activation,
+}
+
+/// The reason a simulated rotation would not complete.
+#[derive(
+	Copy, Clone, RuntimeDebug, PartialEq, Eq, Encode, Decode, TypeInfo, Serialize, Deserialize,
+)]
+pub enum RotationSimulationFailure {
+	/// Authority rotation is disabled by safe mode.
+	RotationsDisabled,
+	/// There is already a rotation in progress.
+	RotationInProgress,
+	/// The auction could not be resolved.
+	AuctionFailed(AuctionError),
+	/// Too many candidates were banned to satisfy the minimum authority set size.
+	NotEnoughAuthorityCandidates,
+	/// Too many current authorities were banned to select the key handover sharing set.
+	NotEnoughSharingParticipants,
+}
+
+impl RotationSimulationFailure {
+	pub fn stage(&self) -> RotationStage {
+		match self {
+			Self::RotationsDisabled | Self::RotationInProgress | Self::AuctionFailed(_) =>
+				RotationStage::Auction,
+			Self::NotEnoughAuthorityCandidates => RotationStage::Keygen,
+			Self::NotEnoughSharingParticipants => RotationStage::KeyHandover,
+		}
+	}
+}
+
+/// Why a node would not be part of the new authority set.
+#[derive(
+	Copy, Clone, RuntimeDebug, PartialEq, Eq, Encode, Decode, TypeInfo, Serialize, Deserialize,
+)]
+pub enum ExclusionReason {
+	/// A current authority that is not bidding.
+	NotBidding,
+	/// A bidder that does not meet the keygen qualification criteria.
+	NotQualified,
+	/// A qualified bidder that did not win a place in the set.
+	Outbid,
+	/// A candidate that would be banned for failing to participate in keygen.
+	FailedKeygen,
+	/// A current authority that would be banned for failing to participate in key handover.
+	FailedKeyHandover,
+}
+
+/// Why a bidder would not win a place in the authority set if the auction were resolved now.
+#[derive(
+	Copy, Clone, RuntimeDebug, PartialEq, Eq, Encode, Decode, TypeInfo, Serialize, Deserialize,
+)]
+pub enum BidderExclusion<R> {
+	/// The bidder does not meet the keygen qualification criteria, for the given reason.
+	NotQualified(R),
+	/// The bidder is qualified but was outbid, and would be a backup.
+	Outbid,
+	/// The bidder is qualified but its bid is below the backup cutoff.
+	BelowBidCutoff,
+}
+
+/// The authority set that the auction would select if it were resolved now.
+#[derive(Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct AuthoritySetPreview<Id: Ord, Amount, R> {
+	/// The projected authorities and their bids, sorted by descending bid.
+	pub projected_authorities: Vec<(Id, Amount)>,
+	/// The bond for the new epoch, if the auction could be resolved.
+	pub bond: Option<Amount>,
+	/// Projected authorities that are not current authorities.
+	pub incoming: BTreeSet<Id>,
+	/// Current authorities that are not projected authorities.
+	pub outgoing: BTreeSet<Id>,
+	/// Bidders that would not win a place in the set.
+	pub excluded: BTreeMap<Id, BidderExclusion<R>>,
+	/// Set if the auction could not be resolved.
+	pub auction_error: Option<AuctionError>,
+}
+
+/// The result of a simulated authority rotation.
+#[derive(Clone, RuntimeDebug, PartialEq, Eq, Encode, Decode, TypeInfo, Serialize, Deserialize)]
+pub struct RotationSimulation<Id: Ord, Amount> {
+	pub new_epoch_index: EpochIndex,
+	/// The bond for the new epoch, if the auction could be resolved.
+	pub bond: Option<Amount>,
+	/// The authorities that would make up the new set.
+	pub new_authorities: BTreeSet<Id>,
+	/// The current authorities that would share the current key during key handover.
+	pub sharing_participants: BTreeSet<Id>,
+	/// Nodes that would not be part of the new set.
+	pub excluded: BTreeMap<Id, ExclusionReason>,
+	/// The number of keygen ceremonies that would be required.
+	pub keygen_attempts: u32,
+	/// The number of key handover ceremonies that would be required.
+	pub key_handover_attempts: u32,
+	/// The reason the rotation would not complete, if any.
+	pub failure: Option<RotationSimulationFailure>,
+}
+
+impl<Id: Ord + Clone, Amount> RotationSimulation<Id, Amount> {
+	fn new(new_epoch_index: EpochIndex) -> Self {
+		Self {
+			new_epoch_index,
+			bond: None,
+			new_authorities: Default::default(),
+			sharing_participants: Default::default(),
+			excluded: Default::default(),
+			keygen_attempts: 0,
+			key_handover_attempts: 0,
+			failure: None,
+		}
+	}
+
+	fn failed(mut self, failure: RotationSimulationFailure) -> Self {
+		self.failure = Some(failure);
+		self
+	}
+
+	fn exclude<'a>(&mut self, ids: impl IntoIterator<Item = &'a Id>, reason: ExclusionReason)
+	where
+		Id: 'a,
+	{
+		for id in ids {
+			self.excluded.insert(id.clone(), reason);
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Simulates an authority rotation starting from the current state, following the same steps
+	/// as a real rotation. Ceremonies are mocked: they are assumed to succeed unless one of the
+	/// `offline` nodes is a participant, in which case those nodes are reported as offenders.
+	///
+	/// Nothing is written to storage. Note that the key handover sharing set is selected
+	/// pseudo-randomly based on the current block number, so it may differ in a real rotation.
+	pub fn simulate_rotation(
+		offline: &BTreeSet<ValidatorIdOf<T>>,
+	) -> RotationSimulation<ValidatorIdOf<T>, T::Amount> {
+		let mut simulation = RotationSimulation::new(CurrentEpoch::<T>::get() + 1);
+
+		if !T::SafeMode::get().authority_rotation_enabled {
+			return simulation.failed(RotationSimulationFailure::RotationsDisabled)
+		}
+		if CurrentRotationPhase::<T>::get() != RotationPhase::Idle {
+			return simulation.failed(RotationSimulationFailure::RotationInProgress)
+		}
+
+		let (qualified_bids, unqualified_bidders) = Self::partition_bids(|bidder_id| {
+			(!T::KeygenQualification::is_qualified(bidder_id))
+				.then_some(ExclusionReason::NotQualified)
+		});
+		let bidders = qualified_bids
+			.iter()
+			.map(|bid| &bid.bidder_id)
+			.chain(unqualified_bidders.keys())
+			.cloned()
+			.collect::<BTreeSet<_>>();
+		simulation
+			.exclude(Self::current_authorities().difference(&bidders), ExclusionReason::NotBidding);
+		simulation.excluded.extend(unqualified_bidders);
+		let qualified_bidders =
+			qualified_bids.iter().map(|bid| bid.bidder_id.clone()).collect::<Vec<_>>();
+
+		let auction_outcome = match Self::resolve_auction(qualified_bids) {
+			Ok(auction_outcome) => auction_outcome,
+			Err(e) => return simulation.failed(RotationSimulationFailure::AuctionFailed(e)),
+		};
+		simulation.bond = Some(auction_outcome.bond);
+
+		let mut rotation_state = RotationState::from_auction_outcome::<T>(auction_outcome);
+		let min_size = Self::minimum_authority_set_size();
+		let success_threshold = Self::current_consensus_success_threshold();
+		let seed = frame_system::Pallet::<T>::current_block_number().unique_saturated_into();
+
+		simulation.failure = 'keygen: loop {
+			let candidates = rotation_state.authority_candidates();
+			if (candidates.len() as u32) < min_size {
+				break Some(RotationSimulationFailure::NotEnoughAuthorityCandidates)
+			}
+
+			simulation.keygen_attempts += 1;
+			let keygen_offenders =
+				candidates.intersection(offline).cloned().collect::<BTreeSet<_>>();
+			if !keygen_offenders.is_empty() {
+				simulation.exclude(&keygen_offenders, ExclusionReason::FailedKeygen);
+				rotation_state.ban(keygen_offenders);
+				continue
+			}
+
+			// The candidates all took part in keygen, so only the sharing participants that are
+			// not candidates can fail the handover. The real rotation retries the handover without
+			// restarting keygen in this case.
+			loop {
+				let Some(sharing_participants) = helpers::select_sharing_participants(
+					success_threshold,
+					rotation_state.unbanned_current_authorities::<T>(),
+					&candidates,
+					seed,
+				) else {
+					break 'keygen Some(RotationSimulationFailure::NotEnoughSharingParticipants)
+				};
+
+				simulation.key_handover_attempts += 1;
+				let handover_offenders =
+					sharing_participants.intersection(offline).cloned().collect::<BTreeSet<_>>();
+				if handover_offenders.is_empty() {
+					simulation.new_authorities = candidates;
+					simulation.sharing_participants = sharing_participants;
+					break 'keygen None
+				}
+				simulation.exclude(&handover_offenders, ExclusionReason::FailedKeyHandover);
+				rotation_state.ban(handover_offenders);
+			}
+		};
+
+		if simulation.failure.is_none() {
+			let outbid = qualified_bidders
+				.iter()
+				.filter(|id| {
+					!simulation.new_authorities.contains(id) &&
+						!simulation.excluded.contains_key(id)
+				})
+				.cloned()
+				.collect::<Vec<_>>();
+			simulation.exclude(&outbid, ExclusionReason::Outbid);
+		}
+
+		simulation
+	}
+
+	/// Previews the authority set that the auction would select if it were resolved now. This is
+	/// the auction stage of [Self::simulate_rotation], without the ceremonies that follow it.
+	///
+	/// `disqualification` gives the reason a bidder fails the keygen qualification criteria, if
+	/// any. It should agree with `T::KeygenQualification`, which it is used in place of.
+	pub fn authority_set_preview<R>(
+		disqualification: impl Fn(&ValidatorIdOf<T>) -> Option<R>,
+	) -> AuthoritySetPreview<ValidatorIdOf<T>, T::Amount, R> {
+		let (qualified_bids, unqualified_bidders) = Self::partition_bids(disqualification);
+		let mut excluded = unqualified_bidders
+			.into_iter()
+			.map(|(bidder_id, reason)| (bidder_id, BidderExclusion::NotQualified(reason)))
+			.collect::<BTreeMap<_, _>>();
+		let bids = qualified_bids
+			.iter()
+			.map(|bid| (bid.bidder_id.clone(), bid.amount))
+			.collect::<BTreeMap<_, _>>();
+
+		match Self::resolve_auction(qualified_bids) {
+			Ok(AuctionOutcome { winners, losers, bond }) => {
+				let projected = winners.iter().cloned().collect::<BTreeSet<_>>();
+				let losers = losers.into_iter().collect::<BTreeSet<_>>();
+				for bidder_id in bids.keys().filter(|id| !projected.contains(id)) {
+					excluded.insert(
+						bidder_id.clone(),
+						if losers.contains(bidder_id) {
+							BidderExclusion::Outbid
+						} else {
+							BidderExclusion::BelowBidCutoff
+						},
+					);
+				}
+				let current_authorities = Self::current_authorities();
+				AuthoritySetPreview {
+					projected_authorities: winners
+						.into_iter()
+						.map(|id| {
+							let bid = bids[&id];
+							(id, bid)
+						})
+						.collect(),
+					bond: Some(bond),
+					incoming: projected.difference(&current_authorities).cloned().collect(),
+					outgoing: current_authorities.difference(&projected).cloned().collect(),
+					excluded,
+					auction_error: None,
+				}
+			},
+			Err(e) => AuthoritySetPreview {
+				projected_authorities: Default::default(),
+				bond: None,
+				incoming: Default::default(),
+				outgoing: Default::default(),
+				excluded,
+				auction_error: Some(e),
+			},
+		}
+	}
+
+	/// Splits the current bids into qualified bids and the unqualified bidders, along with the
+	/// reason given by `disqualification`.
+	fn partition_bids<R>(
+		disqualification: impl Fn(&ValidatorIdOf<T>) -> Option<R>,
+	) -> (Vec<Bid<ValidatorIdOf<T>, T::Amount>>, BTreeMap<ValidatorIdOf<T>, R>) {
+		let mut unqualified_bidders = BTreeMap::new();
+		let qualified_bids = T::BidderProvider::get_bidders()
+			.into_iter()
+			.filter(|bid| match disqualification(&bid.bidder_id) {
+				Some(reason) => {
+					unqualified_bidders.insert(bid.bidder_id.clone(), reason);
+					false
+				},
+				None => true,
+			})
+			.collect();
+		(qualified_bids, unqualified_bidders)
+	}
+
+	fn resolve_auction(
+		qualified_bids: Vec<Bid<ValidatorIdOf<T>, T::Amount>>,
+	) -> Result<AuctionOutcome<ValidatorIdOf<T>, T::Amount>, AuctionError> {
+		SetSizeMaximisingAuctionResolver::try_new(
+			T::EpochInfo::current_authority_count(),
+			AuctionParameters::<T>::get(),
+		)
+		.and_then(|resolver| {
+			resolver.resolve_auction(qualified_bids, AuctionBidCutoffPercentage::<T>::get())
+		})
+	}
+}
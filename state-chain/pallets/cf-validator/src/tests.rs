@@ -650,6 +650,54 @@ mod bond_expiry {
 			assert_eq!(EpochHistory::<Test>::active_bond(&3), 99);
 		});
 	}
+
+	#[test]
+	fn partial_unbond_is_applied_at_the_next_rotation() {
+		new_test_ext().execute_with(|| {
+			let initial_epoch = ValidatorPallet::current_epoch();
+			ValidatorPallet::transition_to_next_epoch(simple_rotation_state(
+				vec![1, 2, 3],
+				Some(100),
+			));
+
+			MockUnbondingSchedule::schedule_unbond(1, 10);
+			MockUnbondingSchedule::schedule_unbond(2, 50);
+			// Authority 3 leaves the set, so its unbond is ignored.
+			MockUnbondingSchedule::schedule_unbond(3, 10);
+			ValidatorPallet::transition_to_next_epoch(simple_rotation_state(vec![1, 2], Some(80)));
+
+			assert_eq!(EpochHistory::<Test>::active_bond(&1), 90);
+			// The bond can't be reduced below the new epoch's bond.
+			assert_eq!(EpochHistory::<Test>::active_bond(&2), 80);
+			assert_eq!(EpochHistory::<Test>::active_bond(&3), 100);
+			assert!(!Unbonded::<Test>::contains_key(3));
+
+			// Once the previous epoch expires, only the new epoch's bond applies.
+			ValidatorPallet::expire_epoch(initial_epoch + 1);
+			assert_eq!(EpochHistory::<Test>::active_bond(&1), 80);
+			assert_eq!(EpochHistory::<Test>::active_bond(&3), 0);
+			assert!(!Unbonded::<Test>::contains_key(1));
+			assert!(!Unbonded::<Test>::contains_key(2));
+		});
+	}
+
+	#[test]
+	fn consecutive_partial_unbonds_accumulate() {
+		new_test_ext().execute_with(|| {
+			ValidatorPallet::transition_to_next_epoch(simple_rotation_state(vec![1, 2], Some(100)));
+
+			MockUnbondingSchedule::schedule_unbond(1, 50);
+			ValidatorPallet::transition_to_next_epoch(simple_rotation_state(vec![1, 2], Some(50)));
+			assert_eq!(EpochHistory::<Test>::active_bond(&1), 50);
+
+			// The earlier unbond still applies to the bond of the first epoch.
+			MockUnbondingSchedule::schedule_unbond(1, 5);
+			ValidatorPallet::transition_to_next_epoch(simple_rotation_state(vec![1, 2], Some(40)));
+			assert_eq!(Unbonded::<Test>::get(1), Some(55));
+			assert_eq!(EpochHistory::<Test>::active_bond(&1), 45);
+			assert_eq!(EpochHistory::<Test>::active_bond(&2), 100);
+		});
+	}
 }
 
 #[test]
@@ -785,6 +833,32 @@ mod keygen {
 		});
 	}
 
+	#[test]
+	fn candidates_excluded_from_keygen_retries_are_banned() {
+		new_test_ext().execute_with(|| {
+			CurrentAuthorities::<Test>::set(AUTHORITIES.collect());
+			CurrentRotationPhase::<Test>::put(RotationPhase::KeygensInProgress(
+				RuntimeRotationState::<Test>::from_auction_outcome::<Test>(AuctionOutcome {
+					winners: CANDIDATES.collect(),
+					losers: Default::default(),
+					bond: Default::default(),
+				}),
+			));
+			let excluded = CANDIDATES.take(2).collect::<BTreeSet<_>>();
+
+			MockVaultRotatorA::keygen_success_excluding(excluded.clone());
+			Pallet::<Test>::on_initialize(1);
+
+			if let RotationPhase::KeyHandoversInProgress(state) =
+				CurrentRotationPhase::<Test>::get()
+			{
+				assert!(state.authority_candidates().is_disjoint(&excluded));
+			} else {
+				panic!("unexpected rotation phase: {:?}", CurrentRotationPhase::<Test>::get());
+			}
+		});
+	}
+
 	#[test]
 	fn abort_on_keygen_failure_if_too_many_banned() {
 		new_test_ext().execute_with(|| {
@@ -1282,3 +1356,152 @@ fn submitting_multiple_versions_ensuring_compatibility() {
 		assert!(QualifyByCfeVersion::<Test>::is_qualified(&VALIDATOR));
 	});
 }
+
+#[cfg(test)]
+mod rotation_simulation {
+	use super::*;
+
+	#[test]
+	fn simulation_matches_auction_outcome() {
+		new_test_ext().then_execute_with_checks(|| {
+			MockBidderProvider::set_default_test_bids();
+
+			let simulation = ValidatorPallet::simulate_rotation(&Default::default());
+
+			assert_eq!(simulation.failure, None);
+			assert_eq!(simulation.new_epoch_index, GENESIS_EPOCH + 1);
+			assert_eq!(simulation.bond, Some(EXPECTED_BOND));
+			assert_eq!(simulation.new_authorities, BTreeSet::from(AUCTION_WINNERS));
+			assert_eq!(simulation.keygen_attempts, 1);
+			assert_eq!(simulation.key_handover_attempts, 1);
+			assert!(!simulation.sharing_participants.is_empty());
+			assert!(simulation
+				.sharing_participants
+				.is_subset(&BTreeSet::from(GENESIS_AUTHORITIES)));
+			assert_eq!(
+				simulation.excluded,
+				GENESIS_AUTHORITIES
+					.into_iter()
+					.map(|id| (id, ExclusionReason::NotBidding))
+					.chain(AUCTION_LOSERS.into_iter().map(|id| (id, ExclusionReason::Outbid)))
+					.chain(sp_std::iter::once((UNQUALIFIED_NODE, ExclusionReason::NotQualified)))
+					.collect::<BTreeMap<_, _>>()
+			);
+
+			// The simulation has no side effects.
+			assert_rotation_phase_matches!(RotationPhase::Idle);
+			assert_eq!(<Test as Config>::VaultRotator::status(), AsyncResult::Void);
+		});
+	}
+
+	#[test]
+	fn offline_candidates_are_replaced_after_failed_keygen() {
+		new_test_ext().then_execute_with_checks(|| {
+			MockBidderProvider::set_default_test_bids();
+			let offline_winner = AUCTION_WINNERS[0];
+
+			let simulation = ValidatorPallet::simulate_rotation(&BTreeSet::from([offline_winner]));
+
+			assert_eq!(simulation.failure, None);
+			assert_eq!(simulation.keygen_attempts, 2);
+			assert_eq!(
+				simulation.new_authorities,
+				AUCTION_WINNERS[1..].iter().chain(&AUCTION_LOSERS[..1]).copied().collect()
+			);
+			assert_eq!(
+				simulation.excluded.get(&offline_winner),
+				Some(&ExclusionReason::FailedKeygen)
+			);
+		});
+	}
+
+	#[test]
+	fn simulation_fails_if_sharing_participants_are_offline() {
+		new_test_ext().then_execute_with_checks(|| {
+			MockBidderProvider::set_default_test_bids();
+
+			let simulation =
+				ValidatorPallet::simulate_rotation(&BTreeSet::from(GENESIS_AUTHORITIES));
+
+			assert_eq!(
+				simulation.failure,
+				Some(RotationSimulationFailure::NotEnoughSharingParticipants)
+			);
+			assert_eq!(simulation.failure.unwrap().stage(), RotationStage::KeyHandover);
+			assert_eq!(simulation.keygen_attempts, 1);
+			assert_eq!(simulation.key_handover_attempts, 1);
+			assert!(simulation.new_authorities.is_empty());
+			assert!(simulation
+				.excluded
+				.values()
+				.any(|reason| *reason == ExclusionReason::FailedKeyHandover));
+		});
+	}
+
+	#[test]
+	fn authority_set_preview_reports_why_bidders_are_excluded() {
+		new_test_ext().then_execute_with_checks(|| {
+			MockBidderProvider::set_default_test_bids();
+			// Only the last loser's bid is below the cutoff.
+			AuctionBidCutoffPercentage::<Test>::put(Percent::from_percent(80));
+
+			let preview = ValidatorPallet::authority_set_preview(|id| {
+				(*id == UNQUALIFIED_NODE).then_some("offline")
+			});
+
+			assert_eq!(preview.auction_error, None);
+			assert_eq!(preview.bond, Some(EXPECTED_BOND));
+			assert_eq!(
+				preview.projected_authorities,
+				AUCTION_WINNERS.into_iter().zip(WINNING_BIDS).collect::<Vec<_>>()
+			);
+			assert_eq!(preview.incoming, BTreeSet::from(AUCTION_WINNERS));
+			assert_eq!(preview.outgoing, BTreeSet::from(GENESIS_AUTHORITIES));
+			assert_eq!(
+				preview.excluded,
+				BTreeMap::from([
+					(UNQUALIFIED_NODE, BidderExclusion::NotQualified("offline")),
+					(AUCTION_LOSERS[0], BidderExclusion::Outbid),
+					(AUCTION_LOSERS[1], BidderExclusion::Outbid),
+					(AUCTION_LOSERS[2], BidderExclusion::BelowBidCutoff),
+				])
+			);
+
+			// The preview agrees with the auction stage of the simulation.
+			let simulation = ValidatorPallet::simulate_rotation(&Default::default());
+			assert_eq!(simulation.bond, preview.bond);
+			assert_eq!(
+				simulation.new_authorities,
+				preview.projected_authorities.iter().map(|(id, _)| *id).collect()
+			);
+		});
+	}
+
+	#[test]
+	fn authority_set_preview_reports_auction_errors() {
+		new_test_ext().then_execute_with_checks(|| {
+			let preview = ValidatorPallet::authority_set_preview(|_| None::<()>);
+
+			assert_eq!(preview.auction_error, Some(AuctionError::NotEnoughBidders));
+			assert!(preview.projected_authorities.is_empty());
+			assert!(preview.excluded.is_empty());
+		});
+	}
+
+	#[test]
+	fn simulation_fails_without_bidders_or_when_disabled() {
+		new_test_ext().then_execute_with_checks(|| {
+			assert_eq!(
+				ValidatorPallet::simulate_rotation(&Default::default()).failure,
+				Some(RotationSimulationFailure::AuctionFailed(AuctionError::NotEnoughBidders))
+			);
+
+			MockBidderProvider::set_default_test_bids();
+			<MockRuntimeSafeMode as SetSafeMode<MockRuntimeSafeMode>>::set_code_red();
+			assert_eq!(
+				ValidatorPallet::simulate_rotation(&Default::default()).failure,
+				Some(RotationSimulationFailure::RotationsDisabled)
+			);
+		});
+	}
+}
@@ -182,5 +182,11 @@ benchmarks_instance_pallet! {
 	verify {
 		assert_eq!(KeygenResponseTimeout::<T, I>::get(), new_timeout);
 	}
+	set_max_keygen_attempts {
+		let call = Call::<T, I>::set_max_keygen_attempts { max_attempts: 5 };
+	} : { call.dispatch_bypass_filter(T::EnsureGovernance::try_successful_origin().unwrap())? }
+	verify {
+		assert_eq!(MaxKeygenAttempts::<T, I>::get(), 5);
+	}
 	impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test,);
 }
@@ -2,7 +2,7 @@
 #![doc = include_str!("../README.md")]
 #![doc = include_str!("../../cf-doc-head.md")]
 
-use cf_chains::{Chain, ChainCrypto, SetAggKeyWithAggKey};
+use cf_chains::{Chain, ChainCrypto, SetAggKeyWithAggKey, SetAggKeyWithAggKeyError};
 use cf_primitives::{
 	AuthorityCount, CeremonyId, EpochIndex, FlipBalance, ThresholdSignatureRequestId,
 };
@@ -19,6 +19,7 @@ use frame_support::{
 };
 use frame_system::pallet_prelude::*;
 pub use pallet::*;
+use serde::{Deserialize, Serialize};
 use sp_std::{
 	collections::{btree_map::BTreeMap, btree_set::BTreeSet},
 	iter::Iterator,
@@ -40,9 +41,10 @@ pub mod migrations;
 mod mock;
 mod tests;
 
-pub const PALLET_VERSION: StorageVersion = StorageVersion::new(2);
+pub const PALLET_VERSION: StorageVersion = StorageVersion::new(3);
 
 const KEYGEN_CEREMONY_RESPONSE_TIMEOUT_BLOCKS_DEFAULT: u32 = 90;
+const MAX_KEYGEN_ATTEMPTS_DEFAULT: u32 = 3;
 
 pub type PayloadFor<T, I = ()> = <<T as Config<I>>::Chain as ChainCrypto>::Payload;
 pub type KeygenOutcomeFor<T, I = ()> =
@@ -153,6 +155,22 @@ pub enum PalletOffence {
 	FailedKeyHandover,
 }
 
+/// How a newly generated key would be activated for a vault.
+///
+/// The new key isn't known until keygen completes, so the activation call is built with the
+/// current key in its place. The call is otherwise the one the vault would broadcast.
+#[derive(Clone, RuntimeDebug, PartialEq, Eq, Encode, Decode, TypeInfo, Serialize, Deserialize)]
+pub enum KeyActivationPreview {
+	/// The SCALE-encoded activation call that would be signed with the current key and broadcast.
+	Broadcast { call: Vec<u8> },
+	/// No activation transaction is required, the new key would be activated immediately.
+	NotRequired,
+	/// There is no active key, so the new key would need to be activated by governance.
+	AwaitingGovernanceActivation,
+	/// The activation transaction could not be built.
+	Failed,
+}
+
 #[derive(Encode, Decode, TypeInfo)]
 pub struct VaultEpochAndState {
 	pub epoch_index: EpochIndex,
@@ -215,6 +233,9 @@ pub mod pallet {
 
 		type ChainTracking: GetBlockHeight<Self::Chain>;
 
+		/// The minimum number of participants required for a keygen ceremony to be retried.
+		type MinimumAuthoritySetSize: Get<AuthorityCount>;
+
 		/// Benchmark stuff
 		type WeightInfo: WeightInfo;
 	}
@@ -252,14 +273,16 @@ pub mod pallet {
 							Self::trigger_keygen_verification(
 								ceremony_id,
 								new_public_key,
-								keygen_participants,
+								keygen_participants.clone(),
 								new_epoch_index,
 							);
 						},
 						|offenders| {
-							Self::terminate_rotation(
-								offenders.into_iter().collect::<Vec<_>>().as_slice(),
-								Event::KeygenFailure(ceremony_id),
+							Self::on_keygen_failure(
+								ceremony_id,
+								&keygen_participants,
+								new_epoch_index,
+								offenders,
 							);
 						},
 					);
@@ -406,6 +429,23 @@ pub mod pallet {
 	#[pallet::storage]
 	pub(super) type KeygenSlashAmount<T, I = ()> = StorageValue<_, FlipBalance, ValueQuery>;
 
+	/// The number of keygen ceremonies that have been started for the pending vault rotation.
+	#[pallet::storage]
+	#[pallet::getter(fn keygen_attempts)]
+	pub type KeygenAttempts<T: Config<I>, I: 'static = ()> = StorageValue<_, u32, ValueQuery>;
+
+	/// The maximum number of keygen ceremonies per vault rotation. Failed ceremonies are retried
+	/// without the offenders until this limit is reached, after which the rotation is aborted.
+	#[pallet::storage]
+	#[pallet::getter(fn max_keygen_attempts)]
+	pub type MaxKeygenAttempts<T: Config<I>, I: 'static = ()> = StorageValue<_, u32, ValueQuery>;
+
+	/// Candidates that have been excluded from keygen retries in the pending vault rotation.
+	#[pallet::storage]
+	#[pallet::getter(fn keygen_excluded_candidates)]
+	pub type KeygenExcludedCandidates<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, BTreeSet<T::ValidatorId>, ValueQuery>;
+
 	/// Counter for generating unique ceremony ids.
 	#[pallet::storage]
 	#[pallet::getter(fn ceremony_id_counter)]
@@ -469,6 +509,17 @@ pub mod pallet {
 		},
 		/// Keygen has failed \[ceremony_id\]
 		KeygenFailure(CeremonyId),
+		/// Keygen has failed and will be retried without the offenders.
+		KeygenRetry {
+			failed_ceremony_id: CeremonyId,
+			excluded: BTreeSet<T::ValidatorId>,
+			/// The number of the upcoming attempt.
+			attempt: u32,
+		},
+		/// The maximum number of keygen attempts per rotation was updated.
+		MaxKeygenAttemptsUpdated {
+			max_attempts: u32,
+		},
 		/// Keygen response timeout has occurred \[ceremony_id\]
 		KeygenResponseTimeout(CeremonyId),
 		KeyHandoverResponseTimeout {
@@ -755,6 +806,26 @@ pub mod pallet {
 
 			Ok(().into())
 		}
+
+		/// Sets the maximum number of keygen ceremonies per vault rotation. A value of 0 or 1
+		/// disables keygen retries.
+		///
+		/// ## Events
+		///
+		/// - [MaxKeygenAttemptsUpdated](Event::MaxKeygenAttemptsUpdated)
+		#[pallet::call_index(8)]
+		#[pallet::weight(T::DbWeight::get().writes(1))]
+		pub fn set_max_keygen_attempts(
+			origin: OriginFor<T>,
+			max_attempts: u32,
+		) -> DispatchResultWithPostInfo {
+			T::EnsureGovernance::ensure_origin(origin)?;
+
+			MaxKeygenAttempts::<T, I>::put(max_attempts);
+			Self::deposit_event(Event::MaxKeygenAttemptsUpdated { max_attempts });
+
+			Ok(().into())
+		}
 	}
 
 	#[pallet::genesis_config]
@@ -763,6 +834,7 @@ pub mod pallet {
 		pub deployment_block: ChainBlockNumberFor<T, I>,
 		pub keygen_response_timeout: BlockNumberFor<T>,
 		pub amount_to_slash: FlipBalance,
+		pub max_keygen_attempts: u32,
 	}
 
 	impl<T: Config<I>, I: 'static> Default for GenesisConfig<T, I> {
@@ -773,6 +845,7 @@ pub mod pallet {
 				deployment_block: Zero::zero(),
 				keygen_response_timeout: KEYGEN_CEREMONY_RESPONSE_TIMEOUT_BLOCKS_DEFAULT.into(),
 				amount_to_slash: 0u128,
+				max_keygen_attempts: MAX_KEYGEN_ATTEMPTS_DEFAULT,
 			}
 		}
 	}
@@ -790,6 +863,7 @@ pub mod pallet {
 			}
 			KeygenSlashAmount::<T, I>::put(self.amount_to_slash);
 			KeygenResponseTimeout::<T, I>::put(self.keygen_response_timeout);
+			MaxKeygenAttempts::<T, I>::put(self.max_keygen_attempts);
 		}
 	}
 }
@@ -827,7 +901,9 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		};
 
 		let candidate_count = response_status.candidate_count();
-		let weight = match response_status.resolve_keygen_outcome(final_key_check) {
+		// Cleared before handling the outcome, since a failed keygen may be retried immediately.
+		PendingSince::kill();
+		match response_status.resolve_keygen_outcome(final_key_check) {
 			Ok(new_public_key) => {
 				debug_assert_eq!(
 					remaining_candidate_count, 0,
@@ -848,9 +924,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 				on_failure_outcome(offenders);
 				T::WeightInfo::on_initialize_failure(offenders_len as u32)
 			},
-		};
-		PendingSince::kill();
-		weight
+		}
 	}
 
 	fn set_vault_key_for_epoch(epoch_index: EpochIndex, vault: Vault<T::Chain>) {
@@ -907,13 +981,51 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		request_id
 	}
 
-	fn terminate_rotation(offenders: &[T::ValidatorId], event: Event<T, I>) {
+	/// Retries keygen without the offenders if the retry limit has not been reached and enough
+	/// participants remain, otherwise terminates the rotation.
+	fn on_keygen_failure(
+		ceremony_id: CeremonyId,
+		participants: &BTreeSet<T::ValidatorId>,
+		new_epoch_index: EpochIndex,
+		offenders: BTreeSet<T::ValidatorId>,
+	) {
+		let remaining_participants =
+			participants.difference(&offenders).cloned().collect::<BTreeSet<_>>();
+
+		if offenders.is_empty() ||
+			KeygenAttempts::<T, I>::get() >= MaxKeygenAttempts::<T, I>::get() ||
+			(remaining_participants.len() as AuthorityCount) < T::MinimumAuthoritySetSize::get()
+		{
+			Self::terminate_rotation(
+				offenders.into_iter().collect::<Vec<_>>().as_slice(),
+				Event::KeygenFailure(ceremony_id),
+			);
+			return
+		}
+
+		Self::punish_keygen_offenders(offenders.iter().cloned().collect::<Vec<_>>().as_slice());
+		KeygenExcludedCandidates::<T, I>::mutate(|excluded| {
+			excluded.extend(offenders.iter().cloned())
+		});
+		Self::deposit_event(Event::KeygenRetry {
+			failed_ceremony_id: ceremony_id,
+			excluded: offenders,
+			attempt: KeygenAttempts::<T, I>::get() + 1,
+		});
+		Self::start_keygen(remaining_participants, new_epoch_index);
+	}
+
+	fn punish_keygen_offenders(offenders: &[T::ValidatorId]) {
 		T::OffenceReporter::report_many(PalletOffence::FailedKeygen, offenders);
 		if T::SafeMode::get().slashing_enabled {
 			for offender in offenders {
 				T::Slasher::slash_balance(offender, KeygenSlashAmount::<T, I>::get());
 			}
 		}
+	}
+
+	fn terminate_rotation(offenders: &[T::ValidatorId], event: Event<T, I>) {
+		Self::punish_keygen_offenders(offenders);
 		PendingVaultRotation::<T, I>::put(VaultRotationStatus::<T, I>::Failed {
 			offenders: offenders.iter().cloned().collect(),
 		});
@@ -951,6 +1063,25 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		Ok(().into())
 	}
 
+	/// Previews how a new key would be activated, using the current key in place of the new one.
+	///
+	/// Building the activation call can have side effects (for example, selecting utxos to sweep),
+	/// so this should only be called where state changes are discarded, such as a runtime API.
+	pub fn key_activation_preview() -> KeyActivationPreview {
+		match Self::active_epoch_key() {
+			Some(EpochKey { key, .. }) =>
+				match <T::SetAggKeyWithAggKey as SetAggKeyWithAggKey<_>>::new_unsigned(
+					Some(key),
+					key,
+				) {
+					Ok(call) => KeyActivationPreview::Broadcast { call: call.encode() },
+					Err(SetAggKeyWithAggKeyError::NotRequired) => KeyActivationPreview::NotRequired,
+					Err(SetAggKeyWithAggKeyError::Failed) => KeyActivationPreview::Failed,
+				},
+			None => KeyActivationPreview::AwaitingGovernanceActivation,
+		}
+	}
+
 	fn activate_new_key(new_agg_key: AggKeyFor<T, I>, block_number: ChainBlockNumberFor<T, I>) {
 		PendingVaultRotation::<T, I>::put(VaultRotationStatus::<T, I>::Complete);
 		Self::set_vault_key_for_epoch(
@@ -0,0 +1,27 @@
+use crate::*;
+#[cfg(feature = "try-runtime")]
+use frame_support::dispatch::DispatchError;
+use frame_support::traits::OnRuntimeUpgrade;
+use sp_std::marker::PhantomData;
+
+/// v3 sets the default MaxKeygenAttempts, which enables keygen retries on existing networks.
+pub struct Migration<T: Config<I>, I: 'static>(PhantomData<(T, I)>);
+
+impl<T: Config<I>, I: 'static> OnRuntimeUpgrade for Migration<T, I> {
+	fn on_runtime_upgrade() -> frame_support::weights::Weight {
+		MaxKeygenAttempts::<T, I>::put(MAX_KEYGEN_ATTEMPTS_DEFAULT);
+
+		T::DbWeight::get().writes(1)
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<Vec<u8>, DispatchError> {
+		Ok(Default::default())
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade(_state: Vec<u8>) -> Result<(), DispatchError> {
+		assert_eq!(MaxKeygenAttempts::<T, I>::get(), MAX_KEYGEN_ATTEMPTS_DEFAULT);
+		Ok(())
+	}
+}
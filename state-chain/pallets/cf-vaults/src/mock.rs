@@ -41,6 +41,7 @@ construct_runtime!(
 
 parameter_types! {
 	pub const BlockHashCount: u64 = 250;
+	pub static MinimumAuthoritySetSize: AuthorityCount = 2;
 }
 
 pub const ETH_DUMMY_SIG: SchnorrVerificationComponents =
@@ -222,6 +223,7 @@ impl pallet_cf_vaults::Config for Test {
 	type SafeMode = MockRuntimeSafeMode;
 	type Slasher = MockSlasher;
 	type ChainTracking = BlockHeightProvider<MockEthereum>;
+	type MinimumAuthoritySetSize = MinimumAuthoritySetSize;
 }
 
 pub const ALICE: <Test as frame_system::Config>::AccountId = 123u64;
@@ -242,6 +244,7 @@ cf_test_utilities::impl_test_helpers! {
 			deployment_block: 0,
 			keygen_response_timeout: MOCK_KEYGEN_RESPONSE_TIMEOUT,
 			amount_to_slash: FLIPPERINOS_PER_FLIP,
+			max_keygen_attempts: 1,
 		},
 	},
 	|| {
@@ -3,9 +3,11 @@
 use core::{marker, panic};
 
 use crate::{
-	mock::*, CeremonyId, Error, Event as PalletEvent, KeyHandoverResolutionPendingSince,
+	mock::*, CeremonyId, Error, Event as PalletEvent, KeyActivationPreview,
+	KeyHandoverResolutionPendingSince, KeygenAttempts, KeygenExcludedCandidates,
 	KeygenFailureVoters, KeygenOutcomeFor, KeygenResolutionPendingSince, KeygenResponseTimeout,
-	KeygenSuccessVoters, PalletOffence, PendingVaultRotation, Vault, VaultRotationStatus, Vaults,
+	KeygenSuccessVoters, MaxKeygenAttempts, PalletOffence, PendingVaultRotation, Vault,
+	VaultRotationStatus, Vaults,
 };
 use cf_chains::{
 	btc::BitcoinCrypto,
@@ -19,8 +21,11 @@ use cf_traits::{
 	AccountRoleRegistry, AsyncResult, Chainflip, EpochInfo, KeyProvider, SafeMode, SetSafeMode,
 	VaultRotator, VaultStatus,
 };
+use codec::Encode;
 use frame_support::{
-	assert_noop, assert_ok, pallet_prelude::DispatchResultWithPostInfo, traits::Hooks,
+	assert_noop, assert_ok,
+	pallet_prelude::DispatchResultWithPostInfo,
+	traits::{Hooks, OnRuntimeUpgrade},
 };
 use frame_system::pallet_prelude::BlockNumberFor;
 use sp_core::Get;
@@ -1269,3 +1274,175 @@ fn can_recover_from_abort_vault_rotation_after_key_handover_failed() {
 		do_full_key_rotation();
 	});
 }
+
+#[test]
+fn key_activation_preview() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(
+			VaultsPallet::key_activation_preview(),
+			KeyActivationPreview::Broadcast {
+				call: (GENESIS_AGG_PUB_KEY, GENESIS_AGG_PUB_KEY).encode()
+			}
+		);
+
+		MockSetAggKeyWithAggKey::set_required(false);
+		assert_eq!(VaultsPallet::key_activation_preview(), KeyActivationPreview::NotRequired);
+
+		// The preview does not start a rotation.
+		assert_eq!(VaultsPallet::status(), AsyncResult::Void);
+	});
+
+	new_test_ext_no_key().execute_with(|| {
+		assert_eq!(
+			VaultsPallet::key_activation_preview(),
+			KeyActivationPreview::AwaitingGovernanceActivation
+		);
+	});
+}
+
+#[cfg(test)]
+mod keygen_retries {
+	use super::*;
+
+	const MAX_ATTEMPTS: u32 = 2;
+
+	// All remaining participants blame the offenders, and the outcome is resolved.
+	fn fail_current_keygen(offenders: &[<Test as Chainflip>::ValidatorId]) {
+		let ceremony_id = current_ceremony_id();
+		let participants = match PendingVaultRotation::<Test, _>::get() {
+			Some(VaultRotationStatus::AwaitingKeygen { keygen_participants, .. }) =>
+				keygen_participants,
+			other => panic!("Unexpected rotation status {other:?}"),
+		};
+		for participant in participants {
+			assert_ok!(VaultsPallet::report_keygen_outcome(
+				RuntimeOrigin::signed(participant),
+				ceremony_id,
+				Err(offenders.iter().cloned().collect())
+			));
+		}
+		VaultsPallet::on_initialize(1);
+	}
+
+	#[test]
+	fn failed_keygen_is_retried_without_offenders() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(VaultsPallet::set_max_keygen_attempts(RuntimeOrigin::root(), MAX_ATTEMPTS));
+			assert_last_event!(crate::Event::MaxKeygenAttemptsUpdated {
+				max_attempts: MAX_ATTEMPTS
+			});
+
+			VaultsPallet::keygen(
+				BTreeSet::from_iter(ALL_CANDIDATES.iter().cloned()),
+				GENESIS_EPOCH,
+			);
+			assert_eq!(KeygenAttempts::<Test, _>::get(), 1);
+			let failed_ceremony_id = current_ceremony_id();
+
+			fail_current_keygen(&[CHARLIE]);
+
+			assert_eq!(VaultsPallet::status(), AsyncResult::Pending);
+			assert_eq!(KeygenAttempts::<Test, _>::get(), 2);
+			assert_eq!(KeygenExcludedCandidates::<Test, _>::get(), BTreeSet::from([CHARLIE]));
+			assert_eq!(VaultsPallet::keygen_exclusions(), BTreeSet::from([CHARLIE]));
+			assert!(KeygenResolutionPendingSince::<Test, _>::exists());
+			MockOffenceReporter::assert_reported(PalletOffence::FailedKeygen, [CHARLIE]);
+			assert_eq!(MockSlasher::slash_count(CHARLIE), 1);
+			assert!(frame_system::Pallet::<Test>::events().into_iter().any(
+				|record| record.event ==
+					PalletEvent::KeygenRetry {
+						failed_ceremony_id,
+						excluded: BTreeSet::from([CHARLIE]),
+						attempt: 2,
+					}
+					.into()
+			));
+			assert_eq!(
+				last_event::<Test>(),
+				PalletEvent::KeygenRequest {
+					ceremony_id: failed_ceremony_id + 1,
+					participants: BTreeSet::from([ALICE, BOB]),
+					epoch_index: GENESIS_EPOCH,
+				}
+				.into()
+			);
+
+			// The exclusions are cleared when a new rotation starts.
+			VaultsPallet::reset_vault_rotation();
+			assert_eq!(KeygenAttempts::<Test, _>::get(), 0);
+			assert!(VaultsPallet::keygen_exclusions().is_empty());
+		});
+	}
+
+	#[test]
+	fn rotation_fails_once_max_attempts_are_reached() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(VaultsPallet::set_max_keygen_attempts(RuntimeOrigin::root(), 1));
+
+			VaultsPallet::keygen(
+				BTreeSet::from_iter(ALL_CANDIDATES.iter().cloned()),
+				GENESIS_EPOCH,
+			);
+			fail_current_keygen(&[CHARLIE]);
+
+			assert_eq!(KeygenAttempts::<Test, _>::get(), 1);
+			assert_eq!(
+				VaultsPallet::status(),
+				AsyncResult::Ready(VaultStatus::Failed(BTreeSet::from([CHARLIE])))
+			);
+			assert_last_event!(crate::Event::KeygenFailure(..));
+		});
+	}
+
+	#[test]
+	fn rotation_fails_if_too_few_participants_would_remain() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(VaultsPallet::set_max_keygen_attempts(RuntimeOrigin::root(), MAX_ATTEMPTS));
+			MinimumAuthoritySetSize::set(ALL_CANDIDATES.len() as AuthorityCount);
+
+			VaultsPallet::keygen(
+				BTreeSet::from_iter(ALL_CANDIDATES.iter().cloned()),
+				GENESIS_EPOCH,
+			);
+			fail_current_keygen(&[CHARLIE]);
+
+			assert_eq!(KeygenAttempts::<Test, _>::get(), 1);
+			assert!(KeygenExcludedCandidates::<Test, _>::get().is_empty());
+			assert_eq!(
+				VaultsPallet::status(),
+				AsyncResult::Ready(VaultStatus::Failed(BTreeSet::from([CHARLIE])))
+			);
+			assert_last_event!(crate::Event::KeygenFailure(..));
+		});
+	}
+
+	#[test]
+	fn keygen_retries_are_enabled_by_default() {
+		new_test_ext_no_key().execute_with(|| {
+			assert_eq!(MaxKeygenAttempts::<Test, _>::get(), crate::MAX_KEYGEN_ATTEMPTS_DEFAULT);
+		});
+		new_test_ext().execute_with(|| {
+			MaxKeygenAttempts::<Test, _>::kill();
+			crate::migrations::v3::Migration::<Test, ()>::on_runtime_upgrade();
+			assert_eq!(MaxKeygenAttempts::<Test, _>::get(), crate::MAX_KEYGEN_ATTEMPTS_DEFAULT);
+		});
+	}
+
+	#[test]
+	fn keygen_is_not_retried_with_a_single_attempt() {
+		new_test_ext().execute_with(|| {
+			assert_eq!(MaxKeygenAttempts::<Test, _>::get(), 1);
+
+			VaultsPallet::keygen(
+				BTreeSet::from_iter(ALL_CANDIDATES.iter().cloned()),
+				GENESIS_EPOCH,
+			);
+			fail_current_keygen(&[CHARLIE]);
+
+			assert_eq!(
+				VaultsPallet::status(),
+				AsyncResult::Ready(VaultStatus::Failed(BTreeSet::from([CHARLIE])))
+			);
+		});
+	}
+}
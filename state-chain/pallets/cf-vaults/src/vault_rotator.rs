@@ -15,24 +15,13 @@ impl<T: Config<I>, I: 'static> VaultRotator for Pallet<T, I> {
 
 		assert_ne!(Self::status(), AsyncResult::Pending);
 
-		let ceremony_id = Self::increment_ceremony_id();
-
-		PendingVaultRotation::<T, I>::put(VaultRotationStatus::AwaitingKeygen {
-			ceremony_id,
-			keygen_participants: candidates.clone(),
-			response_status: KeygenResponseStatus::new(candidates.clone()),
-			new_epoch_index,
-		});
-
-		// Start the timer for resolving Keygen - we check this in the on_initialise() hook each
-		// block
-		KeygenResolutionPendingSince::<T, I>::put(frame_system::Pallet::<T>::current_block_number());
+		KeygenAttempts::<T, I>::kill();
+		KeygenExcludedCandidates::<T, I>::kill();
+		Self::start_keygen(candidates, new_epoch_index);
+	}
 
-		Pallet::<T, I>::deposit_event(Event::KeygenRequest {
-			ceremony_id,
-			participants: candidates,
-			epoch_index: new_epoch_index,
-		});
+	fn keygen_exclusions() -> BTreeSet<Self::ValidatorId> {
+		KeygenExcludedCandidates::<T, I>::get()
 	}
 
 	/// Kicks off the key handover process
@@ -216,6 +205,8 @@ impl<T: Config<I>, I: 'static> VaultRotator for Pallet<T, I> {
 		PendingVaultRotation::<T, I>::kill();
 		KeyHandoverResolutionPendingSince::<T, I>::kill();
 		KeygenResolutionPendingSince::<T, I>::kill();
+		KeygenAttempts::<T, I>::kill();
+		KeygenExcludedCandidates::<T, I>::kill();
 	}
 
 	#[cfg(feature = "runtime-benchmarks")]
@@ -257,3 +248,27 @@ impl<T: Config<I>, I: 'static> VaultRotator for Pallet<T, I> {
 		}
 	}
 }
+
+impl<T: Config<I>, I: 'static> Pallet<T, I> {
+	pub(crate) fn start_keygen(candidates: BTreeSet<T::ValidatorId>, new_epoch_index: EpochIndex) {
+		let ceremony_id = Self::increment_ceremony_id();
+		KeygenAttempts::<T, I>::mutate(|attempts| *attempts += 1);
+
+		PendingVaultRotation::<T, I>::put(VaultRotationStatus::AwaitingKeygen {
+			ceremony_id,
+			keygen_participants: candidates.clone(),
+			response_status: KeygenResponseStatus::new(candidates.clone()),
+			new_epoch_index,
+		});
+
+		// Start the timer for resolving Keygen - we check this in the on_initialise() hook each
+		// block
+		KeygenResolutionPendingSince::<T, I>::put(frame_system::Pallet::<T>::current_block_number());
+
+		Pallet::<T, I>::deposit_event(Event::KeygenRequest {
+			ceremony_id,
+			participants: candidates,
+			epoch_index: new_epoch_index,
+		});
+	}
+}
@@ -9,10 +9,11 @@ mod missed_authorship_slots;
 mod offences;
 mod signer_nomination;
 use crate::{
-	AccountId, AccountRoles, Authorship, BitcoinChainTracking, BitcoinIngressEgress, BitcoinVault,
-	BlockNumber, Emissions, Environment, EthereumBroadcaster, EthereumChainTracking,
-	EthereumIngressEgress, Flip, FlipBalance, PolkadotBroadcaster, PolkadotChainTracking,
-	PolkadotIngressEgress, PolkadotVault, Runtime, RuntimeCall, System, Validator, YEAR,
+	runtime_apis::BidderExclusionReason, AccountId, AccountRoles, Authorship, BitcoinChainTracking,
+	BitcoinIngressEgress, BitcoinVault, BlockNumber, Emissions, Environment, EthereumBroadcaster,
+	EthereumChainTracking, EthereumIngressEgress, Flip, FlipBalance, Funding, PolkadotBroadcaster,
+	PolkadotChainTracking, PolkadotIngressEgress, PolkadotVault, Reputation, Runtime, RuntimeCall,
+	RuntimeOrigin, Session, System, Validator, YEAR,
 };
 use backup_node_rewards::calculate_backup_rewards;
 use cf_chains::{
@@ -42,11 +43,14 @@ use cf_chains::{
 	ChainEnvironment, ChainState, DepositChannel, ForeignChain, ReplayProtectionProvider,
 	SetCommKeyWithAggKey, SetGovKeyWithAggKey, TransactionBuilder,
 };
-use cf_primitives::{chains::assets, AccountRole, Asset, BasisPoints, ChannelId, EgressId};
+use cf_primitives::{
+	chains::assets, AccountRole, Asset, AuthorityCount, BasisPoints, ChannelId, EgressId,
+};
 use cf_traits::{
 	AccountInfo, AccountRoleRegistry, BlockEmissions, BroadcastAnyChainGovKey, Broadcaster,
 	Chainflip, CommKeyBroadcaster, DepositApi, DepositHandler, EgressApi, EpochInfo, Heartbeat,
 	Issuance, KeyProvider, OnBroadcastReady, QualifyNode, RewardsDistribution, RuntimeUpgrade,
+	TransactionFeeAudit,
 };
 use codec::{Decode, Encode};
 use frame_support::{
@@ -55,7 +59,7 @@ use frame_support::{
 		traits::{BlockNumberProvider, One, UniqueSaturatedFrom, UniqueSaturatedInto},
 		FixedPointNumber, FixedU64,
 	},
-	traits::{Defensive, Get},
+	traits::{Contains, Defensive, Get},
 };
 pub use missed_authorship_slots::MissedAuraSlots;
 pub use offences::*;
@@ -101,7 +105,9 @@ impl RewardsDistribution for BackupNodeEmissions {
 			Emissions::current_authority_emission_per_block(),
 			Self::Balance::unique_saturated_from(Validator::current_authority_count()),
 		) {
-			Flip::settle(&validator_id, Self::Issuance::mint(reward).into());
+			for (account_id, share) in Funding::split_reward(&validator_id, reward) {
+				Flip::settle(&account_id, Self::Issuance::mint(share).into());
+			}
 		}
 	}
 }
@@ -205,6 +211,21 @@ impl TransactionBuilder<Ethereum, EthereumApi<EthEnvironment>> for EthTransactio
 	}
 }
 
+pub struct EthTransactionFeeAudit;
+
+impl TransactionFeeAudit<Ethereum> for EthTransactionFeeAudit {
+	/// Compares the effective gas price paid against the gas price implied by the tracked base
+	/// and priority fees.
+	fn fee_paid_percentage(fee_paid: &<Ethereum as Chain>::TransactionFee) -> Option<u32> {
+		let tracked_data = EthereumChainTracking::chain_state()?.tracked_data;
+		fee_paid
+			.effective_gas_price
+			.saturating_mul(100)
+			.checked_div(tracked_data.max_fee_per_gas(One::one()))
+			.map(|percentage| percentage.try_into().unwrap_or(u32::MAX))
+	}
+}
+
 pub struct DotTransactionBuilder;
 impl TransactionBuilder<Polkadot, PolkadotApi<DotEnvironment>> for DotTransactionBuilder {
 	fn build_transaction(
@@ -269,7 +290,11 @@ impl RewardsDistribution for BlockAuthorRewardDistribution {
 		let reward_amount = Emissions::current_authority_emission_per_block();
 		if reward_amount != 0 {
 			if let Some(current_block_author) = Authorship::author() {
-				Flip::settle(&current_block_author, Self::Issuance::mint(reward_amount).into());
+				for (account_id, share) in
+					Funding::split_reward(&current_block_author, reward_amount)
+				{
+					Flip::settle(&account_id, Self::Issuance::mint(share).into());
+				}
 			} else {
 				log::warn!("No block author for block {}.", System::current_block_number());
 			}
@@ -435,6 +460,32 @@ impl CommKeyBroadcaster for TokenholderGovernanceBroadcaster {
 	}
 }
 
+/// The calls that token holders can propose through the tokenholder governance pallet.
+pub struct TokenholderGovernanceWhitelistedCalls;
+
+impl Contains<RuntimeCall> for TokenholderGovernanceWhitelistedCalls {
+	fn contains(call: &RuntimeCall) -> bool {
+		matches!(
+			call,
+			RuntimeCall::Swapping(pallet_cf_swapping::Call::set_minimum_swap_amount { .. }) |
+				RuntimeCall::LiquidityPools(pallet_cf_pools::Call::set_pool_fees { .. }) |
+				RuntimeCall::Emissions(
+					pallet_cf_emissions::Call::update_current_authority_emission_inflation { .. } |
+						pallet_cf_emissions::Call::update_backup_node_emission_inflation { .. }
+				)
+		)
+	}
+}
+
+/// Enacted tokenholder proposals are dispatched as if approved by the governance council.
+pub struct TokenholderGovernanceEnactmentOrigin;
+
+impl Get<RuntimeOrigin> for TokenholderGovernanceEnactmentOrigin {
+	fn get() -> RuntimeOrigin {
+		pallet_cf_governance::RawOrigin::GovernanceApproval.into()
+	}
+}
+
 #[macro_export]
 macro_rules! impl_deposit_api_for_anychain {
 	( $t: ident, $(($chain: ident, $pallet: ident)),+ ) => {
@@ -599,6 +650,59 @@ impl QualifyNode<<Runtime as Chainflip>::ValidatorId> for ValidatorRoleQualifica
 	}
 }
 
+/// Slashes validators and the accounts that have delegated funds to them, pro-rata.
+pub type DelegationAwareSlasher =
+	pallet_cf_funding::DelegationSlasher<Runtime, pallet_cf_flip::FlipSlasher<Runtime>>;
+
+pub struct MinimumAuthoritySetSize;
+
+impl Get<AuthorityCount> for MinimumAuthoritySetSize {
+	fn get() -> AuthorityCount {
+		Validator::minimum_authority_set_size()
+	}
+}
+
+/// The reason a node fails a keygen qualification check. Implemented for each of the checks that
+/// make up the validator pallet's `KeygenQualification`, so that the reason can be derived from it.
+pub trait KeygenDisqualification {
+	fn disqualification(account_id: &AccountId) -> Option<BidderExclusionReason>;
+}
+
+impl<A: KeygenDisqualification, B: KeygenDisqualification> KeygenDisqualification for (A, B) {
+	fn disqualification(account_id: &AccountId) -> Option<BidderExclusionReason> {
+		A::disqualification(account_id).or_else(|| B::disqualification(account_id))
+	}
+}
+
+macro_rules! impl_keygen_disqualification {
+	( $( $check:ty => $reason:ident ),+ $(,)? ) => {
+		$(
+			impl KeygenDisqualification for $check {
+				fn disqualification(account_id: &AccountId) -> Option<BidderExclusionReason> {
+					(!<$check as QualifyNode<AccountId>>::is_qualified(account_id))
+						.then_some(BidderExclusionReason::$reason)
+				}
+			}
+		)+
+	};
+}
+
+impl_keygen_disqualification! {
+	Reputation => Offline,
+	pallet_cf_reputation::ExclusionList<Runtime, KeygenExclusionOffences> => Suspended,
+	pallet_cf_validator::PeerMapping<Runtime> => NoPeerId,
+	cf_traits::SessionKeysRegistered<Runtime, Session> => SessionKeysNotRegistered,
+	ValidatorRoleQualification => NotValidator,
+	pallet_cf_validator::QualifyByCfeVersion<Runtime> => OutdatedCfeVersion,
+}
+
+/// Returns the first keygen qualification check that the node fails, in the order the checks are
+/// applied by the validator pallet's `KeygenQualification`.
+pub fn keygen_disqualification(account_id: &AccountId) -> Option<BidderExclusionReason> {
+	type KeygenQualification = <Runtime as pallet_cf_validator::Config>::KeygenQualification;
+	<KeygenQualification as KeygenDisqualification>::disqualification(account_id)
+}
+
 // Calculates the APY of a given account, returned in Basis Points (1 b.p. = 0.01%)
 // Returns Some(APY) if the account is a Validator/backup validator.
 // Otherwise returns None.
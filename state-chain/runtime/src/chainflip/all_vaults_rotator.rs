@@ -49,7 +49,17 @@ where
 			let statuses = async_results.into_iter().map(AsyncResult::unwrap).collect::<Vec<_>>();
 
 			if statuses.iter().all(|x| matches!(x, VaultStatus::KeygenComplete)) {
-				AsyncResult::Ready(VaultStatus::KeygenComplete)
+				let exclusions =
+					[A::keygen_exclusions(), B::keygen_exclusions(), C::keygen_exclusions()];
+				if exclusions.iter().all(|excluded| *excluded == exclusions[0]) {
+					AsyncResult::Ready(VaultStatus::KeygenComplete)
+				} else {
+					// Some of the keys were generated by a different set of participants, so keygen
+					// has to be restarted without any of the excluded candidates.
+					AsyncResult::Ready(VaultStatus::Failed(
+						exclusions.into_iter().flatten().collect(),
+					))
+				}
 			} else if statuses.iter().all(|x| matches!(x, VaultStatus::KeyHandoverComplete)) {
 				AsyncResult::Ready(VaultStatus::KeyHandoverComplete)
 			} else if statuses.iter().all(|x| matches!(x, VaultStatus::RotationComplete)) {
@@ -77,6 +87,13 @@ where
 		}
 	}
 
+	fn keygen_exclusions() -> BTreeSet<Self::ValidatorId> {
+		[A::keygen_exclusions(), B::keygen_exclusions(), C::keygen_exclusions()]
+			.into_iter()
+			.flatten()
+			.collect()
+	}
+
 	fn activate() {
 		A::activate();
 		B::activate();
@@ -214,6 +231,40 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn keygen_complete_with_same_exclusions() {
+		frame_support::sp_io::TestExternalities::new_empty().execute_with(|| {
+			MockVaultRotatorA::keygen_success_excluding([1, 2]);
+			MockVaultRotatorB::keygen_success_excluding([1, 2]);
+			MockVaultRotatorC::keygen_success_excluding([1, 2]);
+
+			assert_eq!(
+				AllVaultRotator::<MockVaultRotatorA, MockVaultRotatorB, MockVaultRotatorC>::status(
+				),
+				AsyncResult::Ready(VaultStatus::KeygenComplete)
+			);
+			assert_eq!(
+				AllVaultRotator::<MockVaultRotatorA, MockVaultRotatorB, MockVaultRotatorC>::keygen_exclusions(),
+				BTreeSet::from([1, 2])
+			);
+		});
+	}
+
+	#[test]
+	fn keygen_with_different_exclusions_is_failure() {
+		frame_support::sp_io::TestExternalities::new_empty().execute_with(|| {
+			MockVaultRotatorA::keygen_success_excluding([1]);
+			MockVaultRotatorB::keygen_success();
+			MockVaultRotatorC::keygen_success_excluding([2]);
+
+			assert_eq!(
+				AllVaultRotator::<MockVaultRotatorA, MockVaultRotatorB, MockVaultRotatorC>::status(
+				),
+				AsyncResult::Ready(VaultStatus::Failed(BTreeSet::from([1, 2])))
+			);
+		});
+	}
+
 	#[test]
 	fn all_pending_is_pending() {
 		frame_support::sp_io::TestExternalities::new_empty().execute_with(|| {
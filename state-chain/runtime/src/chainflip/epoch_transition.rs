@@ -1,12 +1,15 @@
 use cf_primitives::EpochIndex;
 use cf_traits::EpochTransitionHandler;
 
-use crate::Witnesser;
+use crate::{BitcoinBroadcaster, EthereumBroadcaster, PolkadotBroadcaster, Witnesser};
 
 pub struct ChainflipEpochTransitions;
 
 impl EpochTransitionHandler for ChainflipEpochTransitions {
 	fn on_expired_epoch(expired: EpochIndex) {
 		<Witnesser as EpochTransitionHandler>::on_expired_epoch(expired);
+		<EthereumBroadcaster as EpochTransitionHandler>::on_expired_epoch(expired);
+		<PolkadotBroadcaster as EpochTransitionHandler>::on_expired_epoch(expired);
+		<BitcoinBroadcaster as EpochTransitionHandler>::on_expired_epoch(expired);
 	}
 }
@@ -49,6 +49,10 @@ pub mod common {
 		KEYGEN_TIMEOUT_BUFFER_SECONDS) /
 		SECONDS_PER_BLOCK as u32;
 
+	/// The maximum number of keygen ceremonies per vault rotation. Failed ceremonies are retried
+	/// without the offending nodes.
+	pub const MAX_KEYGEN_ATTEMPTS: u32 = 3;
+
 	// NOTE: Currently it is not possible to change the slot duration after the chain has started.
 	//       Attempting to do so will brick block production.
 	pub const SLOT_DURATION: u64 = MILLISECONDS_PER_BLOCK;
@@ -10,7 +10,11 @@ pub mod test_runner;
 mod weights;
 use crate::{
 	chainflip::{calculate_account_apy, Offence},
-	runtime_apis::{AuctionState, LiquidityProviderInfo, RuntimeApiPenalty},
+	runtime_apis::{
+		AuctionState, AuthoritySetPreview, LiquidityProviderInfo, RestrictedBalance,
+		RotationDryRun, RuntimeApiEpochLiveness, RuntimeApiPenalty, RuntimeApiPenaltyRecord,
+		RuntimeApiRedemptionQueuePosition, ValidatorLiveness,
+	},
 };
 use cf_amm::{
 	common::{Amount, Price, Tick},
@@ -26,7 +30,7 @@ use cf_chains::{
 use cf_primitives::NetworkEnvironment;
 use core::ops::Range;
 pub use frame_system::Call as SystemCall;
-use pallet_cf_governance::GovCallHash;
+use pallet_cf_governance::{GovCallHash, ProposalId, ProposalPreview, ProposalRecord};
 use pallet_cf_ingress_egress::{ChannelAction, DepositWitness};
 use pallet_cf_pools::{AssetsMap, PoolLiquidity, UnidirectionalPoolDepth};
 use pallet_cf_reputation::ExclusionList;
@@ -56,7 +60,7 @@ pub use frame_support::{
 };
 use frame_system::offchain::SendTransactionTypes;
 use pallet_cf_funding::MinimumFunding;
-use pallet_cf_pools::{PoolInfo, PoolOrders};
+use pallet_cf_pools::{FlipBurnRecord, PoolInfo, PoolOrders};
 use pallet_grandpa::AuthorityId as GrandpaId;
 use pallet_session::historical as session_historical;
 pub use pallet_timestamp::Call as TimestampCall;
@@ -76,7 +80,7 @@ use sp_runtime::{
 	ApplyExtrinsicResult, MultiSignature,
 };
 pub use sp_runtime::{Perbill, Permill};
-use sp_std::prelude::*;
+use sp_std::{collections::btree_set::BTreeSet, prelude::*};
 #[cfg(feature = "std")]
 use sp_version::NativeVersion;
 use sp_version::RuntimeVersion;
@@ -100,7 +104,7 @@ use chainflip::{
 use safe_mode::{RuntimeSafeMode, WitnesserCallPermission};
 
 use constants::common::*;
-use pallet_cf_flip::{Bonder, FlipSlasher};
+use pallet_cf_flip::Bonder;
 use pallet_cf_vaults::Vault;
 pub use pallet_transaction_payment::ChargeTransactionPayment;
 
@@ -193,6 +197,7 @@ impl pallet_cf_validator::Config for Runtime {
 	);
 	type OffenceReporter = Reputation;
 	type Bonder = Bonder<Runtime>;
+	type UnbondingSchedule = Funding;
 	type SafeMode = RuntimeSafeMode;
 	type ReputationResetter = Reputation;
 }
@@ -239,7 +244,8 @@ impl pallet_cf_vaults::Config<EthereumInstance> for Runtime {
 	type WeightInfo = pallet_cf_vaults::weights::PalletWeight<Runtime>;
 	type ChainTracking = EthereumChainTracking;
 	type SafeMode = RuntimeSafeMode;
-	type Slasher = FlipSlasher<Self>;
+	type Slasher = chainflip::DelegationAwareSlasher;
+	type MinimumAuthoritySetSize = chainflip::MinimumAuthoritySetSize;
 }
 
 impl pallet_cf_vaults::Config<PolkadotInstance> for Runtime {
@@ -256,7 +262,8 @@ impl pallet_cf_vaults::Config<PolkadotInstance> for Runtime {
 	type WeightInfo = pallet_cf_vaults::weights::PalletWeight<Runtime>;
 	type ChainTracking = PolkadotChainTracking;
 	type SafeMode = RuntimeSafeMode;
-	type Slasher = FlipSlasher<Self>;
+	type Slasher = chainflip::DelegationAwareSlasher;
+	type MinimumAuthoritySetSize = chainflip::MinimumAuthoritySetSize;
 }
 
 impl pallet_cf_vaults::Config<BitcoinInstance> for Runtime {
@@ -273,7 +280,8 @@ impl pallet_cf_vaults::Config<BitcoinInstance> for Runtime {
 	type WeightInfo = pallet_cf_vaults::weights::PalletWeight<Runtime>;
 	type ChainTracking = BitcoinChainTracking;
 	type SafeMode = RuntimeSafeMode;
-	type Slasher = FlipSlasher<Self>;
+	type Slasher = chainflip::DelegationAwareSlasher;
+	type MinimumAuthoritySetSize = chainflip::MinimumAuthoritySetSize;
 }
 
 use chainflip::address_derivation::AddressDerivation;
@@ -328,12 +336,14 @@ impl pallet_cf_ingress_egress::Config<BitcoinInstance> for Runtime {
 
 parameter_types! {
 	pub const NetworkFee: Permill = Permill::from_perthousand(1);
+	pub const MaxFlipBurnHistory: u32 = 100;
 }
 
 impl pallet_cf_pools::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type LpBalance = LiquidityProvider;
 	type NetworkFee = NetworkFee;
+	type MaxFlipBurnHistory = MaxFlipBurnHistory;
 	type SafeMode = RuntimeSafeMode;
 	type WeightInfo = ();
 }
@@ -525,6 +535,10 @@ impl pallet_cf_witnesser::Config for Runtime {
 	type WeightInfo = pallet_cf_witnesser::weights::PalletWeight<Runtime>;
 }
 
+parameter_types! {
+	pub const MaxDelegatorsPerOperator: u32 = 100;
+}
+
 impl pallet_cf_funding::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type ThresholdCallable = RuntimeCall;
@@ -537,6 +551,7 @@ impl pallet_cf_funding::Config for Runtime {
 	type TimeSource = Timestamp;
 	type SafeMode = RuntimeSafeMode;
 	type WeightInfo = pallet_cf_funding::weights::PalletWeight<Runtime>;
+	type MaxDelegatorsPerOperator = MaxDelegatorsPerOperator;
 }
 
 impl pallet_cf_tokenholder_governance::Config for Runtime {
@@ -548,6 +563,8 @@ impl pallet_cf_tokenholder_governance::Config for Runtime {
 	type CommKeyBroadcaster = TokenholderGovernanceBroadcaster;
 	type ProposalFee = ConstU128<{ 1_000 * FLIPPERINOS_PER_FLIP }>;
 	type EnactmentDelay = ConstU32<{ 7 * DAYS }>;
+	type WhitelistedCalls = chainflip::TokenholderGovernanceWhitelistedCalls;
+	type EnactmentOrigin = chainflip::TokenholderGovernanceEnactmentOrigin;
 }
 
 impl pallet_cf_governance::Config for Runtime {
@@ -560,6 +577,7 @@ impl pallet_cf_governance::Config for Runtime {
 	type RuntimeUpgrade = chainflip::RuntimeUpgradeManager;
 	type CompatibleCfeVersions = Environment;
 	type AuthoritiesCfeVersions = Validator;
+	type MaxAuditLogLength = ConstU32<1_000>;
 }
 
 impl pallet_cf_emissions::Config for Runtime {
@@ -595,6 +613,7 @@ impl pallet_transaction_payment::Config for Runtime {
 
 parameter_types! {
 	pub const ReputationPointFloorAndCeiling: (i32, i32) = (-2880, 2880);
+	pub const MaxPenaltyHistory: u32 = 100;
 	pub const MaximumAccruableReputation: pallet_cf_reputation::ReputationPoints = 15;
 }
 
@@ -604,10 +623,11 @@ impl pallet_cf_reputation::Config for Runtime {
 	type Heartbeat = ChainflipHeartbeat;
 	type HeartbeatBlockInterval = ConstU32<HEARTBEAT_BLOCK_INTERVAL>;
 	type ReputationPointFloorAndCeiling = ReputationPointFloorAndCeiling;
-	type Slasher = FlipSlasher<Self>;
+	type Slasher = chainflip::DelegationAwareSlasher;
 	type WeightInfo = pallet_cf_reputation::weights::PalletWeight<Runtime>;
 	type MaximumAccruableReputation = MaximumAccruableReputation;
 	type SafeMode = RuntimeSafeMode;
+	type MaxPenaltyHistory = MaxPenaltyHistory;
 }
 
 impl pallet_cf_threshold_signature::Config<EthereumInstance> for Runtime {
@@ -664,6 +684,8 @@ impl pallet_cf_broadcast::Config<EthereumInstance> for Runtime {
 	type TransactionBuilder = chainflip::EthTransactionBuilder;
 	type BroadcastSignerNomination = chainflip::RandomSignerNomination;
 	type OffenceReporter = Reputation;
+	type Slasher = chainflip::DelegationAwareSlasher;
+	type TransactionFeeAudit = chainflip::EthTransactionFeeAudit;
 	type EnsureThresholdSigned =
 		pallet_cf_threshold_signature::EnsureThresholdSigned<Self, EthereumInstance>;
 	type BroadcastReadyProvider = BroadcastReadyProvider;
@@ -687,6 +709,8 @@ impl pallet_cf_broadcast::Config<PolkadotInstance> for Runtime {
 	type TransactionBuilder = chainflip::DotTransactionBuilder;
 	type BroadcastSignerNomination = chainflip::RandomSignerNomination;
 	type OffenceReporter = Reputation;
+	type Slasher = chainflip::DelegationAwareSlasher;
+	type TransactionFeeAudit = ();
 	type EnsureThresholdSigned =
 		pallet_cf_threshold_signature::EnsureThresholdSigned<Self, PolkadotInstance>;
 	type BroadcastReadyProvider = BroadcastReadyProvider;
@@ -710,6 +734,8 @@ impl pallet_cf_broadcast::Config<BitcoinInstance> for Runtime {
 	type TransactionBuilder = chainflip::BtcTransactionBuilder;
 	type BroadcastSignerNomination = chainflip::RandomSignerNomination;
 	type OffenceReporter = Reputation;
+	type Slasher = chainflip::DelegationAwareSlasher;
+	type TransactionFeeAudit = ();
 	type EnsureThresholdSigned =
 		pallet_cf_threshold_signature::EnsureThresholdSigned<Self, BitcoinInstance>;
 	type BroadcastReadyProvider = BroadcastReadyProvider;
@@ -960,7 +986,7 @@ impl_runtime_apis! {
 			let apy_bp = calculate_account_apy(account_id);
 			let reputation_info = pallet_cf_reputation::Reputations::<Runtime>::get(account_id);
 			let account_info = pallet_cf_flip::Account::<Runtime>::get(account_id);
-			let restricted_balances = pallet_cf_funding::RestrictedBalances::<Runtime>::get(account_id);
+			let restrictions = Funding::restricted_balances(account_id);
 			RuntimeApiAccountInfoV2 {
 				balance: account_info.total(),
 				bond: account_info.bond(),
@@ -974,7 +1000,16 @@ impl_runtime_apis! {
 				is_bidding,
 				bound_redeem_address,
 				apy_bp,
-				restricted_balances,
+				restricted_balances: restrictions
+					.iter()
+					.map(|(address, (_, total, _))| (*address, *total))
+					.collect(),
+				restrictions: restrictions
+					.into_iter()
+					.map(|(address, (reason, total, unlocked))| {
+						(address, RestrictedBalance { reason, total, unlocked })
+					})
+					.collect(),
 			}
 		}
 
@@ -1087,6 +1122,90 @@ impl_runtime_apis! {
 			Environment::network_environment()
 		}
 
+		fn cf_rotation_dry_run(offline: BTreeSet<AccountId>) -> RotationDryRun {
+			let simulation = Validator::simulate_rotation(&offline);
+			let key_activations = if simulation.failure.is_none() {
+				vec![
+					(ForeignChain::Ethereum, EthereumVault::key_activation_preview()),
+					(ForeignChain::Polkadot, PolkadotVault::key_activation_preview()),
+					(ForeignChain::Bitcoin, BitcoinVault::key_activation_preview()),
+				]
+			} else {
+				Default::default()
+			};
+			RotationDryRun { simulation, key_activations }
+		}
+
+		fn cf_authority_set_preview() -> AuthoritySetPreview {
+			Validator::authority_set_preview(chainflip::keygen_disqualification).into()
+		}
+
+		fn cf_governance_proposals() -> Vec<ProposalPreview<AccountId>> {
+			Governance::proposal_previews()
+		}
+
+		fn cf_governance_audit_log() -> Vec<(ProposalId, ProposalRecord<AccountId, BlockNumber>)> {
+			Governance::audit_log()
+		}
+
+		fn cf_projected_flip_supply(epochs: u32) -> Vec<(u32, u128)> {
+			Emissions::project_flip_supply(
+				epochs,
+				Validator::current_epoch_started_at(),
+				Validator::blocks_per_epoch(),
+			)
+		}
+
+		fn cf_flip_burn_history() -> Vec<FlipBurnRecord<BlockNumber>> {
+			LiquidityPools::flip_burn_history()
+		}
+
+		fn cf_penalty_history(account_id: &AccountId) -> Vec<RuntimeApiPenaltyRecord> {
+			Reputation::penalty_history(account_id)
+				.into_iter()
+				.map(|record| RuntimeApiPenaltyRecord {
+					offence: record.offence,
+					reported_at: record.reported_at,
+					reputation_points: record.reputation,
+					suspended_until: record.suspended_until,
+					reported_with: record.reported_with,
+					time_slot: record.time_slot,
+					pardoned: record.pardoned,
+				})
+				.collect()
+		}
+
+		fn cf_validator_liveness(account_id: &AccountId) -> ValidatorLiveness {
+			ValidatorLiveness {
+				last_heartbeat: Reputation::last_heartbeat(account_id),
+				is_online: Reputation::is_qualified(account_id),
+				reputation_points: Reputation::reputation(account_id).reputation_points,
+				epochs: Reputation::liveness_history(account_id)
+					.into_iter()
+					.map(|liveness| RuntimeApiEpochLiveness {
+						epoch: liveness.epoch,
+						heartbeat_intervals: liveness.heartbeat_intervals,
+						missed_heartbeats: liveness.missed_heartbeats,
+						uptime_percent: liveness.uptime().deconstruct(),
+						reputation_points: liveness.reputation_points,
+					})
+					.collect(),
+			}
+		}
+
+		fn cf_redemption_queue_position(
+			account_id: &AccountId,
+		) -> Option<RuntimeApiRedemptionQueuePosition> {
+			Funding::redemption_queue_position(account_id).map(|queued| {
+				RuntimeApiRedemptionQueuePosition {
+					position: queued.position,
+					amount: queued.amount,
+					amount_ahead: queued.amount_ahead,
+					queued_at: queued.queued_at,
+				}
+			})
+		}
+
 		fn cf_min_swap_amount(asset: Asset) -> AssetAmount {
 			Swapping::minimum_swap_amount(asset)
 		}
@@ -5,19 +5,27 @@ use cf_amm::{
 };
 use cf_chains::{eth::Address as EthereumAddress, ForeignChainAddress};
 use cf_primitives::{
-	AccountRole, Asset, AssetAmount, EpochIndex, ForeignChain, NetworkEnvironment, SemVer,
-	SwapOutput,
+	AccountRole, Asset, AssetAmount, BlockNumber, EpochIndex, ForeignChain, NetworkEnvironment,
+	SemVer, SwapOutput,
 };
 use codec::{Decode, Encode};
 use core::ops::Range;
 use frame_support::sp_runtime::AccountId32;
-use pallet_cf_governance::GovCallHash;
-use pallet_cf_pools::{AssetsMap, PoolInfo, PoolLiquidity, PoolOrders, UnidirectionalPoolDepth};
+use pallet_cf_funding::RestrictionReason;
+use pallet_cf_governance::{GovCallHash, ProposalId, ProposalPreview, ProposalRecord};
+use pallet_cf_pools::{
+	AssetsMap, FlipBurnRecord, PoolInfo, PoolLiquidity, PoolOrders, UnidirectionalPoolDepth,
+};
+use pallet_cf_validator::{AuctionError, BidderExclusion, RotationSimulation};
+use pallet_cf_vaults::KeyActivationPreview;
 use scale_info::TypeInfo;
 use serde::{Deserialize, Serialize};
 use sp_api::decl_runtime_apis;
 use sp_runtime::DispatchError;
-use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
+use sp_std::{
+	collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+	vec::Vec,
+};
 
 type VanityName = Vec<u8>;
 
@@ -49,6 +57,16 @@ pub struct RuntimeApiAccountInfoV2 {
 	pub bound_redeem_address: Option<EthereumAddress>,
 	pub apy_bp: Option<u32>, // APY for validator/back only. In Basis points.
 	pub restricted_balances: BTreeMap<EthereumAddress, u128>,
+	pub restrictions: BTreeMap<EthereumAddress, RestrictedBalance>,
+}
+
+#[derive(Encode, Decode, Eq, PartialEq, TypeInfo, Serialize, Deserialize)]
+pub struct RestrictedBalance {
+	pub reason: RestrictionReason,
+	pub total: u128,
+	/// The amount released by the restriction's unlock schedule, which can be redeemed to any
+	/// address.
+	pub unlocked: u128,
 }
 
 #[derive(Encode, Decode, Eq, PartialEq, TypeInfo)]
@@ -57,6 +75,43 @@ pub struct RuntimeApiPenalty {
 	pub suspension_duration_blocks: u32,
 }
 
+#[derive(Encode, Decode, Eq, PartialEq, TypeInfo)]
+pub struct RuntimeApiPenaltyRecord {
+	pub offence: Offence,
+	pub reported_at: BlockNumber,
+	pub reputation_points: i32,
+	pub suspended_until: Option<BlockNumber>,
+	pub reported_with: u32,
+	pub time_slot: Option<Vec<u8>>,
+	pub pardoned: bool,
+}
+
+#[derive(Encode, Decode, Eq, PartialEq, TypeInfo)]
+pub struct RuntimeApiEpochLiveness {
+	pub epoch: u32,
+	pub heartbeat_intervals: u32,
+	pub missed_heartbeats: u32,
+	pub uptime_percent: u8,
+	pub reputation_points: i32,
+}
+
+#[derive(Encode, Decode, Eq, PartialEq, TypeInfo)]
+pub struct ValidatorLiveness {
+	pub last_heartbeat: Option<BlockNumber>,
+	pub is_online: bool,
+	pub reputation_points: i32,
+	/// Liveness over the most recent epochs, oldest first.
+	pub epochs: Vec<RuntimeApiEpochLiveness>,
+}
+
+#[derive(Encode, Decode, Eq, PartialEq, TypeInfo)]
+pub struct RuntimeApiRedemptionQueuePosition {
+	pub position: u32,
+	pub amount: u128,
+	pub amount_ahead: u128,
+	pub queued_at: BlockNumber,
+}
+
 #[derive(Encode, Decode, Eq, PartialEq, TypeInfo)]
 pub struct AuctionState {
 	pub blocks_per_epoch: u32,
@@ -73,6 +128,80 @@ pub struct LiquidityProviderInfo {
 	pub balances: Vec<(Asset, AssetAmount)>,
 }
 
+#[derive(Encode, Decode, Eq, PartialEq, TypeInfo)]
+pub struct RotationDryRun {
+	pub simulation: RotationSimulation<AccountId32, u128>,
+	/// An estimate of how each vault would activate its new key, using the current key in place
+	/// of the new one. Only populated if the simulated rotation reaches the activation stage.
+	pub key_activations: Vec<(ForeignChain, KeyActivationPreview)>,
+}
+
+/// Why a bidder would not be part of the authority set if the auction were resolved now.
+#[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, TypeInfo, Serialize, Deserialize)]
+pub enum BidderExclusionReason {
+	/// The node has not submitted a recent heartbeat.
+	Offline,
+	/// The node is suspended for an offence that excludes it from keygen.
+	Suspended,
+	/// The node has not registered a peer id.
+	NoPeerId,
+	/// The node has not registered its session keys.
+	SessionKeysNotRegistered,
+	/// The account does not have the validator role.
+	NotValidator,
+	/// The node is running a CFE version below the minimum reported version.
+	OutdatedCfeVersion,
+	/// The node is qualified but was outbid, and would be a backup.
+	Outbid,
+	/// The node is qualified but its bid is below the backup cutoff.
+	BelowBidCutoff,
+}
+
+#[derive(Encode, Decode, Eq, PartialEq, TypeInfo)]
+pub struct AuthoritySetPreview {
+	/// The projected authorities and their bids, sorted by descending bid.
+	pub projected_authorities: Vec<(AccountId32, u128)>,
+	pub min_active_bid: Option<u128>,
+	/// Projected authorities that are not current authorities.
+	pub incoming: BTreeSet<AccountId32>,
+	/// Current authorities that are not projected authorities.
+	pub outgoing: BTreeSet<AccountId32>,
+	/// Bidders that would not win a place in the set.
+	pub excluded: BTreeMap<AccountId32, BidderExclusionReason>,
+	/// Set if the auction could not be resolved.
+	pub auction_error: Option<AuctionError>,
+}
+
+type ValidatorAuthoritySetPreview =
+	pallet_cf_validator::AuthoritySetPreview<AccountId32, u128, BidderExclusionReason>;
+
+impl From<ValidatorAuthoritySetPreview> for AuthoritySetPreview {
+	fn from(preview: ValidatorAuthoritySetPreview) -> Self {
+		Self {
+			projected_authorities: preview.projected_authorities,
+			min_active_bid: preview.bond,
+			incoming: preview.incoming,
+			outgoing: preview.outgoing,
+			excluded: preview
+				.excluded
+				.into_iter()
+				.map(|(account_id, exclusion)| {
+					(
+						account_id,
+						match exclusion {
+							BidderExclusion::NotQualified(reason) => reason,
+							BidderExclusion::Outbid => BidderExclusionReason::Outbid,
+							BidderExclusion::BelowBidCutoff =>
+								BidderExclusionReason::BelowBidCutoff,
+						},
+					)
+				})
+				.collect(),
+			auction_error: preview.auction_error,
+		}
+	}
+}
+
 decl_runtime_apis!(
 	/// Definition for all runtime API interfaces.
 	pub trait CustomRuntimeApi {
@@ -132,5 +261,26 @@ decl_runtime_apis!(
 		fn cf_account_role(account_id: AccountId32) -> Option<AccountRole>;
 		fn cf_redemption_tax() -> AssetAmount;
 		fn cf_network_environment() -> NetworkEnvironment;
+		/// Simulates an authority rotation against the current state, assuming all ceremonies
+		/// succeed except those requiring participation from the `offline` nodes.
+		fn cf_rotation_dry_run(offline: BTreeSet<AccountId32>) -> RotationDryRun;
+		/// Resolves the auction against the current bids and qualification state.
+		fn cf_authority_set_preview() -> AuthoritySetPreview;
+		/// All governance proposals that have not yet been executed.
+		fn cf_governance_proposals() -> Vec<ProposalPreview<AccountId32>>;
+		/// Audit records of the most recent governance proposals.
+		fn cf_governance_audit_log() -> Vec<(ProposalId, ProposalRecord<AccountId32, BlockNumber>)>;
+		/// Projects the FLIP supply reported to the State Chain Gateway for the next `epochs`
+		/// epochs, following the emission schedule.
+		fn cf_projected_flip_supply(epochs: u32) -> Vec<(u32, u128)>;
+		/// Records of the most recent network fee Flip buys and their burns.
+		fn cf_flip_burn_history() -> Vec<FlipBurnRecord<BlockNumber>>;
+		/// The most recent penalties imposed on the account, oldest first.
+		fn cf_penalty_history(account_id: &AccountId32) -> Vec<RuntimeApiPenaltyRecord>;
+		fn cf_validator_liveness(account_id: &AccountId32) -> ValidatorLiveness;
+		/// The position of the account's redemption in the redemption queue, if it is queued.
+		fn cf_redemption_queue_position(
+			account_id: &AccountId32,
+		) -> Option<RuntimeApiRedemptionQueuePosition>;
 	}
 );
@@ -24,7 +24,7 @@ use cf_primitives::{
 };
 use codec::{Decode, Encode, MaxEncodedLen};
 use frame_support::{
-	dispatch::{DispatchResultWithPostInfo, UnfilteredDispatchable},
+	dispatch::{DispatchResultWithPostInfo, GetDispatchInfo, UnfilteredDispatchable},
 	error::BadOrigin,
 	pallet_prelude::Member,
 	sp_runtime::{
@@ -66,6 +66,7 @@ pub trait Chainflip: frame_system::Config {
 	type RuntimeCall: Member
 		+ Parameter
 		+ UnfilteredDispatchable<RuntimeOrigin = Self::RuntimeOrigin>
+		+ GetDispatchInfo
 		+ IsType<<Self as frame_system::Config>::RuntimeCall>;
 
 	/// A type that allows us to check if a call was a result of witness consensus.
@@ -170,6 +171,10 @@ pub trait VaultRotator {
 	/// Get the current rotation status.
 	fn status() -> AsyncResult<VaultStatus<Self::ValidatorId>>;
 
+	/// The candidates that were excluded from keygen after failing a previous attempt in the
+	/// current rotation. These are not part of the generated key.
+	fn keygen_exclusions() -> BTreeSet<Self::ValidatorId>;
+
 	/// Activate key/s on particular chain/s. For example, setting the new key
 	/// on the contract for a smart contract chain.
 	fn activate();
@@ -330,6 +335,17 @@ pub trait Slashing {
 	/// Slashes a validator by some fixed amount.
 	fn slash_balance(account_id: &Self::AccountId, slash_amount: FlipBalance);
 
+	/// Slashes a validator whose bond is partly backed by `delegated_stake`, which is held in the
+	/// delegators' accounts and slashed separately. Only the validator's own share of the bond
+	/// protects it from being slashed.
+	fn slash_operator_balance(
+		account_id: &Self::AccountId,
+		slash_amount: FlipBalance,
+		_delegated_stake: FlipBalance,
+	) {
+		Self::slash_balance(account_id, slash_amount)
+	}
+
 	/// Calculate the amount of FLIP to slash
 	fn calculate_slash_amount(
 		account_id: &Self::AccountId,
@@ -603,6 +619,14 @@ pub trait Bonding {
 	fn update_bond(authority: &Self::ValidatorId, bond: Self::Amount);
 }
 
+/// Provides the amounts authorities have scheduled to unbond at the next rotation.
+pub trait UnbondingSchedule {
+	type ValidatorId;
+	type Amount;
+	/// Takes all scheduled unbonds. Called once per rotation.
+	fn take_scheduled_unbonds() -> Vec<(Self::ValidatorId, Self::Amount)>;
+}
+
 pub trait CeremonyIdProvider {
 	/// Increment the ceremony id, returning the new one.
 	fn increment_ceremony_id() -> CeremonyId;
@@ -754,6 +778,11 @@ pub trait CommKeyBroadcaster {
 pub trait FlipBurnInfo {
 	/// Takes the available Flip and returns it.
 	fn take_flip_to_burn() -> AssetAmount;
+
+	/// Called once the Flip returned by `take_flip_to_burn` has been burned, with the egress that
+	/// sends it to the State Chain Gateway and the `UpdateFlipSupply` broadcast reflecting the
+	/// burn.
+	fn on_flip_burned(egress_id: EgressId, supply_update_broadcast_id: BroadcastId);
 }
 
 /// The trait implementation is intentionally no-op by default
@@ -804,6 +833,19 @@ pub trait GetBitcoinFeeInfo {
 pub trait GetBlockHeight<C: Chain> {
 	fn get_block_height() -> C::ChainBlockNumber;
 }
+
+/// Compares the fee paid for a transaction against the fee implied by the tracked chain state.
+pub trait TransactionFeeAudit<C: Chain> {
+	/// Returns the fee paid as a percentage of the fee we would expect to pay, or `None` if the
+	/// two can't be meaningfully compared.
+	fn fee_paid_percentage(fee_paid: &C::TransactionFee) -> Option<u32>;
+}
+
+impl<C: Chain> TransactionFeeAudit<C> for () {
+	fn fee_paid_percentage(_fee_paid: &C::TransactionFee) -> Option<u32> {
+		None
+	}
+}
 pub trait CompatibleCfeVersions {
 	fn current_release_version() -> SemVer;
 }
@@ -5,6 +5,7 @@ use sp_std::collections::btree_set::BTreeSet;
 use super::MockPallet;
 
 const ROTATION_OUTCOME: &[u8] = b"ROTATION_OUTCOME";
+const KEYGEN_EXCLUSIONS: &[u8] = b"KEYGEN_EXCLUSIONS";
 
 macro_rules! mock_vault_rotator {
 	($rotator_name:ident) => {
@@ -48,6 +49,11 @@ macro_rules! mock_vault_rotator {
 			pub fn pending() {
 				Self::put_value(ROTATION_OUTCOME, AsyncResult::<VaultStatus<u64>>::Pending)
 			}
+
+			pub fn keygen_success_excluding<E: IntoIterator<Item = u64>>(excluded: E) {
+				Self::keygen_success();
+				Self::put_value(KEYGEN_EXCLUSIONS, excluded.into_iter().collect::<BTreeSet<_>>());
+			}
 		}
 
 		impl VaultRotator for $rotator_name {
@@ -55,6 +61,7 @@ macro_rules! mock_vault_rotator {
 
 			fn keygen(_candidates: BTreeSet<Self::ValidatorId>, _new_epoch_index: EpochIndex) {
 				Self::put_value(ROTATION_OUTCOME, AsyncResult::<VaultStatus<u64>>::Pending);
+				Self::put_value(KEYGEN_EXCLUSIONS, BTreeSet::<u64>::new());
 			}
 
 			fn key_handover(
@@ -69,6 +76,10 @@ macro_rules! mock_vault_rotator {
 				Self::get_value(ROTATION_OUTCOME).unwrap_or_default()
 			}
 
+			fn keygen_exclusions() -> BTreeSet<Self::ValidatorId> {
+				Self::get_value(KEYGEN_EXCLUSIONS).unwrap_or_default()
+			}
+
 			fn activate() {
 				Self::put_value(ROTATION_OUTCOME, AsyncResult::<VaultStatus<u64>>::Pending);
 			}
@@ -1,4 +1,4 @@
-use crate::{task_scope, Port};
+use crate::{metrics, task_scope, Port};
 use serde::Deserialize;
 use tracing_subscriber::fmt::format::FmtSpan;
 use warp::{Filter, Reply};
@@ -99,6 +99,9 @@ macro_rules! print_start_and_end {
 /// '"debug,warp=off,hyper=off,jsonrpc=off,web3=off,reqwest=off"' 127.0.0.1:36079/tracing
 ///
 /// The full syntax used for specifying filter directives used in both the REST api and in the RUST_LOG environment variable is specified here: https://docs.rs/tracing-subscriber/latest/tracing_subscriber/filter/struct.EnvFilter.html
+///
+/// `curl -X GET 127.0.0.1:36079/rpc_endpoints` - This returns the latency, error rate and
+/// selection state of every external chain rpc endpoint used by the retrier.
 pub async fn init_json_logger(
 	settings: LoggingSettings,
 ) -> impl FnOnce(&task_scope::Scope<'_, anyhow::Error>) {
@@ -128,6 +131,7 @@ pub async fn init_json_logger(
 	move |scope| {
 		scope.spawn_weak(async move {
 			const PATH: &str = "tracing";
+			const RPC_ENDPOINTS_PATH: &str = "rpc_endpoints";
 			const MAX_CONTENT_LENGTH: u64 = 2 * 1024;
 
 			let change_filter = warp::post()
@@ -177,7 +181,12 @@ pub async fn init_json_logger(
 					})
 				});
 
-			warp::serve(change_filter.or(get_filter))
+			let get_rpc_endpoints = warp::get()
+				.and(warp::path(RPC_ENDPOINTS_PATH))
+				.and(warp::path::end())
+				.map(|| warp::reply::json(&metrics::rpc_endpoint_stats()).into_response());
+
+			warp::serve(change_filter.or(get_filter).or(get_rpc_endpoints))
 				.run((std::net::Ipv4Addr::LOCALHOST, settings.command_server_port))
 				.await;
 
@@ -7,12 +7,15 @@ use crate::ArrayCollect;
 use async_channel::{unbounded, Receiver, Sender};
 use lazy_static;
 use prometheus::{
-	register_int_counter_vec_with_registry, register_int_counter_with_registry,
+	core::Collector, register_int_counter_vec_with_registry, register_int_counter_with_registry,
 	register_int_gauge_vec_with_registry, register_int_gauge_with_registry, IntCounter,
 	IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry,
 };
-use serde::Deserialize;
-use std::{collections::HashSet, net::IpAddr};
+use serde::{Deserialize, Serialize};
+use std::{
+	collections::{BTreeMap, HashSet},
+	net::IpAddr,
+};
 use tracing::info;
 use warp::Filter;
 
@@ -435,6 +438,42 @@ build_counter_vec!(
 	"Count all the rpc calls made by the retrier, it counts every single call even if it is the same made multiple times",
 	["client","rpc_method"]
 );
+build_counter_vec!(
+	RPC_QUORUM_DISAGREEMENTS,
+	"rpc_quorum_disagreements",
+	"Count the number of times a provider returned a response that disagreed with the quorum",
+	["client", "rpc_method", "provider"]
+);
+build_gauge_vec!(
+	RPC_ENDPOINT_AVERAGE_LATENCY,
+	"rpc_endpoint_average_latency_ms",
+	"Exponentially weighted average latency in ms of the requests made by the retrier to an endpoint",
+	["client", "endpoint"]
+);
+build_gauge_vec!(
+	RPC_ENDPOINT_ERROR_RATE,
+	"rpc_endpoint_error_rate_permille",
+	"Exponentially weighted rate, in parts per thousand, of the requests made by the retrier to an endpoint that failed",
+	["client", "endpoint"]
+);
+build_gauge_vec!(
+	RPC_ENDPOINT_SELECTED,
+	"rpc_endpoint_selected",
+	"Set to 1 for the endpoint the retrier currently sends new requests to, and 0 otherwise",
+	["client", "endpoint"]
+);
+build_counter_vec!(
+	CHAIN_REORGS,
+	"chain_reorgs",
+	"Count the reorgs of previously witnessed blocks detected by the engine",
+	["chain"]
+);
+build_gauge_vec!(
+	CHAIN_REORG_DEPTH,
+	"chain_reorg_depth",
+	"The number of previously witnessed blocks that were replaced by the most recent reorg",
+	["chain"]
+);
 build_counter_vec!(
 	P2P_MONITOR_EVENT,
 	"p2p_monitor_event",
@@ -549,6 +588,53 @@ impl CeremonyMetrics {
 	}
 }
 
+/// The health of an rpc endpoint, as most recently recorded by the retrier.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct RpcEndpointStats {
+	pub average_latency_ms: i64,
+	pub error_rate_permille: i64,
+	pub selected: bool,
+}
+
+/// Collects the current rpc endpoint statistics from the registered gauges, keyed by the name of
+/// the retrier client and then by endpoint.
+pub fn rpc_endpoint_stats() -> BTreeMap<String, BTreeMap<String, RpcEndpointStats>> {
+	let mut stats = BTreeMap::<String, BTreeMap<String, RpcEndpointStats>>::new();
+
+	let mut collect_gauge = |gauge: &IntGaugeVec, update: fn(&mut RpcEndpointStats, i64)| {
+		for family in gauge.collect() {
+			for metric in family.get_metric() {
+				let label_value = |name: &str| {
+					metric
+						.get_label()
+						.iter()
+						.find(|label| label.get_name() == name)
+						.map(|label| label.get_value().to_string())
+						.unwrap_or_default()
+				};
+				update(
+					stats
+						.entry(label_value("client"))
+						.or_default()
+						.entry(label_value("endpoint"))
+						.or_default(),
+					metric.get_gauge().get_value() as i64,
+				);
+			}
+		}
+	};
+
+	collect_gauge(&RPC_ENDPOINT_AVERAGE_LATENCY.prom_metric, |stats, value| {
+		stats.average_latency_ms = value
+	});
+	collect_gauge(&RPC_ENDPOINT_ERROR_RATE.prom_metric, |stats, value| {
+		stats.error_rate_permille = value
+	});
+	collect_gauge(&RPC_ENDPOINT_SELECTED.prom_metric, |stats, value| stats.selected = value != 0);
+
+	stats
+}
+
 #[tracing::instrument(name = "prometheus-metric", skip_all)]
 pub async fn start<'a, 'env>(
 	scope: &'a task_scope::Scope<'env, anyhow::Error>,
@@ -795,4 +881,35 @@ stage_failing{chain="Chain1",reason="NotEnoughMessages",stage="stage3"} 1
 			.remove_label_values(&["Chain1", "7", "Keygen"])
 			.is_err());
 	}
+
+	#[test]
+	fn rpc_endpoint_stats_are_collected_from_the_gauges() {
+		RPC_ENDPOINT_AVERAGE_LATENCY.set(&["stats_test", "primary"], 120);
+		RPC_ENDPOINT_ERROR_RATE.set(&["stats_test", "primary"], 50);
+		RPC_ENDPOINT_SELECTED.set(&["stats_test", "primary"], 0);
+		RPC_ENDPOINT_AVERAGE_LATENCY.set(&["stats_test", "secondary"], 80);
+		RPC_ENDPOINT_SELECTED.set(&["stats_test", "secondary"], 1);
+
+		assert_eq!(
+			rpc_endpoint_stats().remove("stats_test").unwrap(),
+			BTreeMap::from([
+				(
+					"primary".to_string(),
+					RpcEndpointStats {
+						average_latency_ms: 120,
+						error_rate_permille: 50,
+						selected: false
+					}
+				),
+				(
+					"secondary".to_string(),
+					RpcEndpointStats {
+						average_latency_ms: 80,
+						error_rate_permille: 0,
+						selected: true
+					}
+				),
+			])
+		);
+	}
 }